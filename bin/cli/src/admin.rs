@@ -0,0 +1,1032 @@
+//! Subcommands for the group admin's governance instructions.
+//!
+//! Each of them can either sign and send the transaction with the admin keypair,
+//! or print it as an unsigned base64 transaction for signing by a multisig.
+
+use std::str::FromStr;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::token::Token;
+use clap::Args;
+use mango_v4::accounts_ix::InterestRateParams;
+use mango_v4::state::{
+    BookSide, EventQueue, Group, MintInfo, OracleConfigParams, PerpFundingModel, PerpMarketIndex,
+    Serum3MarketIndex, TokenIndex,
+};
+use mango_v4_client::{keypair_from_cli, pubkey_from_cli, AnyhowWrap, Client, TransactionBuilder};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::Rpc;
+
+#[derive(Args, Debug, Clone)]
+pub struct Admin {
+    #[clap(short, long)]
+    group: String,
+
+    /// keypair of the group admin, with --print-tx its pubkey is enough
+    #[clap(short, long)]
+    admin: String,
+
+    /// print the unsigned transaction as base64 instead of sending it, the admin
+    /// pays for it and any created accounts
+    ///
+    /// The printed transaction is a legacy transaction with the --blockhash, or with a
+    /// default blockhash that is only good for importing into a multisig.
+    #[clap(long)]
+    print_tx: bool,
+
+    /// recent blockhash for the transaction printed with --print-tx
+    #[clap(long, requires = "print_tx")]
+    blockhash: Option<String>,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+impl Admin {
+    fn group(&self) -> Pubkey {
+        pubkey_from_cli(&self.group)
+    }
+
+    fn admin(&self) -> Pubkey {
+        pubkey_from_cli(&self.admin)
+    }
+
+    /// The fee payer defaults to the admin when sending, and to a throwaway
+    /// keypair when only printing.
+    fn client(&self) -> anyhow::Result<Client> {
+        if !self.rpc.fee_payer.is_empty() {
+            self.rpc.client(None)
+        } else if self.print_tx {
+            self.rpc.client_with_fee_payer(Keypair::new())
+        } else {
+            self.rpc.client(Some(&self.admin))
+        }
+    }
+
+    /// Account that pays for accounts created by an instruction
+    fn payer(&self, client: &Client) -> Pubkey {
+        if self.print_tx {
+            self.admin()
+        } else {
+            client.fee_payer.pubkey()
+        }
+    }
+
+    async fn send_or_print(
+        &self,
+        client: &Client,
+        instructions: Vec<Instruction>,
+    ) -> anyhow::Result<()> {
+        if self.print_tx {
+            let mut message = solana_sdk::message::Message::new(&instructions, Some(&self.admin()));
+            if let Some(blockhash) = self.blockhash.as_ref() {
+                message.recent_blockhash = Hash::from_str(blockhash)
+                    .map_err(|e| anyhow::anyhow!("invalid blockhash {}: {:?}", blockhash, e))?;
+            }
+            let tx = solana_sdk::transaction::Transaction::new_unsigned(message);
+            println!("{}", base64::encode(bincode::serialize(&tx)?));
+            return Ok(());
+        }
+
+        let admin = keypair_from_cli(&self.admin);
+        let txsig = TransactionBuilder {
+            instructions,
+            address_lookup_tables: vec![],
+            payer: client.fee_payer.pubkey(),
+            signers: vec![&admin, &*client.fee_payer],
+            config: client.transaction_builder_config,
+        }
+        .send_and_confirm(client)
+        .await?;
+        println!("{}", txsig);
+        Ok(())
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct OracleConfig {
+    #[clap(long, default_value = "0.1")]
+    oracle_conf_filter: f32,
+
+    #[clap(long)]
+    oracle_max_staleness_slots: Option<u32>,
+}
+
+impl OracleConfig {
+    fn params(&self) -> OracleConfigParams {
+        OracleConfigParams {
+            conf_filter: self.oracle_conf_filter,
+            max_staleness_slots: self.oracle_max_staleness_slots,
+        }
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct TokenRegister {
+    #[clap(flatten)]
+    admin: Admin,
+
+    #[clap(long)]
+    token_index: TokenIndex,
+
+    #[clap(long)]
+    name: String,
+
+    #[clap(long)]
+    mint: String,
+
+    #[clap(long)]
+    oracle: String,
+
+    #[clap(flatten)]
+    oracle_config: OracleConfig,
+
+    #[clap(long)]
+    util0: f32,
+    #[clap(long)]
+    rate0: f32,
+    #[clap(long)]
+    util1: f32,
+    #[clap(long)]
+    rate1: f32,
+    #[clap(long)]
+    max_rate: f32,
+    #[clap(long)]
+    adjustment_factor: f32,
+
+    #[clap(long)]
+    loan_fee_rate: f32,
+    #[clap(long)]
+    loan_origination_fee_rate: f32,
+    #[clap(long)]
+    maint_asset_weight: f32,
+    #[clap(long)]
+    init_asset_weight: f32,
+    #[clap(long)]
+    maint_liab_weight: f32,
+    #[clap(long)]
+    init_liab_weight: f32,
+    #[clap(long)]
+    liquidation_fee: f32,
+
+    #[clap(long)]
+    min_vault_to_deposits_ratio: f64,
+    #[clap(long, default_value = "86400")]
+    net_borrow_limit_window_size_ts: u64,
+    /// in native quote units, negative to disable the limit
+    #[clap(long)]
+    net_borrow_limit_per_window_quote: i64,
+}
+
+pub async fn token_register(cmd: TokenRegister) -> anyhow::Result<()> {
+    let client = cmd.admin.client()?;
+    let group = cmd.admin.group();
+    let mint = pubkey_from_cli(&cmd.mint);
+    let program_id = mango_v4::id();
+
+    let bank = Pubkey::find_program_address(
+        &[
+            b"Bank".as_ref(),
+            group.as_ref(),
+            &cmd.token_index.to_le_bytes(),
+            &0u32.to_le_bytes(),
+        ],
+        &program_id,
+    )
+    .0;
+    let vault = Pubkey::find_program_address(
+        &[
+            b"Vault".as_ref(),
+            group.as_ref(),
+            &cmd.token_index.to_le_bytes(),
+            &0u32.to_le_bytes(),
+        ],
+        &program_id,
+    )
+    .0;
+    let mint_info = Pubkey::find_program_address(
+        &[b"MintInfo".as_ref(), group.as_ref(), mint.as_ref()],
+        &program_id,
+    )
+    .0;
+
+    let ix = Instruction {
+        program_id,
+        accounts: mango_v4::accounts::TokenRegister {
+            group,
+            admin: cmd.admin.admin(),
+            mint,
+            bank,
+            vault,
+            mint_info,
+            oracle: pubkey_from_cli(&cmd.oracle),
+            payer: cmd.admin.payer(&client),
+            token_program: Token::id(),
+            system_program: solana_sdk::system_program::id(),
+            rent: solana_sdk::sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: mango_v4::instruction::TokenRegister {
+            token_index: cmd.token_index,
+            name: cmd.name.clone(),
+            oracle_config: cmd.oracle_config.params(),
+            interest_rate_params: InterestRateParams {
+                util0: cmd.util0,
+                rate0: cmd.rate0,
+                util1: cmd.util1,
+                rate1: cmd.rate1,
+                max_rate: cmd.max_rate,
+                adjustment_factor: cmd.adjustment_factor,
+            },
+            loan_fee_rate: cmd.loan_fee_rate,
+            loan_origination_fee_rate: cmd.loan_origination_fee_rate,
+            maint_asset_weight: cmd.maint_asset_weight,
+            init_asset_weight: cmd.init_asset_weight,
+            maint_liab_weight: cmd.maint_liab_weight,
+            init_liab_weight: cmd.init_liab_weight,
+            liquidation_fee: cmd.liquidation_fee,
+            min_vault_to_deposits_ratio: cmd.min_vault_to_deposits_ratio,
+            net_borrow_limit_window_size_ts: cmd.net_borrow_limit_window_size_ts,
+            net_borrow_limit_per_window_quote: cmd.net_borrow_limit_per_window_quote,
+        }
+        .data(),
+    };
+    cmd.admin.send_or_print(&client, vec![ix]).await
+}
+
+/// Only the passed parameters are changed.
+#[derive(Args, Debug, Clone)]
+pub struct TokenEdit {
+    #[clap(flatten)]
+    admin: Admin,
+
+    #[clap(long)]
+    mint: String,
+
+    #[clap(long)]
+    oracle: Option<Pubkey>,
+    #[clap(long)]
+    oracle_conf_filter: Option<f32>,
+    /// only used together with --oracle-conf-filter
+    #[clap(long)]
+    oracle_max_staleness_slots: Option<u32>,
+    #[clap(long)]
+    group_insurance_fund: Option<bool>,
+    /// util0,rate0,util1,rate1,max_rate,adjustment_factor
+    #[clap(long, value_delimiter = ',', number_of_values = 6)]
+    interest_rate_params: Option<Vec<f32>>,
+    #[clap(long)]
+    loan_fee_rate: Option<f32>,
+    #[clap(long)]
+    loan_origination_fee_rate: Option<f32>,
+    #[clap(long)]
+    maint_asset_weight: Option<f32>,
+    #[clap(long)]
+    init_asset_weight: Option<f32>,
+    #[clap(long)]
+    maint_liab_weight: Option<f32>,
+    #[clap(long)]
+    init_liab_weight: Option<f32>,
+    #[clap(long)]
+    liquidation_fee: Option<f32>,
+    #[clap(long)]
+    stable_price_delay_interval_seconds: Option<u32>,
+    #[clap(long)]
+    stable_price_delay_growth_limit: Option<f32>,
+    #[clap(long)]
+    stable_price_growth_limit: Option<f32>,
+    #[clap(long)]
+    min_vault_to_deposits_ratio: Option<f64>,
+    #[clap(long)]
+    net_borrow_limit_per_window_quote: Option<i64>,
+    #[clap(long)]
+    net_borrow_limit_window_size_ts: Option<u64>,
+    #[clap(long)]
+    borrow_weight_scale_start_quote: Option<f64>,
+    #[clap(long)]
+    deposit_weight_scale_start_quote: Option<f64>,
+    #[clap(long)]
+    reset_stable_price: bool,
+    #[clap(long)]
+    reset_net_borrow_limit: bool,
+    #[clap(long)]
+    reduce_only: Option<u8>,
+    #[clap(long)]
+    name: Option<String>,
+    #[clap(long)]
+    force_close: Option<bool>,
+    #[clap(long)]
+    fallback_oracle: Option<Pubkey>,
+    /// switches to another insurance fund, in a separate instruction of the same transaction
+    #[clap(long)]
+    insurance_fund_index: Option<u16>,
+}
+
+/// The vault of an isolated insurance fund must be passed when switching to it
+async fn insurance_fund_vault_metas(
+    client: &Client,
+    group: Pubkey,
+    fund_index: u16,
+) -> anyhow::Result<Vec<AccountMeta>> {
+    if fund_index == 0 {
+        return Ok(vec![]);
+    }
+    let group_data: Group = client.rpc_anchor_account(&group).await?;
+    let vault = group_data
+        .insurance_fund_vault(&group, fund_index)
+        .map_err_anyhow()?;
+    Ok(vec![AccountMeta::new_readonly(vault, false)])
+}
+
+pub async fn token_edit(cmd: TokenEdit) -> anyhow::Result<()> {
+    let client = cmd.admin.client()?;
+    let group = cmd.admin.group();
+    let mint = pubkey_from_cli(&cmd.mint);
+    let mint_info_address = Pubkey::find_program_address(
+        &[b"MintInfo".as_ref(), group.as_ref(), mint.as_ref()],
+        &mango_v4::id(),
+    )
+    .0;
+    let mint_info: MintInfo = client.rpc_anchor_account(&mint_info_address).await?;
+
+    let interest_rate_params_opt = cmd
+        .interest_rate_params
+        .as_ref()
+        .map(|v| InterestRateParams {
+            util0: v[0],
+            rate0: v[1],
+            util1: v[2],
+            rate1: v[3],
+            max_rate: v[4],
+            adjustment_factor: v[5],
+        });
+
+    let mut accounts = mango_v4::accounts::TokenEdit {
+        group,
+        admin: cmd.admin.admin(),
+        mint_info: mint_info_address,
+        oracle: cmd.oracle.unwrap_or(mint_info.oracle),
+    }
+    .to_account_metas(None);
+    // all banks, in MintInfo order
+    accounts.extend(
+        mint_info
+            .banks()
+            .iter()
+            .map(|&bank| AccountMeta::new(bank, false)),
+    );
+
+    let ix = Instruction {
+        program_id: mango_v4::id(),
+        accounts,
+        data: mango_v4::instruction::TokenEdit {
+            oracle_opt: cmd.oracle,
+            oracle_config_opt: cmd
+                .oracle_conf_filter
+                .map(|conf_filter| OracleConfigParams {
+                    conf_filter,
+                    max_staleness_slots: cmd.oracle_max_staleness_slots,
+                }),
+            group_insurance_fund_opt: cmd.group_insurance_fund,
+            interest_rate_params_opt,
+            loan_fee_rate_opt: cmd.loan_fee_rate,
+            loan_origination_fee_rate_opt: cmd.loan_origination_fee_rate,
+            maint_asset_weight_opt: cmd.maint_asset_weight,
+            init_asset_weight_opt: cmd.init_asset_weight,
+            maint_liab_weight_opt: cmd.maint_liab_weight,
+            init_liab_weight_opt: cmd.init_liab_weight,
+            liquidation_fee_opt: cmd.liquidation_fee,
+            stable_price_delay_interval_seconds_opt: cmd.stable_price_delay_interval_seconds,
+            stable_price_delay_growth_limit_opt: cmd.stable_price_delay_growth_limit,
+            stable_price_growth_limit_opt: cmd.stable_price_growth_limit,
+            min_vault_to_deposits_ratio_opt: cmd.min_vault_to_deposits_ratio,
+            net_borrow_limit_per_window_quote_opt: cmd.net_borrow_limit_per_window_quote,
+            net_borrow_limit_window_size_ts_opt: cmd.net_borrow_limit_window_size_ts,
+            borrow_weight_scale_start_quote_opt: cmd.borrow_weight_scale_start_quote,
+            deposit_weight_scale_start_quote_opt: cmd.deposit_weight_scale_start_quote,
+            reset_stable_price: cmd.reset_stable_price,
+            reset_net_borrow_limit: cmd.reset_net_borrow_limit,
+            reduce_only_opt: cmd.reduce_only,
+            name_opt: cmd.name.clone(),
+            force_close_opt: cmd.force_close,
+            fallback_oracle_opt: cmd.fallback_oracle,
+        }
+        .data(),
+    };
+    let mut instructions = vec![ix];
+
+    if let Some(fund_index) = cmd.insurance_fund_index {
+        let mut accounts = mango_v4::accounts::TokenEditInsuranceFund {
+            group,
+            admin: cmd.admin.admin(),
+            mint_info: mint_info_address,
+        }
+        .to_account_metas(None);
+        accounts.extend(insurance_fund_vault_metas(&client, group, fund_index).await?);
+        instructions.push(Instruction {
+            program_id: mango_v4::id(),
+            accounts,
+            data: mango_v4::instruction::TokenEditInsuranceFund { fund_index }.data(),
+        });
+    }
+
+    cmd.admin.send_or_print(&client, instructions).await
+}
+
+/// The bids, asks and event queue accounts are created in a separate transaction
+/// that is always sent and paid by the fee payer.
+#[derive(Args, Debug, Clone)]
+pub struct PerpCreateMarket {
+    #[clap(flatten)]
+    admin: Admin,
+
+    #[clap(long)]
+    perp_market_index: PerpMarketIndex,
+
+    #[clap(long)]
+    name: String,
+
+    #[clap(long)]
+    oracle: String,
+
+    #[clap(flatten)]
+    oracle_config: OracleConfig,
+
+    #[clap(long)]
+    settle_token_index: TokenIndex,
+
+    #[clap(long)]
+    base_decimals: u8,
+    #[clap(long)]
+    quote_lot_size: i64,
+    #[clap(long)]
+    base_lot_size: i64,
+    #[clap(long)]
+    maint_base_asset_weight: f32,
+    #[clap(long)]
+    init_base_asset_weight: f32,
+    #[clap(long)]
+    maint_base_liab_weight: f32,
+    #[clap(long)]
+    init_base_liab_weight: f32,
+    #[clap(long)]
+    maint_overall_asset_weight: f32,
+    #[clap(long)]
+    init_overall_asset_weight: f32,
+    #[clap(long)]
+    base_liquidation_fee: f32,
+    #[clap(long)]
+    maker_fee: f32,
+    #[clap(long)]
+    taker_fee: f32,
+    #[clap(long)]
+    min_funding: f32,
+    #[clap(long)]
+    max_funding: f32,
+    #[clap(long)]
+    impact_quantity: i64,
+    #[clap(long)]
+    group_insurance_fund: bool,
+    #[clap(long)]
+    fee_penalty: f32,
+    #[clap(long)]
+    settle_fee_flat: f32,
+    #[clap(long)]
+    settle_fee_amount_threshold: f32,
+    #[clap(long)]
+    settle_fee_fraction_low_health: f32,
+    #[clap(long)]
+    settle_pnl_limit_factor: f32,
+    #[clap(long)]
+    settle_pnl_limit_window_size_ts: u64,
+    #[clap(long)]
+    positive_pnl_liquidation_fee: f32,
+}
+
+/// Creates an account owned by the mango program that the program initializes later
+async fn create_program_account(client: &Client, len: usize) -> anyhow::Result<Pubkey> {
+    let keypair = Keypair::new();
+    let rent = client
+        .rpc_async()
+        .get_minimum_balance_for_rent_exemption(len)
+        .await?;
+    let ix = solana_sdk::system_instruction::create_account(
+        &client.fee_payer.pubkey(),
+        &keypair.pubkey(),
+        rent,
+        len as u64,
+        &mango_v4::id(),
+    );
+    TransactionBuilder {
+        instructions: vec![ix],
+        address_lookup_tables: vec![],
+        payer: client.fee_payer.pubkey(),
+        signers: vec![&keypair, &*client.fee_payer],
+        config: client.transaction_builder_config,
+    }
+    .send_and_confirm(client)
+    .await?;
+    Ok(keypair.pubkey())
+}
+
+pub async fn perp_create_market(cmd: PerpCreateMarket) -> anyhow::Result<()> {
+    if cmd.admin.print_tx && cmd.admin.rpc.fee_payer.is_empty() {
+        anyhow::bail!("--fee-payer is needed to create the book and event queue accounts");
+    }
+    let client = cmd.admin.client()?;
+    let group = cmd.admin.group();
+
+    let bids = create_program_account(&client, 8 + std::mem::size_of::<BookSide>()).await?;
+    let asks = create_program_account(&client, 8 + std::mem::size_of::<BookSide>()).await?;
+    let event_queue =
+        create_program_account(&client, 8 + std::mem::size_of::<EventQueue>()).await?;
+
+    let perp_market = Pubkey::find_program_address(
+        &[
+            b"PerpMarket".as_ref(),
+            group.as_ref(),
+            cmd.perp_market_index.to_le_bytes().as_ref(),
+        ],
+        &mango_v4::id(),
+    )
+    .0;
+
+    let ix = Instruction {
+        program_id: mango_v4::id(),
+        accounts: mango_v4::accounts::PerpCreateMarket {
+            group,
+            admin: cmd.admin.admin(),
+            oracle: pubkey_from_cli(&cmd.oracle),
+            perp_market,
+            bids,
+            asks,
+            event_queue,
+            payer: cmd.admin.payer(&client),
+            system_program: solana_sdk::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: mango_v4::instruction::PerpCreateMarket {
+            perp_market_index: cmd.perp_market_index,
+            name: cmd.name.clone(),
+            oracle_config: cmd.oracle_config.params(),
+            base_decimals: cmd.base_decimals,
+            quote_lot_size: cmd.quote_lot_size,
+            base_lot_size: cmd.base_lot_size,
+            maint_base_asset_weight: cmd.maint_base_asset_weight,
+            init_base_asset_weight: cmd.init_base_asset_weight,
+            maint_base_liab_weight: cmd.maint_base_liab_weight,
+            init_base_liab_weight: cmd.init_base_liab_weight,
+            maint_overall_asset_weight: cmd.maint_overall_asset_weight,
+            init_overall_asset_weight: cmd.init_overall_asset_weight,
+            base_liquidation_fee: cmd.base_liquidation_fee,
+            maker_fee: cmd.maker_fee,
+            taker_fee: cmd.taker_fee,
+            min_funding: cmd.min_funding,
+            max_funding: cmd.max_funding,
+            impact_quantity: cmd.impact_quantity,
+            group_insurance_fund: cmd.group_insurance_fund,
+            fee_penalty: cmd.fee_penalty,
+            settle_fee_flat: cmd.settle_fee_flat,
+            settle_fee_amount_threshold: cmd.settle_fee_amount_threshold,
+            settle_fee_fraction_low_health: cmd.settle_fee_fraction_low_health,
+            settle_token_index: cmd.settle_token_index,
+            settle_pnl_limit_factor: cmd.settle_pnl_limit_factor,
+            settle_pnl_limit_window_size_ts: cmd.settle_pnl_limit_window_size_ts,
+            positive_pnl_liquidation_fee: cmd.positive_pnl_liquidation_fee,
+        }
+        .data(),
+    };
+    cmd.admin.send_or_print(&client, vec![ix]).await
+}
+
+/// Only the passed parameters are changed.
+#[derive(Args, Debug, Clone)]
+pub struct PerpEditMarket {
+    #[clap(flatten)]
+    admin: Admin,
+
+    #[clap(long)]
+    perp_market_index: PerpMarketIndex,
+
+    #[clap(long)]
+    oracle: Option<Pubkey>,
+    #[clap(long)]
+    oracle_conf_filter: Option<f32>,
+    /// only used together with --oracle-conf-filter
+    #[clap(long)]
+    oracle_max_staleness_slots: Option<u32>,
+    #[clap(long)]
+    base_decimals: Option<u8>,
+    #[clap(long)]
+    maint_base_asset_weight: Option<f32>,
+    #[clap(long)]
+    init_base_asset_weight: Option<f32>,
+    #[clap(long)]
+    maint_base_liab_weight: Option<f32>,
+    #[clap(long)]
+    init_base_liab_weight: Option<f32>,
+    #[clap(long)]
+    maint_overall_asset_weight: Option<f32>,
+    #[clap(long)]
+    init_overall_asset_weight: Option<f32>,
+    #[clap(long)]
+    base_liquidation_fee: Option<f32>,
+    #[clap(long)]
+    maker_fee: Option<f32>,
+    #[clap(long)]
+    taker_fee: Option<f32>,
+    #[clap(long)]
+    min_funding: Option<f32>,
+    #[clap(long)]
+    max_funding: Option<f32>,
+    #[clap(long)]
+    impact_quantity: Option<i64>,
+    #[clap(long)]
+    group_insurance_fund: Option<bool>,
+    #[clap(long)]
+    fee_penalty: Option<f32>,
+    #[clap(long)]
+    settle_fee_flat: Option<f32>,
+    #[clap(long)]
+    settle_fee_amount_threshold: Option<f32>,
+    #[clap(long)]
+    settle_fee_fraction_low_health: Option<f32>,
+    #[clap(long)]
+    stable_price_delay_interval_seconds: Option<u32>,
+    #[clap(long)]
+    stable_price_delay_growth_limit: Option<f32>,
+    #[clap(long)]
+    stable_price_growth_limit: Option<f32>,
+    #[clap(long)]
+    settle_pnl_limit_factor: Option<f32>,
+    #[clap(long)]
+    settle_pnl_limit_window_size_ts: Option<u64>,
+    #[clap(long)]
+    reduce_only: Option<bool>,
+    #[clap(long)]
+    reset_stable_price: bool,
+    #[clap(long)]
+    positive_pnl_liquidation_fee: Option<f32>,
+    #[clap(long)]
+    name: Option<String>,
+    #[clap(long)]
+    force_close: Option<bool>,
+    #[clap(long)]
+    fallback_oracle: Option<Pubkey>,
+    /// switches to another insurance fund, in a separate instruction of the same transaction
+    #[clap(long)]
+    insurance_fund_index: Option<u16>,
+    /// unix timestamp at which the market expires, 0 for a perpetual market
+    #[clap(long)]
+    expiry_timestamp: Option<u64>,
+    #[clap(long)]
+    settlement_window_ts: Option<u64>,
+    #[clap(long)]
+    settlement_uses_stable_price: Option<bool>,
+    /// one of impact-price, premium-twap, stable-price
+    #[clap(long)]
+    funding_model: Option<String>,
+    #[clap(long)]
+    funding_interval_ts: Option<u64>,
+}
+
+fn parse_funding_model(value: &str) -> anyhow::Result<PerpFundingModel> {
+    match value {
+        "impact-price" => Ok(PerpFundingModel::ImpactPrice),
+        "premium-twap" => Ok(PerpFundingModel::PremiumTwap),
+        "stable-price" => Ok(PerpFundingModel::StablePrice),
+        _ => anyhow::bail!("unknown funding model {}", value),
+    }
+}
+
+pub async fn perp_edit_market(cmd: PerpEditMarket) -> anyhow::Result<()> {
+    let client = cmd.admin.client()?;
+    let group = cmd.admin.group();
+    let perp_market_address = Pubkey::find_program_address(
+        &[
+            b"PerpMarket".as_ref(),
+            group.as_ref(),
+            cmd.perp_market_index.to_le_bytes().as_ref(),
+        ],
+        &mango_v4::id(),
+    )
+    .0;
+    let perp_market: mango_v4::state::PerpMarket =
+        client.rpc_anchor_account(&perp_market_address).await?;
+
+    let ix = Instruction {
+        program_id: mango_v4::id(),
+        accounts: mango_v4::accounts::PerpEditMarket {
+            group,
+            admin: cmd.admin.admin(),
+            perp_market: perp_market_address,
+            oracle: cmd.oracle.unwrap_or(perp_market.oracle),
+        }
+        .to_account_metas(None),
+        data: mango_v4::instruction::PerpEditMarket {
+            oracle_opt: cmd.oracle,
+            oracle_config_opt: cmd
+                .oracle_conf_filter
+                .map(|conf_filter| OracleConfigParams {
+                    conf_filter,
+                    max_staleness_slots: cmd.oracle_max_staleness_slots,
+                }),
+            base_decimals_opt: cmd.base_decimals,
+            maint_base_asset_weight_opt: cmd.maint_base_asset_weight,
+            init_base_asset_weight_opt: cmd.init_base_asset_weight,
+            maint_base_liab_weight_opt: cmd.maint_base_liab_weight,
+            init_base_liab_weight_opt: cmd.init_base_liab_weight,
+            maint_overall_asset_weight_opt: cmd.maint_overall_asset_weight,
+            init_overall_asset_weight_opt: cmd.init_overall_asset_weight,
+            base_liquidation_fee_opt: cmd.base_liquidation_fee,
+            maker_fee_opt: cmd.maker_fee,
+            taker_fee_opt: cmd.taker_fee,
+            min_funding_opt: cmd.min_funding,
+            max_funding_opt: cmd.max_funding,
+            impact_quantity_opt: cmd.impact_quantity,
+            group_insurance_fund_opt: cmd.group_insurance_fund,
+            fee_penalty_opt: cmd.fee_penalty,
+            settle_fee_flat_opt: cmd.settle_fee_flat,
+            settle_fee_amount_threshold_opt: cmd.settle_fee_amount_threshold,
+            settle_fee_fraction_low_health_opt: cmd.settle_fee_fraction_low_health,
+            stable_price_delay_interval_seconds_opt: cmd.stable_price_delay_interval_seconds,
+            stable_price_delay_growth_limit_opt: cmd.stable_price_delay_growth_limit,
+            stable_price_growth_limit_opt: cmd.stable_price_growth_limit,
+            settle_pnl_limit_factor_opt: cmd.settle_pnl_limit_factor,
+            settle_pnl_limit_window_size_ts_opt: cmd.settle_pnl_limit_window_size_ts,
+            reduce_only_opt: cmd.reduce_only,
+            reset_stable_price: cmd.reset_stable_price,
+            positive_pnl_liquidation_fee_opt: cmd.positive_pnl_liquidation_fee,
+            name_opt: cmd.name.clone(),
+            force_close_opt: cmd.force_close,
+            fallback_oracle_opt: cmd.fallback_oracle,
+            expiry_timestamp_opt: cmd.expiry_timestamp,
+            settlement_window_ts_opt: cmd.settlement_window_ts,
+            settlement_uses_stable_price_opt: cmd.settlement_uses_stable_price,
+            funding_model_opt: cmd
+                .funding_model
+                .as_deref()
+                .map(parse_funding_model)
+                .transpose()?,
+            funding_interval_ts_opt: cmd.funding_interval_ts,
+        }
+        .data(),
+    };
+    let mut instructions = vec![ix];
+
+    if let Some(fund_index) = cmd.insurance_fund_index {
+        let mut accounts = mango_v4::accounts::PerpEditInsuranceFund {
+            group,
+            admin: cmd.admin.admin(),
+            perp_market: perp_market_address,
+        }
+        .to_account_metas(None);
+        accounts.extend(insurance_fund_vault_metas(&client, group, fund_index).await?);
+        instructions.push(Instruction {
+            program_id: mango_v4::id(),
+            accounts,
+            data: mango_v4::instruction::PerpEditInsuranceFund { fund_index }.data(),
+        });
+    }
+
+    cmd.admin.send_or_print(&client, instructions).await
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct Serum3RegisterMarket {
+    #[clap(flatten)]
+    admin: Admin,
+
+    #[clap(long)]
+    market_index: Serum3MarketIndex,
+
+    #[clap(long)]
+    name: String,
+
+    #[clap(long)]
+    serum_program: Pubkey,
+
+    #[clap(long)]
+    serum_market_external: Pubkey,
+
+    #[clap(long)]
+    base_token_index: TokenIndex,
+
+    #[clap(long)]
+    quote_token_index: TokenIndex,
+}
+
+pub async fn serum3_register_market(cmd: Serum3RegisterMarket) -> anyhow::Result<()> {
+    let client = cmd.admin.client()?;
+    let group = cmd.admin.group();
+    let program_id = mango_v4::id();
+
+    let first_bank = |token_index: TokenIndex| {
+        Pubkey::find_program_address(
+            &[
+                b"Bank".as_ref(),
+                group.as_ref(),
+                &token_index.to_le_bytes(),
+                &0u32.to_le_bytes(),
+            ],
+            &program_id,
+        )
+        .0
+    };
+    let serum_market = Pubkey::find_program_address(
+        &[
+            b"Serum3Market".as_ref(),
+            group.as_ref(),
+            cmd.serum_market_external.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    let index_reservation = Pubkey::find_program_address(
+        &[
+            b"Serum3Index".as_ref(),
+            group.as_ref(),
+            &cmd.market_index.to_le_bytes(),
+        ],
+        &program_id,
+    )
+    .0;
+
+    let ix = Instruction {
+        program_id,
+        accounts: mango_v4::accounts::Serum3RegisterMarket {
+            group,
+            admin: cmd.admin.admin(),
+            serum_program: cmd.serum_program,
+            serum_market_external: cmd.serum_market_external,
+            serum_market,
+            index_reservation,
+            quote_bank: first_bank(cmd.quote_token_index),
+            base_bank: first_bank(cmd.base_token_index),
+            payer: cmd.admin.payer(&client),
+            system_program: solana_sdk::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: mango_v4::instruction::Serum3RegisterMarket {
+            market_index: cmd.market_index,
+            name: cmd.name.clone(),
+        }
+        .data(),
+    };
+    cmd.admin.send_or_print(&client, vec![ix]).await
+}
+
+/// Gates are identified by their IxGate number. Without --ix-gate, the group's
+/// current gates are changed by --enable and --disable.
+#[derive(Args, Debug, Clone)]
+pub struct IxGateSet {
+    #[clap(flatten)]
+    admin: Admin,
+
+    /// replace the whole ix gate bitmask
+    #[clap(long)]
+    ix_gate: Option<u128>,
+
+    #[clap(long, value_delimiter = ',')]
+    enable: Vec<u8>,
+
+    #[clap(long, value_delimiter = ',')]
+    disable: Vec<u8>,
+}
+
+pub async fn ix_gate_set(cmd: IxGateSet) -> anyhow::Result<()> {
+    let client = cmd.admin.client()?;
+    let group = cmd.admin.group();
+
+    let mut ix_gate = match cmd.ix_gate {
+        Some(v) => v,
+        None => client.rpc_anchor_account::<Group>(&group).await?.ix_gate,
+    };
+    for &gate in cmd.enable.iter().chain(cmd.disable.iter()) {
+        if gate >= 128 {
+            anyhow::bail!("ix gate {} is out of range", gate);
+        }
+    }
+    for &gate in cmd.enable.iter() {
+        ix_gate &= !(1u128 << gate);
+    }
+    for &gate in cmd.disable.iter() {
+        ix_gate |= 1u128 << gate;
+    }
+
+    let ix = Instruction {
+        program_id: mango_v4::id(),
+        accounts: mango_v4::accounts::IxGateSet {
+            group,
+            admin: cmd.admin.admin(),
+        }
+        .to_account_metas(None),
+        data: mango_v4::instruction::IxGateSet { ix_gate }.data(),
+    };
+    cmd.admin.send_or_print(&client, vec![ix]).await
+}
+
+/// Only the passed parameters are changed.
+#[derive(Args, Debug, Clone)]
+pub struct GroupEdit {
+    #[clap(flatten)]
+    admin: Admin,
+
+    #[clap(long)]
+    new_admin: Option<Pubkey>,
+    #[clap(long)]
+    fast_listing_admin: Option<Pubkey>,
+    #[clap(long)]
+    security_admin: Option<Pubkey>,
+    #[clap(long)]
+    testing: Option<u8>,
+    #[clap(long)]
+    version: Option<u8>,
+    #[clap(long)]
+    deposit_limit_quote: Option<u64>,
+    #[clap(long)]
+    buyback_fees: Option<bool>,
+    #[clap(long)]
+    buyback_fees_bonus_factor: Option<f32>,
+    #[clap(long)]
+    buyback_fees_swap_mango_account: Option<Pubkey>,
+    #[clap(long)]
+    mngo_token_index: Option<TokenIndex>,
+    #[clap(long)]
+    buyback_fees_expiry_interval: Option<u64>,
+}
+
+pub async fn group_edit(cmd: GroupEdit) -> anyhow::Result<()> {
+    let client = cmd.admin.client()?;
+    let ix = Instruction {
+        program_id: mango_v4::id(),
+        accounts: mango_v4::accounts::GroupEdit {
+            group: cmd.admin.group(),
+            admin: cmd.admin.admin(),
+        }
+        .to_account_metas(None),
+        data: mango_v4::instruction::GroupEdit {
+            admin_opt: cmd.new_admin,
+            fast_listing_admin_opt: cmd.fast_listing_admin,
+            security_admin_opt: cmd.security_admin,
+            testing_opt: cmd.testing,
+            version_opt: cmd.version,
+            deposit_limit_quote_opt: cmd.deposit_limit_quote,
+            buyback_fees_opt: cmd.buyback_fees,
+            buyback_fees_bonus_factor_opt: cmd.buyback_fees_bonus_factor,
+            buyback_fees_swap_mango_account_opt: cmd.buyback_fees_swap_mango_account,
+            mngo_token_index_opt: cmd.mngo_token_index,
+            buyback_fees_expiry_interval_opt: cmd.buyback_fees_expiry_interval,
+        }
+        .data(),
+    };
+    cmd.admin.send_or_print(&client, vec![ix]).await
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct AltExtend {
+    #[clap(flatten)]
+    admin: Admin,
+
+    /// index of the lookup table in the group
+    #[clap(long)]
+    index: u8,
+
+    #[clap(long, value_delimiter = ',', required = true)]
+    addresses: Vec<Pubkey>,
+}
+
+pub async fn alt_extend(cmd: AltExtend) -> anyhow::Result<()> {
+    let client = cmd.admin.client()?;
+    let group = cmd.admin.group();
+    let group_data: Group = client.rpc_anchor_account(&group).await?;
+    let address_lookup_table = *group_data
+        .address_lookup_tables
+        .get(cmd.index as usize)
+        .ok_or_else(|| anyhow::anyhow!("lookup table index {} is out of range", cmd.index))?;
+    if address_lookup_table == Pubkey::default() {
+        anyhow::bail!("group has no lookup table at index {}", cmd.index);
+    }
+
+    let ix = Instruction {
+        program_id: mango_v4::id(),
+        accounts: mango_v4::accounts::AltExtend {
+            group,
+            admin: cmd.admin.admin(),
+            payer: cmd.admin.payer(&client),
+            address_lookup_table,
+        }
+        .to_account_metas(None),
+        data: mango_v4::instruction::AltExtend {
+            index: cmd.index,
+            new_addresses: cmd.addresses.clone(),
+        }
+        .data(),
+    };
+    cmd.admin.send_or_print(&client, vec![ix]).await
+}
@@ -0,0 +1,824 @@
+//! Read-only subcommands that print the state of accounts, banks and perp markets,
+//! and how large a trade an account can make while keeping a health ratio.
+//!
+//! The output is human readable by default, or json with --json.
+
+use clap::{Args, Subcommand};
+use fixed::types::I80F48;
+use mango_v4::accounts_zerocopy::KeyedAccountSharedData;
+use mango_v4::health::HealthType;
+use mango_v4::state::{
+    fixed_price_lots, oracle_pegged_price_offset, Bank, MangoAccountValue, PerpMarket,
+    PerpMarketIndex, Serum3MarketIndex, Side, SideAndOrderTree, TokenIndex, FREE_ORDER_SLOT,
+    QUOTE_NATIVE_TO_UI,
+};
+use mango_v4_client::{
+    account_fetcher_fetch_anchor_account, account_fetcher_fetch_mango_account, health_cache,
+    max_size, pubkey_from_cli, AccountFetcher, MangoGroupContext, RpcAccountFetcher,
+};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+
+use crate::Rpc;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum AccountCommand {
+    /// Print positions, open orders and health of a mango account
+    Show(AccountShow),
+    /// How much of a token can be swapped for another while keeping a health ratio
+    MaxSwap(AccountMaxSwap),
+    /// How much of a token can be withdrawn, borrowing if needed, while keeping a health ratio
+    MaxBorrow(AccountMaxBorrow),
+    /// How much can be bought or sold on a perp market while keeping a health ratio
+    MaxPerp(AccountMaxPerp),
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum BankCommand {
+    /// Print deposits, borrows, interest rates and prices of a token's bank
+    Show(BankShow),
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum PerpCommand {
+    /// Print open interest, funding and prices of a perp market
+    Show(PerpShow),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct AccountShow {
+    #[clap(short, long)]
+    account: String,
+
+    /// print json instead of text
+    #[clap(long)]
+    json: bool,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct AccountMaxSwap {
+    #[clap(short, long)]
+    account: String,
+
+    /// token name or token index
+    #[clap(long)]
+    source: String,
+
+    /// token name or token index
+    #[clap(long)]
+    target: String,
+
+    /// ui amount of target received per ui source, defaults to the oracle prices
+    #[clap(long)]
+    price: Option<f64>,
+
+    /// init health ratio in percent
+    #[clap(long, default_value = "0")]
+    min_health_ratio: f64,
+
+    /// print json instead of text
+    #[clap(long)]
+    json: bool,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct AccountMaxBorrow {
+    #[clap(short, long)]
+    account: String,
+
+    /// token name or token index
+    #[clap(short, long)]
+    token: String,
+
+    /// init health ratio in percent
+    #[clap(long, default_value = "0")]
+    min_health_ratio: f64,
+
+    /// print json instead of text
+    #[clap(long)]
+    json: bool,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct AccountMaxPerp {
+    #[clap(short, long)]
+    account: String,
+
+    /// perp market name or perp market index
+    #[clap(short, long)]
+    market: String,
+
+    /// bid or ask
+    #[clap(short, long)]
+    side: String,
+
+    /// ui price in the settle token, defaults to the oracle price
+    #[clap(long)]
+    price: Option<f64>,
+
+    /// init health ratio in percent
+    #[clap(long, default_value = "0")]
+    min_health_ratio: f64,
+
+    /// print json instead of text
+    #[clap(long)]
+    json: bool,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct BankShow {
+    #[clap(short, long)]
+    group: String,
+
+    /// token name or token index
+    #[clap(short, long)]
+    token: String,
+
+    /// print json instead of text
+    #[clap(long)]
+    json: bool,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PerpShow {
+    #[clap(short, long)]
+    group: String,
+
+    /// perp market name or perp market index
+    #[clap(short, long)]
+    market: String,
+
+    /// print json instead of text
+    #[clap(long)]
+    json: bool,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+#[derive(Serialize)]
+struct TokenPositionReport {
+    token_index: TokenIndex,
+    name: String,
+    balance: f64,
+    value: f64,
+}
+
+#[derive(Serialize)]
+struct Serum3OrdersReport {
+    market_index: Serum3MarketIndex,
+    name: String,
+    open_orders: String,
+}
+
+#[derive(Serialize)]
+struct PerpPositionReport {
+    market_index: PerpMarketIndex,
+    name: String,
+    base_position: f64,
+    quote_position: f64,
+    bids: f64,
+    asks: f64,
+    unsettled_pnl: f64,
+    settle_limit_min: f64,
+    settle_limit_max: f64,
+}
+
+#[derive(Serialize)]
+struct PerpOrderReport {
+    market_index: PerpMarketIndex,
+    name: String,
+    side: String,
+    price: String,
+    order_id: String,
+    client_order_id: u64,
+}
+
+#[derive(Serialize)]
+struct AccountReport {
+    address: String,
+    name: String,
+    owner: String,
+    delegate: String,
+    being_liquidated: bool,
+    init_health: f64,
+    maint_health: f64,
+    init_health_ratio: f64,
+    maint_health_ratio: f64,
+    tokens: Vec<TokenPositionReport>,
+    serum3: Vec<Serum3OrdersReport>,
+    perps: Vec<PerpPositionReport>,
+    perp_orders: Vec<PerpOrderReport>,
+}
+
+#[derive(Serialize)]
+struct BankReport {
+    token_index: TokenIndex,
+    name: String,
+    mint: String,
+    oracle: String,
+    oracle_price: f64,
+    stable_price: f64,
+    deposits: f64,
+    borrows: f64,
+    utilization: f64,
+    avg_utilization: f64,
+    deposit_rate: f64,
+    borrow_rate: f64,
+    loan_fee_rate: f64,
+    util0: f64,
+    rate0: f64,
+    util1: f64,
+    rate1: f64,
+    max_rate: f64,
+    adjusted_rate0: f64,
+    adjusted_rate1: f64,
+    adjusted_max_rate: f64,
+    maint_asset_weight: f64,
+    init_asset_weight: f64,
+    maint_liab_weight: f64,
+    init_liab_weight: f64,
+}
+
+#[derive(Serialize)]
+struct PerpMarketReport {
+    market_index: PerpMarketIndex,
+    name: String,
+    address: String,
+    oracle: String,
+    oracle_price: f64,
+    stable_price: f64,
+    settle_token_index: TokenIndex,
+    base_lot_size: i64,
+    quote_lot_size: i64,
+    open_interest: f64,
+    long_funding: f64,
+    short_funding: f64,
+    funding_model: String,
+    fees_accrued: f64,
+    maint_base_asset_weight: f64,
+    init_base_asset_weight: f64,
+    /// None for perpetual markets
+    expiry_timestamp: Option<u64>,
+    /// Set once an expired dated market has fixed its settlement price
+    settlement_price: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct MaxSwapReport {
+    source: String,
+    target: String,
+    min_health_ratio: f64,
+    max_source: f64,
+    max_source_native: String,
+}
+
+#[derive(Serialize)]
+struct MaxBorrowReport {
+    token: String,
+    min_health_ratio: f64,
+    max_borrow: f64,
+    max_borrow_native: String,
+}
+
+#[derive(Serialize)]
+struct MaxPerpReport {
+    market: String,
+    side: String,
+    min_health_ratio: f64,
+    /// None if trading at the price only increases health
+    max_base_lots: Option<i64>,
+    max_base: Option<f64>,
+}
+
+fn to_f64(value: I80F48) -> f64 {
+    value.to_num()
+}
+
+/// Converts a native/native price to a ui price for a token with `decimals` decimals
+fn ui_price(price: I80F48, decimals: u8) -> f64 {
+    to_f64(price * I80F48::from_num(10u64.pow(decimals.into())) * QUOTE_NATIVE_TO_UI)
+}
+
+fn ui_quote(native: I80F48) -> f64 {
+    to_f64(native * QUOTE_NATIVE_TO_UI)
+}
+
+fn print(report: &impl Serialize, json: bool, text: impl FnOnce()) -> anyhow::Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(report)?);
+    } else {
+        text();
+    }
+    Ok(())
+}
+
+async fn fetch_oracle_account(
+    fetcher: &impl AccountFetcher,
+    oracle: &Pubkey,
+) -> anyhow::Result<KeyedAccountSharedData> {
+    Ok(KeyedAccountSharedData::new(
+        *oracle,
+        fetcher.fetch_raw_account(oracle).await?,
+    ))
+}
+
+fn parse_token_index(context: &MangoGroupContext, token: &str) -> anyhow::Result<TokenIndex> {
+    if let Some(token_index) = context.token_indexes_by_name.get(token) {
+        return Ok(*token_index);
+    }
+    let token_index = token
+        .parse::<TokenIndex>()
+        .map_err(|_| anyhow::anyhow!("no token with name or index {}", token))?;
+    if !context.tokens.contains_key(&token_index) {
+        anyhow::bail!("no token with index {}", token_index);
+    }
+    Ok(token_index)
+}
+
+fn parse_perp_market_index(
+    context: &MangoGroupContext,
+    market: &str,
+) -> anyhow::Result<PerpMarketIndex> {
+    if let Some(market_index) = context.perp_market_indexes_by_name.get(market) {
+        return Ok(*market_index);
+    }
+    let market_index = market
+        .parse::<PerpMarketIndex>()
+        .map_err(|_| anyhow::anyhow!("no perp market with name or index {}", market))?;
+    if !context.perp_markets.contains_key(&market_index) {
+        anyhow::bail!("no perp market with index {}", market_index);
+    }
+    Ok(market_index)
+}
+
+fn parse_side(side: &str) -> anyhow::Result<Side> {
+    match side.to_lowercase().as_str() {
+        "bid" | "buy" => Ok(Side::Bid),
+        "ask" | "sell" => Ok(Side::Ask),
+        _ => anyhow::bail!("side must be bid or ask, got {}", side),
+    }
+}
+
+async fn load_account(
+    rpc: &Rpc,
+    account: &str,
+) -> anyhow::Result<(RpcAccountFetcher, MangoAccountValue, MangoGroupContext)> {
+    let client = rpc.client_with_fee_payer(Keypair::new())?;
+    let fetcher = RpcAccountFetcher {
+        rpc: client.rpc_async(),
+    };
+    let account = account_fetcher_fetch_mango_account(&fetcher, &pubkey_from_cli(account)).await?;
+    let context = MangoGroupContext::new_from_rpc(&client.rpc_async(), account.fixed.group).await?;
+    Ok((fetcher, account, context))
+}
+
+fn perp_order_price(market: &PerpMarket, side_and_tree: SideAndOrderTree, id: u128) -> String {
+    let price_data = (id >> 64) as u64;
+    match side_and_tree {
+        SideAndOrderTree::BidFixed | SideAndOrderTree::AskFixed => {
+            let price = market.lot_to_native_price(fixed_price_lots(price_data));
+            format!("{}", ui_price(price, market.base_decimals))
+        }
+        SideAndOrderTree::BidOraclePegged | SideAndOrderTree::AskOraclePegged => {
+            let offset = market.lot_to_native_price(oracle_pegged_price_offset(price_data));
+            format!("oracle{:+}", ui_price(offset, market.base_decimals))
+        }
+    }
+}
+
+pub async fn account_show(cmd: AccountShow) -> anyhow::Result<()> {
+    let client = cmd.rpc.client_with_fee_payer(Keypair::new())?;
+    let address = pubkey_from_cli(&cmd.account);
+    let fetcher = RpcAccountFetcher {
+        rpc: client.rpc_async(),
+    };
+    let account = account_fetcher_fetch_mango_account(&fetcher, &address).await?;
+    let context = MangoGroupContext::new_from_rpc(&client.rpc_async(), account.fixed.group).await?;
+    let health_cache = health_cache::new(&context, &fetcher, &account).await?;
+
+    let mut tokens = vec![];
+    for position in account.active_token_positions() {
+        let token = context.token(position.token_index);
+        let info = health_cache.token_info(position.token_index)?;
+        tokens.push(TokenPositionReport {
+            token_index: position.token_index,
+            name: token.name.clone(),
+            balance: token.native_to_ui(info.balance_native),
+            value: ui_quote(info.balance_native * info.prices.oracle),
+        });
+    }
+
+    let serum3 = account
+        .active_serum3_orders()
+        .map(|orders| Serum3OrdersReport {
+            market_index: orders.market_index,
+            name: context
+                .serum3_markets
+                .get(&orders.market_index)
+                .map(|m| m.market.name().to_string())
+                .unwrap_or_default(),
+            open_orders: orders.open_orders.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut perps = vec![];
+    for position in account.active_perp_positions() {
+        let market = &context.perp(position.market_index).market;
+        let base_lot_size = I80F48::from(market.base_lot_size);
+        let lots_to_ui = |lots: i64| {
+            to_f64(I80F48::from(lots) * base_lot_size) / 10f64.powi(market.base_decimals.into())
+        };
+        // quote amounts are in the market's settle token
+        let settle_token = context.token(market.settle_token_index);
+        let price = health_cache.perp_info(position.market_index)?.prices.oracle;
+        let (settle_limit_min, settle_limit_max) = position.available_settle_limit(market);
+        perps.push(PerpPositionReport {
+            market_index: position.market_index,
+            name: market.name().to_string(),
+            base_position: lots_to_ui(position.base_position_lots()),
+            quote_position: settle_token.native_to_ui(position.quote_position_native()),
+            bids: lots_to_ui(position.bids_base_lots),
+            asks: lots_to_ui(position.asks_base_lots),
+            unsettled_pnl: settle_token.native_to_ui(position.unsettled_pnl(market, price)?),
+            settle_limit_min: settle_token.native_to_ui(I80F48::from(settle_limit_min)),
+            settle_limit_max: settle_token.native_to_ui(I80F48::from(settle_limit_max)),
+        });
+    }
+
+    let perp_orders = account
+        .all_perp_orders()
+        .filter(|order| order.market != FREE_ORDER_SLOT)
+        .map(|order| {
+            let market = &context.perp(order.market).market;
+            let side_and_tree = order.side_and_tree();
+            PerpOrderReport {
+                market_index: order.market,
+                name: market.name().to_string(),
+                side: format!("{:?}", side_and_tree.side()),
+                price: perp_order_price(market, side_and_tree, order.id),
+                order_id: order.id.to_string(),
+                client_order_id: order.client_id,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let report = AccountReport {
+        address: address.to_string(),
+        name: account.fixed.name().to_string(),
+        owner: account.fixed.owner.to_string(),
+        delegate: account.fixed.delegate.to_string(),
+        being_liquidated: account.fixed.being_liquidated(),
+        init_health: ui_quote(health_cache.health(HealthType::Init)),
+        maint_health: ui_quote(health_cache.health(HealthType::Maint)),
+        init_health_ratio: to_f64(health_cache.health_ratio(HealthType::Init)),
+        maint_health_ratio: to_f64(health_cache.health_ratio(HealthType::Maint)),
+        tokens,
+        serum3,
+        perps,
+        perp_orders,
+    };
+
+    print(&report, cmd.json, || {
+        println!("account {} \"{}\"", report.address, report.name);
+        println!("owner {}, delegate {}", report.owner, report.delegate);
+        if report.being_liquidated {
+            println!("being liquidated");
+        }
+        println!(
+            "health: init {:.6} ({:.2}%), maint {:.6} ({:.2}%)",
+            report.init_health,
+            report.init_health_ratio,
+            report.maint_health,
+            report.maint_health_ratio
+        );
+        println!("tokens:");
+        for t in report.tokens.iter() {
+            println!(
+                "  {:>3} {:<10} {:>20.9} value {:.6}",
+                t.token_index, t.name, t.balance, t.value
+            );
+        }
+        if !report.serum3.is_empty() {
+            println!("serum3 open orders:");
+            for s in report.serum3.iter() {
+                println!("  {:>3} {:<10} {}", s.market_index, s.name, s.open_orders);
+            }
+        }
+        if !report.perps.is_empty() {
+            println!("perp positions:");
+            for p in report.perps.iter() {
+                println!(
+                    "  {:>3} {:<10} base {} quote {:.6} bids {} asks {} unsettled pnl {:.6} settle limit [{:.6}, {:.6}]",
+                    p.market_index,
+                    p.name,
+                    p.base_position,
+                    p.quote_position,
+                    p.bids,
+                    p.asks,
+                    p.unsettled_pnl,
+                    p.settle_limit_min,
+                    p.settle_limit_max
+                );
+            }
+        }
+        if !report.perp_orders.is_empty() {
+            println!("perp orders:");
+            for o in report.perp_orders.iter() {
+                println!(
+                    "  {:>3} {:<10} {} {} id {} client id {}",
+                    o.market_index, o.name, o.side, o.price, o.order_id, o.client_order_id
+                );
+            }
+        }
+    })
+}
+
+pub async fn bank_show(cmd: BankShow) -> anyhow::Result<()> {
+    let client = cmd.rpc.client_with_fee_payer(Keypair::new())?;
+    let group = pubkey_from_cli(&cmd.group);
+    let fetcher = RpcAccountFetcher {
+        rpc: client.rpc_async(),
+    };
+    let context = MangoGroupContext::new_from_rpc(&client.rpc_async(), group).await?;
+    let token_index = parse_token_index(&context, &cmd.token)?;
+    let token = context.token(token_index);
+    let bank: Bank =
+        account_fetcher_fetch_anchor_account(&fetcher, &token.mint_info.first_bank()).await?;
+    let oracle = fetch_oracle_account(&fetcher, &bank.oracle).await?;
+    let oracle_price = bank.oracle_price(&oracle, None)?;
+
+    let deposits = bank.native_deposits();
+    let borrows = bank.native_borrows();
+    let utilization = if deposits > 0 {
+        borrows / deposits
+    } else {
+        I80F48::ZERO
+    };
+    let borrow_rate = bank.compute_interest_rate(utilization);
+    let deposit_rate = borrow_rate * utilization;
+    let (adjusted_rate0, adjusted_rate1, adjusted_max_rate) = bank.compute_rates();
+
+    let report = BankReport {
+        token_index,
+        name: bank.name().to_string(),
+        mint: bank.mint.to_string(),
+        oracle: bank.oracle.to_string(),
+        oracle_price: ui_price(oracle_price, bank.mint_decimals),
+        stable_price: ui_price(bank.stable_price(), bank.mint_decimals),
+        deposits: token.native_to_ui(deposits),
+        borrows: token.native_to_ui(borrows),
+        utilization: to_f64(utilization),
+        avg_utilization: to_f64(bank.avg_utilization),
+        deposit_rate: to_f64(deposit_rate),
+        borrow_rate: to_f64(borrow_rate),
+        loan_fee_rate: to_f64(bank.loan_fee_rate),
+        util0: to_f64(bank.util0),
+        rate0: to_f64(bank.rate0),
+        util1: to_f64(bank.util1),
+        rate1: to_f64(bank.rate1),
+        max_rate: to_f64(bank.max_rate),
+        adjusted_rate0: to_f64(adjusted_rate0),
+        adjusted_rate1: to_f64(adjusted_rate1),
+        adjusted_max_rate: to_f64(adjusted_max_rate),
+        maint_asset_weight: to_f64(bank.maint_asset_weight),
+        init_asset_weight: to_f64(bank.init_asset_weight),
+        maint_liab_weight: to_f64(bank.maint_liab_weight),
+        init_liab_weight: to_f64(bank.init_liab_weight),
+    };
+
+    print(&report, cmd.json, || {
+        println!(
+            "token {} \"{}\", mint {}",
+            report.token_index, report.name, report.mint
+        );
+        println!(
+            "price: oracle {} ({}), stable {}",
+            report.oracle_price, report.oracle, report.stable_price
+        );
+        println!(
+            "deposits {}, borrows {}, utilization {:.4} (avg {:.4})",
+            report.deposits, report.borrows, report.utilization, report.avg_utilization
+        );
+        println!(
+            "rates: deposit {:.4}, borrow {:.4} + loan fee {:.4}",
+            report.deposit_rate, report.borrow_rate, report.loan_fee_rate
+        );
+        println!(
+            "curve: util0 {:.4} rate0 {:.4}, util1 {:.4} rate1 {:.4}, max rate {:.4}",
+            report.util0, report.rate0, report.util1, report.rate1, report.max_rate
+        );
+        println!(
+            "next adjusted curve: rate0 {:.4}, rate1 {:.4}, max rate {:.4}",
+            report.adjusted_rate0, report.adjusted_rate1, report.adjusted_max_rate
+        );
+        println!(
+            "weights: asset maint {:.4} init {:.4}, liab maint {:.4} init {:.4}",
+            report.maint_asset_weight,
+            report.init_asset_weight,
+            report.maint_liab_weight,
+            report.init_liab_weight
+        );
+    })
+}
+
+pub async fn perp_show(cmd: PerpShow) -> anyhow::Result<()> {
+    let client = cmd.rpc.client_with_fee_payer(Keypair::new())?;
+    let group = pubkey_from_cli(&cmd.group);
+    let fetcher = RpcAccountFetcher {
+        rpc: client.rpc_async(),
+    };
+    let context = MangoGroupContext::new_from_rpc(&client.rpc_async(), group).await?;
+    let market_index = parse_perp_market_index(&context, &cmd.market)?;
+    let address = context.perp_market_address(market_index);
+    let market: PerpMarket = account_fetcher_fetch_anchor_account(&fetcher, &address).await?;
+    let oracle = fetch_oracle_account(&fetcher, &market.oracle).await?;
+    let oracle_price = market.oracle_price(&oracle, None)?;
+
+    let base_lots_to_ui = |lots: i64| {
+        to_f64(I80F48::from(lots) * I80F48::from(market.base_lot_size))
+            / 10f64.powi(market.base_decimals.into())
+    };
+
+    let report = PerpMarketReport {
+        market_index,
+        name: market.name().to_string(),
+        address: address.to_string(),
+        oracle: market.oracle.to_string(),
+        oracle_price: ui_price(oracle_price, market.base_decimals),
+        stable_price: ui_price(market.stable_price(), market.base_decimals),
+        settle_token_index: market.settle_token_index,
+        base_lot_size: market.base_lot_size,
+        quote_lot_size: market.quote_lot_size,
+        open_interest: base_lots_to_ui(market.open_interest),
+        long_funding: to_f64(market.long_funding),
+        short_funding: to_f64(market.short_funding),
+        funding_model: format!("{:?}", market.funding_model()),
+        fees_accrued: ui_quote(market.fees_accrued),
+        maint_base_asset_weight: to_f64(market.maint_base_asset_weight),
+        init_base_asset_weight: to_f64(market.init_base_asset_weight),
+        expiry_timestamp: market.is_dated().then_some(market.expiry_timestamp),
+        settlement_price: (market.has_settlement_price == 1)
+            .then(|| ui_price(market.settlement_price, market.base_decimals)),
+    };
+
+    print(&report, cmd.json, || {
+        println!(
+            "perp market {} \"{}\" {}",
+            report.market_index, report.name, report.address
+        );
+        println!(
+            "price: oracle {} ({}), stable {}",
+            report.oracle_price, report.oracle, report.stable_price
+        );
+        println!(
+            "lot sizes: base {}, quote {}, settle token {}",
+            report.base_lot_size, report.quote_lot_size, report.settle_token_index
+        );
+        println!(
+            "open interest {}, funding long {} short {} ({}), fees accrued {:.6}",
+            report.open_interest,
+            report.long_funding,
+            report.short_funding,
+            report.funding_model,
+            report.fees_accrued
+        );
+        println!(
+            "base asset weights: maint {:.4} init {:.4}",
+            report.maint_base_asset_weight, report.init_base_asset_weight
+        );
+        if let Some(expiry_timestamp) = report.expiry_timestamp {
+            match report.settlement_price {
+                Some(price) => println!(
+                    "dated market, expired at {}, settlement price {}",
+                    expiry_timestamp, price
+                ),
+                None => println!("dated market, expires at {}", expiry_timestamp),
+            }
+        }
+    })
+}
+
+pub async fn account_max_swap(cmd: AccountMaxSwap) -> anyhow::Result<()> {
+    let (fetcher, account, context) = load_account(&cmd.rpc, &cmd.account).await?;
+    let source = context.token(parse_token_index(&context, &cmd.source)?);
+    let target = context.token(parse_token_index(&context, &cmd.target)?);
+    let price = cmd.price.map(|price| {
+        I80F48::from_num(price) * I80F48::from_num(10f64.powi(target.decimals.into()))
+            / I80F48::from_num(10f64.powi(source.decimals.into()))
+    });
+
+    let max_source = max_size::max_swap_source(
+        &context,
+        &fetcher,
+        &account,
+        source.token_index,
+        target.token_index,
+        price,
+        I80F48::from_num(cmd.min_health_ratio),
+    )
+    .await?;
+
+    let report = MaxSwapReport {
+        source: source.name.clone(),
+        target: target.name.clone(),
+        min_health_ratio: cmd.min_health_ratio,
+        max_source: source.native_to_ui(max_source),
+        max_source_native: max_source.floor().to_string(),
+    };
+    print(&report, cmd.json, || {
+        println!(
+            "max swap {} -> {} keeping health ratio {}%: {} {}",
+            report.source, report.target, report.min_health_ratio, report.max_source, report.source
+        );
+    })
+}
+
+pub async fn account_max_borrow(cmd: AccountMaxBorrow) -> anyhow::Result<()> {
+    let (fetcher, account, context) = load_account(&cmd.rpc, &cmd.account).await?;
+    let token = context.token(parse_token_index(&context, &cmd.token)?);
+
+    let max_borrow = max_size::max_borrow(
+        &context,
+        &fetcher,
+        &account,
+        token.token_index,
+        I80F48::from_num(cmd.min_health_ratio),
+    )
+    .await?;
+
+    let report = MaxBorrowReport {
+        token: token.name.clone(),
+        min_health_ratio: cmd.min_health_ratio,
+        max_borrow: token.native_to_ui(max_borrow),
+        max_borrow_native: max_borrow.floor().to_string(),
+    };
+    print(&report, cmd.json, || {
+        println!(
+            "max withdraw of {} keeping health ratio {}%: {}",
+            report.token, report.min_health_ratio, report.max_borrow
+        );
+    })
+}
+
+pub async fn account_max_perp(cmd: AccountMaxPerp) -> anyhow::Result<()> {
+    let (fetcher, account, context) = load_account(&cmd.rpc, &cmd.account).await?;
+    let market_index = parse_perp_market_index(&context, &cmd.market)?;
+    let market = &context.perp(market_index).market;
+    let side = parse_side(&cmd.side)?;
+    let settle_decimals = context.token(market.settle_token_index).decimals;
+    let price = cmd.price.map(|price| {
+        I80F48::from_num(price) * I80F48::from_num(10f64.powi(settle_decimals.into()))
+            / I80F48::from_num(10f64.powi(market.base_decimals.into()))
+    });
+
+    let max_base_lots = max_size::max_perp(
+        &context,
+        &fetcher,
+        &account,
+        market_index,
+        side,
+        price,
+        I80F48::from_num(cmd.min_health_ratio),
+    )
+    .await?;
+    let max_base_lots = (max_base_lots != i64::MAX).then(|| max_base_lots);
+
+    let report = MaxPerpReport {
+        market: market.name().to_string(),
+        side: format!("{:?}", side),
+        min_health_ratio: cmd.min_health_ratio,
+        max_base_lots,
+        max_base: max_base_lots.map(|lots| {
+            (lots as f64) * (market.base_lot_size as f64) / 10f64.powi(market.base_decimals.into())
+        }),
+    };
+    print(&report, cmd.json, || {
+        match (report.max_base_lots, report.max_base) {
+            (Some(lots), Some(base)) => println!(
+                "max {:?} on {} keeping health ratio {}%: {} ({} lots)",
+                side, report.market, report.min_health_ratio, base, lots
+            ),
+            _ => println!(
+                "{:?} on {} at this price only increases health, no limit",
+                side, report.market
+            ),
+        }
+    })
+}
@@ -4,9 +4,13 @@ use mango_v4_client::{
     TransactionBuilderConfig,
 };
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
 use std::str::FromStr;
 use std::sync::Arc;
 
+mod admin;
+mod inspect;
+
 #[derive(Parser, Debug, Clone)]
 #[clap()]
 struct Cli {
@@ -61,6 +65,33 @@ struct Deposit {
     rpc: Rpc,
 }
 
+#[derive(Args, Debug, Clone)]
+struct Transfer {
+    #[clap(long)]
+    account: String,
+
+    /// must be owned by the same owner as account
+    #[clap(long)]
+    destination_account: String,
+
+    /// also pays for everything
+    #[clap(short, long)]
+    owner: String,
+
+    #[clap(short, long)]
+    mint: String,
+
+    #[clap(short, long)]
+    amount: u64,
+
+    /// allow the transfer to create a borrow on the source account
+    #[clap(long)]
+    allow_borrow: bool,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
 #[derive(Args, Debug, Clone)]
 struct JupiterSwap {
     #[clap(long)]
@@ -90,6 +121,7 @@ struct JupiterSwap {
 enum Command {
     CreateAccount(CreateAccount),
     Deposit(Deposit),
+    Transfer(Transfer),
     JupiterSwap(JupiterSwap),
     GroupAddress {
         #[clap(short, long)]
@@ -108,11 +140,29 @@ enum Command {
         #[clap(short, long, default_value = "0")]
         num: u32,
     },
+    TokenRegister(admin::TokenRegister),
+    TokenEdit(admin::TokenEdit),
+    PerpCreateMarket(admin::PerpCreateMarket),
+    PerpEditMarket(admin::PerpEditMarket),
+    Serum3RegisterMarket(admin::Serum3RegisterMarket),
+    IxGateSet(admin::IxGateSet),
+    GroupEdit(admin::GroupEdit),
+    AltExtend(admin::AltExtend),
+    #[clap(subcommand)]
+    Account(inspect::AccountCommand),
+    #[clap(subcommand)]
+    Bank(inspect::BankCommand),
+    #[clap(subcommand)]
+    Perp(inspect::PerpCommand),
 }
 
 impl Rpc {
     fn client(&self, override_fee_payer: Option<&str>) -> anyhow::Result<Client> {
         let fee_payer = keypair_from_cli(override_fee_payer.unwrap_or(&self.fee_payer));
+        self.client_with_fee_payer(fee_payer)
+    }
+
+    fn client_with_fee_payer(&self, fee_payer: Keypair) -> anyhow::Result<Client> {
         Ok(Client::new(
             anchor_client::Cluster::from_str(&self.url)?,
             solana_sdk::commitment_config::CommitmentConfig::confirmed(),
@@ -171,6 +221,18 @@ async fn main() -> Result<(), anyhow::Error> {
             let txsig = client.token_deposit(mint, cmd.amount, false).await?;
             println!("{}", txsig);
         }
+        Command::Transfer(cmd) => {
+            let client = cmd.rpc.client(Some(&cmd.owner))?;
+            let account = pubkey_from_cli(&cmd.account);
+            let destination_account = pubkey_from_cli(&cmd.destination_account);
+            let owner = Arc::new(keypair_from_cli(&cmd.owner));
+            let mint = pubkey_from_cli(&cmd.mint);
+            let client = MangoClient::new_for_existing_account(client, account, owner).await?;
+            let txsig = client
+                .token_transfer(mint, cmd.amount, cmd.allow_borrow, destination_account)
+                .await?;
+            println!("{}", txsig);
+        }
         Command::JupiterSwap(cmd) => {
             let client = cmd.rpc.client(Some(&cmd.owner))?;
             let account = pubkey_from_cli(&cmd.account);
@@ -208,6 +270,26 @@ async fn main() -> Result<(), anyhow::Error> {
             .0;
             println!("{}", address);
         }
+        Command::TokenRegister(cmd) => admin::token_register(cmd).await?,
+        Command::TokenEdit(cmd) => admin::token_edit(cmd).await?,
+        Command::PerpCreateMarket(cmd) => admin::perp_create_market(cmd).await?,
+        Command::PerpEditMarket(cmd) => admin::perp_edit_market(cmd).await?,
+        Command::Serum3RegisterMarket(cmd) => admin::serum3_register_market(cmd).await?,
+        Command::IxGateSet(cmd) => admin::ix_gate_set(cmd).await?,
+        Command::GroupEdit(cmd) => admin::group_edit(cmd).await?,
+        Command::AltExtend(cmd) => admin::alt_extend(cmd).await?,
+        Command::Account(inspect::AccountCommand::Show(cmd)) => inspect::account_show(cmd).await?,
+        Command::Account(inspect::AccountCommand::MaxSwap(cmd)) => {
+            inspect::account_max_swap(cmd).await?
+        }
+        Command::Account(inspect::AccountCommand::MaxBorrow(cmd)) => {
+            inspect::account_max_borrow(cmd).await?
+        }
+        Command::Account(inspect::AccountCommand::MaxPerp(cmd)) => {
+            inspect::account_max_perp(cmd).await?
+        }
+        Command::Bank(inspect::BankCommand::Show(cmd)) => inspect::bank_show(cmd).await?,
+        Command::Perp(inspect::PerpCommand::Show(cmd)) => inspect::perp_show(cmd).await?,
     };
 
     Ok(())
@@ -87,8 +87,8 @@ pub async fn runner(
         // TODO: grouping tokens whose oracle might have less confidencen e.g. ORCA with the rest, fails whole ix
         // TokenUpdateIndexAndRate is known to take max 71k cu
         // from cargo test-bpf local tests
-        // chunk size of 8 seems to be max before encountering "VersionedTransaction too large" issues
-        .chunks(8)
+        // with the group's address lookup tables, 16 fit into a transaction and the 1.4M cu limit
+        .chunks(16)
         .into_iter()
         .map(|chunk| {
             loop_update_index_and_rate(
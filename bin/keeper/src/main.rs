@@ -1,5 +1,6 @@
 mod crank;
 mod taker;
+mod trigger_orders;
 
 use std::sync::Arc;
 use std::time::Duration;
@@ -69,6 +70,37 @@ struct Cli {
 enum Command {
     Crank {},
     Taker {},
+    TriggerOrders {
+        #[clap(long, env)]
+        serum_program: Pubkey,
+
+        #[clap(long, env, default_value = "300")]
+        snapshot_interval_secs: u64,
+
+        /// how many getMultipleAccounts requests to send in parallel
+        #[clap(long, env, default_value = "10")]
+        parallel_rpc_requests: usize,
+
+        /// typically 100 is the max number of accounts getMultipleAccounts will retrieve at once
+        #[clap(long, env, default_value = "100")]
+        get_multiple_accounts_count: usize,
+
+        /// only execute trigger orders paying at least this incentive, in native settle token
+        #[clap(long, env, default_value = "0")]
+        min_incentive: u64,
+
+        /// seconds to wait before retrying execution of the same trigger order
+        #[clap(long, env, default_value = "10")]
+        execute_cooldown_secs: u64,
+
+        /// maximum number of book orders an execution may match, at least 4
+        #[clap(long, env, default_value = "10")]
+        match_limit: u8,
+
+        /// address lookup tables to use in addition to the group's, comma separated
+        #[clap(long, env, value_delimiter = ',')]
+        lookup_tables: Vec<Pubkey>,
+    },
 }
 
 #[tokio::main]
@@ -95,24 +127,53 @@ async fn main() -> Result<(), anyhow::Error> {
     let commitment = match cli.command {
         Command::Crank { .. } => CommitmentConfig::confirmed(),
         Command::Taker { .. } => CommitmentConfig::confirmed(),
+        Command::TriggerOrders { .. } => CommitmentConfig::processed(),
     };
 
-    let mango_client = Arc::new(
-        MangoClient::new_for_existing_account(
-            Client::new(
-                cluster,
-                commitment,
-                owner.clone(),
-                Some(Duration::from_secs(cli.timeout)),
-                TransactionBuilderConfig {
-                    prioritization_micro_lamports: (cli.prioritization_micro_lamports > 0)
-                        .then_some(cli.prioritization_micro_lamports),
-                },
-            ),
+    let client = Client::new(
+        cluster,
+        commitment,
+        owner.clone(),
+        Some(Duration::from_secs(cli.timeout)),
+        TransactionBuilderConfig {
+            prioritization_micro_lamports: (cli.prioritization_micro_lamports > 0)
+                .then_some(cli.prioritization_micro_lamports),
+        },
+    );
+
+    // The trigger order executor maintains its own account data via websocket
+    // and snapshot feeds, so it doesn't share the rpc based client below.
+    if let Command::TriggerOrders {
+        serum_program,
+        snapshot_interval_secs,
+        parallel_rpc_requests,
+        get_multiple_accounts_count,
+        min_incentive,
+        execute_cooldown_secs,
+        match_limit,
+        lookup_tables,
+    } = cli.command
+    {
+        return trigger_orders::runner(
+            client,
             cli.mango_account,
-            owner.clone(),
+            owner,
+            trigger_orders::Config {
+                serum_program,
+                snapshot_interval: Duration::from_secs(snapshot_interval_secs),
+                parallel_rpc_requests,
+                get_multiple_accounts_count,
+                min_incentive,
+                execute_cooldown: Duration::from_secs(execute_cooldown_secs),
+                match_limit,
+                lookup_tables,
+            },
         )
-        .await?,
+        .await;
+    }
+
+    let mango_client = Arc::new(
+        MangoClient::new_for_existing_account(client, cli.mango_account, owner.clone()).await?,
     );
 
     let debugging_handle = async {
@@ -146,5 +207,6 @@ async fn main() -> Result<(), anyhow::Error> {
             let client = mango_client.clone();
             taker::runner(client, debugging_handle).await
         }
+        Command::TriggerOrders { .. } => unreachable!(),
     }
 }
@@ -0,0 +1,394 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use fixed::types::I80F48;
+use itertools::Itertools;
+use log::*;
+use mango_v4::accounts_zerocopy::{KeyedAccountSharedData, LoadZeroCopy};
+use mango_v4::state::{PerpMarket, PerpMarketIndex};
+use mango_v4_client::{
+    account_update_stream, chain_data, prettify_solana_client_error, snapshot_source,
+    websocket_source, AsyncChannelSendUnlessFull, Client, MangoClient, MangoGroupContext,
+    TransactionBuilder,
+};
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::address_lookup_table_account::AddressLookupTableAccount;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+
+pub struct Config {
+    pub serum_program: Pubkey,
+    pub snapshot_interval: Duration,
+    pub parallel_rpc_requests: usize,
+    pub get_multiple_accounts_count: usize,
+
+    /// Trigger orders paying a smaller incentive (in native settle token) are ignored
+    pub min_incentive: u64,
+
+    /// Amount of time to wait before retrying the same trigger order
+    pub execute_cooldown: Duration,
+
+    /// Maximum number of book orders to match per execution
+    pub match_limit: u8,
+
+    /// Address lookup tables to use in addition to the group's
+    pub lookup_tables: Vec<Pubkey>,
+}
+
+/// Watches all MangoAccounts of the group and executes perp trigger orders whose
+/// condition is met, collecting the incentive on the keeper's mango account.
+pub async fn runner(
+    client: Client,
+    mango_account: Pubkey,
+    owner: Arc<Keypair>,
+    config: Config,
+) -> anyhow::Result<()> {
+    let rpc_url = client.cluster.url().to_string();
+    let ws_url = client.cluster.ws_url().to_string();
+
+    // The representation of current on-chain account data
+    let chain_data = Arc::new(RwLock::new(chain_data::ChainData::new()));
+    // Reading accounts from chain_data
+    let account_fetcher = Arc::new(chain_data::AccountFetcher {
+        chain_data: chain_data.clone(),
+        rpc: client.rpc_async(),
+    });
+
+    let mango_group = account_fetcher
+        .fetch_fresh_mango_account(&mango_account)
+        .await?
+        .fixed
+        .group;
+    let group_context = MangoGroupContext::new_from_rpc(&client.rpc_async(), mango_group).await?;
+
+    let perp_oracles = group_context
+        .perp_markets
+        .values()
+        .map(|p| p.market.oracle)
+        .unique()
+        .collect::<HashSet<Pubkey>>();
+    let mango_oracles = group_context
+        .tokens
+        .values()
+        .map(|value| value.mint_info.oracle)
+        .chain(perp_oracles.iter().cloned())
+        .unique()
+        .collect::<Vec<Pubkey>>();
+
+    let (account_update_sender, account_update_receiver) =
+        async_channel::unbounded::<account_update_stream::Message>();
+
+    websocket_source::start(
+        websocket_source::Config {
+            rpc_ws_url: ws_url,
+            serum_program: config.serum_program,
+            open_orders_authority: mango_group,
+        },
+        mango_oracles.clone(),
+        account_update_sender.clone(),
+    );
+
+    let first_websocket_slot = websocket_source::get_next_create_bank_slot(
+        account_update_receiver.clone(),
+        Duration::from_secs(10),
+    )
+    .await?;
+
+    snapshot_source::start(
+        snapshot_source::Config {
+            rpc_http_url: rpc_url,
+            mango_group,
+            get_multiple_accounts_count: config.get_multiple_accounts_count,
+            parallel_rpc_requests: config.parallel_rpc_requests,
+            snapshot_interval: config.snapshot_interval,
+            min_slot: first_websocket_slot + 10,
+        },
+        mango_oracles,
+        account_update_sender,
+    );
+
+    let mango_client = Arc::new(MangoClient::new_detail(
+        client,
+        mango_account,
+        owner,
+        group_context,
+        account_fetcher.clone(),
+    )?);
+    for table in config.lookup_tables.iter() {
+        mango_client.alt_cache.add_private_table(*table);
+    }
+
+    let mut state = TriggerOrderState {
+        mango_client,
+        account_fetcher,
+        min_incentive: config.min_incentive,
+        execute_cooldown: config.execute_cooldown,
+        match_limit: config.match_limit,
+        recently_executed: Default::default(),
+    };
+
+    let shared_state = Arc::new(RwLock::new(SharedState::default()));
+    let (check_sender, check_receiver) = async_channel::bounded::<()>(1);
+
+    info!("trigger order main loop");
+
+    // Job to update chain_data and notify the execution job when a new check is needed.
+    let data_job = tokio::spawn({
+        use account_update_stream::Message;
+
+        let shared_state = shared_state.clone();
+
+        async move {
+            loop {
+                let message = account_update_receiver
+                    .recv()
+                    .await
+                    .expect("channel not closed");
+
+                message.update_chain_data(&mut chain_data.write().unwrap());
+
+                match message {
+                    Message::Account(account_write) => {
+                        if snapshot_source::is_mango_account(&account_write.account, &mango_group)
+                            .is_some()
+                        {
+                            let mut state = shared_state.write().unwrap();
+                            state.mango_accounts.insert(account_write.pubkey);
+                            check_sender.send_unless_full(()).unwrap();
+                        } else if perp_oracles.contains(&account_write.pubkey)
+                            || is_perp_market(&account_write.account, &mango_group).is_some()
+                        {
+                            check_sender.send_unless_full(()).unwrap();
+                        }
+                    }
+                    Message::Snapshot(snapshot) => {
+                        let mut state = shared_state.write().unwrap();
+                        for update in snapshot.iter() {
+                            if snapshot_source::is_mango_account(&update.account, &mango_group)
+                                .is_some()
+                            {
+                                state.mango_accounts.insert(update.pubkey);
+                            }
+                        }
+                        state.one_snapshot_done = true;
+                        check_sender.send_unless_full(()).unwrap();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    });
+
+    let execute_job = tokio::spawn({
+        let shared_state = shared_state.clone();
+        async move {
+            loop {
+                check_receiver.recv().await.unwrap();
+
+                let account_addresses;
+                {
+                    let shared_state = shared_state.read().unwrap();
+                    if !shared_state.one_snapshot_done {
+                        continue;
+                    }
+                    account_addresses = shared_state.mango_accounts.iter().cloned().collect();
+                }
+
+                if let Err(err) = state.execute(account_addresses).await {
+                    error!("error executing trigger orders: {:?}", err);
+                }
+            }
+        }
+    });
+
+    use futures::StreamExt;
+    let mut jobs: futures::stream::FuturesUnordered<_> =
+        vec![data_job, execute_job].into_iter().collect();
+    jobs.next().await;
+
+    error!("a critical job aborted, exiting");
+    Ok(())
+}
+
+#[derive(Default)]
+struct SharedState {
+    /// Addresses of the MangoAccounts belonging to the group
+    mango_accounts: HashSet<Pubkey>,
+
+    /// Is the first snapshot done? Only start executing when it is.
+    one_snapshot_done: bool,
+}
+
+fn is_perp_market<'a>(account: &'a AccountSharedData, group_id: &Pubkey) -> Option<&'a PerpMarket> {
+    let perp_market = account.load::<PerpMarket>().ok()?;
+    if perp_market.group != *group_id {
+        return None;
+    }
+    Some(perp_market)
+}
+
+fn perp_market_prices(
+    mango_client: &MangoClient,
+    account_fetcher: &chain_data::AccountFetcher,
+) -> HashMap<PerpMarketIndex, I80F48> {
+    mango_client
+        .context
+        .perp_markets
+        .iter()
+        .map(|(market_index, perp)| {
+            let perp_market = account_fetcher.fetch::<PerpMarket>(&perp.address)?;
+            let oracle_acc = account_fetcher.fetch_raw(&perp_market.oracle)?;
+            let oracle_price = perp_market.oracle_price(
+                &KeyedAccountSharedData::new(perp_market.oracle, oracle_acc),
+                None,
+            )?;
+            Ok((*market_index, oracle_price))
+        })
+        .filter_map(|v: anyhow::Result<(PerpMarketIndex, I80F48)>| match v {
+            Ok(v) => Some(v),
+            Err(err) => {
+                error!("error while retriving perp market price: {:?}", err);
+                None
+            }
+        })
+        .collect()
+}
+
+struct TriggerOrderState {
+    mango_client: Arc<MangoClient>,
+    account_fetcher: Arc<chain_data::AccountFetcher>,
+    min_incentive: u64,
+    execute_cooldown: Duration,
+    match_limit: u8,
+
+    recently_executed: HashMap<(Pubkey, u64), Instant>,
+}
+
+impl TriggerOrderState {
+    async fn execute(&mut self, accounts: Vec<Pubkey>) -> anyhow::Result<()> {
+        let now = Instant::now();
+        let cooldown = self.execute_cooldown;
+        self.recently_executed
+            .retain(|_, last_execute| now.duration_since(*last_execute) < cooldown);
+
+        let now_ts: u64 = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+        let mango_client = &*self.mango_client;
+        let account_fetcher = &*self.account_fetcher;
+        let prices = perp_market_prices(mango_client, account_fetcher);
+
+        // Find all executable trigger orders: (account, trigger order id, incentive)
+        let mut executable = vec![];
+        for account_key in accounts.iter() {
+            let account = match account_fetcher.fetch_mango_account(account_key) {
+                Ok(v) => v,
+                Err(err) => {
+                    error!("error fetching mango account {}: {:?}", account_key, err);
+                    continue;
+                }
+            };
+            if account.fixed.group != mango_client.group()
+                || *account_key == mango_client.mango_account_address
+            {
+                continue;
+            }
+            for trigger_order in account.active_perp_trigger_orders() {
+                if trigger_order.incentive < self.min_incentive
+                    || trigger_order.is_expired(now_ts)
+                    || self
+                        .recently_executed
+                        .contains_key(&(*account_key, trigger_order.id))
+                {
+                    continue;
+                }
+                let price = match prices.get(&trigger_order.market) {
+                    Some(v) => *v,
+                    None => continue,
+                };
+                if trigger_order.is_triggered(price) {
+                    executable.push((*account_key, trigger_order.id, trigger_order.incentive));
+                }
+            }
+        }
+        if executable.is_empty() {
+            return Ok(());
+        }
+
+        // most lucrative first
+        executable.sort_by_key(|v| std::cmp::Reverse(v.2));
+
+        // Executions are sent in separate transactions: an execution fails when its order
+        // doesn't match anything, and that must not revert the other executions.
+        let address_lookup_tables = mango_client.mango_address_lookup_tables().await?;
+        let blockhash = mango_client
+            .client
+            .rpc_async()
+            .get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+            .await?
+            .0;
+        let executor = TriggerOrderExecutor {
+            mango_client,
+            account_fetcher,
+            match_limit: self.match_limit,
+            blockhash,
+            address_lookup_tables: &address_lookup_tables,
+        };
+
+        for (account, trigger_order_id, _) in executable {
+            if let Err(err) = executor.send(account, trigger_order_id).await {
+                error!(
+                    "error executing trigger order {} of account {}: {:?}",
+                    trigger_order_id, account, err
+                );
+            }
+            self.recently_executed
+                .insert((account, trigger_order_id), Instant::now());
+        }
+
+        Ok(())
+    }
+}
+
+struct TriggerOrderExecutor<'a> {
+    mango_client: &'a MangoClient,
+    account_fetcher: &'a chain_data::AccountFetcher,
+    match_limit: u8,
+    blockhash: solana_sdk::hash::Hash,
+    address_lookup_tables: &'a Vec<AddressLookupTableAccount>,
+}
+
+impl<'a> TriggerOrderExecutor<'a> {
+    async fn send(&self, account: Pubkey, trigger_order_id: u64) -> anyhow::Result<Signature> {
+        let account_value = self.account_fetcher.fetch_mango_account(&account)?;
+        let ix = self.mango_client.perp_execute_trigger_order_instruction(
+            (&account, &account_value),
+            trigger_order_id,
+            self.match_limit,
+        )?;
+
+        let client = &self.mango_client.client;
+        let fee_payer = &*client.fee_payer;
+        let tx = TransactionBuilder {
+            instructions: vec![ix],
+            address_lookup_tables: self.address_lookup_tables.clone(),
+            payer: fee_payer.pubkey(),
+            signers: vec![&*self.mango_client.owner, fee_payer],
+            config: client.transaction_builder_config,
+        }
+        .transaction_with_blockhash(self.blockhash)?;
+
+        let txsig = client
+            .rpc_async()
+            .send_transaction_with_config(&tx, client.rpc_send_transaction_config)
+            .await
+            .map_err(prettify_solana_client_error)?;
+        info!(
+            "sent execution of trigger order {} of account {}: {}",
+            trigger_order_id, account, txsig
+        );
+        Ok(txsig)
+    }
+}
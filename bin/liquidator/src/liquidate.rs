@@ -2,11 +2,17 @@ use std::collections::HashSet;
 use std::time::Duration;
 
 use mango_v4::accounts_zerocopy::KeyedAccountSharedData;
-use mango_v4::health::{HealthCache, HealthType};
+use mango_v4::health::{HealthCache, HealthType, PerpInfo, TokenInfo};
+use mango_v4::state::Serum3MarketIndex;
 use mango_v4::state::{
-    Bank, MangoAccountValue, PerpMarketIndex, Side, TokenIndex, QUOTE_TOKEN_INDEX,
+    Bank, MangoAccountValue, PerpMarketIndex, PlaceOrderType, SelfTradeBehavior, Side, TokenIndex,
+    QUOTE_TOKEN_INDEX,
 };
-use mango_v4_client::{chain_data, health_cache, AccountFetcher, JupiterSwapMode, MangoClient};
+use mango_v4_client::{
+    chain_data, health_cache, tx_tracker::TxTracker, AccountFetcher, JupiterSwapMode, MangoClient,
+    MangoGroupContext,
+};
+use solana_sdk::instruction::Instruction;
 use solana_sdk::signature::Signature;
 
 use futures::{stream, StreamExt, TryStreamExt};
@@ -16,6 +22,284 @@ use {anyhow::Context, fixed::types::I80F48, solana_sdk::pubkey::Pubkey};
 pub struct Config {
     pub min_health_ratio: f64,
     pub refresh_timeout: Duration,
+    /// Fetch the liqor account via rpc before sizing a liquidation.
+    ///
+    /// Disabled when running against recorded data, where there is no rpc.
+    pub refresh_liqor_account: bool,
+    /// Same-tx unwind: sell what the liqor receives in the same transaction as the
+    /// liquidation. Assets from token liquidations are sold with a jupiter swap funded
+    /// by a flash loan, base positions from perp liquidations are closed with a
+    /// reduce-only IOC order.
+    ///
+    /// The unwind comes after the liquidation instruction, whose liqor health check
+    /// runs on-chain before anything is sold. Liquidations are therefore still sized
+    /// with min_health_ratio; the unwind only avoids holding the acquired position
+    /// until the next rebalance.
+    pub flash_loan_unwind: bool,
+    pub flash_loan_slippage_bps: u64,
+}
+
+/// Swap that sells the asset received in a token liquidation for the liab token.
+#[derive(Clone, Debug)]
+pub struct UnwindSwap {
+    /// native asset tokens, funded by a flash loan
+    pub amount: u64,
+    pub slippage_bps: u64,
+}
+
+/// IOC order that closes the base position received in a perp liquidation.
+#[derive(Clone, Debug)]
+pub struct PerpUnwindOrder {
+    pub side: Side,
+    pub price_lots: i64,
+    pub max_base_lots: i64,
+}
+
+/// A liquidation step chosen by LiquidateHelper, not yet sent.
+#[derive(Clone, Debug)]
+pub enum LiquidationAction {
+    Serum3ForceCancelOrders {
+        market_index: Serum3MarketIndex,
+        open_orders: Pubkey,
+    },
+    PerpForceCancelOrders {
+        perp_market_index: PerpMarketIndex,
+    },
+    PerpLiqBaseOrPositivePnl {
+        perp_market_index: PerpMarketIndex,
+        max_base_transfer: i64,
+        max_pnl_transfer: u64,
+        unwind_order: Option<PerpUnwindOrder>,
+    },
+    PerpLiqNegativePnlOrBankruptcy {
+        perp_market_index: PerpMarketIndex,
+        max_liab_transfer: u64,
+    },
+    TokenLiqWithToken {
+        asset_token_index: TokenIndex,
+        liab_token_index: TokenIndex,
+        max_liab_transfer: I80F48,
+        unwind_swap: Option<UnwindSwap>,
+    },
+    TokenLiqBankruptcy {
+        liab_token_index: TokenIndex,
+        max_liab_transfer: I80F48,
+    },
+}
+
+impl LiquidationAction {
+    /// Identifies the action by its kind and the liquidated tokens or markets,
+    /// but not the amounts, which change with every price update.
+    pub fn tracking_key(&self) -> String {
+        match self {
+            Self::Serum3ForceCancelOrders { market_index, .. } => {
+                format!("serum3 force cancel orders {market_index}")
+            }
+            Self::PerpForceCancelOrders { perp_market_index } => {
+                format!("perp force cancel orders {perp_market_index}")
+            }
+            Self::PerpLiqBaseOrPositivePnl {
+                perp_market_index, ..
+            } => format!("perp liq base or positive pnl {perp_market_index}"),
+            Self::PerpLiqNegativePnlOrBankruptcy {
+                perp_market_index, ..
+            } => format!("perp liq negative pnl or bankruptcy {perp_market_index}"),
+            Self::TokenLiqWithToken {
+                asset_token_index,
+                liab_token_index,
+                ..
+            } => format!("token liq with token {asset_token_index} {liab_token_index}"),
+            Self::TokenLiqBankruptcy {
+                liab_token_index, ..
+            } => format!("token liq bankruptcy {liab_token_index}"),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::Serum3ForceCancelOrders { market_index, .. } => {
+                format!("Force cancelled serum orders, market index {market_index}")
+            }
+            Self::PerpForceCancelOrders { perp_market_index } => {
+                format!("Force cancelled perp orders, market index {perp_market_index}")
+            }
+            Self::PerpLiqBaseOrPositivePnl {
+                perp_market_index,
+                max_base_transfer,
+                max_pnl_transfer,
+                unwind_order: None,
+            } => format!(
+                "Liquidated base position for perp market, market index {perp_market_index}, max_base_transfer {max_base_transfer}, max_pnl_transfer {max_pnl_transfer}"
+            ),
+            Self::PerpLiqBaseOrPositivePnl {
+                perp_market_index,
+                max_base_transfer,
+                max_pnl_transfer,
+                unwind_order: Some(PerpUnwindOrder { price_lots, .. }),
+            } => format!(
+                "Liquidated base position for perp market and unwound it, market index {perp_market_index}, max_base_transfer {max_base_transfer}, max_pnl_transfer {max_pnl_transfer}, unwind price lots {price_lots}"
+            ),
+            Self::PerpLiqNegativePnlOrBankruptcy {
+                perp_market_index, ..
+            } => format!("Liquidated negative perp pnl, market index {perp_market_index}"),
+            Self::TokenLiqWithToken {
+                asset_token_index,
+                liab_token_index,
+                max_liab_transfer,
+                unwind_swap: None,
+            } => format!(
+                "Liquidated token with token, asset {asset_token_index}, liab {liab_token_index}, max_liab_transfer {max_liab_transfer}"
+            ),
+            Self::TokenLiqWithToken {
+                asset_token_index,
+                liab_token_index,
+                max_liab_transfer,
+                unwind_swap: Some(UnwindSwap { amount, .. }),
+            } => format!(
+                "Liquidated token with token and flash loan swap, asset {asset_token_index}, liab {liab_token_index}, max_liab_transfer {max_liab_transfer}, swapped asset {amount}"
+            ),
+            Self::TokenLiqBankruptcy {
+                liab_token_index,
+                max_liab_transfer,
+            } => format!(
+                "Liquidated bankruptcy, liab {liab_token_index}, max_liab_transfer {max_liab_transfer}"
+            ),
+        }
+    }
+
+    /// The liquidation instruction, without any instructions for unwinding
+    pub async fn instruction(
+        &self,
+        client: &MangoClient,
+        liqee: (&Pubkey, &MangoAccountValue),
+    ) -> anyhow::Result<Instruction> {
+        match *self {
+            Self::Serum3ForceCancelOrders {
+                market_index,
+                open_orders,
+            } => {
+                client.serum3_liq_force_cancel_orders_instruction(liqee, market_index, &open_orders)
+            }
+            Self::PerpForceCancelOrders { perp_market_index } => {
+                client.perp_liq_force_cancel_orders_instruction(liqee, perp_market_index)
+            }
+            Self::PerpLiqBaseOrPositivePnl {
+                perp_market_index,
+                max_base_transfer,
+                max_pnl_transfer,
+                ..
+            } => {
+                client
+                    .perp_liq_base_or_positive_pnl_instruction(
+                        liqee,
+                        perp_market_index,
+                        max_base_transfer,
+                        max_pnl_transfer,
+                    )
+                    .await
+            }
+            Self::PerpLiqNegativePnlOrBankruptcy {
+                perp_market_index,
+                max_liab_transfer,
+            } => {
+                client
+                    .perp_liq_negative_pnl_or_bankruptcy_instruction(
+                        liqee,
+                        perp_market_index,
+                        max_liab_transfer,
+                    )
+                    .await
+            }
+            Self::TokenLiqWithToken {
+                asset_token_index,
+                liab_token_index,
+                max_liab_transfer,
+                ..
+            } => {
+                client
+                    .token_liq_with_token_instruction(
+                        liqee,
+                        asset_token_index,
+                        liab_token_index,
+                        max_liab_transfer,
+                    )
+                    .await
+            }
+            Self::TokenLiqBankruptcy {
+                liab_token_index,
+                max_liab_transfer,
+            } => {
+                client
+                    .token_liq_bankruptcy_instruction(liqee, liab_token_index, max_liab_transfer)
+                    .await
+            }
+        }
+    }
+
+    pub async fn send(
+        &self,
+        client: &MangoClient,
+        liqee: (&Pubkey, &MangoAccountValue),
+    ) -> anyhow::Result<Signature> {
+        let ix = self.instruction(client, liqee).await?;
+        match self {
+            // force cancels don't need the liqor
+            Self::Serum3ForceCancelOrders { .. } | Self::PerpForceCancelOrders { .. } => {
+                client.send_and_confirm_permissionless_tx(vec![ix]).await
+            }
+            Self::TokenLiqWithToken {
+                asset_token_index,
+                liab_token_index,
+                unwind_swap: Some(unwind_swap),
+                ..
+            } => {
+                // No other mango instructions are allowed inside a flash loan, so the
+                // liquidation comes first and the flash loan swap unwinds it afterwards.
+                // The liqor health check of the liquidation doesn't see the swap.
+                // The flash loan's health accounts include the asset and liab tokens
+                // that the liquidation adds to the liqor.
+                client
+                    .jupiter_swap_flash_loan(
+                        client.context.token(*asset_token_index).mint_info.mint,
+                        client.context.token(*liab_token_index).mint_info.mint,
+                        unwind_swap.amount,
+                        unwind_swap.slippage_bps,
+                        JupiterSwapMode::ExactIn,
+                    )
+                    .await
+                    .context("building unwind swap")?
+                    .pre_instruction(ix)
+                    .send_and_confirm()
+                    .await
+            }
+            Self::PerpLiqBaseOrPositivePnl {
+                perp_market_index,
+                unwind_order: Some(unwind_order),
+                ..
+            } => {
+                // The liqor's health is checked after the liquidation, before the
+                // order closes the acquired base position
+                let unwind_ix = client
+                    .perp_place_order_instruction(
+                        *perp_market_index,
+                        unwind_order.side,
+                        unwind_order.price_lots,
+                        unwind_order.max_base_lots,
+                        i64::MAX,
+                        0,
+                        PlaceOrderType::ImmediateOrCancel,
+                        true,
+                        0,
+                        10,
+                        SelfTradeBehavior::DecrementTake,
+                    )
+                    .await
+                    .context("building unwind order")?;
+                client.send_and_confirm_owner_tx(vec![ix, unwind_ix]).await
+            }
+            _ => client.send_and_confirm_owner_tx(vec![ix]).await,
+        }
+    }
 }
 
 pub async fn jupiter_market_can_buy(
@@ -82,12 +366,27 @@ struct LiquidateHelper<'a> {
     health_cache: &'a HealthCache,
     maint_health: I80F48,
     liqor_min_health_ratio: I80F48,
+    refresh_liqor_account: bool,
+    flash_loan_unwind: bool,
+    flash_loan_slippage_bps: u64,
     allowed_asset_tokens: HashSet<Pubkey>,
     allowed_liab_tokens: HashSet<Pubkey>,
 }
 
 impl<'a> LiquidateHelper<'a> {
-    async fn serum3_close_orders(&self) -> anyhow::Result<Option<Signature>> {
+    async fn liqor_account(&self) -> anyhow::Result<MangoAccountValue> {
+        let address = &self.client.mango_account_address;
+        if self.refresh_liqor_account {
+            self.account_fetcher
+                .fetch_fresh_mango_account(address)
+                .await
+        } else {
+            self.account_fetcher.fetch_mango_account(address)
+        }
+        .context("getting liquidator account")
+    }
+
+    async fn serum3_close_orders(&self) -> anyhow::Result<Option<LiquidationAction>> {
         // look for any open serum orders or settleable balances
         let serum_oos: anyhow::Result<Vec<_>> = stream::iter(self.liqee.active_serum3_orders())
             .then(|orders| async {
@@ -118,25 +417,13 @@ impl<'a> LiquidateHelper<'a> {
         }
         // Cancel all orders on a random serum market
         let serum_orders = serum_force_cancels.choose(&mut rand::thread_rng()).unwrap();
-        let sig = self
-            .client
-            .serum3_liq_force_cancel_orders(
-                (self.pubkey, &self.liqee),
-                serum_orders.market_index,
-                &serum_orders.open_orders,
-            )
-            .await?;
-        log::info!(
-            "Force cancelled serum orders on account {}, market index {}, maint_health was {}, tx sig {:?}",
-            self.pubkey,
-            serum_orders.market_index,
-            self.maint_health,
-            sig
-        );
-        Ok(Some(sig))
+        Ok(Some(LiquidationAction::Serum3ForceCancelOrders {
+            market_index: serum_orders.market_index,
+            open_orders: serum_orders.open_orders,
+        }))
     }
 
-    async fn perp_close_orders(&self) -> anyhow::Result<Option<Signature>> {
+    async fn perp_close_orders(&self) -> anyhow::Result<Option<LiquidationAction>> {
         let perp_force_cancels = self
             .liqee
             .active_perp_positions()
@@ -148,21 +435,12 @@ impl<'a> LiquidateHelper<'a> {
 
         // Cancel all orders on a random perp market
         let perp_market_index = *perp_force_cancels.choose(&mut rand::thread_rng()).unwrap();
-        let sig = self
-            .client
-            .perp_liq_force_cancel_orders((self.pubkey, &self.liqee), perp_market_index)
-            .await?;
-        log::info!(
-            "Force cancelled perp orders on account {}, market index {}, maint_health was {}, tx sig {:?}",
-            self.pubkey,
+        Ok(Some(LiquidationAction::PerpForceCancelOrders {
             perp_market_index,
-            self.maint_health,
-            sig
-        );
-        Ok(Some(sig))
+        }))
     }
 
-    async fn perp_liq_base_or_positive_pnl(&self) -> anyhow::Result<Option<Signature>> {
+    async fn perp_liq_base_or_positive_pnl(&self) -> anyhow::Result<Option<LiquidationAction>> {
         let all_perp_base_positions: anyhow::Result<
             Vec<Option<(PerpMarketIndex, i64, I80F48, I80F48)>>,
         > = stream::iter(self.liqee.active_perp_positions())
@@ -215,11 +493,7 @@ impl<'a> LiquidateHelper<'a> {
         // in the market before agreeding to take it over. Also, the liqor should check how much
         // settle limit it's going to get along with the unsettled pnl.
         let (max_base_transfer_abs, max_pnl_transfer) = {
-            let mut liqor = self
-                .account_fetcher
-                .fetch_fresh_mango_account(&self.client.mango_account_address)
-                .await
-                .context("getting liquidator account")?;
+            let mut liqor = self.liqor_account().await?;
             liqor.ensure_perp_position(*perp_market_index, QUOTE_TOKEN_INDEX)?;
             let mut health_cache =
                 health_cache::new(&self.client.context, self.account_fetcher, &liqor)
@@ -264,23 +538,29 @@ impl<'a> LiquidateHelper<'a> {
         };
         log::info!("computed max_base_transfer: {max_base_transfer_abs}, max_pnl_transfer: {max_pnl_transfer}");
 
-        let sig = self
-            .client
-            .perp_liq_base_or_positive_pnl(
-                (self.pubkey, &self.liqee),
-                *perp_market_index,
-                side_signum * max_base_transfer_abs,
-                max_pnl_transfer,
-            )
-            .await?;
-        log::info!(
-            "Liquidated base position for perp market on account {}, market index {}, maint_health was {}, tx sig {:?}",
-            self.pubkey,
-            perp_market_index,
-            self.maint_health,
-            sig
-        );
-        Ok(Some(sig))
+        // The liqor takes over the liqee's base position and closes it on the book
+        let unwind_order = if self.flash_loan_unwind && max_base_transfer_abs > 0 {
+            let slippage = I80F48::from(self.flash_loan_slippage_bps) / I80F48::from(10_000);
+            let unwind_side = side.invert_side();
+            let order_price = match unwind_side {
+                Side::Bid => *price * (I80F48::ONE + slippage),
+                Side::Ask => *price * (I80F48::ONE - slippage),
+            };
+            Some(PerpUnwindOrder {
+                side: unwind_side,
+                price_lots: perp.market.native_price_to_lot(order_price),
+                max_base_lots: max_base_transfer_abs,
+            })
+        } else {
+            None
+        };
+
+        Ok(Some(LiquidationAction::PerpLiqBaseOrPositivePnl {
+            perp_market_index: *perp_market_index,
+            max_base_transfer: side_signum * max_base_transfer_abs,
+            max_pnl_transfer,
+            unwind_order,
+        }))
     }
 
     /*
@@ -360,7 +640,9 @@ impl<'a> LiquidateHelper<'a> {
     }
     */
 
-    async fn perp_liq_negative_pnl_or_bankruptcy(&self) -> anyhow::Result<Option<Signature>> {
+    async fn perp_liq_negative_pnl_or_bankruptcy(
+        &self,
+    ) -> anyhow::Result<Option<LiquidationAction>> {
         if !self.health_cache.in_phase3_liquidation() {
             return Ok(None);
         }
@@ -382,23 +664,11 @@ impl<'a> LiquidateHelper<'a> {
         }
         let (perp_market_index, _) = perp_negative_pnl.first().unwrap();
 
-        let sig = self
-            .client
-            .perp_liq_negative_pnl_or_bankruptcy(
-                (self.pubkey, &self.liqee),
-                *perp_market_index,
-                // Always use the max amount, since the health effect is >= 0
-                u64::MAX,
-            )
-            .await?;
-        log::info!(
-            "Liquidated negative perp pnl on account {}, market index {}, maint_health was {}, tx sig {:?}",
-            self.pubkey,
-            perp_market_index,
-            self.maint_health,
-            sig
-        );
-        Ok(Some(sig))
+        Ok(Some(LiquidationAction::PerpLiqNegativePnlOrBankruptcy {
+            perp_market_index: *perp_market_index,
+            // Always use the max amount, since the health effect is >= 0
+            max_liab_transfer: u64::MAX,
+        }))
     }
 
     async fn tokens(&self) -> anyhow::Result<Vec<(TokenIndex, I80F48, I80F48)>> {
@@ -434,12 +704,9 @@ impl<'a> LiquidateHelper<'a> {
         &self,
         source: TokenIndex,
         target: TokenIndex,
+        min_health_ratio: I80F48,
     ) -> anyhow::Result<I80F48> {
-        let mut liqor = self
-            .account_fetcher
-            .fetch_fresh_mango_account(&self.client.mango_account_address)
-            .await
-            .context("getting liquidator account")?;
+        let mut liqor = self.liqor_account().await?;
 
         // Ensure the tokens are activated, so they appear in the health cache and
         // max_swap_source() will work.
@@ -465,13 +732,13 @@ impl<'a> LiquidateHelper<'a> {
                 source_price,
                 &target_bank,
                 oracle_swap_price,
-                self.liqor_min_health_ratio,
+                min_health_ratio,
             )
             .context("getting max_swap_source")?;
         Ok(amount)
     }
 
-    async fn token_liq(&self) -> anyhow::Result<Option<Signature>> {
+    async fn token_liq(&self) -> anyhow::Result<Option<LiquidationAction>> {
         if !self.health_cache.has_spot_assets() || !self.health_cache.has_spot_borrows() {
             return Ok(None);
         }
@@ -513,34 +780,45 @@ impl<'a> LiquidateHelper<'a> {
             .0;
 
         let max_liab_transfer = self
-            .max_token_liab_transfer(liab_token_index, asset_token_index)
+            .max_token_liab_transfer(
+                liab_token_index,
+                asset_token_index,
+                self.liqor_min_health_ratio,
+            )
             .await
             .context("getting max_liab_transfer")?;
 
+        let unwind_swap = if self.flash_loan_unwind {
+            let asset_bank = self.client.first_bank(asset_token_index).await?;
+            let liab_bank = self.client.first_bank(liab_token_index).await?;
+            let (_, asset_transfer) = token_liq_transfer(
+                self.health_cache,
+                &asset_bank,
+                &liab_bank,
+                max_liab_transfer,
+            )?;
+            let amount = asset_transfer.floor().to_num::<u64>();
+            (amount > 0).then_some(UnwindSwap {
+                amount,
+                slippage_bps: self.flash_loan_slippage_bps,
+            })
+        } else {
+            None
+        };
+
         //
         // TODO: log liqor's assets in UI form
         // TODO: log liquee's liab_needed, need to refactor program code to be able to be accessed from client side
         //
-        let sig = self
-            .client
-            .token_liq_with_token(
-                (self.pubkey, &self.liqee),
-                asset_token_index,
-                liab_token_index,
-                max_liab_transfer,
-            )
-            .await
-            .context("sending liq_token_with_token")?;
-        log::info!(
-            "Liquidated token with token for {}, maint_health was {}, tx sig {:?}",
-            self.pubkey,
-            self.maint_health,
-            sig
-        );
-        Ok(Some(sig))
+        Ok(Some(LiquidationAction::TokenLiqWithToken {
+            asset_token_index,
+            liab_token_index,
+            max_liab_transfer,
+            unwind_swap,
+        }))
     }
 
-    async fn token_liq_bankruptcy(&self) -> anyhow::Result<Option<Signature>> {
+    async fn token_liq_bankruptcy(&self) -> anyhow::Result<Option<LiquidationAction>> {
         if !self.health_cache.in_phase3_liquidation() || !self.health_cache.has_spot_borrows() {
             return Ok(None);
         }
@@ -572,28 +850,21 @@ impl<'a> LiquidateHelper<'a> {
 
         let quote_token_index = 0;
         let max_liab_transfer = self
-            .max_token_liab_transfer(liab_token_index, quote_token_index)
-            .await?;
-
-        let sig = self
-            .client
-            .token_liq_bankruptcy(
-                (self.pubkey, &self.liqee),
+            .max_token_liab_transfer(
                 liab_token_index,
-                max_liab_transfer,
+                quote_token_index,
+                self.liqor_min_health_ratio,
             )
-            .await
-            .context("sending liq_token_bankruptcy")?;
-        log::info!(
-            "Liquidated bankruptcy for {}, maint_health was {}, tx sig {:?}",
-            self.pubkey,
-            self.maint_health,
-            sig
-        );
-        Ok(Some(sig))
+            .await?;
+
+        Ok(Some(LiquidationAction::TokenLiqBankruptcy {
+            liab_token_index,
+            max_liab_transfer,
+        }))
     }
 
-    async fn send_liq_tx(&self) -> anyhow::Result<Option<Signature>> {
+    /// Decides on the next liquidation step, without sending anything
+    async fn liq_action(&self) -> anyhow::Result<Option<LiquidationAction>> {
         // TODO: Should we make an attempt to settle positive PNL first?
         // The problem with it is that small market movements can continuously create
         // small amounts of new positive PNL while base_position > 0.
@@ -607,11 +878,11 @@ impl<'a> LiquidateHelper<'a> {
         // Phase 1: Try to close orders before touching the user's positions
         //
         // TODO: All these close ix could be in one transaction.
-        if let Some(txsig) = self.perp_close_orders().await? {
-            return Ok(Some(txsig));
+        if let Some(action) = self.perp_close_orders().await? {
+            return Ok(Some(action));
         }
-        if let Some(txsig) = self.serum3_close_orders().await? {
-            return Ok(Some(txsig));
+        if let Some(action) = self.serum3_close_orders().await? {
+            return Ok(Some(action));
         }
 
         if self.health_cache.has_phase1_liquidatable() {
@@ -626,12 +897,12 @@ impl<'a> LiquidateHelper<'a> {
         // Phase 2: token, perp base, perp positive pnl
         //
 
-        if let Some(txsig) = self.perp_liq_base_or_positive_pnl().await? {
-            return Ok(Some(txsig));
+        if let Some(action) = self.perp_liq_base_or_positive_pnl().await? {
+            return Ok(Some(action));
         }
 
-        if let Some(txsig) = self.token_liq().await? {
-            return Ok(Some(txsig));
+        if let Some(action) = self.token_liq().await? {
+            return Ok(Some(action));
         }
 
         if self.health_cache.has_perp_open_fills() {
@@ -656,13 +927,13 @@ impl<'a> LiquidateHelper<'a> {
         //
 
         // Negative pnl: take over (paid by liqee or insurance) or socialize the loss
-        if let Some(txsig) = self.perp_liq_negative_pnl_or_bankruptcy().await? {
-            return Ok(Some(txsig));
+        if let Some(action) = self.perp_liq_negative_pnl_or_bankruptcy().await? {
+            return Ok(Some(action));
         }
 
         // Socialize/insurance fund unliquidatable borrows
-        if let Some(txsig) = self.token_liq_bankruptcy().await? {
-            return Ok(Some(txsig));
+        if let Some(action) = self.token_liq_bankruptcy().await? {
+            return Ok(Some(action));
         }
 
         // TODO: What about unliquidatable positive perp pnl?
@@ -675,68 +946,241 @@ impl<'a> LiquidateHelper<'a> {
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-pub async fn maybe_liquidate_account(
+/// Decides how `liqee` would be liquidated, without sending anything.
+///
+/// Returns None if there's nothing to do right now, for example because the
+/// account is waiting for open perp fills to be processed.
+pub async fn liquidation_action(
     mango_client: &MangoClient,
     account_fetcher: &chain_data::AccountFetcher,
     pubkey: &Pubkey,
+    liqee: &MangoAccountValue,
+    health_cache: &HealthCache,
     config: &Config,
-) -> anyhow::Result<bool> {
-    let liqor_min_health_ratio = I80F48::from_num(config.min_health_ratio);
+) -> anyhow::Result<Option<LiquidationAction>> {
+    let all_token_mints = HashSet::from_iter(
+        mango_client
+            .context
+            .tokens
+            .values()
+            .map(|c| c.mint_info.mint),
+    );
 
-    let account = account_fetcher.fetch_mango_account(pubkey)?;
-    let health_cache = health_cache::new(&mango_client.context, account_fetcher, &account)
-        .await
-        .context("creating health cache 1")?;
-    let maint_health = health_cache.health(HealthType::Maint);
-    if !health_cache.is_liquidatable() {
-        return Ok(false);
+    LiquidateHelper {
+        client: mango_client,
+        account_fetcher,
+        pubkey,
+        liqee,
+        health_cache,
+        maint_health: health_cache.health(HealthType::Maint),
+        liqor_min_health_ratio: I80F48::from_num(config.min_health_ratio),
+        refresh_liqor_account: config.refresh_liqor_account,
+        flash_loan_unwind: config.flash_loan_unwind,
+        flash_loan_slippage_bps: config.flash_loan_slippage_bps,
+        allowed_asset_tokens: all_token_mints.clone(),
+        allowed_liab_tokens: all_token_mints,
     }
+    .liq_action()
+    .await
+}
 
-    log::trace!(
-        "possible candidate: {}, with owner: {}, maint health: {}",
-        pubkey,
-        account.fixed.owner,
-        maint_health,
-    );
+/// Expected (liab_transfer, asset_transfer) of a token_liq_with_token on the liqee,
+/// in native tokens.
+///
+/// Mirrors token_liq_with_token::liquidation_action.
+pub fn token_liq_transfer(
+    health_cache: &HealthCache,
+    asset_bank: &Bank,
+    liab_bank: &Bank,
+    max_liab_transfer: I80F48,
+) -> anyhow::Result<(I80F48, I80F48)> {
+    let liq_end_health = health_cache.health(HealthType::LiquidationEnd);
+    let asset_info = health_cache.token_info(asset_bank.token_index)?;
+    let liab_info = health_cache.token_info(liab_bank.token_index)?;
+
+    let asset_oracle_price = asset_info.prices.oracle;
+    let liab_oracle_price_adjusted =
+        liab_info.prices.oracle * (I80F48::ONE + liab_bank.liquidation_fee);
+    let liab_needed = -liq_end_health
+        / (liab_info.prices.liab(HealthType::LiquidationEnd) * liab_bank.init_liab_weight
+            - liab_oracle_price_adjusted
+                * asset_bank.init_asset_weight
+                * (asset_info.prices.asset(HealthType::LiquidationEnd) / asset_oracle_price));
+    let liab_possible = asset_info.balance_native * asset_oracle_price / liab_oracle_price_adjusted;
+    let liab_transfer = liab_needed
+        .min(-liab_info.balance_native)
+        .min(liab_possible)
+        .min(max_liab_transfer)
+        .max(I80F48::ZERO);
+    let asset_transfer = liab_transfer * liab_oracle_price_adjusted / asset_oracle_price;
+    Ok((liab_transfer, asset_transfer))
+}
+
+/// Cheap check that skips building a health cache for accounts that can't be
+/// liquidatable: without borrows and perp positions, health can't be negative.
+pub fn may_be_liquidatable(account: &MangoAccountValue) -> bool {
+    account
+        .active_token_positions()
+        .any(|position| position.indexed_position.is_negative())
+        || account.active_perp_positions().next().is_some()
+}
+
+/// Rough estimate of the profit from liquidating an account, in health reference
+/// token native units.
+///
+/// Adds up the liquidation fees on everything that could be taken over and subtracts
+/// the estimated cost of unwinding the acquired positions afterwards. Liquidation
+/// amounts are limited by the liqor's health, so this is only good for ranking.
+pub fn estimate_liquidation_profit(
+    context: &MangoGroupContext,
+    account_fetcher: &chain_data::AccountFetcher,
+    liqee: &MangoAccountValue,
+    health_cache: &HealthCache,
+    unwind_cost_bps: u64,
+) -> anyhow::Result<I80F48> {
+    let unwind_cost_rate = I80F48::from(unwind_cost_bps) / I80F48::from(10_000);
+    let mut profit = I80F48::ZERO;
+    let liquidation_fee = |token_index: TokenIndex| -> anyhow::Result<I80F48> {
+        let bank =
+            account_fetcher.fetch::<Bank>(&context.token(token_index).mint_info.first_bank())?;
+        Ok(bank.liquidation_fee)
+    };
+
+    // Like token_liq(), assume that borrows are liquidated against the largest asset
+    let mut largest_asset: Option<(TokenIndex, I80F48)> = None;
+    for position in liqee.active_token_positions() {
+        let info = health_cache.token_info(position.token_index)?;
+        let value = info.balance_native * info.prices.oracle;
+        if value.is_positive() && largest_asset.map_or(true, |(_, largest)| value > largest) {
+            largest_asset = Some((position.token_index, value));
+        }
+    }
+    let asset_liquidation_fee = match largest_asset {
+        Some((token_index, _)) => liquidation_fee(token_index)?,
+        None => I80F48::ZERO,
+    };
+
+    for position in liqee.active_token_positions() {
+        let info = health_cache.token_info(position.token_index)?;
+        if !info.balance_native.is_negative() {
+            continue;
+        }
+        profit += token_liquidation_profit(
+            info,
+            liquidation_fee(position.token_index)?,
+            asset_liquidation_fee,
+            unwind_cost_rate,
+        );
+    }
+
+    for position in liqee.active_perp_positions() {
+        let info = health_cache.perp_info(position.market_index)?;
+        let perp_market = &context.perp(position.market_index).market;
+        profit += perp_liquidation_profit(
+            info,
+            perp_market.base_liquidation_fee,
+            perp_market.positive_pnl_liquidation_fee,
+            unwind_cost_rate,
+        );
+    }
 
+    Ok(profit)
+}
+
+/// Fee earned on taking over a token borrow, minus the cost of unwinding it
+///
+/// The liqor gets assets worth the liab times the fee factors of both banks.
+fn token_liquidation_profit(
+    info: &TokenInfo,
+    liab_liquidation_fee: I80F48,
+    asset_liquidation_fee: I80F48,
+    unwind_cost_rate: I80F48,
+) -> I80F48 {
+    let liab_value = -info.balance_native.min(I80F48::ZERO) * info.prices.liab(HealthType::Maint);
+    let fee_factor = (I80F48::ONE + liab_liquidation_fee) * (I80F48::ONE + asset_liquidation_fee);
+    liab_value * (fee_factor - I80F48::ONE - unwind_cost_rate)
+}
+
+/// Fee earned on taking over a perp base position, minus the cost of unwinding it,
+/// plus the fee on taking over positive pnl of a closed position
+fn perp_liquidation_profit(
+    info: &PerpInfo,
+    base_liquidation_fee: I80F48,
+    positive_pnl_liquidation_fee: I80F48,
+    unwind_cost_rate: I80F48,
+) -> I80F48 {
+    let base_value = I80F48::from(info.base_lots.abs() * info.base_lot_size) * info.prices.oracle;
+    let mut profit = base_value * (base_liquidation_fee - unwind_cost_rate);
+    if info.base_lots == 0 && info.quote.is_positive() {
+        profit += info.quote * positive_pnl_liquidation_fee;
+    }
+    profit
+}
+
+/// Orders liquidation candidates by estimated profit, most profitable first.
+///
+/// The sort is stable, candidates with equal profit keep their order.
+pub fn rank_by_profit(mut candidates: Vec<(Pubkey, I80F48)>) -> Vec<Pubkey> {
+    candidates.sort_by(|a, b| b.1.cmp(&a.1));
+    candidates.into_iter().map(|(pubkey, _)| pubkey).collect()
+}
+
+/// Liquidates an account that looked liquidatable in the cached account data.
+///
+/// Safe to call for several accounts concurrently: the tx tracker reserves each
+/// liquidation before it is sent.
+pub async fn maybe_liquidate_account(
+    mango_client: &MangoClient,
+    account_fetcher: &chain_data::AccountFetcher,
+    tx_tracker: &TxTracker,
+    pubkey: &Pubkey,
+    config: &Config,
+) -> anyhow::Result<bool> {
     // Fetch a fresh account and re-compute
     // This is -- unfortunately -- needed because the websocket streams seem to not
     // be great at providing timely updates to the account data.
     let account = account_fetcher.fetch_fresh_mango_account(pubkey).await?;
     let health_cache = health_cache::new(&mango_client.context, account_fetcher, &account)
         .await
-        .context("creating health cache 2")?;
+        .context("creating health cache")?;
     if !health_cache.is_liquidatable() {
         return Ok(false);
     }
 
     let maint_health = health_cache.health(HealthType::Maint);
 
-    let all_token_mints = HashSet::from_iter(
-        mango_client
-            .context
-            .tokens
-            .values()
-            .map(|c| c.mint_info.mint),
-    );
-
     // try liquidating
-    let maybe_txsig = LiquidateHelper {
-        client: mango_client,
+    let maybe_action = liquidation_action(
+        mango_client,
         account_fetcher,
         pubkey,
-        liqee: &account,
-        health_cache: &health_cache,
-        maint_health,
-        liqor_min_health_ratio,
-        allowed_asset_tokens: all_token_mints.clone(),
-        allowed_liab_tokens: all_token_mints,
-    }
-    .send_liq_tx()
+        &account,
+        &health_cache,
+        config,
+    )
     .await?;
 
-    if let Some(txsig) = maybe_txsig {
+    if let Some(action) = maybe_action {
+        // Don't send the same liquidation again while an earlier one may still land
+        let action_key = format!("liquidate {}: {}", pubkey, action.tracking_key());
+        let txsig = match tx_tracker
+            .send_once(&action_key, || {
+                action.send(mango_client, (pubkey, &account))
+            })
+            .await
+            .with_context(|| format!("sending liquidation: {}", action.description()))?
+        {
+            Some(txsig) => txsig,
+            None => return Ok(false),
+        };
+        log::info!(
+            "{} on account {}, maint_health was {}, tx sig {:?}",
+            action.description(),
+            pubkey,
+            maint_health,
+            txsig
+        );
+
         let slot = account_fetcher.transaction_max_slot(&[txsig]).await?;
         if let Err(e) = account_fetcher
             .refresh_accounts_via_rpc_until_slot(
@@ -752,3 +1196,98 @@ pub async fn maybe_liquidate_account(
 
     Ok(true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mango_v4::health::Prices;
+
+    fn token_info(balance: f64, price: f64) -> TokenInfo {
+        TokenInfo {
+            token_index: 1,
+            maint_asset_weight: I80F48::from_num(0.9),
+            init_asset_weight: I80F48::from_num(0.8),
+            init_scaled_asset_weight: I80F48::from_num(0.8),
+            maint_liab_weight: I80F48::from_num(1.1),
+            init_liab_weight: I80F48::from_num(1.2),
+            init_scaled_liab_weight: I80F48::from_num(1.2),
+            prices: Prices::new_single_price(I80F48::from_num(price)),
+            balance_native: I80F48::from_num(balance),
+        }
+    }
+
+    fn perp_info(base_lots: i64, quote: f64, price: f64) -> PerpInfo {
+        PerpInfo {
+            perp_market_index: 0,
+            maint_base_asset_weight: I80F48::from_num(0.9),
+            init_base_asset_weight: I80F48::from_num(0.8),
+            maint_base_liab_weight: I80F48::from_num(1.1),
+            init_base_liab_weight: I80F48::from_num(1.2),
+            maint_overall_asset_weight: I80F48::ONE,
+            init_overall_asset_weight: I80F48::ONE,
+            base_lot_size: 10,
+            base_lots,
+            bids_base_lots: 0,
+            asks_base_lots: 0,
+            quote: I80F48::from_num(quote),
+            prices: Prices::new_single_price(I80F48::from_num(price)),
+            has_open_orders: false,
+            has_open_fills: false,
+        }
+    }
+
+    #[test]
+    fn test_token_liquidation_profit() {
+        let fee = I80F48::from_num(0.02);
+        let unwind = I80F48::from_num(0.005);
+        let zero = I80F48::ZERO;
+
+        // 1000 borrowed at price 2: 2000 value, 1.5% net
+        let profit = token_liquidation_profit(&token_info(-1000.0, 2.0), fee, zero, unwind);
+        assert!((profit.to_num::<f64>() - 30.0).abs() < 1e-6);
+
+        // the asset fee compounds with the liab fee: 1.02 * 1.01 - 1 - 0.005 = 2.52% net
+        let asset_fee = I80F48::from_num(0.01);
+        let profit = token_liquidation_profit(&token_info(-1000.0, 2.0), fee, asset_fee, unwind);
+        assert!((profit.to_num::<f64>() - 50.4).abs() < 1e-6);
+
+        // deposits earn nothing
+        let profit = token_liquidation_profit(&token_info(1000.0, 2.0), fee, asset_fee, unwind);
+        assert_eq!(profit, I80F48::ZERO);
+
+        // unwinding can cost more than the fee
+        let profit = token_liquidation_profit(&token_info(-1000.0, 2.0), unwind, zero, fee);
+        assert!(profit < 0);
+    }
+
+    #[test]
+    fn test_perp_liquidation_profit() {
+        let base_fee = I80F48::from_num(0.01);
+        let pnl_fee = I80F48::from_num(0.05);
+        let unwind = I80F48::from_num(0.005);
+
+        // short 5 lots of 10 at price 4: 200 value, 0.5% net; pnl isn't taken over
+        let profit = perp_liquidation_profit(&perp_info(-5, 500.0, 4.0), base_fee, pnl_fee, unwind);
+        assert!((profit.to_num::<f64>() - 1.0).abs() < 1e-6);
+
+        // without a base position, positive pnl can be taken over
+        let profit = perp_liquidation_profit(&perp_info(0, 100.0, 4.0), base_fee, pnl_fee, unwind);
+        assert!((profit.to_num::<f64>() - 5.0).abs() < 1e-6);
+
+        // negative pnl alone earns nothing
+        let profit = perp_liquidation_profit(&perp_info(0, -100.0, 4.0), base_fee, pnl_fee, unwind);
+        assert_eq!(profit, I80F48::ZERO);
+    }
+
+    #[test]
+    fn test_rank_by_profit() {
+        let keys = (0..4).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        let ranked = rank_by_profit(vec![
+            (keys[0], I80F48::from(1)),
+            (keys[1], I80F48::from(5)),
+            (keys[2], I80F48::from(-2)),
+            (keys[3], I80F48::from(5)),
+        ]);
+        assert_eq!(ranked, vec![keys[1], keys[3], keys[0], keys[2]]);
+    }
+}
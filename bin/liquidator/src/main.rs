@@ -4,10 +4,13 @@ use std::time::Duration;
 
 use anchor_client::Cluster;
 use clap::Parser;
+use fixed::types::I80F48;
 use log::*;
+use mango_v4::health::HealthType;
 use mango_v4::state::{PerpMarketIndex, TokenIndex};
 use mango_v4_client::{
-    account_update_stream, chain_data, keypair_from_cli, snapshot_source, websocket_source,
+    account_update_stream, chain_data, health_cache, keypair_from_cli, snapshot_file,
+    snapshot_source, tx_tracker, tx_tracker::TxTracker, websocket_source,
     AsyncChannelSendUnlessFull, Client, MangoClient, MangoClientError, MangoGroupContext,
     TransactionBuilderConfig,
 };
@@ -20,6 +23,7 @@ use std::collections::HashSet;
 pub mod liquidate;
 pub mod metrics;
 pub mod rebalance;
+pub mod simulate;
 pub mod util;
 
 use crate::util::{is_mango_account, is_mango_bank, is_mint_info, is_perp_market};
@@ -70,12 +74,63 @@ struct Cli {
     #[clap(long, env, default_value = "50")]
     min_health_ratio: f64,
 
+    /// estimated cost of unwinding positions taken over in a liquidation, used for
+    /// ranking liquidation candidates by profit
+    #[clap(long, env, default_value = "50")]
+    liquidation_unwind_cost_bps: u64,
+
+    /// maximum number of liquidations to run at the same time
+    #[clap(long, env, default_value = "1")]
+    max_concurrent_liquidations: usize,
+
+    /// liquidate concurrently only if the liquidator's init health ratio is above this
+    #[clap(long, env, default_value = "150")]
+    concurrent_liquidation_min_health_ratio: f64,
+
+    /// same-tx unwind: sell what liquidations acquire in the same transaction, token
+    /// assets with a flash loan funded jupiter swap, perp base positions with an ioc order
+    ///
+    /// The liquidator's health is checked on-chain after the liquidation instruction and
+    /// before the unwind, so liquidations are still sized with min_health_ratio.
+    #[clap(long, env)]
+    flash_loan_unwind: bool,
+
+    /// slippage for unwind swaps and orders
+    #[clap(long, env, default_value = "100")]
+    flash_loan_slippage_bps: u64,
+
     #[clap(long, env, default_value = "100")]
     rebalance_slippage_bps: u64,
 
+    /// swap backends for rebalancing, tried in order: jupiter, serum3, perp
+    ///
+    /// perp hedges tokens on a perp market with the same oracle instead of swapping
+    #[clap(long, env, value_delimiter = ',', default_value = "jupiter,serum3")]
+    rebalance_backends: Vec<rebalance::Backend>,
+
+    /// token inventory the rebalancer keeps instead of selling, as NAME:AMOUNT[:TOLERANCE]
+    /// in ui units, comma separated
+    #[clap(long, env, value_delimiter = ',')]
+    rebalance_token_targets: Vec<rebalance::NamedTarget>,
+
+    /// perp base positions the rebalancer keeps instead of closing, as
+    /// MARKET:AMOUNT[:TOLERANCE] in ui units, comma separated
+    #[clap(long, env, value_delimiter = ',')]
+    rebalance_perp_targets: Vec<rebalance::NamedTarget>,
+
     /// prioritize each transaction with this many microlamports/cu
     #[clap(long, env, default_value = "0")]
     prioritization_micro_lamports: u64,
+
+    /// record the account state to this file after every snapshot and, if it
+    /// exists on startup, load it while waiting for the first rpc snapshot
+    #[clap(long, env)]
+    snapshot_file: Option<std::path::PathBuf>,
+
+    /// ignore the snapshot file on startup if its newest account is older than
+    /// this many slots
+    #[clap(long, env, default_value = "300")]
+    snapshot_file_max_age_slots: u64,
 }
 
 pub fn encode_address(addr: &Pubkey) -> String {
@@ -84,6 +139,14 @@ pub fn encode_address(addr: &Pubkey) -> String {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // `liquidator simulate ...` replays liquidation decisions against recorded data
+    // instead of running against a live rpc node
+    if std::env::args_os().nth(1).as_deref() == Some(std::ffi::OsStr::new("simulate")) {
+        solana_logger::setup_with_default("info");
+        let cli = simulate::Cli::parse_from(std::env::args_os().skip(1));
+        return simulate::run(cli).await;
+    }
+
     let args = if let Ok(cli_dotenv) = CliDotenv::try_parse() {
         dotenv::from_path(cli_dotenv.dotenv)?;
         cli_dotenv.remaining_args
@@ -167,6 +230,20 @@ async fn main() -> anyhow::Result<()> {
     )
     .await?;
 
+    // Initial account state from a previous run, if available and recent enough.
+    // The first rpc snapshot is still requested right away.
+    if let Some(path) = cli.snapshot_file.as_ref().filter(|path| path.exists()) {
+        let min_slot = first_websocket_slot.saturating_sub(cli.snapshot_file_max_age_slots);
+        match snapshot_file::replay(path, min_slot, &account_update_sender).await {
+            Ok(count) => info!(
+                "loaded {} accounts from snapshot file {}",
+                count,
+                path.display()
+            ),
+            Err(err) => warn!("not using snapshot file: {:?}", err),
+        }
+    }
+
     // Getting solana account snapshots via jsonrpc
     // FUTURE: of what to fetch a snapshot - should probably take as an input
     snapshot_source::start(
@@ -203,6 +280,9 @@ async fn main() -> anyhow::Result<()> {
         min_health_ratio: cli.min_health_ratio,
         // TODO: config
         refresh_timeout: Duration::from_secs(30),
+        refresh_liqor_account: true,
+        flash_loan_unwind: cli.flash_loan_unwind,
+        flash_loan_slippage_bps: cli.flash_loan_slippage_bps,
     };
 
     let mut rebalance_interval = tokio::time::interval(Duration::from_secs(5));
@@ -211,12 +291,45 @@ async fn main() -> anyhow::Result<()> {
         // TODO: config
         borrow_settle_excess: 1.05,
         refresh_timeout: Duration::from_secs(30),
+        backends: cli.rebalance_backends.clone(),
+        token_targets: cli
+            .rebalance_token_targets
+            .iter()
+            .map(|t| {
+                let token_index = *mango_client
+                    .context
+                    .token_indexes_by_name
+                    .get(&t.name)
+                    .ok_or_else(|| anyhow::anyhow!("no token named {}", t.name))?;
+                Ok((token_index, t.target))
+            })
+            .collect::<anyhow::Result<_>>()?,
+        perp_targets: cli
+            .rebalance_perp_targets
+            .iter()
+            .map(|t| {
+                let perp_market_index = *mango_client
+                    .context
+                    .perp_market_indexes_by_name
+                    .get(&t.name)
+                    .ok_or_else(|| anyhow::anyhow!("no perp market named {}", t.name))?;
+                Ok((perp_market_index, t.target))
+            })
+            .collect::<anyhow::Result<_>>()?,
     };
 
+    // Shared by liquidation and rebalancing, to avoid sending the same action again
+    // while an earlier transaction for it could still land.
+    let tx_tracker = Arc::new(TxTracker::new(
+        mango_client.client.rpc_async(),
+        tx_tracker::Config::default(),
+    ));
+
     let rebalancer = Arc::new(rebalance::Rebalancer {
         mango_client: mango_client.clone(),
         account_fetcher: account_fetcher.clone(),
         mango_account_address: cli.liqor_mango_account,
+        tx_tracker: tx_tracker.clone(),
         config: rebalance_config,
     });
 
@@ -225,10 +338,14 @@ async fn main() -> anyhow::Result<()> {
         account_fetcher,
         liquidation_config: liq_config,
         rebalancer: rebalancer.clone(),
+        tx_tracker,
         accounts_with_errors: Default::default(),
         error_skip_threshold: 5,
         error_skip_duration: std::time::Duration::from_secs(120),
         error_reset_duration: std::time::Duration::from_secs(360),
+        unwind_cost_bps: cli.liquidation_unwind_cost_bps,
+        max_concurrent_liquidations: cli.max_concurrent_liquidations.max(1),
+        concurrent_min_health_ratio: I80F48::from_num(cli.concurrent_liquidation_min_health_ratio),
     };
 
     let (liquidation_trigger_sender, liquidation_trigger_receiver) =
@@ -250,6 +367,9 @@ async fn main() -> anyhow::Result<()> {
         let mut oracles = HashSet::<Pubkey>::new();
         let mut perp_markets = HashMap::<PerpMarketIndex, Pubkey>::new();
 
+        let snapshot_file_path = cli.snapshot_file.clone();
+        let mut recorder = snapshot_file::Recorder::default();
+
         async move {
             loop {
                 let message = account_update_receiver
@@ -259,6 +379,9 @@ async fn main() -> anyhow::Result<()> {
                 metric_account_update_queue_len.set(account_update_receiver.len() as u64);
 
                 message.update_chain_data(&mut chain_data.write().unwrap());
+                if snapshot_file_path.is_some() {
+                    recorder.record(&message);
+                }
 
                 match message {
                     Message::Account(account_write) => {
@@ -320,6 +443,10 @@ async fn main() -> anyhow::Result<()> {
                         state.one_snapshot_done = true;
                         state.health_check_all = true;
 
+                        if let Some(path) = snapshot_file_path.as_ref() {
+                            recorder.write_in_background(path);
+                        }
+
                         liquidation_trigger_sender.send_unless_full(()).unwrap();
                     }
                     _ => {}
@@ -336,13 +463,8 @@ async fn main() -> anyhow::Result<()> {
                 if !shared_state.read().unwrap().one_snapshot_done {
                     continue;
                 }
-                if let Err(err) = rebalancer.zero_all_non_quote().await {
+                if let Err(err) = rebalancer.rebalance_to_targets().await {
                     log::error!("failed to rebalance liqor: {:?}", err);
-
-                    // Workaround: We really need a sequence enforcer in the liquidator since we don't want to
-                    // accidentally send a similar tx again when we incorrectly believe an earlier one got forked
-                    // off. For now, hard sleep on error to avoid the most frequent error cases.
-                    std::thread::sleep(Duration::from_secs(10));
                 }
             }
         }
@@ -412,11 +534,15 @@ struct LiquidationState {
     mango_client: Arc<MangoClient>,
     account_fetcher: Arc<chain_data::AccountFetcher>,
     rebalancer: Arc<rebalance::Rebalancer>,
+    tx_tracker: Arc<TxTracker>,
     liquidation_config: liquidate::Config,
     accounts_with_errors: HashMap<Pubkey, ErrorTracking>,
     error_skip_threshold: u64,
     error_skip_duration: std::time::Duration,
     error_reset_duration: std::time::Duration,
+    unwind_cost_bps: u64,
+    max_concurrent_liquidations: usize,
+    concurrent_min_health_ratio: I80F48,
 }
 
 impl LiquidationState {
@@ -424,22 +550,30 @@ impl LiquidationState {
         &mut self,
         accounts_iter: impl Iterator<Item = &'b Pubkey>,
     ) -> anyhow::Result<()> {
-        use rand::seq::SliceRandom;
-
-        let mut accounts = accounts_iter.collect::<Vec<&Pubkey>>();
-        {
-            let mut rng = rand::thread_rng();
-            accounts.shuffle(&mut rng);
+        let candidates = self.ranked_candidates(accounts_iter).await;
+        if candidates.is_empty() {
+            return Ok(());
         }
+        let concurrency = self.liquidation_concurrency().await;
 
         let mut liquidated_one = false;
-        for pubkey in accounts {
-            if self
-                .maybe_liquidate_and_log_error(pubkey)
-                .await
-                .unwrap_or(false)
-            {
-                liquidated_one = true;
+        for batch in candidates.chunks(concurrency) {
+            let results = futures::future::join_all(batch.iter().map(|pubkey| {
+                liquidate::maybe_liquidate_account(
+                    &self.mango_client,
+                    &self.account_fetcher,
+                    &self.tx_tracker,
+                    pubkey,
+                    &self.liquidation_config,
+                )
+            }))
+            .await;
+            for (pubkey, result) in batch.iter().zip(results.into_iter()) {
+                if self.log_liquidation_result(pubkey, result).unwrap_or(false) {
+                    liquidated_one = true;
+                }
+            }
+            if liquidated_one {
                 break;
             }
         }
@@ -447,16 +581,96 @@ impl LiquidationState {
             return Ok(());
         }
 
-        if let Err(err) = self.rebalancer.zero_all_non_quote().await {
+        if let Err(err) = self.rebalancer.rebalance_to_targets().await {
             log::error!("failed to rebalance liqor: {:?}", err);
         }
         Ok(())
     }
 
-    async fn maybe_liquidate_and_log_error(&mut self, pubkey: &Pubkey) -> anyhow::Result<bool> {
-        let now = std::time::Instant::now();
+    /// Liquidatable accounts, most profitable first
+    async fn ranked_candidates<'b>(
+        &self,
+        accounts_iter: impl Iterator<Item = &'b Pubkey>,
+    ) -> Vec<Pubkey> {
+        use rand::seq::SliceRandom;
+
+        let mut accounts = accounts_iter
+            .filter(|pubkey| !self.skip_due_to_errors(pubkey))
+            .collect::<Vec<&Pubkey>>();
+        // randomize the order of candidates with equal profit
+        {
+            let mut rng = rand::thread_rng();
+            accounts.shuffle(&mut rng);
+        }
+
+        let mut candidates = vec![];
+        for pubkey in accounts {
+            match self.liquidation_profit(pubkey).await {
+                Ok(Some(profit)) => candidates.push((*pubkey, profit)),
+                Ok(None) => {}
+                Err(err) => log::trace!("could not rank account {}: {:?}", pubkey, err),
+            }
+        }
+        log::trace!("liquidation candidates with profit: {:?}", candidates);
+
+        liquidate::rank_by_profit(candidates)
+    }
+
+    /// Estimated liquidation profit, or None if the account isn't liquidatable
+    ///
+    /// Only accounts with borrows or perp positions need a health cache. The liquidation
+    /// itself only re-checks liquidatable candidates, on freshly fetched data.
+    async fn liquidation_profit(&self, pubkey: &Pubkey) -> anyhow::Result<Option<I80F48>> {
+        let account = self.account_fetcher.fetch_mango_account(pubkey)?;
+        if !liquidate::may_be_liquidatable(&account) {
+            return Ok(None);
+        }
+        let health_cache =
+            health_cache::new(&self.mango_client.context, &*self.account_fetcher, &account).await?;
+        if !health_cache.is_liquidatable() {
+            return Ok(None);
+        }
+        let profit = liquidate::estimate_liquidation_profit(
+            &self.mango_client.context,
+            &self.account_fetcher,
+            &account,
+            &health_cache,
+            self.unwind_cost_bps,
+        )?;
+        Ok(Some(profit))
+    }
 
-        // Skip a pubkey if there've been too many errors recently
+    /// Number of liquidations to attempt at once. Each liquidation is sized against the
+    /// liqor's current health, so only run several if there's plenty of health to spare.
+    async fn liquidation_concurrency(&self) -> usize {
+        if self.max_concurrent_liquidations <= 1 {
+            return 1;
+        }
+        let liqor_health_ratio = async {
+            let liqor = self
+                .account_fetcher
+                .fetch_mango_account(&self.mango_client.mango_account_address)?;
+            let health_cache =
+                health_cache::new(&self.mango_client.context, &*self.account_fetcher, &liqor)
+                    .await?;
+            anyhow::Ok(health_cache.health_ratio(HealthType::Init))
+        }
+        .await;
+        match liqor_health_ratio {
+            Ok(ratio) if ratio >= self.concurrent_min_health_ratio => {
+                self.max_concurrent_liquidations
+            }
+            Ok(_) => 1,
+            Err(err) => {
+                log::error!("could not compute liqor health: {:?}", err);
+                1
+            }
+        }
+    }
+
+    /// Skip a pubkey if there've been too many errors recently
+    fn skip_due_to_errors(&self, pubkey: &Pubkey) -> bool {
+        let now = std::time::Instant::now();
         if let Some(error_entry) = self.accounts_with_errors.get(pubkey) {
             if error_entry.count >= self.error_skip_threshold
                 && now.duration_since(error_entry.last_at) < self.error_skip_duration
@@ -465,17 +679,18 @@ impl LiquidationState {
                     "skip checking account {pubkey}, had {} errors recently",
                     error_entry.count
                 );
-                return Ok(false);
+                return true;
             }
         }
+        false
+    }
 
-        let result = liquidate::maybe_liquidate_account(
-            &self.mango_client,
-            &self.account_fetcher,
-            pubkey,
-            &self.liquidation_config,
-        )
-        .await;
+    fn log_liquidation_result(
+        &mut self,
+        pubkey: &Pubkey,
+        result: anyhow::Result<bool>,
+    ) -> anyhow::Result<bool> {
+        let now = std::time::Instant::now();
 
         if let Err(err) = result.as_ref() {
             // Keep track of pubkeys that had errors
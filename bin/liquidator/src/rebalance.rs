@@ -1,9 +1,12 @@
+use mango_v4::accounts_ix::{Serum3OrderType, Serum3SelfTradeBehavior, Serum3Side};
 use mango_v4::accounts_zerocopy::KeyedAccountSharedData;
 use mango_v4::state::{
-    Bank, BookSide, PlaceOrderType, Side, TokenIndex, TokenPosition, QUOTE_TOKEN_INDEX,
+    Bank, BookSide, MangoAccountValue, PerpMarketIndex, PerpPosition, PlaceOrderType,
+    SelfTradeBehavior, Side, TokenIndex, TokenPosition, QUOTE_TOKEN_INDEX,
 };
 use mango_v4_client::{
-    chain_data, perp_pnl, AccountFetcher, AnyhowWrap, JupiterSwapMode, MangoClient, TokenContext,
+    chain_data, perp_pnl, tx_tracker::TxTracker, AccountFetcher, AnyhowWrap, JupiterSwapMode,
+    MangoClient, PerpMarketContext, TokenContext,
 };
 
 use {fixed::types::I80F48, solana_sdk::pubkey::Pubkey};
@@ -13,15 +16,86 @@ use solana_sdk::signature::Signature;
 use std::sync::Arc;
 use std::{collections::HashMap, time::Duration};
 
+/// Ways of swapping tokens into and out of the quote token
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// Route swaps through the Jupiter aggregator
+    Jupiter,
+    /// Trade with IOC orders on the group's registered Serum3 markets
+    Serum3,
+    /// Hedge with IOC orders on a perp market that uses the token's oracle. The token
+    /// position stays, but the hedge counts towards it. The hedge is kept only while it
+    /// offsets a token position beyond its target.
+    Perp,
+}
+
+impl std::str::FromStr for Backend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "jupiter" => Ok(Backend::Jupiter),
+            "serum3" => Ok(Backend::Serum3),
+            "perp" => Ok(Backend::Perp),
+            _ => anyhow::bail!("unknown rebalance backend '{}'", s),
+        }
+    }
+}
+
+/// Desired inventory of a token or perp base position, in ui units
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Target {
+    pub amount: f64,
+    /// Positions within amount +- tolerance are left alone
+    pub tolerance: f64,
+}
+
+/// A rebalance target for a named token or perp market, parsed from
+/// `NAME:AMOUNT` or `NAME:AMOUNT:TOLERANCE`
+#[derive(Clone, Debug)]
+pub struct NamedTarget {
+    pub name: String,
+    pub target: Target,
+}
+
+impl std::str::FromStr for NamedTarget {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.trim().split(':').collect::<Vec<_>>();
+        let (name, amount, tolerance) = match parts.as_slice() {
+            [name, amount] => (name, amount.parse()?, 0.0),
+            [name, amount, tolerance] => (name, amount.parse()?, tolerance.parse()?),
+            _ => anyhow::bail!("rebalance target '{}' is not NAME:AMOUNT[:TOLERANCE]", s),
+        };
+        anyhow::ensure!(
+            tolerance >= 0.0,
+            "rebalance target '{}' has a negative tolerance",
+            s
+        );
+        Ok(Self {
+            name: name.to_string(),
+            target: Target { amount, tolerance },
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct Config {
-    /// Maximum slippage allowed in Jupiter
+    /// Maximum slippage allowed in Jupiter, also used to set the Serum3 limit price
     pub slippage_bps: u64,
     /// When closing borrows, the rebalancer can't close token positions exactly.
     /// Instead it purchases too much and then gets rid of the excess in a second step.
     /// If this is 1.05, then it'll swap borrow_value * 1.05 quote token into borrow token.
     pub borrow_settle_excess: f64,
     pub refresh_timeout: Duration,
+    /// Backends to swap with, in order. If one fails, the next one is tried.
+    pub backends: Vec<Backend>,
+    /// Token inventory to keep. Tokens without a target are sold for the quote token.
+    pub token_targets: HashMap<TokenIndex, Target>,
+    /// Perp base positions to keep. Markets without a target are closed, unless they
+    /// hedge a token for the perp backend.
+    pub perp_targets: HashMap<PerpMarketIndex, Target>,
 }
 
 #[derive(Debug)]
@@ -72,11 +146,14 @@ pub struct Rebalancer {
     pub mango_client: Arc<MangoClient>,
     pub account_fetcher: Arc<chain_data::AccountFetcher>,
     pub mango_account_address: Pubkey,
+    pub tx_tracker: Arc<TxTracker>,
     pub config: Config,
 }
 
 impl Rebalancer {
-    pub async fn zero_all_non_quote(&self) -> anyhow::Result<()> {
+    /// Trades perp and token positions back into the configured target bands,
+    /// selling everything without a target for the quote token.
+    pub async fn rebalance_to_targets(&self) -> anyhow::Result<()> {
         log::trace!("checking for rebalance: {}", self.mango_account_address);
 
         self.rebalance_perps().await?;
@@ -105,6 +182,317 @@ impl Rebalancer {
         Ok(true)
     }
 
+    /// Swaps `amount` native `input_mint` into `output_mint`, trying the configured
+    /// backends in order until one of them succeeds.
+    async fn swap(
+        &self,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        amount: u64,
+    ) -> anyhow::Result<Signature> {
+        let mut last_error = None;
+        for backend in self.config.backends.iter() {
+            let result = match backend {
+                Backend::Jupiter => {
+                    self.mango_client
+                        .jupiter_swap(
+                            input_mint,
+                            output_mint,
+                            amount,
+                            self.config.slippage_bps,
+                            JupiterSwapMode::ExactIn,
+                        )
+                        .await
+                }
+                Backend::Serum3 => self.serum3_swap(input_mint, output_mint, amount).await,
+                Backend::Perp => self.perp_hedge(input_mint, output_mint, amount).await,
+            };
+            match result {
+                Ok(txsig) => return Ok(txsig),
+                Err(err) => {
+                    log::warn!("rebalance swap with {:?} failed: {:?}", backend, err);
+                    last_error = Some(err);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("no rebalance backends configured")))
+    }
+
+    /// Swaps by sending an IOC order to the Serum3 market between the two tokens,
+    /// limited to the oracle price plus slippage, and settles the proceeds in the
+    /// same transaction.
+    async fn serum3_swap(
+        &self,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        amount: u64,
+    ) -> anyhow::Result<Signature> {
+        let context = &self.mango_client.context;
+        let input_token = context.token_by_mint(&input_mint)?;
+        let output_token = context.token_by_mint(&output_mint)?;
+
+        let (market_index, market_name, side) = context
+            .serum3_markets
+            .iter()
+            .find_map(|(market_index, s3)| {
+                let base = s3.market.base_token_index;
+                let quote = s3.market.quote_token_index;
+                let side = if base == input_token.token_index && quote == output_token.token_index {
+                    Serum3Side::Ask
+                } else if base == output_token.token_index && quote == input_token.token_index {
+                    Serum3Side::Bid
+                } else {
+                    return None;
+                };
+                Some((*market_index, s3.market.name().to_string(), side))
+            })
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "no serum3 market between {} and {}",
+                    input_token.name,
+                    output_token.name
+                )
+            })?;
+        let (base_token, quote_token) = match side {
+            Serum3Side::Ask => (input_token, output_token),
+            Serum3Side::Bid => (output_token, input_token),
+        };
+
+        let account = self.mango_client.mango_account().await?;
+        if account.serum3_orders(market_index).is_err() {
+            let txsig = self
+                .mango_client
+                .serum3_create_open_orders(&market_name)
+                .await?;
+            log::info!(
+                "created serum3 open orders for {} in {}",
+                market_name,
+                txsig
+            );
+            if !self.refresh_mango_account_after_tx(txsig).await? {
+                anyhow::bail!("could not refresh account after creating open orders");
+            }
+        }
+
+        // Serum3 prices and sizes are in ui units
+        let base_price = TokenState::fetch_price(
+            base_token,
+            &TokenState::bank(base_token, &self.account_fetcher)?,
+            &self.account_fetcher,
+        )
+        .await?;
+        let quote_price = TokenState::fetch_price(
+            quote_token,
+            &TokenState::bank(quote_token, &self.account_fetcher)?,
+            &self.account_fetcher,
+        )
+        .await?;
+        let ui_price = (base_price / quote_price).to_num::<f64>()
+            * 10f64.powi(base_token.decimals as i32 - quote_token.decimals as i32);
+        let slippage = self.config.slippage_bps as f64 / 10_000.0;
+        let (limit_price, size) = match side {
+            Serum3Side::Ask => {
+                let limit_price = ui_price * (1.0 - slippage);
+                (limit_price, base_token.native_to_ui(I80F48::from(amount)))
+            }
+            Serum3Side::Bid => {
+                let limit_price = ui_price * (1.0 + slippage);
+                let quote_ui = quote_token.native_to_ui(I80F48::from(amount));
+                (limit_price, quote_ui / limit_price)
+            }
+        };
+
+        let place_ix = self
+            .mango_client
+            .serum3_place_order_instruction(
+                &market_name,
+                side,
+                limit_price,
+                size,
+                Serum3SelfTradeBehavior::DecrementTake,
+                Serum3OrderType::ImmediateOrCancel,
+                0,
+                10,
+            )
+            .await?;
+        let settle_ix = self
+            .mango_client
+            .serum3_settle_funds_instruction(&market_name)
+            .await?;
+        self.mango_client
+            .send_and_confirm_owner_tx(vec![place_ix, settle_ix])
+            .await
+    }
+
+    /// The perp market that can hedge a token: the perp backend must be enabled and the
+    /// market must use the same oracle and have no perp target of its own.
+    fn hedge_market(&self, token: &TokenContext) -> Option<&PerpMarketContext> {
+        if !self.config.backends.contains(&Backend::Perp) {
+            return None;
+        }
+        self.mango_client
+            .context
+            .perp_markets
+            .values()
+            .find(|perp| {
+                perp.market.oracle == token.mint_info.oracle
+                    && !self
+                        .config
+                        .perp_targets
+                        .contains_key(&perp.market.perp_market_index)
+            })
+    }
+
+    /// Converts native perp base units to native token units
+    fn perp_to_token_native_factor(token: &TokenContext, perp: &PerpMarketContext) -> I80F48 {
+        I80F48::from_num(10f64.powi(token.decimals as i32 - perp.market.base_decimals as i32))
+    }
+
+    /// The hedge position on the token's hedge market, in native token units
+    fn hedge_native(&self, account: &MangoAccountValue, token: &TokenContext) -> I80F48 {
+        let perp = match self.hedge_market(token) {
+            Some(v) => v,
+            None => return I80F48::ZERO,
+        };
+        let base_lots = account
+            .perp_position(perp.market.perp_market_index)
+            .map(|pp| pp.effective_base_position_lots())
+            .unwrap_or(0);
+        I80F48::from(base_lots * perp.market.base_lot_size)
+            * Self::perp_to_token_native_factor(token, perp)
+    }
+
+    /// Current native token position and hedge position
+    async fn token_exposure(&self, token: &TokenContext) -> anyhow::Result<(I80F48, I80F48)> {
+        let account = self.mango_client.mango_account().await?;
+        let bank = TokenState::bank(token, &self.account_fetcher)?;
+        let token_native = account
+            .token_position_and_raw_index(token.token_index)
+            .map(|(position, _)| position.native(&bank))
+            .unwrap_or(I80F48::ZERO);
+        Ok((token_native, self.hedge_native(&account, token)))
+    }
+
+    /// Instead of swapping, trades the same base amount on the token's hedge market:
+    /// selling a token opens a short, buying it opens a long.
+    async fn perp_hedge(
+        &self,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        amount: u64,
+    ) -> anyhow::Result<Signature> {
+        let context = &self.mango_client.context;
+        let input_token = context.token_by_mint(&input_mint)?;
+        let output_token = context.token_by_mint(&output_mint)?;
+
+        let (side, token) = if output_token.token_index == QUOTE_TOKEN_INDEX {
+            (Side::Ask, input_token)
+        } else if input_token.token_index == QUOTE_TOKEN_INDEX {
+            (Side::Bid, output_token)
+        } else {
+            anyhow::bail!(
+                "can only hedge swaps from or to the quote token, not {} to {}",
+                input_token.name,
+                output_token.name
+            );
+        };
+        let perp = self
+            .hedge_market(token)
+            .ok_or_else(|| anyhow::anyhow!("no perp market to hedge {}", token.name))?;
+
+        let token_price = TokenState::fetch_price(
+            token,
+            &TokenState::bank(token, &self.account_fetcher)?,
+            &self.account_fetcher,
+        )
+        .await?;
+        // buys are given as a quote amount
+        let token_native = match side {
+            Side::Ask => I80F48::from(amount),
+            Side::Bid => I80F48::from(amount) / token_price,
+        };
+        let base_lots = (token_native
+            / Self::perp_to_token_native_factor(token, perp)
+            / I80F48::from(perp.market.base_lot_size))
+        .floor()
+        .to_num::<i64>();
+        anyhow::ensure!(
+            base_lots > 0,
+            "{} native {} is less than a lot on {}",
+            token_native,
+            token.name,
+            perp.market.name()
+        );
+
+        let oracle_account_data = self.account_fetcher.fetch_raw(&perp.market.oracle)?;
+        let oracle_price = perp.market.oracle_price(
+            &KeyedAccountSharedData::new(perp.market.oracle, oracle_account_data),
+            None,
+        )?;
+        let slippage = I80F48::from_num(self.config.slippage_bps as f64 / 10_000.0);
+        let order_price = match side {
+            Side::Ask => oracle_price * (I80F48::ONE - slippage),
+            Side::Bid => oracle_price * (I80F48::ONE + slippage),
+        };
+        self.mango_client
+            .perp_place_order(
+                perp.market.perp_market_index,
+                side,
+                perp.market.native_price_to_lot(order_price),
+                base_lots,
+                i64::MAX,
+                0,
+                PlaceOrderType::ImmediateOrCancel,
+                false,
+                0,
+                10,
+                SelfTradeBehavior::DecrementTake,
+            )
+            .await
+    }
+
+    /// For a hedge market, the part of the perp position that offsets the hedged token's
+    /// excess over its target, in base lots
+    async fn hedge_target_lots(
+        &self,
+        perp: &PerpMarketContext,
+        effective_lots: i64,
+    ) -> anyhow::Result<Option<i64>> {
+        let token = match self
+            .mango_client
+            .context
+            .tokens
+            .values()
+            .find(|token| token.mint_info.oracle == perp.market.oracle)
+        {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        if self.hedge_market(token).map(|p| p.address) != Some(perp.address) {
+            return Ok(None);
+        }
+
+        let (token_native, _) = self.token_exposure(token).await?;
+        let target = self
+            .config
+            .token_targets
+            .get(&token.token_index)
+            .copied()
+            .unwrap_or_default();
+        let target_native = I80F48::from_num(target.amount * 10f64.powi(token.decimals as i32));
+        let excess_lots = ((token_native - target_native)
+            / Self::perp_to_token_native_factor(token, perp)
+            / I80F48::from(perp.market.base_lot_size))
+        .round_to_zero()
+        .to_num::<i64>();
+        if excess_lots.signum() * effective_lots.signum() >= 0 {
+            return Ok(Some(0));
+        }
+        Ok(Some(
+            effective_lots.signum() * effective_lots.abs().min(excess_lots.abs()),
+        ))
+    }
+
     async fn rebalance_tokens(&self) -> anyhow::Result<()> {
         let account = self
             .account_fetcher
@@ -125,7 +513,25 @@ impl Rebalancer {
                 })
                 .try_collect()
                 .await;
-        let tokens = tokens?;
+        let mut tokens = tokens?;
+
+        // Tokens with a target may need to be bought even without a position
+        for token_index in self.config.token_targets.keys() {
+            if tokens.contains_key(token_index) {
+                continue;
+            }
+            let token = self.mango_client.context.token(*token_index);
+            let bank = TokenState::bank(token, &self.account_fetcher)?;
+            let price = TokenState::fetch_price(token, &bank, &self.account_fetcher).await?;
+            tokens.insert(
+                *token_index,
+                TokenState {
+                    price,
+                    native_position: I80F48::ZERO,
+                    in_use: false,
+                },
+            );
+        }
         log::trace!("account tokens: {:?}", tokens);
 
         for (token_index, token_state) in tokens {
@@ -148,25 +554,45 @@ impl Rebalancer {
             // again withdraw the dust at the end.
             let dust_threshold = I80F48::from(2) / token_state.price;
 
-            let mut amount = token_state.native_position;
+            // Without a target, everything is sold for the quote token. With one, only the
+            // amount outside of the tolerance band is traded.
+            let target = self
+                .config
+                .token_targets
+                .get(&token_index)
+                .copied()
+                .unwrap_or_default();
+            let native_factor = 10f64.powi(token.decimals as i32);
+            let target_native = I80F48::from_num(target.amount * native_factor);
+            let tolerance_native = I80F48::from_num(target.tolerance * native_factor);
+            // Buys overshoot by borrow_settle_excess, that must not trigger a sell
+            let buy_excess_native = I80F48::from_num(
+                target.amount.max(0.0) * native_factor * (self.config.borrow_settle_excess - 1.0),
+            )
+            .max(I80F48::ZERO);
+            let sell_threshold = (buy_excess_native + tolerance_native).max(dust_threshold);
+
+            // A position on the token's hedge market counts towards the token position
+            let mut token_amount = token_state.native_position;
+            let mut amount = token_amount + self.hedge_native(&account, token);
 
-            if amount < 0 {
+            if amount < target_native - tolerance_native {
                 // Buy
-                let buy_amount =
-                    amount.abs().ceil() + (dust_threshold - I80F48::ONE).max(I80F48::ZERO);
+                let buy_amount = (target_native - amount).ceil()
+                    + (dust_threshold - I80F48::ONE).max(I80F48::ZERO);
                 let input_amount = buy_amount
                     * token_state.price
                     * I80F48::from_num(self.config.borrow_settle_excess);
-                let txsig = self
-                    .mango_client
-                    .jupiter_swap(
-                        quote_mint,
-                        token_mint,
-                        input_amount.to_num::<u64>(),
-                        self.config.slippage_bps,
-                        JupiterSwapMode::ExactIn,
-                    )
-                    .await?;
+                let txsig = match self
+                    .tx_tracker
+                    .send_once(&format!("rebalance buy {}", token.name), || {
+                        self.swap(quote_mint, token_mint, input_amount.to_num::<u64>())
+                    })
+                    .await?
+                {
+                    Some(txsig) => txsig,
+                    None => continue,
+                };
                 log::info!(
                     "bought {} {} for {} in tx {}",
                     token.native_to_ui(buy_amount),
@@ -177,31 +603,27 @@ impl Rebalancer {
                 if !self.refresh_mango_account_after_tx(txsig).await? {
                     return Ok(());
                 }
-                let bank = TokenState::bank(token, &self.account_fetcher)?;
-                amount = self
-                    .mango_client
-                    .mango_account()
-                    .await?
-                    .token_position_and_raw_index(token_index)
-                    .map(|(position, _)| position.native(&bank))
-                    .unwrap_or(I80F48::ZERO);
+                let (new_token_amount, hedge_amount) = self.token_exposure(token).await?;
+                token_amount = new_token_amount;
+                amount = token_amount + hedge_amount;
             }
 
-            if amount > dust_threshold {
+            if amount - target_native > sell_threshold {
                 // Sell
-                let txsig = self
-                    .mango_client
-                    .jupiter_swap(
-                        token_mint,
-                        quote_mint,
-                        amount.to_num::<u64>(),
-                        self.config.slippage_bps,
-                        JupiterSwapMode::ExactIn,
-                    )
-                    .await?;
+                let sell_amount = amount - target_native;
+                let txsig = match self
+                    .tx_tracker
+                    .send_once(&format!("rebalance sell {}", token.name), || {
+                        self.swap(token_mint, quote_mint, sell_amount.to_num::<u64>())
+                    })
+                    .await?
+                {
+                    Some(txsig) => txsig,
+                    None => continue,
+                };
                 log::info!(
                     "sold {} {} for {} in tx {}",
-                    token.native_to_ui(amount),
+                    token.native_to_ui(sell_amount),
                     token.name,
                     quote_token.name,
                     txsig
@@ -209,38 +631,46 @@ impl Rebalancer {
                 if !self.refresh_mango_account_after_tx(txsig).await? {
                     return Ok(());
                 }
-                let bank = TokenState::bank(token, &self.account_fetcher)?;
-                amount = self
-                    .mango_client
-                    .mango_account()
-                    .await?
-                    .token_position_and_raw_index(token_index)
-                    .map(|(position, _)| position.native(&bank))
-                    .unwrap_or(I80F48::ZERO);
+                let (new_token_amount, hedge_amount) = self.token_exposure(token).await?;
+                token_amount = new_token_amount;
+                amount = token_amount + hedge_amount;
             }
 
             // Any remainder that could not be sold just gets withdrawn to ensure the
             // TokenPosition is freed up
-            if amount > 0 && amount <= dust_threshold && !token_state.in_use {
+            if target_native == 0
+                && token_amount > 0
+                && token_amount <= dust_threshold
+                && amount == token_amount
+                && !token_state.in_use
+            {
                 let allow_borrow = false;
-                let txsig = self
-                    .mango_client
-                    .token_withdraw(token_mint, u64::MAX, allow_borrow)
-                    .await?;
+                let txsig = match self
+                    .tx_tracker
+                    .send_once(&format!("rebalance withdraw {}", token.name), || {
+                        self.mango_client
+                            .token_withdraw(token_mint, u64::MAX, allow_borrow)
+                    })
+                    .await?
+                {
+                    Some(txsig) => txsig,
+                    None => continue,
+                };
                 log::info!(
                     "withdrew {} {} to liqor wallet in {}",
-                    token.native_to_ui(amount),
+                    token.native_to_ui(token_amount),
                     token.name,
                     txsig
                 );
                 if !self.refresh_mango_account_after_tx(txsig).await? {
                     return Ok(());
                 }
-            } else if amount > dust_threshold {
+            } else if amount - target_native > sell_threshold {
                 anyhow::bail!(
-                    "unexpected {} position after rebalance swap: {} native",
+                    "unexpected {} position after rebalance swap: {} native, target {} native",
                     token.name,
-                    amount
+                    amount,
+                    target_native
                 );
             }
         }
@@ -257,7 +687,21 @@ impl Rebalancer {
             .account_fetcher
             .fetch_mango_account(&self.mango_account_address)?;
 
-        for perp_position in account.active_perp_positions() {
+        // Markets with a target may need a position to be opened
+        let mut perp_positions = account.active_perp_positions().cloned().collect::<Vec<_>>();
+        for market_index in self.config.perp_targets.keys() {
+            if !perp_positions
+                .iter()
+                .any(|pp| pp.market_index == *market_index)
+            {
+                perp_positions.push(PerpPosition {
+                    market_index: *market_index,
+                    ..PerpPosition::default()
+                });
+            }
+        }
+
+        for perp_position in perp_positions.iter() {
             let perp = self.mango_client.context.perp(perp_position.market_index);
             let base_lots = perp_position.base_position_lots();
             let effective_lots = perp_position.effective_base_position_lots();
@@ -270,14 +714,32 @@ impl Rebalancer {
                 quote_native,
             );
 
-            if effective_lots != 0 {
-                // send an ioc order to reduce the base position
+            let target = self
+                .config
+                .perp_targets
+                .get(&perp_position.market_index)
+                .copied()
+                .unwrap_or_default();
+            let ui_to_lots =
+                10f64.powi(perp.market.base_decimals as i32) / perp.market.base_lot_size as f64;
+            let mut target_lots = (target.amount * ui_to_lots).round() as i64;
+            let tolerance_lots = (target.tolerance * ui_to_lots).floor() as i64;
+
+            // A hedge position is kept as long as it offsets a token position beyond
+            // the token target
+            if let Some(hedge_lots) = self.hedge_target_lots(perp, effective_lots).await? {
+                target_lots = hedge_lots;
+            }
+            let excess_lots = effective_lots - target_lots;
+
+            if excess_lots.abs() > tolerance_lots {
+                // send an ioc order to move the base position to the target
                 let oracle_account_data = self.account_fetcher.fetch_raw(&perp.market.oracle)?;
                 let oracle_account =
                     KeyedAccountSharedData::new(perp.market.oracle, oracle_account_data);
                 let oracle_price = perp.market.oracle_price(&oracle_account, None)?;
                 let oracle_price_lots = perp.market.native_price_to_lot(oracle_price);
-                let (side, order_price, oo_lots) = if effective_lots > 0 {
+                let (side, order_price, oo_lots) = if excess_lots > 0 {
                     (
                         Side::Ask,
                         oracle_price * (I80F48::ONE - perp.market.base_liquidation_fee),
@@ -291,13 +753,14 @@ impl Rebalancer {
                     )
                 };
                 let price_lots = perp.market.native_price_to_lot(order_price);
-                let max_base_lots = effective_lots.abs() - oo_lots;
+                let max_base_lots = excess_lots.abs() - oo_lots;
                 if max_base_lots <= 0 {
                     log::warn!(
-                        "cannot place reduce-only order on {} {:?}, base pos: {}, in open orders: {}",
+                        "cannot place rebalance order on {} {:?}, base pos: {}, target: {}, in open orders: {}",
                         perp.market.name(),
                         side,
                         effective_lots,
+                        target_lots,
                         oo_lots,
                     );
                     continue;
@@ -322,25 +785,36 @@ impl Rebalancer {
                     continue;
                 }
 
-                let txsig = self
-                    .mango_client
-                    .perp_place_order(
-                        perp_position.market_index,
-                        side,
-                        price_lots,
-                        max_base_lots,
-                        i64::MAX,
-                        0,
-                        PlaceOrderType::ImmediateOrCancel,
-                        true, // reduce only
-                        0,
-                        10,
-                    )
-                    .await?;
+                // Only moving towards a zero position can be reduce-only
+                let reduce_only =
+                    target_lots * effective_lots >= 0 && target_lots.abs() < effective_lots.abs();
+                let txsig = match self
+                    .tx_tracker
+                    .send_once(&format!("rebalance perp {}", perp.market.name()), || {
+                        self.mango_client.perp_place_order(
+                            perp_position.market_index,
+                            side,
+                            price_lots,
+                            max_base_lots,
+                            i64::MAX,
+                            0,
+                            PlaceOrderType::ImmediateOrCancel,
+                            reduce_only,
+                            0,
+                            10,
+                            SelfTradeBehavior::DecrementTake,
+                        )
+                    })
+                    .await?
+                {
+                    Some(txsig) => txsig,
+                    None => continue,
+                };
                 log::info!(
-                    "attempt to ioc reduce perp base position of {} {} at price {} in {}",
+                    "attempt to ioc rebalance perp base position of {} {} towards {} lots at price {} in {}",
                     perp_position.base_position_native(&perp.market),
                     perp.market.name(),
+                    target_lots,
                     order_price,
                     txsig
                 );
@@ -384,20 +858,37 @@ impl Rebalancer {
                         (&self.mango_account_address, &account),
                     )
                 };
-                let txsig = self
-                    .mango_client
-                    .perp_settle_pnl(perp_position.market_index, account_a, account_b)
-                    .await?;
+                let txsig = match self
+                    .tx_tracker
+                    .send_once(&format!("settle perp {}", perp.market.name()), || {
+                        self.mango_client.perp_settle_pnl(
+                            perp_position.market_index,
+                            account_a,
+                            account_b,
+                        )
+                    })
+                    .await?
+                {
+                    Some(txsig) => txsig,
+                    None => continue,
+                };
                 log::info!("settled perp {} pnl, tx sig {}", perp.market.name(), txsig);
                 if !self.refresh_mango_account_after_tx(txsig).await? {
                     return Ok(());
                 }
             } else if base_lots == 0 && quote_native == 0 {
                 // close perp position
-                let txsig = self
-                    .mango_client
-                    .perp_deactivate_position(perp_position.market_index)
-                    .await?;
+                let txsig = match self
+                    .tx_tracker
+                    .send_once(&format!("deactivate perp {}", perp.market.name()), || {
+                        self.mango_client
+                            .perp_deactivate_position(perp_position.market_index)
+                    })
+                    .await?
+                {
+                    Some(txsig) => txsig,
+                    None => continue,
+                };
                 log::info!(
                     "closed perp position on {} in {}",
                     perp.market.name(),
@@ -406,6 +897,12 @@ impl Rebalancer {
                 if !self.refresh_mango_account_after_tx(txsig).await? {
                     return Ok(());
                 }
+            } else if effective_lots != 0 {
+                log::trace!(
+                    "perp {} position of {} lots is within the target band",
+                    perp.market.name(),
+                    effective_lots
+                );
             } else {
                 // maybe we're still waiting for consume_events
                 log::info!(
@@ -421,3 +918,52 @@ impl Rebalancer {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> anyhow::Result<NamedTarget> {
+        s.parse()
+    }
+
+    #[test]
+    fn test_parse_named_target() -> anyhow::Result<()> {
+        let t = parse("SOL:1.5")?;
+        assert_eq!(t.name, "SOL");
+        assert_eq!(
+            t.target,
+            Target {
+                amount: 1.5,
+                tolerance: 0.0
+            }
+        );
+
+        let t = parse(" SOL-PERP:-2:0.25 ")?;
+        assert_eq!(t.name, "SOL-PERP");
+        assert_eq!(
+            t.target,
+            Target {
+                amount: -2.0,
+                tolerance: 0.25
+            }
+        );
+
+        assert!(parse("SOL").is_err());
+        assert!(parse("SOL:").is_err());
+        assert!(parse("SOL:abc").is_err());
+        assert!(parse("SOL:1:x").is_err());
+        assert!(parse("SOL:1:-0.5").is_err());
+        assert!(parse("SOL:1:2:3").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_backend() -> anyhow::Result<()> {
+        assert_eq!("jupiter".parse::<Backend>()?, Backend::Jupiter);
+        assert_eq!(" Serum3".parse::<Backend>()?, Backend::Serum3);
+        assert_eq!("perp".parse::<Backend>()?, Backend::Perp);
+        assert!("orca".parse::<Backend>().is_err());
+        Ok(())
+    }
+}
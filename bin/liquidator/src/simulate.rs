@@ -0,0 +1,358 @@
+//! Offline liquidation simulation
+//!
+//! Loads a recorded set of accounts into a ChainData, runs the liquidator's
+//! candidate selection on every MangoAccount in the group and prints what it
+//! would have sent. Nothing is ever sent and no rpc connection is needed.
+
+use std::sync::{Arc, RwLock};
+
+use anchor_client::Cluster;
+use clap::Parser;
+use fixed::types::I80F48;
+use mango_v4::accounts_zerocopy::KeyedAccountSharedData;
+use mango_v4::health::{HealthCache, HealthType};
+use mango_v4::state::{MangoAccountValue, PerpMarket};
+use mango_v4_client::{
+    account_update_stream, chain_data, health_cache, keypair_from_cli, snapshot_file, Client,
+    MangoClient, MangoGroupContext, TransactionBuilderConfig,
+};
+use solana_client::nonblocking::rpc_client::RpcClient as RpcClientAsync;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+
+use anyhow::Context;
+
+use crate::liquidate::{self, LiquidationAction};
+use crate::util::is_mango_account;
+
+#[derive(Parser)]
+#[clap()]
+pub struct Cli {
+    /// account snapshot file, as written by the liquidator or settler --snapshot-file option
+    #[clap(long)]
+    snapshot: std::path::PathBuf,
+
+    #[clap(long)]
+    liqor_mango_account: Pubkey,
+
+    /// keypair used as the liqor owner in the printed instructions,
+    /// a random one is used when not provided
+    #[clap(long)]
+    liqor_owner: Option<String>,
+
+    /// liquidator health ratio should not fall below this value
+    #[clap(long, default_value = "50")]
+    min_health_ratio: f64,
+
+    /// only simulate liquidation of these accounts
+    #[clap(long)]
+    account: Vec<Pubkey>,
+}
+
+pub async fn run(cli: Cli) -> anyhow::Result<()> {
+    let snapshot = snapshot_file::read(&cli.snapshot)?;
+    let max_slot = snapshot.iter().map(|u| u.slot).max().unwrap_or(0);
+
+    let chain_data = Arc::new(RwLock::new(chain_data::ChainData::new()));
+    {
+        let mut chain = chain_data.write().unwrap();
+        account_update_stream::Message::Snapshot(snapshot).update_chain_data(&mut chain);
+        // Without a rooted slot none of the account writes would be considered live
+        chain.update_slot(chain_data::SlotData {
+            slot: max_slot,
+            parent: None,
+            status: chain_data::SlotStatus::Rooted,
+            chain: 0,
+        });
+    }
+
+    // The rpc client is never used: all data must come from the snapshot.
+    let account_fetcher = Arc::new(chain_data::AccountFetcher {
+        chain_data: chain_data.clone(),
+        rpc: RpcClientAsync::new(Cluster::Localnet.url().to_string()),
+    });
+
+    let liqor = account_fetcher
+        .fetch_mango_account(&cli.liqor_mango_account)
+        .context("liqor account must be in the snapshot")?;
+    let group = liqor.fixed.group;
+    let group_context = MangoGroupContext::new_from_account_fetcher(&*account_fetcher, group)
+        .await
+        .context("building group context from snapshot")?;
+
+    let liqor_owner = Arc::new(
+        cli.liqor_owner
+            .as_deref()
+            .map(keypair_from_cli)
+            .unwrap_or_else(Keypair::new),
+    );
+    let client = Client::new(
+        Cluster::Localnet,
+        CommitmentConfig::processed(),
+        liqor_owner.clone(),
+        None,
+        TransactionBuilderConfig {
+            prioritization_micro_lamports: None,
+        },
+    );
+    let mango_client = MangoClient::new_detail(
+        client,
+        cli.liqor_mango_account,
+        liqor_owner,
+        group_context,
+        account_fetcher.clone(),
+    )?;
+
+    let liq_config = liquidate::Config {
+        min_health_ratio: cli.min_health_ratio,
+        refresh_timeout: std::time::Duration::ZERO,
+        refresh_liqor_account: false,
+        flash_loan_unwind: false,
+        flash_loan_slippage_bps: 0,
+    };
+
+    let mut accounts = if cli.account.is_empty() {
+        let chain = chain_data.read().unwrap();
+        chain
+            .iter_accounts()
+            .filter(|(_, data)| is_mango_account(&data.account, &group).is_some())
+            .map(|(pk, _)| *pk)
+            .collect::<Vec<Pubkey>>()
+    } else {
+        cli.account.clone()
+    };
+    accounts.sort();
+
+    for pubkey in accounts.iter() {
+        if *pubkey == cli.liqor_mango_account {
+            continue;
+        }
+        if let Err(err) =
+            simulate_account(&mango_client, &account_fetcher, pubkey, &liq_config).await
+        {
+            println!("{pubkey}: error: {err:?}");
+        }
+    }
+
+    Ok(())
+}
+
+async fn simulate_account(
+    mango_client: &MangoClient,
+    account_fetcher: &chain_data::AccountFetcher,
+    pubkey: &Pubkey,
+    config: &liquidate::Config,
+) -> anyhow::Result<()> {
+    let liqee = account_fetcher.fetch_mango_account(pubkey)?;
+    let health_cache = health_cache::new(&mango_client.context, account_fetcher, &liqee)
+        .await
+        .context("creating health cache")?;
+    if !health_cache.is_liquidatable() {
+        return Ok(());
+    }
+
+    println!(
+        "{pubkey}: maint health {}, liq end health {}",
+        health_cache.health(HealthType::Maint),
+        health_cache.health(HealthType::LiquidationEnd)
+    );
+
+    let action = match liquidate::liquidation_action(
+        mango_client,
+        account_fetcher,
+        pubkey,
+        &liqee,
+        &health_cache,
+        config,
+    )
+    .await?
+    {
+        Some(action) => action,
+        None => {
+            println!("  no action");
+            return Ok(());
+        }
+    };
+
+    println!("  {}", action.description());
+    let ix = action.instruction(mango_client, (pubkey, &liqee)).await?;
+    print_instruction(&ix);
+
+    match estimate_health_after(
+        mango_client,
+        account_fetcher,
+        &liqee,
+        &health_cache,
+        &action,
+    )
+    .await?
+    {
+        Some(cache) => println!(
+            "  expected after: maint health {}, liq end health {}",
+            cache.health(HealthType::Maint),
+            cache.health(HealthType::LiquidationEnd)
+        ),
+        None => println!("  expected after: not estimated for this action"),
+    }
+
+    Ok(())
+}
+
+fn print_instruction(ix: &Instruction) {
+    println!("  instruction for program {}", ix.program_id);
+    for meta in ix.accounts.iter() {
+        println!(
+            "    {} {}{}",
+            meta.pubkey,
+            if meta.is_writable { "w" } else { "-" },
+            if meta.is_signer { "s" } else { "-" },
+        );
+    }
+    println!("    data: {}", bs58::encode(&ix.data).into_string());
+}
+
+async fn fetch_perp_market(
+    mango_client: &MangoClient,
+    account_fetcher: &chain_data::AccountFetcher,
+    perp_market_index: mango_v4::state::PerpMarketIndex,
+) -> anyhow::Result<(PerpMarket, I80F48)> {
+    let perp = mango_client.context.perp(perp_market_index);
+    let perp_market = account_fetcher.fetch::<PerpMarket>(&perp.address)?;
+    let oracle = account_fetcher.fetch_raw(&perp_market.oracle)?;
+    let price = perp_market.oracle_price(
+        &KeyedAccountSharedData::new(perp_market.oracle, oracle),
+        None,
+    )?;
+    Ok((perp_market, price))
+}
+
+/// Approximates the liqee's health after the action executed.
+///
+/// This mirrors the amount computations in the program's liquidation instructions
+/// in a simplified way: it bounds transfers by the liqee's positions, the action's
+/// max amounts and the amount needed to bring liquidation end health to zero.
+/// Returns None for actions whose effect is not modeled.
+async fn estimate_health_after(
+    mango_client: &MangoClient,
+    account_fetcher: &chain_data::AccountFetcher,
+    liqee: &MangoAccountValue,
+    health_cache: &HealthCache,
+    action: &LiquidationAction,
+) -> anyhow::Result<Option<HealthCache>> {
+    let mut cache = health_cache.clone();
+    let liq_end_health = health_cache.health(HealthType::LiquidationEnd);
+
+    match *action {
+        // Cancelling serum orders moves reserved funds back to the free balances,
+        // the health computation already assumes the worst case for them.
+        LiquidationAction::Serum3ForceCancelOrders { .. } => return Ok(None),
+        LiquidationAction::PerpForceCancelOrders { perp_market_index } => {
+            let (perp_market, _) =
+                fetch_perp_market(mango_client, account_fetcher, perp_market_index).await?;
+            let mut perp_position = liqee.perp_position(perp_market_index)?.clone();
+            perp_position.bids_base_lots = 0;
+            perp_position.asks_base_lots = 0;
+            cache.recompute_perp_info(&perp_position, &perp_market)?;
+        }
+        LiquidationAction::PerpLiqBaseOrPositivePnl {
+            perp_market_index,
+            max_base_transfer,
+            ..
+        } => {
+            let (perp_market, oracle_price) =
+                fetch_perp_market(mango_client, account_fetcher, perp_market_index).await?;
+            let mut perp_position = liqee.perp_position(perp_market_index)?.clone();
+            let base_lots = perp_position.base_position_lots();
+            let base_lot_size = I80F48::from(perp_market.base_lot_size);
+            let price_per_lot = oracle_price * base_lot_size;
+
+            // see perp_liq_base_or_positive_pnl::liquidation_action
+            let (fee_factor, health_per_lot) = if base_lots > 0 {
+                let fee_factor = I80F48::ONE - perp_market.base_liquidation_fee;
+                (
+                    fee_factor,
+                    price_per_lot * (fee_factor - perp_market.init_base_asset_weight),
+                )
+            } else {
+                let fee_factor = I80F48::ONE + perp_market.base_liquidation_fee;
+                (
+                    fee_factor,
+                    price_per_lot * (perp_market.init_base_liab_weight - fee_factor),
+                )
+            };
+            let lots_needed = if health_per_lot > 0 {
+                (-liq_end_health / health_per_lot)
+                    .ceil()
+                    .max(I80F48::ZERO)
+                    .to_num::<i64>()
+            } else {
+                0
+            };
+            let transfer = lots_needed
+                .min(base_lots.abs())
+                .min(max_base_transfer.abs())
+                * base_lots.signum();
+
+            perp_position.base_position_lots -= transfer;
+            perp_position.quote_position_native +=
+                I80F48::from(transfer) * price_per_lot * fee_factor;
+            cache.recompute_perp_info(&perp_position, &perp_market)?;
+        }
+        LiquidationAction::PerpLiqNegativePnlOrBankruptcy {
+            perp_market_index, ..
+        } => {
+            let (perp_market, _) =
+                fetch_perp_market(mango_client, account_fetcher, perp_market_index).await?;
+            let settle_bank = mango_client
+                .first_bank(perp_market.settle_token_index)
+                .await?;
+            let mut perp_position = liqee.perp_position(perp_market_index)?.clone();
+            let pnl = perp_position.quote_position_native();
+            if pnl >= 0 {
+                return Ok(Some(cache));
+            }
+
+            // The liqor takes over the negative pnl in exchange for the liqee's settle tokens
+            let settle = (-pnl).min(health_cache.perp_settle_health().max(I80F48::ZERO));
+            perp_position.quote_position_native += settle;
+            cache.adjust_token_balance(&settle_bank, -settle)?;
+
+            // Anything beyond that is covered by the insurance fund or socialized,
+            // if the account is bankrupt
+            if health_cache.in_phase3_liquidation() && !cache.has_spot_assets() {
+                perp_position.quote_position_native = I80F48::ZERO;
+            }
+            cache.recompute_perp_info(&perp_position, &perp_market)?;
+        }
+        LiquidationAction::TokenLiqWithToken {
+            asset_token_index,
+            liab_token_index,
+            max_liab_transfer,
+            ..
+        } => {
+            let asset_bank = mango_client.first_bank(asset_token_index).await?;
+            let liab_bank = mango_client.first_bank(liab_token_index).await?;
+            let (liab_transfer, asset_transfer) = liquidate::token_liq_transfer(
+                health_cache,
+                &asset_bank,
+                &liab_bank,
+                max_liab_transfer,
+            )?;
+
+            cache.adjust_token_balance(&liab_bank, liab_transfer)?;
+            cache.adjust_token_balance(&asset_bank, -asset_transfer)?;
+        }
+        LiquidationAction::TokenLiqBankruptcy {
+            liab_token_index, ..
+        } => {
+            // Whatever the insurance fund doesn't cover gets socialized: the liab is gone
+            let liab_bank = mango_client.first_bank(liab_token_index).await?;
+            let liab_native = health_cache.token_info(liab_token_index)?.balance_native;
+            cache.adjust_token_balance(&liab_bank, -liab_native.min(I80F48::ZERO))?;
+        }
+    }
+
+    Ok(Some(cache))
+}
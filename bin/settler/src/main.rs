@@ -7,8 +7,9 @@ use clap::Parser;
 use log::*;
 use mango_v4::state::{PerpMarketIndex, TokenIndex};
 use mango_v4_client::{
-    account_update_stream, chain_data, keypair_from_cli, snapshot_source, websocket_source,
-    AsyncChannelSendUnlessFull, Client, MangoClient, MangoGroupContext, TransactionBuilderConfig,
+    account_update_stream, chain_data, keypair_from_cli, snapshot_file, snapshot_source,
+    websocket_source, AsyncChannelSendUnlessFull, Client, MangoClient, MangoGroupContext,
+    TransactionBuilderConfig,
 };
 
 use itertools::Itertools;
@@ -67,6 +68,20 @@ struct Cli {
     /// prioritize each transaction with this many microlamports/cu
     #[clap(long, env, default_value = "0")]
     prioritization_micro_lamports: u64,
+
+    /// record the account state to this file after every snapshot and, if it
+    /// exists on startup, load it while waiting for the first rpc snapshot
+    #[clap(long, env)]
+    snapshot_file: Option<std::path::PathBuf>,
+
+    /// ignore the snapshot file on startup if its newest account is older than
+    /// this many slots
+    #[clap(long, env, default_value = "300")]
+    snapshot_file_max_age_slots: u64,
+
+    /// address lookup tables to use in addition to the group's, comma separated
+    #[clap(long, env, value_delimiter = ',')]
+    lookup_tables: Vec<Pubkey>,
 }
 
 pub fn encode_address(addr: &Pubkey) -> String {
@@ -158,6 +173,20 @@ async fn main() -> anyhow::Result<()> {
     )
     .await?;
 
+    // Initial account state from a previous run, if available and recent enough.
+    // The first rpc snapshot is still requested right away.
+    if let Some(path) = cli.snapshot_file.as_ref().filter(|path| path.exists()) {
+        let min_slot = first_websocket_slot.saturating_sub(cli.snapshot_file_max_age_slots);
+        match snapshot_file::replay(path, min_slot, &account_update_sender).await {
+            Ok(count) => info!(
+                "loaded {} accounts from snapshot file {}",
+                count,
+                path.display()
+            ),
+            Err(err) => warn!("not using snapshot file: {:?}", err),
+        }
+    }
+
     // Getting solana account snapshots via jsonrpc
     // FUTURE: of what to fetch a snapshot - should probably take as an input
     snapshot_source::start(
@@ -189,6 +218,9 @@ async fn main() -> anyhow::Result<()> {
             account_fetcher.clone(),
         )?)
     };
+    for table in cli.lookup_tables.iter() {
+        mango_client.alt_cache.add_private_table(*table);
+    }
 
     let settle_config = settle::Config {
         settle_cooldown: std::time::Duration::from_secs(10),
@@ -219,6 +251,9 @@ async fn main() -> anyhow::Result<()> {
         let mut oracles = HashSet::<Pubkey>::new();
         let mut perp_markets = HashMap::<PerpMarketIndex, Pubkey>::new();
 
+        let snapshot_file_path = cli.snapshot_file.clone();
+        let mut recorder = snapshot_file::Recorder::default();
+
         async move {
             loop {
                 let message = account_update_receiver
@@ -228,6 +263,9 @@ async fn main() -> anyhow::Result<()> {
                 metric_account_update_queue_len.set(account_update_receiver.len() as u64);
 
                 message.update_chain_data(&mut chain_data.write().unwrap());
+                if snapshot_file_path.is_some() {
+                    recorder.record(&message);
+                }
 
                 match message {
                     Message::Account(account_write) => {
@@ -289,6 +327,10 @@ async fn main() -> anyhow::Result<()> {
                         state.one_snapshot_done = true;
                         state.health_check_all = true;
 
+                        if let Some(path) = snapshot_file_path.as_ref() {
+                            recorder.write_in_background(path);
+                        }
+
                         settle_trigger_sender.send_unless_full(()).unwrap();
                     }
                     _ => {}
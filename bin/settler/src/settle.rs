@@ -179,7 +179,7 @@ impl SettlementState {
                 account_fetcher,
                 perp_market_index,
                 instructions: Vec::new(),
-                max_batch_size: 8, // the 1.4M max CU limit if we assume settle ix can be up to around 150k
+                max_batch_size: 9, // the 1.4M max CU limit if we assume settle ix can be up to around 150k
                 blockhash: mango_client
                     .client
                     .rpc_async()
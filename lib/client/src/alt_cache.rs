@@ -0,0 +1,201 @@
+//! Cache of the address lookup tables used when building transactions.
+//!
+//! The group's list of lookup tables can change with alt_set and the tables
+//! themselves grow with alt_extend, so the cache re-fetches everything once it's
+//! older than the refresh interval. Bots can add private lookup tables, for example
+//! with accounts they often interact with, to fit more instructions per transaction.
+//!
+//! Tables that can't be fetched or parsed are skipped with a warning, keeping their
+//! last good version if there is one. A refresh that fails as a whole keeps all
+//! previously fetched tables.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use itertools::Itertools;
+use mango_v4::state::Group;
+use solana_address_lookup_table_program::state::AddressLookupTable;
+use solana_client::nonblocking::rpc_client::RpcClient as RpcClientAsync;
+use solana_sdk::address_lookup_table_account::AddressLookupTableAccount;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::gpa::fetch_anchor_account;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Config {
+    pub refresh_interval: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            refresh_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Default)]
+struct AltCacheState {
+    group_tables: Vec<Pubkey>,
+    private_tables: Vec<Pubkey>,
+    tables: Vec<AddressLookupTableAccount>,
+    /// None if the tables need to be fetched before the next use
+    last_refresh: Option<Instant>,
+}
+
+pub struct AltCache {
+    config: Config,
+    rpc: RpcClientAsync,
+    group: Pubkey,
+    state: RwLock<AltCacheState>,
+    /// Held while refreshing, so concurrent users wait for one refresh
+    refresh_lock: tokio::sync::Mutex<()>,
+}
+
+impl AltCache {
+    pub fn new(
+        rpc: RpcClientAsync,
+        group: Pubkey,
+        group_tables: Vec<Pubkey>,
+        config: Config,
+    ) -> Self {
+        Self {
+            config,
+            rpc,
+            group,
+            state: RwLock::new(AltCacheState {
+                group_tables,
+                ..Default::default()
+            }),
+            refresh_lock: Default::default(),
+        }
+    }
+
+    /// Adds a lookup table that is used in addition to the group's tables
+    pub fn add_private_table(&self, address: Pubkey) {
+        let mut state = self.state.write().unwrap();
+        if !state.private_tables.contains(&address) {
+            state.private_tables.push(address);
+            state.last_refresh = None;
+        }
+    }
+
+    pub fn private_tables(&self) -> Vec<Pubkey> {
+        self.state.read().unwrap().private_tables.clone()
+    }
+
+    /// Forces a refresh on the next use, for example after sending alt_set or alt_extend
+    pub fn invalidate(&self) {
+        self.state.write().unwrap().last_refresh = None;
+    }
+
+    /// Re-fetches the group's list of lookup tables and the contents of all tables
+    pub async fn refresh(&self) -> anyhow::Result<()> {
+        let _guard = self.refresh_lock.lock().await;
+        self.refresh_locked().await
+    }
+
+    async fn refresh_locked(&self) -> anyhow::Result<()> {
+        let group: Group = fetch_anchor_account(&self.rpc, &self.group)
+            .await
+            .context("fetching group for address lookup tables")?;
+        let group_tables = group
+            .address_lookup_tables
+            .iter()
+            .filter(|&&k| k != Pubkey::default())
+            .cloned()
+            .collect::<Vec<Pubkey>>();
+        let private_tables = self.private_tables();
+
+        let addresses = group_tables
+            .iter()
+            .chain(private_tables.iter())
+            .unique()
+            .cloned()
+            .collect::<Vec<Pubkey>>();
+        let accounts = self
+            .rpc
+            .get_multiple_accounts(&addresses)
+            .await
+            .context("fetching address lookup tables")?;
+
+        let mut previous = self
+            .state
+            .read()
+            .unwrap()
+            .tables
+            .iter()
+            .map(|table| (table.key, table.clone()))
+            .collect::<HashMap<_, _>>();
+        let mut tables = vec![];
+        for (address, account) in addresses.iter().zip(accounts.into_iter()) {
+            let parsed = account
+                .ok_or_else(|| anyhow::anyhow!("address lookup table {} not found", address))
+                .and_then(|account| {
+                    AddressLookupTable::deserialize(&account.data)
+                        .map(|table| table.addresses.to_vec())
+                        .with_context(|| format!("deserializing address lookup table {}", address))
+                });
+            match parsed {
+                Ok(addresses) => tables.push(AddressLookupTableAccount {
+                    key: *address,
+                    addresses,
+                }),
+                Err(err) => {
+                    let fallback = previous.remove(address);
+                    log::warn!(
+                        "skipping address lookup table {}, keeping previous version: {}: {:?}",
+                        address,
+                        fallback.is_some(),
+                        err
+                    );
+                    tables.extend(fallback);
+                }
+            }
+        }
+
+        let mut state = self.state.write().unwrap();
+        state.group_tables = group_tables;
+        state.tables = tables;
+        state.last_refresh = Some(Instant::now());
+        Ok(())
+    }
+
+    fn is_stale(&self) -> bool {
+        match self.state.read().unwrap().last_refresh {
+            Some(last_refresh) => last_refresh.elapsed() > self.config.refresh_interval,
+            None => true,
+        }
+    }
+
+    /// The group's lookup tables followed by the private ones, refreshed if stale.
+    ///
+    /// If refreshing fails, the previously fetched tables are returned. Fails only if
+    /// there are none.
+    pub async fn tables(&self) -> anyhow::Result<Vec<AddressLookupTableAccount>> {
+        if self.is_stale() {
+            let _guard = self.refresh_lock.lock().await;
+            // another caller may have refreshed while this one was waiting
+            if self.is_stale() {
+                if let Err(err) = self.refresh_locked().await {
+                    let state = self.state.read().unwrap();
+                    if state.last_refresh.is_none() && state.tables.is_empty() {
+                        return Err(err);
+                    }
+                    log::warn!(
+                        "could not refresh address lookup tables, using previous ones: {:?}",
+                        err
+                    );
+                }
+            }
+        }
+        Ok(self.state.read().unwrap().tables.clone())
+    }
+
+    /// Addresses of the group's lookup tables as of the last refresh
+    pub fn group_tables(&self) -> Vec<Pubkey> {
+        self.state.read().unwrap().group_tables.clone()
+    }
+}
@@ -15,10 +15,12 @@ use fixed::types::I80F48;
 use futures::{stream, StreamExt, TryStreamExt};
 use itertools::Itertools;
 
-use mango_v4::accounts_ix::{Serum3OrderType, Serum3SelfTradeBehavior, Serum3Side};
+use mango_v4::accounts_ix::{
+    PerpCancelOrders, PerpNewOrder, Serum3OrderType, Serum3SelfTradeBehavior, Serum3Side,
+};
 use mango_v4::state::{
-    Bank, Group, MangoAccountValue, PerpMarketIndex, PlaceOrderType, Serum3MarketIndex, Side,
-    TokenIndex, INSURANCE_TOKEN_INDEX,
+    Bank, Group, MangoAccountValue, PerpMarketIndex, PlaceOrderType, SelfTradeBehavior,
+    Serum3MarketIndex, Side, TokenIndex, INSURANCE_TOKEN_INDEX,
 };
 
 use solana_address_lookup_table_program::state::AddressLookupTable;
@@ -31,9 +33,13 @@ use solana_sdk::signer::keypair;
 use solana_sdk::transaction::TransactionError;
 
 use crate::account_fetcher::*;
+use crate::alt_cache::{self, AltCache};
 use crate::context::{MangoGroupContext, Serum3MarketContext, TokenContext};
+use crate::flash_loan::FlashLoanBuilder;
 use crate::gpa::{fetch_anchor_account, fetch_mango_accounts};
 use crate::jupiter;
+use crate::max_size;
+use crate::util::AnyhowWrap;
 
 use anyhow::Context;
 use solana_sdk::account::ReadableAccount;
@@ -105,6 +111,9 @@ pub struct MangoClient {
 
     pub context: MangoGroupContext,
 
+    /// The group's and any private address lookup tables for building transactions
+    pub alt_cache: AltCache,
+
     pub http_client: reqwest::Client,
 }
 
@@ -259,12 +268,19 @@ impl MangoClient {
         group_context: MangoGroupContext,
         account_fetcher: Arc<dyn AccountFetcher>,
     ) -> anyhow::Result<Self> {
+        let alt_cache = AltCache::new(
+            client.rpc_async(),
+            group_context.group,
+            group_context.address_lookup_tables.clone(),
+            alt_cache::Config::default(),
+        );
         Ok(Self {
             client,
             account_fetcher,
             owner,
             mango_account_address: account,
             context: group_context,
+            alt_cache,
             http_client: reqwest::Client::new(),
         })
     }
@@ -287,6 +303,66 @@ impl MangoClient {
         account_fetcher_fetch_anchor_account(&*self.account_fetcher, &bank_address).await
     }
 
+    /// Max native amount of `source` the account can swap for `target` while keeping
+    /// the init health ratio above `min_health_ratio`, see max_size::max_swap_source()
+    pub async fn max_swap_source(
+        &self,
+        source: TokenIndex,
+        target: TokenIndex,
+        price: Option<I80F48>,
+        min_health_ratio: I80F48,
+    ) -> anyhow::Result<I80F48> {
+        let account = self.mango_account().await?;
+        max_size::max_swap_source(
+            &self.context,
+            &*self.account_fetcher,
+            &account,
+            source,
+            target,
+            price,
+            min_health_ratio,
+        )
+        .await
+    }
+
+    /// Max native amount of a token the account can withdraw, borrowing if needed
+    pub async fn max_borrow(
+        &self,
+        token_index: TokenIndex,
+        min_health_ratio: I80F48,
+    ) -> anyhow::Result<I80F48> {
+        let account = self.mango_account().await?;
+        max_size::max_borrow(
+            &self.context,
+            &*self.account_fetcher,
+            &account,
+            token_index,
+            min_health_ratio,
+        )
+        .await
+    }
+
+    /// Max base lots the account can buy or sell on a perp market, see max_size::max_perp()
+    pub async fn max_perp(
+        &self,
+        perp_market_index: PerpMarketIndex,
+        side: Side,
+        price: Option<I80F48>,
+        min_health_ratio: I80F48,
+    ) -> anyhow::Result<i64> {
+        let account = self.mango_account().await?;
+        max_size::max_perp(
+            &self.context,
+            &*self.account_fetcher,
+            &account,
+            perp_market_index,
+            side,
+            price,
+            min_health_ratio,
+        )
+        .await
+    }
+
     pub async fn derive_health_check_remaining_account_metas(
         &self,
         affected_tokens: Vec<TokenIndex>,
@@ -412,6 +488,47 @@ impl MangoClient {
         self.send_and_confirm_owner_tx(ixs).await
     }
 
+    /// Moves tokens from this account to another account of the same owner
+    pub async fn token_transfer(
+        &self,
+        mint: Pubkey,
+        amount: u64,
+        allow_borrow: bool,
+        destination_account: Pubkey,
+    ) -> anyhow::Result<Signature> {
+        let token = self.context.token_by_mint(&mint)?;
+        let token_index = token.token_index;
+        let mint_info = token.mint_info;
+
+        let health_check_metas = self
+            .derive_health_check_remaining_account_metas(vec![token_index], vec![], vec![])
+            .await?;
+
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::TokenTransfer {
+                        group: self.group(),
+                        source_account: self.mango_account_address,
+                        destination_account,
+                        owner: self.owner(),
+                        bank: mint_info.first_bank(),
+                        oracle: mint_info.oracle,
+                    },
+                    None,
+                );
+                ams.extend(health_check_metas.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::TokenTransfer {
+                amount,
+                allow_borrow,
+            }),
+        };
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
     pub async fn get_oracle_price(
         &self,
         token_name: &str,
@@ -501,7 +618,7 @@ impl MangoClient {
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub async fn serum3_place_order(
+    pub async fn serum3_place_order_instruction(
         &self,
         name: &str,
         side: Serum3Side,
@@ -511,7 +628,7 @@ impl MangoClient {
         order_type: Serum3OrderType,
         client_order_id: u64,
         limit: u16,
-    ) -> anyhow::Result<Signature> {
+    ) -> anyhow::Result<Instruction> {
         let s3 = self.serum3_data_by_market_name(name)?;
 
         let account = self.mango_account().await?;
@@ -622,10 +739,37 @@ impl MangoClient {
                 limit,
             }),
         };
+        Ok(ix)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn serum3_place_order(
+        &self,
+        name: &str,
+        side: Serum3Side,
+        price: f64,
+        size: f64,
+        self_trade_behavior: Serum3SelfTradeBehavior,
+        order_type: Serum3OrderType,
+        client_order_id: u64,
+        limit: u16,
+    ) -> anyhow::Result<Signature> {
+        let ix = self
+            .serum3_place_order_instruction(
+                name,
+                side,
+                price,
+                size,
+                self_trade_behavior,
+                order_type,
+                client_order_id,
+                limit,
+            )
+            .await?;
         self.send_and_confirm_owner_tx(vec![ix]).await
     }
 
-    pub async fn serum3_settle_funds(&self, name: &str) -> anyhow::Result<Signature> {
+    pub async fn serum3_settle_funds_instruction(&self, name: &str) -> anyhow::Result<Instruction> {
         let s3 = self.serum3_data_by_market_name(name)?;
 
         let account = self.mango_account().await?;
@@ -655,6 +799,11 @@ impl MangoClient {
             ),
             data: anchor_lang::InstructionData::data(&mango_v4::instruction::Serum3SettleFunds {}),
         };
+        Ok(ix)
+    }
+
+    pub async fn serum3_settle_funds(&self, name: &str) -> anyhow::Result<Signature> {
+        let ix = self.serum3_settle_funds_instruction(name).await?;
         self.send_and_confirm_owner_tx(vec![ix]).await
     }
 
@@ -693,12 +842,12 @@ impl MangoClient {
         Ok(orders)
     }
 
-    pub async fn serum3_liq_force_cancel_orders(
+    pub fn serum3_liq_force_cancel_orders_instruction(
         &self,
         liqee: (&Pubkey, &MangoAccountValue),
         market_index: Serum3MarketIndex,
         open_orders: &Pubkey,
-    ) -> anyhow::Result<Signature> {
+    ) -> anyhow::Result<Instruction> {
         let s3 = self.serum3_data_by_market_index(market_index)?;
 
         let health_remaining_ams = self
@@ -706,7 +855,7 @@ impl MangoClient {
             .derive_health_check_remaining_account_metas(liqee.1, vec![], vec![], vec![])
             .unwrap();
 
-        let ix = Instruction {
+        Ok(Instruction {
             program_id: mango_v4::id(),
             accounts: {
                 let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
@@ -737,7 +886,17 @@ impl MangoClient {
             data: anchor_lang::InstructionData::data(
                 &mango_v4::instruction::Serum3LiqForceCancelOrders { limit: 5 },
             ),
-        };
+        })
+    }
+
+    pub async fn serum3_liq_force_cancel_orders(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        market_index: Serum3MarketIndex,
+        open_orders: &Pubkey,
+    ) -> anyhow::Result<Signature> {
+        let ix =
+            self.serum3_liq_force_cancel_orders_instruction(liqee, market_index, open_orders)?;
         self.send_and_confirm_permissionless_tx(vec![ix]).await
     }
 
@@ -782,7 +941,8 @@ impl MangoClient {
     //
     // Perps
     //
-    pub async fn perp_place_order(
+    #[allow(clippy::too_many_arguments)]
+    pub async fn perp_place_order_instruction(
         &self,
         market_index: PerpMarketIndex,
         side: Side,
@@ -794,7 +954,8 @@ impl MangoClient {
         reduce_only: bool,
         expiry_timestamp: u64,
         limit: u8,
-    ) -> anyhow::Result<Signature> {
+        self_trade_behavior: SelfTradeBehavior,
+    ) -> anyhow::Result<Instruction> {
         let perp = self.context.perp(market_index);
 
         let health_check_metas = self
@@ -809,22 +970,24 @@ impl MangoClient {
             program_id: mango_v4::id(),
             accounts: {
                 let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
-                    &mango_v4::accounts::PerpPlaceOrder {
-                        group: self.group(),
-                        account: self.mango_account_address,
-                        owner: self.owner(),
-                        perp_market: perp.address,
-                        bids: perp.market.bids,
-                        asks: perp.market.asks,
-                        event_queue: perp.market.event_queue,
-                        oracle: perp.market.oracle,
+                    &mango_v4::accounts::PerpPlaceOrderV2 {
+                        v1: mango_v4::accounts::PerpPlaceOrder {
+                            group: self.group(),
+                            account: self.mango_account_address,
+                            owner: self.owner(),
+                            perp_market: perp.address,
+                            bids: perp.market.bids,
+                            asks: perp.market.asks,
+                            event_queue: perp.market.event_queue,
+                            oracle: perp.market.oracle,
+                        },
                     },
                     None,
                 );
                 ams.extend(health_check_metas.into_iter());
                 ams
             },
-            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpPlaceOrder {
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpPlaceOrderV2 {
                 side,
                 price_lots,
                 max_base_lots,
@@ -834,8 +997,113 @@ impl MangoClient {
                 reduce_only,
                 expiry_timestamp,
                 limit,
+                self_trade_behavior,
             }),
         };
+        Ok(ix)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn perp_place_order(
+        &self,
+        market_index: PerpMarketIndex,
+        side: Side,
+        price_lots: i64,
+        max_base_lots: i64,
+        max_quote_lots: i64,
+        client_order_id: u64,
+        order_type: PlaceOrderType,
+        reduce_only: bool,
+        expiry_timestamp: u64,
+        limit: u8,
+        self_trade_behavior: SelfTradeBehavior,
+    ) -> anyhow::Result<Signature> {
+        let ix = self
+            .perp_place_order_instruction(
+                market_index,
+                side,
+                price_lots,
+                max_base_lots,
+                max_quote_lots,
+                client_order_id,
+                order_type,
+                reduce_only,
+                expiry_timestamp,
+                limit,
+                self_trade_behavior,
+            )
+            .await?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Cancels orders and places new ones atomically, with a single health check.
+    ///
+    /// Useful for market makers updating all their quotes in one transaction.
+    pub async fn perp_cancel_replace_orders_instruction(
+        &self,
+        market_index: PerpMarketIndex,
+        cancel: PerpCancelOrders,
+        new_orders: Vec<PerpNewOrder>,
+        limit: u8,
+        self_trade_behavior: SelfTradeBehavior,
+    ) -> anyhow::Result<Instruction> {
+        let perp = self.context.perp(market_index);
+
+        let health_check_metas = self
+            .derive_health_check_remaining_account_metas(
+                vec![],
+                vec![],
+                vec![perp.market.perp_market_index],
+            )
+            .await?;
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpCancelReplaceOrders {
+                        group: self.group(),
+                        account: self.mango_account_address,
+                        owner: self.owner(),
+                        perp_market: perp.address,
+                        bids: perp.market.bids,
+                        asks: perp.market.asks,
+                        event_queue: perp.market.event_queue,
+                        oracle: perp.market.oracle,
+                    },
+                    None,
+                );
+                ams.extend(health_check_metas.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpCancelReplaceOrders {
+                    cancel,
+                    new_orders,
+                    limit,
+                    self_trade_behavior,
+                },
+            ),
+        })
+    }
+
+    pub async fn perp_cancel_replace_orders(
+        &self,
+        market_index: PerpMarketIndex,
+        cancel: PerpCancelOrders,
+        new_orders: Vec<PerpNewOrder>,
+        limit: u8,
+        self_trade_behavior: SelfTradeBehavior,
+    ) -> anyhow::Result<Signature> {
+        let ix = self
+            .perp_cancel_replace_orders_instruction(
+                market_index,
+                cancel,
+                new_orders,
+                limit,
+                self_trade_behavior,
+            )
+            .await?;
         self.send_and_confirm_owner_tx(vec![ix]).await
     }
 
@@ -924,11 +1192,74 @@ impl MangoClient {
         self.send_and_confirm_permissionless_tx(vec![ix]).await
     }
 
-    pub async fn perp_liq_force_cancel_orders(
+    /// `limit` is the maximum number of book orders to match, the program requires
+    /// at least TRIGGER_ORDER_MIN_MATCH_LIMIT (4).
+    pub fn perp_execute_trigger_order_instruction(
+        &self,
+        account: (&Pubkey, &MangoAccountValue),
+        trigger_order_id: u64,
+        limit: u8,
+    ) -> anyhow::Result<Instruction> {
+        let (trigger_order, _) = account
+            .1
+            .perp_trigger_order_and_raw_index(trigger_order_id)?;
+        let perp = self.context.perp(trigger_order.market);
+        let settle_token_index = perp.market.settle_token_index;
+        let settlement_token = self.context.token(settle_token_index);
+
+        // the incentive is paid in the settle token
+        let health_remaining_ams = self.context.derive_health_check_remaining_account_metas(
+            account.1,
+            vec![settle_token_index],
+            vec![settle_token_index],
+            vec![perp.market.perp_market_index],
+        )?;
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpExecuteTriggerOrder {
+                        group: self.group(),
+                        account: *account.0,
+                        executor: self.mango_account_address,
+                        executor_owner: self.owner(),
+                        perp_market: perp.address,
+                        bids: perp.market.bids,
+                        asks: perp.market.asks,
+                        event_queue: perp.market.event_queue,
+                        oracle: perp.market.oracle,
+                        settle_bank: settlement_token.mint_info.first_bank(),
+                    },
+                    None,
+                );
+                ams.extend(health_remaining_ams.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpExecuteTriggerOrder {
+                    trigger_order_id,
+                    limit,
+                },
+            ),
+        })
+    }
+
+    pub async fn perp_execute_trigger_order(
+        &self,
+        account: (&Pubkey, &MangoAccountValue),
+        trigger_order_id: u64,
+        limit: u8,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_execute_trigger_order_instruction(account, trigger_order_id, limit)?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn perp_liq_force_cancel_orders_instruction(
         &self,
         liqee: (&Pubkey, &MangoAccountValue),
         market_index: PerpMarketIndex,
-    ) -> anyhow::Result<Signature> {
+    ) -> anyhow::Result<Instruction> {
         let perp = self.context.perp(market_index);
 
         let health_remaining_ams = self
@@ -936,7 +1267,7 @@ impl MangoClient {
             .derive_health_check_remaining_account_metas(liqee.1, vec![], vec![], vec![])
             .unwrap();
 
-        let ix = Instruction {
+        Ok(Instruction {
             program_id: mango_v4::id(),
             accounts: {
                 let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
@@ -955,17 +1286,25 @@ impl MangoClient {
             data: anchor_lang::InstructionData::data(
                 &mango_v4::instruction::PerpLiqForceCancelOrders { limit: 5 },
             ),
-        };
+        })
+    }
+
+    pub async fn perp_liq_force_cancel_orders(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        market_index: PerpMarketIndex,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_liq_force_cancel_orders_instruction(liqee, market_index)?;
         self.send_and_confirm_permissionless_tx(vec![ix]).await
     }
 
-    pub async fn perp_liq_base_or_positive_pnl(
+    pub async fn perp_liq_base_or_positive_pnl_instruction(
         &self,
         liqee: (&Pubkey, &MangoAccountValue),
         market_index: PerpMarketIndex,
         max_base_transfer: i64,
         max_pnl_transfer: u64,
-    ) -> anyhow::Result<Signature> {
+    ) -> anyhow::Result<Instruction> {
         let perp = self.context.perp(market_index);
         let settle_token_info = self.context.token(perp.market.settle_token_index);
 
@@ -974,7 +1313,7 @@ impl MangoClient {
             .await
             .unwrap();
 
-        let ix = Instruction {
+        Ok(Instruction {
             program_id: mango_v4::id(),
             accounts: {
                 let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
@@ -1000,16 +1339,33 @@ impl MangoClient {
                     max_pnl_transfer,
                 },
             ),
-        };
+        })
+    }
+
+    pub async fn perp_liq_base_or_positive_pnl(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        market_index: PerpMarketIndex,
+        max_base_transfer: i64,
+        max_pnl_transfer: u64,
+    ) -> anyhow::Result<Signature> {
+        let ix = self
+            .perp_liq_base_or_positive_pnl_instruction(
+                liqee,
+                market_index,
+                max_base_transfer,
+                max_pnl_transfer,
+            )
+            .await?;
         self.send_and_confirm_owner_tx(vec![ix]).await
     }
 
-    pub async fn perp_liq_negative_pnl_or_bankruptcy(
+    pub async fn perp_liq_negative_pnl_or_bankruptcy_instruction(
         &self,
         liqee: (&Pubkey, &MangoAccountValue),
         market_index: PerpMarketIndex,
         max_liab_transfer: u64,
-    ) -> anyhow::Result<Signature> {
+    ) -> anyhow::Result<Instruction> {
         let group = account_fetcher_fetch_anchor_account::<Group>(
             &*self.account_fetcher,
             &self.context.group,
@@ -1018,6 +1374,12 @@ impl MangoClient {
 
         let perp = self.context.perp(market_index);
         let settle_token_info = self.context.token(perp.market.settle_token_index);
+        let insurance_vault = match perp.market.covering_insurance_fund() {
+            Some(fund_index) => group
+                .insurance_fund_vault(&self.group(), fund_index)
+                .map_err_anyhow()?,
+            None => group.insurance_vault,
+        };
 
         let health_remaining_ams = self
             .derive_liquidation_health_check_remaining_account_metas(
@@ -1028,7 +1390,7 @@ impl MangoClient {
             .await
             .unwrap();
 
-        let ix = Instruction {
+        Ok(Instruction {
             program_id: mango_v4::id(),
             accounts: {
                 let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
@@ -1042,7 +1404,7 @@ impl MangoClient {
                         settle_bank: settle_token_info.mint_info.first_bank(),
                         settle_vault: settle_token_info.mint_info.first_vault(),
                         settle_oracle: settle_token_info.mint_info.oracle,
-                        insurance_vault: group.insurance_vault,
+                        insurance_vault,
                         token_program: Token::id(),
                     },
                     None,
@@ -1053,7 +1415,18 @@ impl MangoClient {
             data: anchor_lang::InstructionData::data(
                 &mango_v4::instruction::PerpLiqNegativePnlOrBankruptcy { max_liab_transfer },
             ),
-        };
+        })
+    }
+
+    pub async fn perp_liq_negative_pnl_or_bankruptcy(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        market_index: PerpMarketIndex,
+        max_liab_transfer: u64,
+    ) -> anyhow::Result<Signature> {
+        let ix = self
+            .perp_liq_negative_pnl_or_bankruptcy_instruction(liqee, market_index, max_liab_transfer)
+            .await?;
         self.send_and_confirm_owner_tx(vec![ix]).await
     }
 
@@ -1061,13 +1434,13 @@ impl MangoClient {
     // Liquidation
     //
 
-    pub async fn token_liq_with_token(
+    pub async fn token_liq_with_token_instruction(
         &self,
         liqee: (&Pubkey, &MangoAccountValue),
         asset_token_index: TokenIndex,
         liab_token_index: TokenIndex,
         max_liab_transfer: I80F48,
-    ) -> anyhow::Result<Signature> {
+    ) -> anyhow::Result<Instruction> {
         let health_remaining_ams = self
             .derive_liquidation_health_check_remaining_account_metas(
                 liqee.1,
@@ -1077,7 +1450,7 @@ impl MangoClient {
             .await
             .unwrap();
 
-        let ix = Instruction {
+        Ok(Instruction {
             program_id: mango_v4::id(),
             accounts: {
                 let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
@@ -1097,16 +1470,33 @@ impl MangoClient {
                 liab_token_index,
                 max_liab_transfer,
             }),
-        };
-        self.send_and_confirm_owner_tx(vec![ix]).await
+        })
     }
 
-    pub async fn token_liq_bankruptcy(
+    pub async fn token_liq_with_token(
         &self,
         liqee: (&Pubkey, &MangoAccountValue),
+        asset_token_index: TokenIndex,
         liab_token_index: TokenIndex,
         max_liab_transfer: I80F48,
     ) -> anyhow::Result<Signature> {
+        let ix = self
+            .token_liq_with_token_instruction(
+                liqee,
+                asset_token_index,
+                liab_token_index,
+                max_liab_transfer,
+            )
+            .await?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub async fn token_liq_bankruptcy_instruction(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        liab_token_index: TokenIndex,
+        max_liab_transfer: I80F48,
+    ) -> anyhow::Result<Instruction> {
         let quote_token_index = 0;
 
         let quote_info = self.context.token(quote_token_index);
@@ -1133,8 +1523,14 @@ impl MangoClient {
             &self.context.group,
         )
         .await?;
+        let insurance_vault = match liab_info.mint_info.covering_insurance_fund() {
+            Some(fund_index) => group
+                .insurance_fund_vault(&self.group(), fund_index)
+                .map_err_anyhow()?,
+            None => group.insurance_vault,
+        };
 
-        let ix = Instruction {
+        Ok(Instruction {
             program_id: mango_v4::id(),
             accounts: {
                 let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
@@ -1145,7 +1541,7 @@ impl MangoClient {
                         liqor_owner: self.owner(),
                         liab_mint_info: liab_info.mint_info_address,
                         quote_vault: quote_info.mint_info.first_vault(),
-                        insurance_vault: group.insurance_vault,
+                        insurance_vault,
                         token_program: Token::id(),
                     },
                     None,
@@ -1157,7 +1553,18 @@ impl MangoClient {
             data: anchor_lang::InstructionData::data(&mango_v4::instruction::TokenLiqBankruptcy {
                 max_liab_transfer,
             }),
-        };
+        })
+    }
+
+    pub async fn token_liq_bankruptcy(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        liab_token_index: TokenIndex,
+        max_liab_transfer: I80F48,
+    ) -> anyhow::Result<Signature> {
+        let ix = self
+            .token_liq_bankruptcy_instruction(liqee, liab_token_index, max_liab_transfer)
+            .await?;
         self.send_and_confirm_owner_tx(vec![ix]).await
     }
 
@@ -1216,14 +1623,16 @@ impl MangoClient {
         Ok(route.clone())
     }
 
-    pub async fn jupiter_swap(
+    /// A flash loan that funds a jupiter swap, with the address lookup tables
+    /// needed by the jupiter instructions.
+    pub async fn jupiter_swap_flash_loan(
         &self,
         input_mint: Pubkey,
         output_mint: Pubkey,
         amount: u64,
         slippage: u64,
         swap_mode: JupiterSwapMode,
-    ) -> anyhow::Result<Signature> {
+    ) -> anyhow::Result<FlashLoanBuilder<'_>> {
         let source_token = self.context.token_by_mint(&input_mint)?;
         let target_token = self.context.token_by_mint(&output_mint)?;
         let route = self
@@ -1280,49 +1689,95 @@ impl MangoClient {
             .filter(|ix| !is_setup_ix(ix.program_id))
             .collect::<Vec<_>>();
 
-        let bank_ams = [
-            source_token.mint_info.first_bank(),
-            target_token.mint_info.first_bank(),
-        ]
-        .into_iter()
-        .map(to_writable_account_meta)
-        .collect::<Vec<_>>();
-
-        let vault_ams = [
-            source_token.mint_info.first_vault(),
-            target_token.mint_info.first_vault(),
-        ]
-        .into_iter()
-        .map(to_writable_account_meta)
-        .collect::<Vec<_>>();
-
-        let token_ams = [source_token.mint_info.mint, target_token.mint_info.mint]
-            .into_iter()
-            .map(|mint| {
+        let loans = [
+            (
+                source_token.token_index,
+                match swap_mode {
+                    JupiterSwapMode::ExactIn => amount,
+                    // in amount + slippage
+                    JupiterSwapMode::ExactOut => {
+                        u64::from_str(&route.other_amount_threshold).unwrap()
+                    }
+                },
+            ),
+            (target_token.token_index, 0u64),
+        ];
+
+        let mut flash_loan = FlashLoanBuilder::new(self)
+            .instructions(jup_action_ix)
+            .address_lookup_tables(jup_alts)
+            .flash_loan_type(mango_v4::accounts_ix::FlashLoanType::Swap);
+        for ix in jup_cu_ix {
+            flash_loan = flash_loan.pre_instruction(ix);
+        }
+        for (token_index, amount) in loans {
+            flash_loan = flash_loan.loan(token_index, amount);
+        }
+        Ok(flash_loan)
+    }
+
+    pub async fn jupiter_swap(
+        &self,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        amount: u64,
+        slippage: u64,
+        swap_mode: JupiterSwapMode,
+    ) -> anyhow::Result<Signature> {
+        self.jupiter_swap_flash_loan(input_mint, output_mint, amount, slippage, swap_mode)
+            .await?
+            .send_and_confirm()
+            .await
+    }
+
+    /// Wraps `inner_instructions` in FlashLoanBegin and FlashLoanEndV2.
+    ///
+    /// Each loan moves the native amount of a token from its vault to the owner's
+    /// associated token account, which gets created if necessary. FlashLoanEndV2 then
+    /// applies the token account changes to the mango account and checks health.
+    /// The inner instructions must not call into the mango program.
+    pub async fn flash_loan_instructions(
+        &self,
+        loans: &[(TokenIndex, u64)],
+        inner_instructions: Vec<Instruction>,
+        flash_loan_type: mango_v4::accounts_ix::FlashLoanType,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let tokens = loans
+            .iter()
+            .map(|(token_index, _)| self.context.token(*token_index))
+            .collect::<Vec<_>>();
+        let token_indexes = tokens.iter().map(|t| t.token_index).collect::<Vec<_>>();
+
+        let bank_ams = tokens
+            .iter()
+            .map(|t| to_writable_account_meta(t.mint_info.first_bank()))
+            .collect::<Vec<_>>();
+
+        let vault_ams = tokens
+            .iter()
+            .map(|t| to_writable_account_meta(t.mint_info.first_vault()))
+            .collect::<Vec<_>>();
+
+        let token_ams = tokens
+            .iter()
+            .map(|t| {
                 to_writable_account_meta(
                     anchor_spl::associated_token::get_associated_token_address(
                         &self.owner(),
-                        &mint,
+                        &t.mint_info.mint,
                     ),
                 )
             })
             .collect::<Vec<_>>();
 
-        let loan_amounts = vec![
-            match swap_mode {
-                JupiterSwapMode::ExactIn => amount,
-                // in amount + slippage
-                JupiterSwapMode::ExactOut => u64::from_str(&route.other_amount_threshold).unwrap(),
-            },
-            0u64,
-        ];
+        let loan_amounts = loans.iter().map(|(_, amount)| *amount).collect::<Vec<_>>();
         let num_loans: u8 = loan_amounts.len().try_into().unwrap();
 
         // This relies on the fact that health account banks will be identical to the first_bank above!
         let health_ams = self
             .derive_health_check_remaining_account_metas(
-                vec![source_token.token_index, target_token.token_index],
-                vec![source_token.token_index, target_token.token_index],
+                token_indexes.clone(),
+                token_indexes,
                 vec![],
             )
             .await
@@ -1330,25 +1785,16 @@ impl MangoClient {
 
         let mut instructions = Vec::new();
 
-        for ix in jup_cu_ix {
-            instructions.push(ix.clone());
+        for token in tokens.iter() {
+            instructions.push(
+                spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                    &self.owner.pubkey(),
+                    &self.owner.pubkey(),
+                    &token.mint_info.mint,
+                    &Token::id(),
+                ),
+            );
         }
-        instructions.push(
-            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
-                &self.owner.pubkey(),
-                &self.owner.pubkey(),
-                &source_token.mint_info.mint,
-                &Token::id(),
-            ),
-        );
-        instructions.push(
-            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
-                &self.owner.pubkey(),
-                &self.owner.pubkey(),
-                &target_token.mint_info.mint,
-                &Token::id(),
-            ),
-        );
         instructions.push(Instruction {
             program_id: mango_v4::id(),
             accounts: {
@@ -1371,9 +1817,7 @@ impl MangoClient {
                 loan_amounts,
             }),
         });
-        for ix in jup_action_ix {
-            instructions.push(ix.clone());
-        }
+        instructions.extend(inner_instructions);
         instructions.push(Instruction {
             program_id: mango_v4::id(),
             accounts: {
@@ -1393,23 +1837,11 @@ impl MangoClient {
             },
             data: anchor_lang::InstructionData::data(&mango_v4::instruction::FlashLoanEndV2 {
                 num_loans,
-                flash_loan_type: mango_v4::accounts_ix::FlashLoanType::Swap,
+                flash_loan_type,
             }),
         });
 
-        let payer = self.owner.pubkey(); // maybe use fee_payer? but usually it's the same
-        let mut address_lookup_tables = self.mango_address_lookup_tables().await?;
-        address_lookup_tables.extend(jup_alts.into_iter());
-
-        TransactionBuilder {
-            instructions,
-            address_lookup_tables,
-            payer,
-            signers: vec![&*self.owner],
-            config: self.client.transaction_builder_config,
-        }
-        .send_and_confirm(&self.client)
-        .await
+        Ok(instructions)
     }
 
     async fn fetch_address_lookup_table(
@@ -1430,10 +1862,7 @@ impl MangoClient {
     pub async fn mango_address_lookup_tables(
         &self,
     ) -> anyhow::Result<Vec<AddressLookupTableAccount>> {
-        stream::iter(self.context.address_lookup_tables.iter())
-            .then(|&k| self.fetch_address_lookup_table(k))
-            .try_collect::<Vec<_>>()
-            .await
+        self.alt_cache.tables().await
     }
 
     async fn deserialize_instructions_and_alts(
@@ -1506,7 +1935,7 @@ impl MangoClient {
     ) -> anyhow::Result<Signature> {
         TransactionBuilder {
             instructions,
-            address_lookup_tables: vec![],
+            address_lookup_tables: self.mango_address_lookup_tables().await?,
             payer: self.client.fee_payer.pubkey(),
             signers: vec![&*self.client.fee_payer],
             config: self.client.transaction_builder_config,
@@ -3,9 +3,10 @@ use std::collections::HashMap;
 use anchor_client::ClientError;
 
 use anchor_lang::__private::bytemuck;
+use anchor_lang::{AccountDeserialize, Discriminator};
 
 use mango_v4::state::{
-    Group, MangoAccountValue, MintInfo, PerpMarket, PerpMarketIndex, Serum3Market,
+    Bank, Group, MangoAccountValue, MintInfo, PerpMarket, PerpMarketIndex, Serum3Market,
     Serum3MarketIndex, TokenIndex,
 };
 
@@ -14,9 +15,10 @@ use futures::{stream, StreamExt, TryStreamExt};
 use itertools::Itertools;
 
 use crate::gpa::*;
+use crate::{account_fetcher_fetch_anchor_account, AccountFetcher};
 
 use solana_client::nonblocking::rpc_client::RpcClient as RpcClientAsync;
-use solana_sdk::account::Account;
+use solana_sdk::account::{Account, AccountSharedData, ReadableAccount};
 use solana_sdk::instruction::AccountMeta;
 use solana_sdk::pubkey::Pubkey;
 
@@ -27,6 +29,8 @@ pub struct TokenContext {
     pub mint_info: MintInfo,
     pub mint_info_address: Pubkey,
     pub decimals: u8,
+    /// Pubkey::default() if the token has no fallback oracle
+    pub fallback_oracle: Pubkey,
 }
 
 impl TokenContext {
@@ -100,8 +104,93 @@ impl MangoGroupContext {
     pub async fn new_from_rpc(rpc: &RpcClientAsync, group: Pubkey) -> anyhow::Result<Self> {
         let program = mango_v4::ID;
 
-        // tokens
         let mint_info_tuples = fetch_mint_infos(rpc, program, group).await?;
+        let bank_tuples = fetch_banks(rpc, program, group).await?;
+        let serum3_market_tuples = fetch_serum3_markets(rpc, program, group).await?;
+        let serum3_markets_external = stream::iter(serum3_market_tuples.iter())
+            .then(|(_, s)| fetch_raw_account(rpc, s.serum_market_external))
+            .map_ok(AccountSharedData::from)
+            .try_collect::<Vec<_>>()
+            .await?;
+        let perp_market_tuples = fetch_perp_markets(rpc, program, group).await?;
+        let group_data = fetch_anchor_account::<Group>(rpc, &group).await?;
+
+        Ok(Self::new_from_accounts(
+            group,
+            &group_data,
+            mint_info_tuples,
+            bank_tuples,
+            serum3_market_tuples,
+            serum3_markets_external,
+            perp_market_tuples,
+        ))
+    }
+
+    /// Builds the context from whatever data the account fetcher has available.
+    ///
+    /// Useful for running against a ChainData that was filled from a recorded
+    /// snapshot, where no rpc connection exists.
+    pub async fn new_from_account_fetcher(
+        account_fetcher: &dyn AccountFetcher,
+        group: Pubkey,
+    ) -> anyhow::Result<Self> {
+        let program = mango_v4::ID;
+
+        async fn group_accounts<T: AccountDeserialize + Discriminator>(
+            account_fetcher: &dyn AccountFetcher,
+            program: &Pubkey,
+            group: &Pubkey,
+        ) -> anyhow::Result<Vec<(Pubkey, T)>> {
+            let accounts = account_fetcher
+                .fetch_program_accounts(program, T::discriminator())
+                .await?;
+            let mut result = vec![];
+            for (pk, acc) in accounts {
+                // all the relevant accounts store the group right after the discriminator
+                let data = acc.data();
+                if data.len() < 40 || data[8..40] != group.to_bytes() {
+                    continue;
+                }
+                result.push((pk, T::try_deserialize(&mut &data[..])?));
+            }
+            Ok(result)
+        }
+
+        let mint_info_tuples =
+            group_accounts::<MintInfo>(account_fetcher, &program, &group).await?;
+        let bank_tuples = group_accounts::<Bank>(account_fetcher, &program, &group).await?;
+        let serum3_market_tuples =
+            group_accounts::<Serum3Market>(account_fetcher, &program, &group).await?;
+        let serum3_markets_external = stream::iter(serum3_market_tuples.iter())
+            .then(|(_, s)| account_fetcher.fetch_raw_account(&s.serum_market_external))
+            .try_collect::<Vec<_>>()
+            .await?;
+        let perp_market_tuples =
+            group_accounts::<PerpMarket>(account_fetcher, &program, &group).await?;
+        let group_data =
+            account_fetcher_fetch_anchor_account::<Group>(account_fetcher, &group).await?;
+
+        Ok(Self::new_from_accounts(
+            group,
+            &group_data,
+            mint_info_tuples,
+            bank_tuples,
+            serum3_market_tuples,
+            serum3_markets_external,
+            perp_market_tuples,
+        ))
+    }
+
+    fn new_from_accounts(
+        group: Pubkey,
+        group_data: &Group,
+        mint_info_tuples: Vec<(Pubkey, MintInfo)>,
+        bank_tuples: Vec<(Pubkey, Bank)>,
+        serum3_market_tuples: Vec<(Pubkey, Serum3Market)>,
+        serum3_markets_external: Vec<AccountSharedData>,
+        perp_market_tuples: Vec<(Pubkey, PerpMarket)>,
+    ) -> Self {
+        // tokens
         let mut tokens = mint_info_tuples
             .iter()
             .map(|(pk, mi)| {
@@ -113,34 +202,30 @@ impl MangoGroupContext {
                         mint_info: *mi,
                         mint_info_address: *pk,
                         decimals: u8::MAX,
+                        fallback_oracle: Pubkey::default(),
                     },
                 )
             })
             .collect::<HashMap<_, _>>();
 
-        // reading the banks is only needed for the token names and decimals
+        // reading the banks is only needed for the token names, decimals and fallback oracles
         // FUTURE: either store the names on MintInfo as well, or maybe don't store them at all
         //         because they are in metaplex?
-        let bank_tuples = fetch_banks(rpc, program, group).await?;
         for (_, bank) in bank_tuples {
             let token = tokens.get_mut(&bank.token_index).unwrap();
             token.name = bank.name().into();
             token.decimals = bank.mint_decimals;
+            token.fallback_oracle = bank.fallback_oracle;
         }
         assert!(tokens.values().all(|t| t.decimals != u8::MAX));
 
         // serum3 markets
-        let serum3_market_tuples = fetch_serum3_markets(rpc, program, group).await?;
-        let serum3_markets_external = stream::iter(serum3_market_tuples.iter())
-            .then(|(_, s)| fetch_raw_account(rpc, s.serum_market_external))
-            .try_collect::<Vec<_>>()
-            .await?;
         let serum3_markets = serum3_market_tuples
             .iter()
             .zip(serum3_markets_external.iter())
             .map(|((pk, s), market_external_account)| {
                 let market_external: &serum_dex::state::MarketState = bytemuck::from_bytes(
-                    &market_external_account.data
+                    &market_external_account.data()
                         [5..5 + std::mem::size_of::<serum_dex::state::MarketState>()],
                 );
                 let vault_signer = serum_dex::state::gen_vault_signer_key(
@@ -169,7 +254,6 @@ impl MangoGroupContext {
             .collect::<HashMap<_, _>>();
 
         // perp markets
-        let perp_market_tuples = fetch_perp_markets(rpc, program, group).await?;
         let perp_markets = perp_market_tuples
             .iter()
             .map(|(pk, pm)| {
@@ -197,7 +281,6 @@ impl MangoGroupContext {
             .map(|(i, p)| (p.market.name().to_string(), *i))
             .collect::<HashMap<_, _>>();
 
-        let group_data = fetch_anchor_account::<Group>(rpc, &group).await?;
         let address_lookup_tables = group_data
             .address_lookup_tables
             .iter()
@@ -205,7 +288,7 @@ impl MangoGroupContext {
             .cloned()
             .collect::<Vec<Pubkey>>();
 
-        Ok(MangoGroupContext {
+        MangoGroupContext {
             group,
             tokens,
             token_indexes_by_name,
@@ -214,7 +297,7 @@ impl MangoGroupContext {
             perp_markets,
             perp_market_indexes_by_name,
             address_lookup_tables,
-        })
+        }
     }
 
     pub fn derive_health_check_remaining_account_metas(
@@ -255,6 +338,10 @@ impl MangoGroupContext {
         let perp_oracles = account
             .active_perp_positions()
             .map(|&pa| self.perp(pa.market_index).market.oracle);
+        let fallback_oracles = self.fallback_oracles(
+            account.active_token_positions().map(|p| p.token_index),
+            account.active_perp_positions().map(|p| p.market_index),
+        );
 
         let to_account_meta = |pubkey| AccountMeta {
             pubkey,
@@ -273,9 +360,25 @@ impl MangoGroupContext {
             .chain(perp_markets.map(to_account_meta))
             .chain(perp_oracles.map(to_account_meta))
             .chain(serum_oos.map(to_account_meta))
+            .chain(fallback_oracles.into_iter().map(to_account_meta))
             .collect())
     }
 
+    /// The fallback oracles of the given tokens and perp markets, which are passed
+    /// after all other health accounts
+    fn fallback_oracles(
+        &self,
+        token_indexes: impl Iterator<Item = TokenIndex>,
+        perp_market_indexes: impl Iterator<Item = PerpMarketIndex>,
+    ) -> Vec<Pubkey> {
+        token_indexes
+            .map(|token_index| self.token(token_index).fallback_oracle)
+            .chain(perp_market_indexes.map(|index| self.perp(index).market.fallback_oracle))
+            .filter(|oracle| *oracle != Pubkey::default())
+            .unique()
+            .collect()
+    }
+
     pub fn derive_health_check_remaining_account_metas_two_accounts(
         &self,
         account1: &MangoAccountValue,
@@ -294,7 +397,8 @@ impl MangoGroupContext {
             .chain(affected_tokens.iter().copied())
             .unique();
 
-        for token_index in token_indexes {
+        let banks_token_indexes = token_indexes.collect::<Vec<_>>();
+        for &token_index in banks_token_indexes.iter() {
             let mint_info = self.mint_info(token_index);
             let writable_bank = writable_banks.iter().contains(&token_index);
             banks.push((mint_info.first_bank(), writable_bank));
@@ -317,6 +421,10 @@ impl MangoGroupContext {
         let perp_oracles = perp_market_indexes
             .iter()
             .map(|&index| self.perp(index).market.oracle);
+        let fallback_oracles = self.fallback_oracles(
+            banks_token_indexes.iter().copied(),
+            perp_market_indexes.iter().copied(),
+        );
 
         let to_account_meta = |pubkey| AccountMeta {
             pubkey,
@@ -335,6 +443,7 @@ impl MangoGroupContext {
             .chain(perp_markets.map(to_account_meta))
             .chain(perp_oracles.map(to_account_meta))
             .chain(serum_oos.map(to_account_meta))
+            .chain(fallback_oracles.into_iter().map(to_account_meta))
             .collect())
     }
 
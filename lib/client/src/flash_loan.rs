@@ -0,0 +1,220 @@
+//! Building transactions that borrow tokens from mango for the duration of a
+//! transaction.
+//!
+//! A flash loan moves tokens from the bank vaults to the owner's token accounts
+//! in FlashLoanBegin. Arbitrary instructions may then use them, and FlashLoanEndV2
+//! applies whatever changed in the token accounts to the mango account and checks
+//! its health.
+
+use std::collections::HashSet;
+
+use mango_v4::accounts_ix::FlashLoanType;
+use mango_v4::state::TokenIndex;
+use solana_sdk::address_lookup_table_account::AddressLookupTableAccount;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::{MangoClient, TransactionBuilder};
+
+/// Collects loans and instructions and turns them into a flash loan transaction
+/// for the client's mango account.
+///
+/// Example:
+/// ```ignore
+/// let signature = FlashLoanBuilder::new(&client)
+///     .loan(usdc_token_index, 1_000_000)
+///     .loan(sol_token_index, 0)
+///     .instructions(swap_ixs)
+///     .address_lookup_tables(swap_alts)
+///     .send_and_confirm()
+///     .await?;
+/// ```
+pub struct FlashLoanBuilder<'a> {
+    client: &'a MangoClient,
+    loans: Vec<(TokenIndex, u64)>,
+    pre_instructions: Vec<Instruction>,
+    instructions: Vec<Instruction>,
+    address_lookup_tables: Vec<AddressLookupTableAccount>,
+    flash_loan_type: Option<FlashLoanType>,
+}
+
+impl<'a> FlashLoanBuilder<'a> {
+    pub fn new(client: &'a MangoClient) -> Self {
+        Self {
+            client,
+            loans: vec![],
+            pre_instructions: vec![],
+            instructions: vec![],
+            address_lookup_tables: vec![],
+            flash_loan_type: None,
+        }
+    }
+
+    /// Borrow a native amount of a token.
+    ///
+    /// Tokens that are only received during the flash loan still need a loan
+    /// with amount 0, so their vault and token account are part of the flash loan.
+    pub fn loan(mut self, token_index: TokenIndex, amount: u64) -> Self {
+        self.loans.push((token_index, amount));
+        self
+    }
+
+    /// Add an instruction that runs before FlashLoanBegin.
+    ///
+    /// Unlike instructions inside the flash loan, these may call the mango program.
+    ///
+    /// The health accounts for FlashLoanEndV2 are derived from the mango account as it
+    /// is before these instructions run, plus the loaned tokens. Pre-instructions may
+    /// only add token positions for loaned tokens and must not close any positions.
+    pub fn pre_instruction(mut self, instruction: Instruction) -> Self {
+        self.pre_instructions.push(instruction);
+        self
+    }
+
+    /// Add an instruction that runs inside the flash loan. It must not call the
+    /// mango program.
+    pub fn instruction(mut self, instruction: Instruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    pub fn instructions(mut self, instructions: impl IntoIterator<Item = Instruction>) -> Self {
+        self.instructions.extend(instructions);
+        self
+    }
+
+    /// Address lookup tables in addition to the group's tables
+    pub fn address_lookup_tables(
+        mut self,
+        tables: impl IntoIterator<Item = AddressLookupTableAccount>,
+    ) -> Self {
+        self.address_lookup_tables.extend(tables);
+        self
+    }
+
+    /// Overrides the flash loan type that would be derived from the loans
+    pub fn flash_loan_type(mut self, flash_loan_type: FlashLoanType) -> Self {
+        self.flash_loan_type = Some(flash_loan_type);
+        self
+    }
+
+    /// All instructions of the transaction, in order
+    pub async fn build_instructions(&self) -> anyhow::Result<Vec<Instruction>> {
+        check_loans(&self.loans)?;
+        for ix in self.instructions.iter() {
+            if ix.program_id == mango_v4::id() {
+                anyhow::bail!("instructions inside a flash loan must not call the mango program");
+            }
+        }
+
+        let flash_loan_type = self
+            .flash_loan_type
+            .unwrap_or_else(|| derive_flash_loan_type(&self.loans));
+
+        let mut instructions = self.pre_instructions.clone();
+        instructions.extend(
+            self.client
+                .flash_loan_instructions(&self.loans, self.instructions.clone(), flash_loan_type)
+                .await?,
+        );
+        Ok(instructions)
+    }
+
+    /// A transaction builder using the group's address lookup tables, paid by the
+    /// client's fee payer
+    pub async fn transaction_builder(self) -> anyhow::Result<TransactionBuilder<'a>> {
+        let instructions = self.build_instructions().await?;
+        let mut address_lookup_tables = self.client.mango_address_lookup_tables().await?;
+        address_lookup_tables.extend(self.address_lookup_tables);
+
+        Ok(TransactionBuilder {
+            instructions,
+            address_lookup_tables,
+            payer: self.client.client.fee_payer.pubkey(),
+            signers: vec![&*self.client.owner, &*self.client.client.fee_payer],
+            config: self.client.client.transaction_builder_config,
+        })
+    }
+
+    /// A signed versioned transaction with a recent blockhash
+    pub async fn transaction(self) -> anyhow::Result<VersionedTransaction> {
+        let rpc = self.client.client.rpc_async();
+        self.transaction_builder().await?.transaction(&rpc).await
+    }
+
+    pub async fn send_and_confirm(self) -> anyhow::Result<Signature> {
+        let client = self.client;
+        self.transaction_builder()
+            .await?
+            .send_and_confirm(&client.client)
+            .await
+    }
+}
+
+/// Swap if exactly one token is lent out and exactly one other token is
+/// received, Unknown otherwise.
+fn derive_flash_loan_type(loans: &[(TokenIndex, u64)]) -> FlashLoanType {
+    let lent = loans.iter().filter(|(_, amount)| *amount > 0).count();
+    if loans.len() == 2 && lent == 1 {
+        FlashLoanType::Swap
+    } else {
+        FlashLoanType::Unknown
+    }
+}
+
+/// There must be at least one loan and at most one per token
+fn check_loans(loans: &[(TokenIndex, u64)]) -> anyhow::Result<()> {
+    if loans.is_empty() {
+        anyhow::bail!("flash loan needs at least one loan");
+    }
+    let mut seen = HashSet::new();
+    for (token_index, _) in loans.iter() {
+        if !seen.insert(*token_index) {
+            anyhow::bail!("flash loan has more than one loan for token {token_index}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_flash_loan_type() {
+        assert_eq!(
+            derive_flash_loan_type(&[(0, 100), (1, 0)]),
+            FlashLoanType::Swap
+        );
+        assert_eq!(
+            derive_flash_loan_type(&[(1, 0), (0, 100)]),
+            FlashLoanType::Swap
+        );
+        // both lent out
+        assert_eq!(
+            derive_flash_loan_type(&[(0, 100), (1, 100)]),
+            FlashLoanType::Unknown
+        );
+        // nothing lent out
+        assert_eq!(
+            derive_flash_loan_type(&[(0, 0), (1, 0)]),
+            FlashLoanType::Unknown
+        );
+        assert_eq!(derive_flash_loan_type(&[(0, 100)]), FlashLoanType::Unknown);
+        assert_eq!(
+            derive_flash_loan_type(&[(0, 100), (1, 0), (2, 0)]),
+            FlashLoanType::Unknown
+        );
+    }
+
+    #[test]
+    fn test_check_loans() {
+        assert!(check_loans(&[]).is_err());
+        assert!(check_loans(&[(0, 100)]).is_ok());
+        assert!(check_loans(&[(0, 100), (1, 0)]).is_ok());
+        assert!(check_loans(&[(0, 100), (0, 0)]).is_err());
+        assert!(check_loans(&[(0, 100), (1, 0), (0, 5)]).is_err());
+    }
+}
@@ -7,11 +7,12 @@ use mango_v4::state::MangoAccountValue;
 
 pub async fn new(
     context: &MangoGroupContext,
-    account_fetcher: &impl AccountFetcher,
+    account_fetcher: &(impl AccountFetcher + ?Sized),
     account: &MangoAccountValue,
 ) -> anyhow::Result<HealthCache> {
     let active_token_len = account.active_token_positions().count();
     let active_perp_len = account.active_perp_positions().count();
+    let active_serum3_len = account.active_serum3_orders().count();
 
     let metas =
         context.derive_health_check_remaining_account_metas(account, vec![], vec![], vec![])?;
@@ -31,6 +32,7 @@ pub async fn new(
         n_perps: active_perp_len,
         begin_perp: active_token_len * 2,
         begin_serum3: active_token_len * 2 + active_perp_len * 2,
+        begin_fallback_oracles: active_token_len * 2 + active_perp_len * 2 + active_serum3_len,
         staleness_slot: None,
     };
     mango_v4::health::new_health_cache(&account.borrow(), &retriever).context("make health cache")
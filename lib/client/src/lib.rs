@@ -5,14 +5,19 @@ pub use util::*;
 
 mod account_fetcher;
 pub mod account_update_stream;
+pub mod alt_cache;
 pub mod chain_data;
 mod chain_data_fetcher;
 mod client;
 mod context;
+pub mod flash_loan;
 mod gpa;
 pub mod health_cache;
 mod jupiter;
+pub mod max_size;
 pub mod perp_pnl;
+pub mod snapshot_file;
 pub mod snapshot_source;
+pub mod tx_tracker;
 mod util;
 pub mod websocket_source;
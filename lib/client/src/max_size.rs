@@ -0,0 +1,108 @@
+//! How large a swap, borrow or perp trade an account can make while keeping its
+//! init health ratio above a minimum, based on current chain state.
+//!
+//! The health ratio is in percent, see HealthCache::health_ratio().
+
+use anyhow::Context;
+use fixed::types::I80F48;
+use mango_v4::state::{Bank, MangoAccountValue, PerpMarketIndex, Side, TokenIndex};
+
+use crate::{
+    account_fetcher_fetch_anchor_account, health_cache, AccountFetcher, MangoGroupContext,
+};
+
+async fn first_bank(
+    context: &MangoGroupContext,
+    account_fetcher: &dyn AccountFetcher,
+    token_index: TokenIndex,
+) -> anyhow::Result<Bank> {
+    let bank_address = context.mint_info(token_index).first_bank();
+    account_fetcher_fetch_anchor_account(account_fetcher, &bank_address).await
+}
+
+/// The max native amount of `source` that can be swapped for `target`.
+///
+/// `price` is the amount of target native received per source native and defaults
+/// to the ratio of the oracle prices.
+pub async fn max_swap_source(
+    context: &MangoGroupContext,
+    account_fetcher: &dyn AccountFetcher,
+    account: &MangoAccountValue,
+    source: TokenIndex,
+    target: TokenIndex,
+    price: Option<I80F48>,
+    min_health_ratio: I80F48,
+) -> anyhow::Result<I80F48> {
+    // The health computation needs positions for both tokens
+    let mut account = account.clone();
+    account.ensure_token_position(source)?;
+    account.ensure_token_position(target)?;
+
+    let health_cache = health_cache::new(context, account_fetcher, &account).await?;
+    let source_bank = first_bank(context, account_fetcher, source).await?;
+    let target_bank = first_bank(context, account_fetcher, target).await?;
+
+    let source_price = health_cache.token_info(source)?.prices.oracle;
+    let price = match price {
+        Some(price) => price,
+        None => source_price / health_cache.token_info(target)?.prices.oracle,
+    };
+
+    health_cache
+        .max_swap_source_for_health_ratio(
+            &account,
+            &source_bank,
+            source_price,
+            &target_bank,
+            price,
+            min_health_ratio,
+        )
+        .context("computing max swap source")
+}
+
+/// The max native amount of `token_index` that can be withdrawn, borrowing if needed.
+pub async fn max_borrow(
+    context: &MangoGroupContext,
+    account_fetcher: &dyn AccountFetcher,
+    account: &MangoAccountValue,
+    token_index: TokenIndex,
+    min_health_ratio: I80F48,
+) -> anyhow::Result<I80F48> {
+    let mut account = account.clone();
+    account.ensure_token_position(token_index)?;
+
+    let health_cache = health_cache::new(context, account_fetcher, &account).await?;
+    let bank = first_bank(context, account_fetcher, token_index).await?;
+
+    health_cache
+        .max_borrow_for_health_ratio(&account, &bank, min_health_ratio)
+        .context("computing max borrow")
+}
+
+/// The max number of base lots that can be bought (Bid) or sold (Ask) on a perp market.
+///
+/// `price` is in settle token native per base native and defaults to the oracle price.
+/// Returns i64::MAX if trading at the price only increases health.
+pub async fn max_perp(
+    context: &MangoGroupContext,
+    account_fetcher: &dyn AccountFetcher,
+    account: &MangoAccountValue,
+    perp_market_index: PerpMarketIndex,
+    side: Side,
+    price: Option<I80F48>,
+    min_health_ratio: I80F48,
+) -> anyhow::Result<i64> {
+    let settle_token_index = context.perp(perp_market_index).market.settle_token_index;
+    let mut account = account.clone();
+    account.ensure_perp_position(perp_market_index, settle_token_index)?;
+
+    let health_cache = health_cache::new(context, account_fetcher, &account).await?;
+    let price = match price {
+        Some(price) => price,
+        None => health_cache.perp_info(perp_market_index)?.prices.oracle,
+    };
+
+    health_cache
+        .max_perp_for_health_ratio(perp_market_index, price, side, min_health_ratio)
+        .context("computing max perp trade")
+}
@@ -0,0 +1,312 @@
+//! Recording account updates to disk and replaying them later.
+//!
+//! The file contains the latest known state of every account that was fed into
+//! ChainData via the account_update_stream. It can be used to have account state
+//! available before the first getProgramAccounts snapshot completes or to debug a
+//! situation offline.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::account::{Account, AccountSharedData, ReadableAccount};
+use solana_sdk::pubkey::Pubkey;
+
+use anyhow::Context;
+use log::*;
+use std::collections::HashMap;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::account_update_stream::{AccountUpdate, Message};
+
+const MAGIC: [u8; 8] = *b"mngosnap";
+const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    magic: [u8; 8],
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    pubkey: Pubkey,
+    owner: Pubkey,
+    slot: u64,
+    lamports: u64,
+    executable: bool,
+    rent_epoch: u64,
+    data: Vec<u8>,
+}
+
+impl From<&AccountUpdate> for Entry {
+    fn from(update: &AccountUpdate) -> Self {
+        Entry {
+            pubkey: update.pubkey,
+            owner: *update.account.owner(),
+            slot: update.slot,
+            lamports: update.account.lamports(),
+            executable: update.account.executable(),
+            rent_epoch: update.account.rent_epoch(),
+            data: update.account.data().to_vec(),
+        }
+    }
+}
+
+impl From<Entry> for AccountUpdate {
+    fn from(entry: Entry) -> Self {
+        AccountUpdate {
+            pubkey: entry.pubkey,
+            slot: entry.slot,
+            account: AccountSharedData::from(Account {
+                lamports: entry.lamports,
+                data: entry.data,
+                owner: entry.owner,
+                executable: entry.executable,
+                rent_epoch: entry.rent_epoch,
+            }),
+        }
+    }
+}
+
+/// Writes the account updates to `path`.
+///
+/// The file is written to a temporary location first and then moved, so readers
+/// never see a partially written file.
+pub fn write(path: &Path, updates: &[AccountUpdate]) -> anyhow::Result<()> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    {
+        let file = std::fs::File::create(&tmp_path)
+            .with_context(|| format!("creating snapshot file {}", tmp_path.display()))?;
+        let mut writer = BufWriter::new(file);
+        bincode::serialize_into(
+            &mut writer,
+            &Header {
+                magic: MAGIC,
+                version: VERSION,
+            },
+        )?;
+        let entries = updates.iter().map(Entry::from).collect::<Vec<_>>();
+        bincode::serialize_into(&mut writer, &entries)?;
+        use std::io::Write;
+        writer.flush()?;
+    }
+
+    std::fs::rename(&tmp_path, path)
+        .with_context(|| format!("moving snapshot file to {}", path.display()))?;
+    Ok(())
+}
+
+/// Reads all account updates stored in the file at `path`.
+pub fn read(path: &Path) -> anyhow::Result<Vec<AccountUpdate>> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("opening snapshot file {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+
+    let header: Header = bincode::deserialize_from(&mut reader)
+        .with_context(|| format!("reading header of snapshot file {}", path.display()))?;
+    if header.magic != MAGIC {
+        anyhow::bail!("{} is not a snapshot file", path.display());
+    }
+    if header.version != VERSION {
+        anyhow::bail!(
+            "snapshot file {} has version {}, expected {}",
+            path.display(),
+            header.version,
+            VERSION
+        );
+    }
+
+    let entries: Vec<Entry> = bincode::deserialize_from(&mut reader)
+        .with_context(|| format!("reading snapshot file {}", path.display()))?;
+    Ok(entries.into_iter().map(AccountUpdate::from).collect())
+}
+
+/// Keeps the latest update for each account seen on the account_update_stream.
+#[derive(Default)]
+pub struct Recorder {
+    accounts: HashMap<Pubkey, AccountUpdate>,
+    writing: Arc<AtomicBool>,
+}
+
+impl Recorder {
+    pub fn record(&mut self, message: &Message) {
+        match message {
+            Message::Account(update) => self.insert(update),
+            Message::Snapshot(snapshot) => {
+                for update in snapshot {
+                    self.insert(update);
+                }
+            }
+            Message::Slot(_) => {}
+        }
+    }
+
+    fn insert(&mut self, update: &AccountUpdate) {
+        match self.accounts.get(&update.pubkey) {
+            Some(existing) if existing.slot > update.slot => {}
+            _ => {
+                self.accounts.insert(update.pubkey, update.clone());
+            }
+        }
+    }
+
+    pub fn accounts_count(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn updates(&self) -> Vec<AccountUpdate> {
+        self.accounts.values().cloned().collect()
+    }
+
+    /// Writes the recorded state to `path` on a blocking thread, errors are logged.
+    ///
+    /// If the previous write has not finished yet, this one is skipped: both would
+    /// use the same temporary file.
+    pub fn write_in_background(&self, path: &Path) {
+        if self.writing.swap(true, Ordering::AcqRel) {
+            warn!(
+                "skipping write of snapshot file {}, previous write still in progress",
+                path.display()
+            );
+            return;
+        }
+
+        let updates = self.updates();
+        let path = path.to_path_buf();
+        let writing = self.writing.clone();
+        tokio::task::spawn_blocking(move || {
+            match write(&path, &updates) {
+                Ok(()) => info!(
+                    "wrote {} accounts to snapshot file {}",
+                    updates.len(),
+                    path.display()
+                ),
+                Err(err) => warn!("could not write snapshot file: {:?}", err),
+            }
+            writing.store(false, Ordering::Release);
+        });
+    }
+}
+
+/// Sends the contents of the snapshot file at `path` to `sender`.
+///
+/// The accounts are sent as a single Message::Snapshot. Files whose newest account
+/// is older than `min_slot` are rejected, to avoid acting on stale state.
+///
+/// No slot messages are sent: the accounts become live in ChainData once the
+/// websocket source reports a newer rooted slot.
+///
+/// Returns the number of accounts that were sent.
+pub async fn replay(
+    path: &Path,
+    min_slot: u64,
+    sender: &async_channel::Sender<Message>,
+) -> anyhow::Result<usize> {
+    let updates = read(path)?;
+    let count = updates.len();
+    let max_slot = updates.iter().map(|u| u.slot).max().unwrap_or(0);
+    if max_slot < min_slot {
+        anyhow::bail!(
+            "snapshot file {} is too old: newest slot {}, need at least {}",
+            path.display(),
+            max_slot,
+            min_slot
+        );
+    }
+
+    sender
+        .send(Message::Snapshot(updates))
+        .await
+        .context("sending replayed snapshot")?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(slot: u64, data: Vec<u8>) -> AccountUpdate {
+        AccountUpdate {
+            pubkey: Pubkey::new_unique(),
+            slot,
+            account: AccountSharedData::from(Account {
+                lamports: 42 + slot,
+                data,
+                owner: Pubkey::new_unique(),
+                executable: false,
+                rent_epoch: 7,
+            }),
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}-{}.snap", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_write_read_roundtrip() -> anyhow::Result<()> {
+        let path = temp_path("mango-snapshot-roundtrip");
+        let updates = vec![
+            update(10, vec![1, 2, 3]),
+            update(12, vec![]),
+            update(11, vec![9; 100]),
+        ];
+
+        write(&path, &updates)?;
+        let read_back = read(&path)?;
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(read_back.len(), updates.len());
+        for (a, b) in updates.iter().zip(read_back.iter()) {
+            assert_eq!(a.pubkey, b.pubkey);
+            assert_eq!(a.slot, b.slot);
+            assert_eq!(a.account, b.account);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_rejects_other_files() -> anyhow::Result<()> {
+        let path = temp_path("mango-snapshot-garbage");
+        std::fs::write(&path, b"definitely not a snapshot file")?;
+        let result = read(&path);
+        std::fs::remove_file(&path)?;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_recorder_keeps_newest() {
+        let mut recorder = Recorder::default();
+        let newer = update(20, vec![2]);
+        let mut older = newer.clone();
+        older.slot = 10;
+
+        recorder.record(&Message::Account(newer.clone()));
+        recorder.record(&Message::Snapshot(vec![older]));
+
+        let updates = recorder.updates();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].slot, 20);
+    }
+
+    #[tokio::test]
+    async fn test_replay_rejects_old_files() -> anyhow::Result<()> {
+        let path = temp_path("mango-snapshot-replay");
+        write(&path, &[update(100, vec![1])])?;
+        let (sender, receiver) = async_channel::unbounded();
+
+        let too_old = replay(&path, 101, &sender).await;
+        let fresh = replay(&path, 100, &sender).await;
+        std::fs::remove_file(&path)?;
+
+        assert!(too_old.is_err());
+        assert_eq!(fresh?, 1);
+        assert_eq!(receiver.len(), 1);
+        Ok(())
+    }
+}
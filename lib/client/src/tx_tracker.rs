@@ -0,0 +1,377 @@
+//! Client-side de-duplication of transactions.
+//!
+//! Bots like the liquidator decide what to do based on account data that may lag
+//! behind the chain. If a transaction landed but the data doesn't reflect it yet,
+//! or it landed on a fork, the bot would happily send the same action again.
+//!
+//! The TxTracker keys each sent transaction by a logical action (like "sell token 4")
+//! and refuses to send the same action again until the previous transaction is
+//! finalized, failed or expired. The action is reserved before sending, so concurrent
+//! callers can't both send it.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::rpc_client::RpcClient as RpcClientAsync;
+use solana_client::rpc_request::RpcError;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+
+use crate::MangoClientError;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Config {
+    /// Time after which a transaction that isn't finalized is assumed to never land.
+    /// Should be longer than the lifetime of a blockhash.
+    ///
+    /// Sends that returned an error are blocked for as long if the transaction may
+    /// still have landed, for example if confirming it timed out.
+    pub expiry: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            expiry: Duration::from_secs(90),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxStatus {
+    /// Being sent or sent, but not finalized yet
+    Pending,
+    Finalized,
+    Failed,
+    /// Never seen finalized within the expiry time
+    Expired,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum InflightState {
+    /// Reserved, the send hasn't returned yet
+    Sending,
+    Sent(Signature),
+    /// Sending returned an error, but the transaction may have been submitted.
+    /// The signature is unknown.
+    Errored,
+}
+
+#[derive(Clone, Debug)]
+struct InflightTx {
+    sequence: u64,
+    state: InflightState,
+    sent_at: Instant,
+}
+
+pub struct TxTracker {
+    config: Config,
+    rpc: RpcClientAsync,
+    inner: Mutex<TxTrackerInner>,
+}
+
+#[derive(Default)]
+struct TxTrackerInner {
+    next_sequence: u64,
+    inflight: HashMap<String, InflightTx>,
+}
+
+impl TxTrackerInner {
+    fn insert(&mut self, action: &str, state: InflightState) -> u64 {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.inflight.insert(
+            action.to_string(),
+            InflightTx {
+                sequence,
+                state,
+                sent_at: Instant::now(),
+            },
+        );
+        sequence
+    }
+}
+
+impl TxTracker {
+    pub fn new(rpc: RpcClientAsync, config: Config) -> Self {
+        Self {
+            config,
+            rpc,
+            inner: Default::default(),
+        }
+    }
+
+    async fn inflight_status(&self, inflight: &InflightTx) -> anyhow::Result<TxStatus> {
+        let expired = inflight.sent_at.elapsed() > self.config.expiry;
+        let signature = match inflight.state {
+            InflightState::Sent(v) => v,
+            InflightState::Sending | InflightState::Errored => {
+                return Ok(if expired {
+                    TxStatus::Expired
+                } else {
+                    TxStatus::Pending
+                })
+            }
+        };
+
+        let status_opt = self
+            .rpc
+            .get_signature_statuses(&[signature])
+            .await?
+            .value
+            .remove(0);
+        Ok(match status_opt {
+            Some(status) if status.err.is_some() => TxStatus::Failed,
+            Some(status) if status.satisfies_commitment(CommitmentConfig::finalized()) => {
+                TxStatus::Finalized
+            }
+            _ if expired => TxStatus::Expired,
+            _ => TxStatus::Pending,
+        })
+    }
+
+    /// Status of the last transaction sent for an action, if any is being tracked
+    pub async fn status(&self, action: &str) -> anyhow::Result<Option<TxStatus>> {
+        let inflight = match self.inner.lock().unwrap().inflight.get(action) {
+            Some(v) => v.clone(),
+            None => return Ok(None),
+        };
+        Ok(Some(self.inflight_status(&inflight).await?))
+    }
+
+    /// Reserves the action for a new send, unless a transaction for it is pending.
+    ///
+    /// Forgets about the action's previous transaction once it is resolved. Returns
+    /// the reservation's sequence number.
+    async fn reserve(&self, action: &str) -> anyhow::Result<Option<u64>> {
+        let previous = self.inner.lock().unwrap().inflight.get(action).cloned();
+        if let Some(inflight) = &previous {
+            let status = self.inflight_status(inflight).await?;
+            if status == TxStatus::Pending {
+                return Ok(None);
+            }
+            log::trace!("tracked tx for '{}' resolved as {:?}", action, status);
+        }
+
+        // Someone else may have reserved the action while the status was fetched
+        let mut inner = self.inner.lock().unwrap();
+        let current_sequence = inner.inflight.get(action).map(|tx| tx.sequence);
+        if current_sequence != previous.map(|tx| tx.sequence) {
+            return Ok(None);
+        }
+        Ok(Some(inner.insert(action, InflightState::Sending)))
+    }
+
+    /// Records the outcome of the send with the given reservation
+    fn record(&self, action: &str, sequence: u64, state: InflightState) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(inflight) = inner.inflight.get_mut(action) {
+            if inflight.sequence == sequence {
+                inflight.state = state;
+                inflight.sent_at = Instant::now();
+            }
+        }
+    }
+
+    /// Drops the reservation, allowing the action to be sent again right away
+    fn forget(&self, action: &str, sequence: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.inflight.get(action).map(|tx| tx.sequence) == Some(sequence) {
+            inner.inflight.remove(action);
+        }
+    }
+
+    /// Sequence number of the tracked transaction for the action
+    pub fn sequence(&self, action: &str) -> Option<u64> {
+        self.inner
+            .lock()
+            .unwrap()
+            .inflight
+            .get(action)
+            .map(|tx| tx.sequence)
+    }
+
+    /// Calls `send` unless a transaction for the same action is being sent or
+    /// still pending.
+    ///
+    /// Returns Ok(None) if sending was skipped.
+    pub async fn send_once<F, Fut>(
+        &self,
+        action: &str,
+        send: F,
+    ) -> anyhow::Result<Option<Signature>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<Signature>>,
+    {
+        let sequence = match self.reserve(action).await? {
+            Some(v) => v,
+            None => {
+                log::info!(
+                    "not sending tx for '{}': previous tx with sequence {:?} is still pending",
+                    action,
+                    self.sequence(action)
+                );
+                return Ok(None);
+            }
+        };
+
+        match send().await {
+            Ok(signature) => {
+                self.record(action, sequence, InflightState::Sent(signature));
+                log::debug!(
+                    "sent tx {} for '{}', sequence {}",
+                    signature,
+                    action,
+                    sequence
+                );
+                Ok(Some(signature))
+            }
+            Err(err) if may_have_been_submitted(&err) => {
+                self.record(action, sequence, InflightState::Errored);
+                Err(err)
+            }
+            Err(err) => {
+                self.forget(action, sequence);
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Whether the transaction may still land after sending it returned `err`.
+///
+/// Transactions that the rpc node rejected, like ones failing the preflight
+/// simulation, were never submitted. Errors that don't come from the rpc client
+/// happened before sending.
+fn may_have_been_submitted(err: &anyhow::Error) -> bool {
+    if let Some(MangoClientError::SendTransactionPreflightFailure { .. }) =
+        err.downcast_ref::<MangoClientError>()
+    {
+        return false;
+    }
+    match err.downcast_ref::<ClientError>().map(|e| e.kind()) {
+        Some(ClientErrorKind::RpcError(RpcError::RpcResponseError { .. }))
+        | Some(ClientErrorKind::TransactionError(_))
+        | Some(ClientErrorKind::SigningError(_)) => false,
+        // connection errors and transactions that couldn't be confirmed in time
+        Some(_) => true,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // The mock rpc client reports signature statuses depending on its url
+    fn tracker(rpc_url: &str, expiry: Duration) -> TxTracker {
+        TxTracker::new(
+            RpcClientAsync::new_mock(rpc_url.to_string()),
+            Config { expiry },
+        )
+    }
+
+    async fn send_counted(
+        tracker: &TxTracker,
+        sends: &AtomicUsize,
+        result: anyhow::Result<Signature>,
+    ) -> anyhow::Result<Option<Signature>> {
+        tracker
+            .send_once("action", move || async move {
+                sends.fetch_add(1, Ordering::SeqCst);
+                tokio::task::yield_now().await;
+                result
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_pending_blocks_resend() -> anyhow::Result<()> {
+        let tracker = tracker("sig_not_found", Duration::from_secs(60));
+        let sends = AtomicUsize::new(0);
+
+        let sig = Signature::new_unique();
+        assert_eq!(send_counted(&tracker, &sends, Ok(sig)).await?, Some(sig));
+        assert_eq!(tracker.status("action").await?, Some(TxStatus::Pending));
+        assert_eq!(send_counted(&tracker, &sends, Ok(sig)).await?, None);
+        assert_eq!(sends.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_finalized_allows_resend() -> anyhow::Result<()> {
+        let tracker = tracker("succeeds", Duration::from_secs(60));
+        let sends = AtomicUsize::new(0);
+
+        let sig = Signature::new_unique();
+        send_counted(&tracker, &sends, Ok(sig)).await?;
+        assert_eq!(tracker.status("action").await?, Some(TxStatus::Finalized));
+        assert_eq!(tracker.sequence("action"), Some(0));
+
+        assert_eq!(send_counted(&tracker, &sends, Ok(sig)).await?, Some(sig));
+        assert_eq!(tracker.sequence("action"), Some(1));
+        assert_eq!(sends.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_sends_reserve() -> anyhow::Result<()> {
+        let tracker = tracker("succeeds", Duration::from_secs(60));
+        let sends = AtomicUsize::new(0);
+
+        let sig = Signature::new_unique();
+        let (a, b) = tokio::join!(
+            send_counted(&tracker, &sends, Ok(sig)),
+            send_counted(&tracker, &sends, Ok(sig)),
+        );
+        assert_eq!(sends.load(Ordering::SeqCst), 1);
+        assert!(a?.is_some() != b?.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_errored_blocks_until_expiry() -> anyhow::Result<()> {
+        let expiry = Duration::from_millis(200);
+        let tracker = tracker("succeeds", expiry);
+        let sends = AtomicUsize::new(0);
+
+        let timeout = ClientError::from(ClientErrorKind::Custom(
+            "unable to confirm transaction".to_string(),
+        ));
+        let err = send_counted(&tracker, &sends, Err(timeout.into())).await;
+        assert!(err.is_err());
+        assert_eq!(tracker.status("action").await?, Some(TxStatus::Pending));
+        let sig = Signature::new_unique();
+        assert_eq!(send_counted(&tracker, &sends, Ok(sig)).await?, None);
+
+        tokio::time::sleep(expiry).await;
+        assert_eq!(tracker.status("action").await?, Some(TxStatus::Expired));
+        assert_eq!(send_counted(&tracker, &sends, Ok(sig)).await?, Some(sig));
+        assert_eq!(sends.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_preflight_failure_allows_resend() -> anyhow::Result<()> {
+        let tracker = tracker("succeeds", Duration::from_secs(60));
+        let sends = AtomicUsize::new(0);
+
+        let preflight_failure = MangoClientError::SendTransactionPreflightFailure {
+            err: None,
+            logs: vec![],
+        };
+        let err = send_counted(&tracker, &sends, Err(preflight_failure.into())).await;
+        assert!(err.is_err());
+        assert_eq!(tracker.status("action").await?, None);
+
+        let sig = Signature::new_unique();
+        assert_eq!(send_counted(&tracker, &sends, Ok(sig)).await?, Some(sig));
+        assert_eq!(sends.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(fund_index: u16)]
+pub struct InsuranceFundClose<'info> {
+    #[account(
+        has_one = admin,
+        constraint = group.load()?.is_ix_enabled(IxGate::InsuranceFundClose) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        address = group.load()?.insurance_fund_vault(&group.key(), fund_index)?,
+    )]
+    pub insurance_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    /// CHECK: target for account rent needs no checks
+    pub sol_destination: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
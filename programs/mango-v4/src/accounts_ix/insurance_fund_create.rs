@@ -0,0 +1,36 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(fund_index: u16)]
+pub struct InsuranceFundCreate<'info> {
+    #[account(
+        mut,
+        has_one = admin,
+        has_one = insurance_mint,
+        constraint = group.load()?.is_ix_enabled(IxGate::InsuranceFundCreate) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+    pub admin: Signer<'info>,
+
+    pub insurance_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        seeds = [b"InsuranceVault".as_ref(), group.key().as_ref(), &fund_index.to_le_bytes()],
+        bump,
+        token::authority = group,
+        token::mint = insurance_mint,
+        payer = payer
+    )]
+    pub insurance_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
@@ -0,0 +1,37 @@
+use crate::{error::MangoError, state::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(fund_index: u16)]
+pub struct InsuranceFundWithdraw<'info> {
+    #[account(
+        has_one = admin,
+        constraint = group.load()?.is_ix_enabled(IxGate::InsuranceFundWithdraw) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        address = group.load()?.insurance_fund_vault(&group.key(), fund_index)?,
+    )]
+    pub insurance_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> InsuranceFundWithdraw<'info> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let program = self.token_program.to_account_info();
+        let accounts = token::Transfer {
+            from: self.insurance_vault.to_account_info(),
+            to: self.destination.to_account_info(),
+            authority: self.group.to_account_info(),
+        };
+        CpiContext::new(program, accounts)
+    }
+}
@@ -14,21 +14,30 @@ pub use group_create::*;
 pub use group_edit::*;
 pub use group_withdraw_insurance_fund::*;
 pub use health_region::*;
+pub use insurance_fund_close::*;
+pub use insurance_fund_create::*;
+pub use insurance_fund_withdraw::*;
 pub use ix_gate_set::*;
 pub use perp_cancel_all_orders::*;
 pub use perp_cancel_all_orders_by_side::*;
 pub use perp_cancel_order::*;
 pub use perp_cancel_order_by_client_order_id::*;
+pub use perp_cancel_replace_orders::*;
+pub use perp_cancel_trigger_order::*;
+pub use perp_close_expired_position::*;
 pub use perp_close_market::*;
 pub use perp_consume_events::*;
 pub use perp_create_market::*;
 pub use perp_deactivate_position::*;
+pub use perp_edit_insurance_fund::*;
 pub use perp_edit_market::*;
+pub use perp_execute_trigger_order::*;
 pub use perp_force_close_position::*;
 pub use perp_liq_base_or_positive_pnl::*;
 pub use perp_liq_force_cancel_orders::*;
 pub use perp_liq_negative_pnl_or_bankruptcy::*;
 pub use perp_place_order::*;
+pub use perp_place_trigger_order::*;
 pub use perp_settle_fees::*;
 pub use perp_settle_pnl::*;
 pub use perp_update_funding::*;
@@ -49,11 +58,13 @@ pub use token_add_bank::*;
 pub use token_deposit::*;
 pub use token_deregister::*;
 pub use token_edit::*;
+pub use token_edit_insurance_fund::*;
 pub use token_force_close_borrows_with_token::*;
 pub use token_liq_bankruptcy::*;
 pub use token_liq_with_token::*;
 pub use token_register::*;
 pub use token_register_trustless::*;
+pub use token_transfer::*;
 pub use token_update_index_and_rate::*;
 pub use token_withdraw::*;
 
@@ -73,21 +84,30 @@ mod group_create;
 mod group_edit;
 mod group_withdraw_insurance_fund;
 mod health_region;
+mod insurance_fund_close;
+mod insurance_fund_create;
+mod insurance_fund_withdraw;
 mod ix_gate_set;
 mod perp_cancel_all_orders;
 mod perp_cancel_all_orders_by_side;
 mod perp_cancel_order;
 mod perp_cancel_order_by_client_order_id;
+mod perp_cancel_replace_orders;
+mod perp_cancel_trigger_order;
+mod perp_close_expired_position;
 mod perp_close_market;
 mod perp_consume_events;
 mod perp_create_market;
 mod perp_deactivate_position;
+mod perp_edit_insurance_fund;
 mod perp_edit_market;
+mod perp_execute_trigger_order;
 mod perp_force_close_position;
 mod perp_liq_base_or_positive_pnl;
 mod perp_liq_force_cancel_orders;
 mod perp_liq_negative_pnl_or_bankruptcy;
 mod perp_place_order;
+mod perp_place_trigger_order;
 mod perp_settle_fees;
 mod perp_settle_pnl;
 mod perp_update_funding;
@@ -108,10 +128,12 @@ mod token_add_bank;
 mod token_deposit;
 mod token_deregister;
 mod token_edit;
+mod token_edit_insurance_fund;
 mod token_force_close_borrows_with_token;
 mod token_liq_bankruptcy;
 mod token_liq_with_token;
 mod token_register;
 mod token_register_trustless;
+mod token_transfer;
 mod token_update_index_and_rate;
 mod token_withdraw;
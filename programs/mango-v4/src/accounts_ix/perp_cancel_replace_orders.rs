@@ -0,0 +1,86 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+/// Which of the account's orders on the market to cancel before placing new ones
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum PerpCancelOrders {
+    None,
+
+    /// All orders, or only the orders on one side
+    All {
+        side_option: Option<Side>,
+    },
+
+    /// Orders that are no longer on the book (filled or expired) are skipped
+    ByOrderIds {
+        order_ids: Vec<u128>,
+    },
+
+    /// Orders that are no longer on the book (filled or expired) are skipped
+    ByClientOrderIds {
+        client_order_ids: Vec<u64>,
+    },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum PerpOrderPrice {
+    /// The price in lots (quote lots per base lots), see perp_place_order
+    Fixed { price_lots: i64 },
+
+    /// The adjustment from the oracle price and the peg limit, see perp_place_order_pegged
+    OraclePegged {
+        price_offset_lots: i64,
+        peg_limit: i64,
+    },
+}
+
+/// A new order in perp_cancel_replace_orders
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct PerpNewOrder {
+    pub side: Side,
+    pub price: PerpOrderPrice,
+    pub max_base_lots: i64,
+    pub max_quote_lots: i64,
+    pub client_order_id: u64,
+    pub order_type: PlaceOrderType,
+    pub reduce_only: bool,
+    /// Timestamp of when the order expires, 0 for never
+    pub expiry_timestamp: u64,
+}
+
+#[derive(Accounts)]
+pub struct PerpCancelReplaceOrders<'info> {
+    #[account(
+        constraint = group.load()?.is_ix_enabled(IxGate::PerpCancelReplaceOrders) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+
+    #[account(
+        mut,
+        has_one = group,
+        constraint = account.load()?.is_operational() @ MangoError::AccountIsFrozen
+        // owner is checked at #1
+    )]
+    pub account: AccountLoader<'info, MangoAccountFixed>,
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = group,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue,
+        has_one = oracle,
+    )]
+    pub perp_market: AccountLoader<'info, PerpMarket>,
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BookSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, BookSide>,
+    #[account(mut)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    /// CHECK: The oracle can be one of several different account types and the pubkey is checked above
+    pub oracle: UncheckedAccount<'info>,
+}
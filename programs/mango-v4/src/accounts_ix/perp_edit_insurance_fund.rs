@@ -0,0 +1,23 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+/// Switches a perp market to another insurance fund.
+///
+/// When switching to an isolated insurance fund, its vault must be passed as
+/// a remaining account.
+#[derive(Accounts)]
+pub struct PerpEditInsuranceFund<'info> {
+    // mut for tracking insurance fund references
+    #[account(
+        mut,
+        has_one = admin,
+    )]
+    pub group: AccountLoader<'info, Group>,
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = group
+    )]
+    pub perp_market: AccountLoader<'info, PerpMarket>,
+}
@@ -0,0 +1,52 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+/// Permissionless: anyone can execute a trigger order once its condition is met
+/// and receives the trigger order's incentive on the executor account.
+#[derive(Accounts)]
+pub struct PerpExecuteTriggerOrder<'info> {
+    #[account(
+        constraint = group.load()?.is_ix_enabled(IxGate::PerpExecuteTriggerOrder) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+
+    #[account(
+        mut,
+        has_one = group,
+        constraint = account.load()?.is_operational() @ MangoError::AccountIsFrozen
+    )]
+    pub account: AccountLoader<'info, MangoAccountFixed>,
+
+    #[account(
+        mut,
+        has_one = group,
+        constraint = executor.load()?.is_operational() @ MangoError::AccountIsFrozen
+        // executor_owner is checked at #1
+    )]
+    pub executor: AccountLoader<'info, MangoAccountFixed>,
+    pub executor_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = group,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue,
+        has_one = oracle,
+    )]
+    pub perp_market: AccountLoader<'info, PerpMarket>,
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BookSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, BookSide>,
+    #[account(mut)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    /// CHECK: The oracle can be one of several different account types and the pubkey is checked above
+    pub oracle: UncheckedAccount<'info>,
+
+    // bank correctness is checked at #2
+    #[account(mut, has_one = group)]
+    pub settle_bank: AccountLoader<'info, Bank>,
+}
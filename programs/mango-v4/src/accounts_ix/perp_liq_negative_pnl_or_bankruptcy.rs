@@ -6,7 +6,6 @@ use anchor_spl::token::{self, Token, TokenAccount};
 #[derive(Accounts)]
 pub struct PerpLiqNegativePnlOrBankruptcy<'info> {
     #[account(
-        has_one = insurance_vault,
         constraint = group.load()?.is_ix_enabled(IxGate::PerpLiqNegativePnlOrBankruptcy) @ MangoError::IxIsDisabled,
     )]
     pub group: AccountLoader<'info, Group>,
@@ -48,9 +47,16 @@ pub struct PerpLiqNegativePnlOrBankruptcy<'info> {
     #[account(address = settle_bank.load()?.oracle)]
     pub settle_oracle: UncheckedAccount<'info>,
 
-    // future: this would be an insurance fund vault specific to a
-    // trustless token, separate from the shared one on the group
-    #[account(mut)]
+    // The vault of the insurance fund covering the perp market: either the group
+    // insurance vault or the market's isolated one, the exact address is checked at #3
+    #[account(
+        mut,
+        constraint = group.load()?.is_insurance_vault(
+            &group.key(),
+            perp_market.load()?.insurance_fund_index,
+            &insurance_vault.key(),
+        )?
+    )]
     pub insurance_vault: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
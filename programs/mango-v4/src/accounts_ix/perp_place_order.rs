@@ -37,3 +37,21 @@ pub struct PerpPlaceOrder<'info> {
     /// CHECK: The oracle can be one of several different account types and the pubkey is checked above
     pub oracle: UncheckedAccount<'info>,
 }
+
+/// PerpPlaceOrder with its own ix gate, in addition to the PerpPlaceOrder one
+#[derive(Accounts)]
+pub struct PerpPlaceOrderV2<'info> {
+    #[account(
+        constraint = v1.group.load()?.is_ix_enabled(IxGate::PerpPlaceOrderV2) @ MangoError::IxIsDisabled,
+    )]
+    pub v1: PerpPlaceOrder<'info>,
+}
+
+/// PerpPlaceOrder with its own ix gate, in addition to the PerpPlaceOrder one
+#[derive(Accounts)]
+pub struct PerpPlaceOrderPeggedV2<'info> {
+    #[account(
+        constraint = v1.group.load()?.is_ix_enabled(IxGate::PerpPlaceOrderPeggedV2) @ MangoError::IxIsDisabled,
+    )]
+    pub v1: PerpPlaceOrder<'info>,
+}
@@ -0,0 +1,23 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct PerpPlaceTriggerOrder<'info> {
+    #[account(
+        constraint = group.load()?.is_ix_enabled(IxGate::PerpPlaceTriggerOrder) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+
+    #[account(
+        mut,
+        has_one = group,
+        constraint = account.load()?.is_operational() @ MangoError::AccountIsFrozen
+        // owner is checked at #1
+    )]
+    pub account: AccountLoader<'info, MangoAccountFixed>,
+    pub owner: Signer<'info>,
+
+    #[account(has_one = group)]
+    pub perp_market: AccountLoader<'info, PerpMarket>,
+}
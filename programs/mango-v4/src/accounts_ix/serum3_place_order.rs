@@ -7,7 +7,17 @@ use num_enum::TryFromPrimitive;
 
 /// Copy paste a bunch of enums so that we could AnchorSerialize & AnchorDeserialize them
 
-#[derive(Clone, Copy, TryFromPrimitive, IntoPrimitive, AnchorSerialize, AnchorDeserialize)]
+#[derive(
+    Eq,
+    PartialEq,
+    Copy,
+    Clone,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Debug,
+    AnchorSerialize,
+    AnchorDeserialize,
+)]
 #[repr(u8)]
 pub enum Serum3SelfTradeBehavior {
     DecrementTake = 0,
@@ -0,0 +1,23 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+/// Switches a token to another insurance fund.
+///
+/// When switching to an isolated insurance fund, its vault must be passed as
+/// a remaining account.
+#[derive(Accounts)]
+pub struct TokenEditInsuranceFund<'info> {
+    // mut for tracking insurance fund references
+    #[account(
+        mut,
+        has_one = admin,
+    )]
+    pub group: AccountLoader<'info, Group>,
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = group
+    )]
+    pub mint_info: AccountLoader<'info, MintInfo>,
+}
@@ -12,7 +12,6 @@ use crate::state::*;
 #[derive(Accounts)]
 pub struct TokenLiqBankruptcy<'info> {
     #[account(
-        has_one = insurance_vault,
         constraint = group.load()?.is_ix_enabled(IxGate::TokenLiqBankruptcy) @ MangoError::IxIsDisabled,
     )]
     pub group: AccountLoader<'info, Group>,
@@ -42,9 +41,16 @@ pub struct TokenLiqBankruptcy<'info> {
     // address is checked at #2 a) and b)
     pub quote_vault: Account<'info, TokenAccount>,
 
-    // future: this would be an insurance fund vault specific to a
-    // trustless token, separate from the shared one on the group
-    #[account(mut)]
+    // The vault of the insurance fund covering the liab token: either the group
+    // insurance vault or the token's isolated one, the exact address is checked at #3
+    #[account(
+        mut,
+        constraint = group.load()?.is_insurance_vault(
+            &group.key(),
+            liab_mint_info.load()?.insurance_fund_index,
+            &insurance_vault.key(),
+        )?
+    )]
     pub insurance_vault: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
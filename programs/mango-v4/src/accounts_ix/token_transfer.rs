@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::error::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct TokenTransfer<'info> {
+    #[account(
+        constraint = group.load()?.is_ix_enabled(IxGate::TokenTransfer) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+
+    #[account(
+        mut,
+        has_one = group,
+        has_one = owner,
+        constraint = source_account.load()?.is_operational() @ MangoError::AccountIsFrozen
+    )]
+    pub source_account: AccountLoader<'info, MangoAccountFixed>,
+
+    #[account(
+        mut,
+        has_one = group,
+        has_one = owner,
+        constraint = destination_account.load()?.is_operational() @ MangoError::AccountIsFrozen
+    )]
+    pub destination_account: AccountLoader<'info, MangoAccountFixed>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = group,
+        has_one = oracle,
+    )]
+    pub bank: AccountLoader<'info, Bank>,
+
+    /// CHECK: The oracle can be one of several different account types
+    pub oracle: UncheckedAccount<'info>,
+}
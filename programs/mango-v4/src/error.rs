@@ -101,6 +101,18 @@ pub enum MangoError {
     TokenInForceClose,
     #[msg("incorrect number of health accounts")]
     InvalidHealthAccountCount,
+    #[msg("perp trigger order not found")]
+    PerpTriggerOrderNotFound,
+    #[msg("perp trigger order condition is not met")]
+    PerpTriggerOrderNotTriggered,
+    #[msg("the order would self trade")]
+    WouldSelfTrade,
+    #[msg("the fill-or-kill order could not be filled completely")]
+    FillOrKillNotFilled,
+    #[msg("the perp market has expired")]
+    PerpMarketExpired,
+    #[msg("the perp market has not expired yet")]
+    PerpMarketNotExpired,
 }
 
 impl MangoError {
@@ -27,5 +27,14 @@ pub struct TokenEquity {
 #[derive(AnchorDeserialize, AnchorSerialize, Debug)]
 pub struct PerpEquity {
     pub perp_market_index: PerpMarketIndex,
-    value: I80F48, // in native quote
+    /// Unsettled pnl at the oracle price, including unsettled funding
+    pub value: I80F48, // in native quote
+    /// Unsettled pnl at the stable price, including unsettled funding
+    pub stable_value: I80F48, // in native quote
+    /// Value of the base position at the oracle price
+    pub base_value: I80F48, // in native quote
+    /// Value of the base position at the stable price
+    pub stable_base_value: I80F48, // in native quote
+    /// Funding the account still needs to pay, negative if it is owed funding
+    pub unsettled_funding: I80F48, // in native quote
 }
@@ -47,12 +47,15 @@ pub trait AccountRetriever {
 /// 3. PerpMarket accounts, in the order of account.perps.iter_active_accounts()
 /// 4. PerpMarket oracle accounts, in the order of the perp market accounts
 /// 5. serum3 OpenOrders accounts, in the order of account.serum3.iter_active()
+/// 6. fallback oracle accounts, in any order (optional): any of the distinct fallback
+///    oracles that are configured on the banks and perp markets above
 pub struct FixedOrderAccountRetriever<T: KeyedAccountReader> {
     pub ais: Vec<T>,
     pub n_banks: usize,
     pub n_perps: usize,
     pub begin_perp: usize,
     pub begin_serum3: usize,
+    pub begin_fallback_oracles: usize,
     pub staleness_slot: Option<u64>,
 }
 
@@ -66,22 +69,58 @@ pub fn new_fixed_order_account_retriever<'a, 'info>(
     let expected_ais = active_token_len * 2 // banks + oracles
         + active_perp_len * 2 // PerpMarkets + Oracles
         + active_serum3_len; // open_orders
-    require_msg_typed!(ais.len() == expected_ais, MangoError::InvalidHealthAccountCount,
-        "received {} accounts but expected {} ({} banks, {} bank oracles, {} perp markets, {} perp oracles, {} serum3 oos)",
+    require_msg_typed!(ais.len() >= expected_ais, MangoError::InvalidHealthAccountCount,
+        "received {} accounts but expected at least {} ({} banks, {} bank oracles, {} perp markets, {} perp oracles, {} serum3 oos)",
         ais.len(), expected_ais,
         active_token_len, active_token_len, active_perp_len, active_perp_len, active_serum3_len
     );
 
+    let ais = AccountInfoRef::borrow_slice(ais)?;
+    let begin_perp = active_token_len * 2;
+    let fallback_oracle_len = fallback_oracle_count(
+        &ais[..active_token_len],
+        &ais[begin_perp..begin_perp + active_perp_len],
+    )?;
+    require_msg_typed!(ais.len() <= expected_ais + fallback_oracle_len, MangoError::InvalidHealthAccountCount,
+        "received {} accounts but expected at most {} ({} banks, {} bank oracles, {} perp markets, {} perp oracles, {} serum3 oos, {} fallback oracles)",
+        ais.len(), expected_ais + fallback_oracle_len,
+        active_token_len, active_token_len, active_perp_len, active_perp_len, active_serum3_len, fallback_oracle_len
+    );
+
     Ok(FixedOrderAccountRetriever {
-        ais: AccountInfoRef::borrow_slice(ais)?,
+        ais,
         n_banks: active_token_len,
         n_perps: active_perp_len,
-        begin_perp: active_token_len * 2,
+        begin_perp,
         begin_serum3: active_token_len * 2 + active_perp_len * 2,
+        begin_fallback_oracles: expected_ais,
         staleness_slot: Some(Clock::get()?.slot),
     })
 }
 
+/// Number of distinct fallback oracles configured on the bank and perp market accounts
+fn fallback_oracle_count<T: KeyedAccountReader>(banks: &[T], perp_markets: &[T]) -> Result<usize> {
+    let mut keys = Vec::with_capacity(banks.len() + perp_markets.len());
+    for bank_ai in banks {
+        keys.push(bank_ai.load::<Bank>()?.fallback_oracle);
+    }
+    for perp_market_ai in perp_markets {
+        keys.push(perp_market_ai.load::<PerpMarket>()?.fallback_oracle);
+    }
+    keys.retain(|key| *key != Pubkey::default());
+    keys.sort_unstable();
+    keys.dedup();
+    Ok(keys.len())
+}
+
+/// Finds the fallback oracle account with `key`, if it is set and was passed
+fn find_fallback_oracle<'b, T: KeyedAccountReader>(ais: &'b [T], key: &Pubkey) -> Option<&'b T> {
+    if *key == Pubkey::default() {
+        return None;
+    }
+    ais.iter().find(|ai| ai.key() == key)
+}
+
 impl<T: KeyedAccountReader> FixedOrderAccountRetriever<T> {
     fn bank(&self, group: &Pubkey, account_index: usize, token_index: TokenIndex) -> Result<&Bank> {
         let bank = self.ais[account_index].load::<Bank>()?;
@@ -103,14 +142,20 @@ impl<T: KeyedAccountReader> FixedOrderAccountRetriever<T> {
         Ok(market)
     }
 
+    fn fallback_oracle(&self, key: &Pubkey) -> Option<&T> {
+        find_fallback_oracle(&self.ais[self.begin_fallback_oracles..], key)
+    }
+
     fn oracle_price_bank(&self, account_index: usize, bank: &Bank) -> Result<I80F48> {
         let oracle = &self.ais[account_index];
-        bank.oracle_price(oracle, self.staleness_slot)
+        let fallback_oracle = self.fallback_oracle(&bank.fallback_oracle);
+        bank.oracle_price_with_fallback(oracle, fallback_oracle, self.staleness_slot)
     }
 
     fn oracle_price_perp(&self, account_index: usize, perp_market: &PerpMarket) -> Result<I80F48> {
         let oracle = &self.ais[account_index];
-        perp_market.oracle_price(oracle, self.staleness_slot)
+        let fallback_oracle = self.fallback_oracle(&perp_market.fallback_oracle);
+        perp_market.oracle_price_with_fallback(oracle, fallback_oracle, self.staleness_slot)
     }
 }
 
@@ -198,7 +243,8 @@ impl<T: KeyedAccountReader> AccountRetriever for FixedOrderAccountRetriever<T> {
 /// - the same number of oracles in the same order as the banks, followed by
 /// - an unknown number of PerpMarket accounts
 /// - the same number of oracles in the same order as the perp markets
-/// - an unknown number of serum3 OpenOrders accounts
+/// - an unknown number of serum3 OpenOrders accounts, followed by
+/// - the fallback oracles that may be needed, in any order
 /// and retrieves accounts needed for the health computation by doing a linear
 /// scan for each request.
 pub struct ScanningAccountRetriever<'a, 'info> {
@@ -206,6 +252,7 @@ pub struct ScanningAccountRetriever<'a, 'info> {
     oracles: Vec<AccountInfoRef<'a, 'info>>,
     perp_markets: Vec<AccountInfoRef<'a, 'info>>,
     perp_oracles: Vec<AccountInfoRef<'a, 'info>>,
+    /// serum3 OpenOrders accounts followed by fallback oracles
     serum3_oos: Vec<AccountInfoRef<'a, 'info>>,
     token_index_map: HashMap<TokenIndex, usize>,
     perp_index_map: HashMap<PerpMarketIndex, usize>,
@@ -319,6 +366,10 @@ impl<'a, 'info> ScanningAccountRetriever<'a, 'info> {
             .ok_or_else(|| error_msg!("perp market index {} not found", perp_market_index))?)
     }
 
+    fn fallback_oracle(&self, key: &Pubkey) -> Option<&AccountInfoRef<'a, 'info>> {
+        find_fallback_oracle(&self.serum3_oos, key)
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn banks_mut_and_oracles(
         &mut self,
@@ -329,7 +380,9 @@ impl<'a, 'info> ScanningAccountRetriever<'a, 'info> {
             let index = self.bank_index(token_index1)?;
             let bank = self.banks[index].load_mut_fully_unchecked::<Bank>()?;
             let oracle = &self.oracles[index];
-            let price = bank.oracle_price(oracle, self.staleness_slot)?;
+            let fallback_oracle = find_fallback_oracle(&self.serum3_oos, &bank.fallback_oracle);
+            let price =
+                bank.oracle_price_with_fallback(oracle, fallback_oracle, self.staleness_slot)?;
             return Ok((bank, price, None));
         }
         let index1 = self.bank_index(token_index1)?;
@@ -347,8 +400,10 @@ impl<'a, 'info> ScanningAccountRetriever<'a, 'info> {
         let bank2 = second_bank_part[second - (first + 1)].load_mut_fully_unchecked::<Bank>()?;
         let oracle1 = &self.oracles[first];
         let oracle2 = &self.oracles[second];
-        let price1 = bank1.oracle_price(oracle1, self.staleness_slot)?;
-        let price2 = bank2.oracle_price(oracle2, self.staleness_slot)?;
+        let fallback1 = find_fallback_oracle(&self.serum3_oos, &bank1.fallback_oracle);
+        let fallback2 = find_fallback_oracle(&self.serum3_oos, &bank2.fallback_oracle);
+        let price1 = bank1.oracle_price_with_fallback(oracle1, fallback1, self.staleness_slot)?;
+        let price2 = bank2.oracle_price_with_fallback(oracle2, fallback2, self.staleness_slot)?;
         if swap {
             Ok((bank2, price2, Some((bank1, price1))))
         } else {
@@ -361,7 +416,9 @@ impl<'a, 'info> ScanningAccountRetriever<'a, 'info> {
         // The account was already loaded successfully during construction
         let bank = self.banks[index].load_fully_unchecked::<Bank>()?;
         let oracle = &self.oracles[index];
-        let price = bank.oracle_price(oracle, self.staleness_slot)?;
+        let fallback_oracle = self.fallback_oracle(&bank.fallback_oracle);
+        let price =
+            bank.oracle_price_with_fallback(oracle, fallback_oracle, self.staleness_slot)?;
         Ok((bank, price))
     }
 
@@ -373,7 +430,12 @@ impl<'a, 'info> ScanningAccountRetriever<'a, 'info> {
         // The account was already loaded successfully during construction
         let perp_market = self.perp_markets[index].load_fully_unchecked::<PerpMarket>()?;
         let oracle_acc = &self.perp_oracles[index];
-        let price = perp_market.oracle_price(oracle_acc, self.staleness_slot)?;
+        let fallback_oracle = self.fallback_oracle(&perp_market.fallback_oracle);
+        let price = perp_market.oracle_price_with_fallback(
+            oracle_acc,
+            fallback_oracle,
+            self.staleness_slot,
+        )?;
         Ok((perp_market, price))
     }
 
@@ -825,14 +825,19 @@ pub fn new_health_cache(
             i,
             perp_position.market_index,
         )?;
-        perp_infos.push(PerpInfo::new(
-            perp_position,
-            perp_market,
+        // Expired dated markets are valued at their settlement price once it's fixed
+        let prices = if perp_market.has_settlement_price == 1 {
+            Prices {
+                oracle: perp_market.settlement_price,
+                stable: perp_market.settlement_price,
+            }
+        } else {
             Prices {
                 oracle: oracle_price,
                 stable: perp_market.stable_price(),
-            },
-        )?);
+            }
+        };
+        perp_infos.push(PerpInfo::new(perp_position, perp_market, prices)?);
     }
 
     Ok(HealthCache {
@@ -1257,4 +1262,49 @@ mod tests {
             test_health1_runner(testcase);
         }
     }
+
+    #[test]
+    fn test_expired_perp_uses_settlement_price() {
+        let buffer = MangoAccount::default_for_tests().try_to_vec().unwrap();
+        let mut account = MangoAccountValue::from_bytes(&buffer).unwrap();
+
+        let group = Pubkey::new_unique();
+
+        let (mut bank1, mut oracle1) = mock_bank_and_oracle(group, 1, 1.0, 0.2, 0.1);
+        let (_bank2, mut oracle2) = mock_bank_and_oracle(group, 4, 5.0, 0.5, 0.3);
+        bank1
+            .data()
+            .deposit(
+                account.ensure_token_position(1).unwrap().0,
+                I80F48::from(100),
+                DUMMY_NOW_TS,
+            )
+            .unwrap();
+
+        let mut perp1 = mock_perp_market(group, oracle2.pubkey, 5.0, 9, (0.2, 0.1), (0.05, 0.02));
+        let perpaccount = account.ensure_perp_position(9, 1).unwrap().0;
+        perpaccount.record_trade(perp1.data(), 3, -I80F48::from(150u16));
+
+        let mut health_prices = |perp1: &mut TestAccount<PerpMarket>| {
+            let ais = vec![
+                bank1.as_account_info(),
+                oracle1.as_account_info(),
+                perp1.as_account_info(),
+                oracle2.as_account_info(),
+            ];
+            let retriever =
+                ScanningAccountRetriever::new_with_staleness(&ais, &group, None).unwrap();
+            let cache = new_health_cache(&account.borrow(), &retriever).unwrap();
+            cache.perp_info(9).unwrap().prices.clone()
+        };
+
+        let prices = health_prices(&mut perp1);
+        assert_eq!(prices.oracle, I80F48::from(5));
+
+        perp1.data().settlement_price = I80F48::from(2);
+        perp1.data().has_settlement_price = 1;
+        let prices = health_prices(&mut perp1);
+        assert_eq!(prices.oracle, I80F48::from(2));
+        assert_eq!(prices.stable, I80F48::from(2));
+    }
 }
@@ -38,14 +38,16 @@ pub fn account_buyback_fees_with_mngo(
     let now_ts = clock.unix_timestamp.try_into().unwrap();
     let slot = clock.slot;
 
-    let mngo_oracle_price = mngo_bank.oracle_price(
+    let mngo_oracle_price = mngo_bank.oracle_price_with_fallback(
         &AccountInfoRef::borrow(&ctx.accounts.mngo_oracle.as_ref())?,
+        fallback_oracle_account(ctx.remaining_accounts, &mngo_bank.fallback_oracle)?.as_ref(),
         Some(slot),
     )?;
     let mngo_asset_price = mngo_oracle_price.min(mngo_bank.stable_price());
 
-    let fees_oracle_price = fees_bank.oracle_price(
+    let fees_oracle_price = fees_bank.oracle_price_with_fallback(
         &AccountInfoRef::borrow(&ctx.accounts.fees_oracle.as_ref())?,
+        fallback_oracle_account(ctx.remaining_accounts, &fees_bank.fallback_oracle)?.as_ref(),
         Some(slot),
     )?;
     let fees_liab_price = fees_oracle_price.max(fees_bank.stable_price());
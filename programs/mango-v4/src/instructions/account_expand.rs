@@ -9,8 +9,27 @@ pub fn account_expand(
     serum3_count: u8,
     perp_count: u8,
     perp_oo_count: u8,
+    perp_trigger_order_count_opt: Option<u8>,
 ) -> Result<()> {
-    let new_space = MangoAccount::space(token_count, serum3_count, perp_count, perp_oo_count)?;
+    // Keep the existing trigger order slots if no new count is requested
+    let perp_trigger_order_count = match perp_trigger_order_count_opt {
+        Some(count) => count,
+        None => {
+            ctx.accounts
+                .account
+                .load_full()?
+                .header
+                .perp_trigger_order_count
+        }
+    };
+
+    let new_space = MangoAccount::space(
+        token_count,
+        serum3_count,
+        perp_count,
+        perp_oo_count,
+        perp_trigger_order_count,
+    )?;
     let new_rent_minimum = Rent::get()?.minimum_balance(new_space);
 
     let realloc_account = ctx.accounts.account.as_ref();
@@ -36,7 +55,13 @@ pub fn account_expand(
 
     // expand dynamic content, e.g. to grow token positions, we need to slide serum3orders further later, and so on....
     let mut account = ctx.accounts.account.load_full_mut()?;
-    account.expand_dynamic_content(token_count, serum3_count, perp_count, perp_oo_count)?;
+    account.expand_dynamic_content(
+        token_count,
+        serum3_count,
+        perp_count,
+        perp_oo_count,
+        perp_trigger_order_count,
+    )?;
 
     Ok(())
 }
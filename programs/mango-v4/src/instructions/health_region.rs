@@ -16,8 +16,11 @@ pub fn health_region_begin<'key, 'accounts, 'remaining, 'info>(
         crate::instruction::PerpCancelAllOrdersBySide::discriminator(),
         crate::instruction::PerpCancelOrder::discriminator(),
         crate::instruction::PerpCancelOrderByClientOrderId::discriminator(),
+        crate::instruction::PerpCancelReplaceOrders::discriminator(),
         crate::instruction::PerpPlaceOrder::discriminator(),
         crate::instruction::PerpPlaceOrderPegged::discriminator(),
+        crate::instruction::PerpPlaceOrderPeggedV2::discriminator(),
+        crate::instruction::PerpPlaceOrderV2::discriminator(),
         crate::instruction::Serum3CancelAllOrders::discriminator(),
         crate::instruction::Serum3CancelOrder::discriminator(),
         crate::instruction::Serum3PlaceOrder::discriminator(),
@@ -0,0 +1,38 @@
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount};
+
+pub fn insurance_fund_close(ctx: Context<InsuranceFundClose>, fund_index: u16) -> Result<()> {
+    // The group insurance fund can't be closed
+    require_neq!(fund_index, 0, MangoError::SomeError);
+
+    // Bankruptcies in tokens and perp markets that use the fund would fail
+    // if its vault didn't exist anymore
+    let group = ctx.accounts.group.load()?;
+    let references = group.insurance_fund_references[fund_index as usize];
+    require_msg!(
+        references == 0,
+        "insurance fund {} is still used by {} tokens or perp markets",
+        fund_index,
+        references
+    );
+
+    // close insurance vault (must be empty)
+    let group_seeds = group_seeds!(group);
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.insurance_vault.to_account_info(),
+        destination: ctx.accounts.sol_destination.to_account_info(),
+        authority: ctx.accounts.group.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::close_account(CpiContext::new_with_signer(
+        cpi_program,
+        cpi_accounts,
+        &[group_seeds],
+    ))?;
+    ctx.accounts.insurance_vault.exit(ctx.program_id)?;
+
+    Ok(())
+}
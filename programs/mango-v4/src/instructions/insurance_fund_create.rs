@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::state::*;
+
+pub fn insurance_fund_create(ctx: Context<InsuranceFundCreate>, fund_index: u16) -> Result<()> {
+    // Index 0 refers to the group insurance fund
+    require_neq!(fund_index, 0, MangoError::SomeError);
+    require_msg!(
+        (fund_index as usize) < MAX_INSURANCE_FUNDS,
+        "insurance fund index {} out of range",
+        fund_index
+    );
+
+    let mut group = ctx.accounts.group.load_mut()?;
+    group.insurance_fund_vault_bumps[fund_index as usize] = *ctx
+        .bumps
+        .get("insurance_vault")
+        .ok_or(MangoError::SomeError)?;
+
+    msg!(
+        "created insurance fund {} with vault {}",
+        fund_index,
+        ctx.accounts.insurance_vault.key()
+    );
+
+    Ok(())
+}
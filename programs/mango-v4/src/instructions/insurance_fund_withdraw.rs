@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+
+use crate::error::MangoError;
+use crate::{accounts_ix::InsuranceFundWithdraw, group_seeds};
+
+pub fn insurance_fund_withdraw(
+    ctx: Context<InsuranceFundWithdraw>,
+    fund_index: u16,
+    amount: u64,
+) -> Result<()> {
+    // Use GroupWithdrawInsuranceFund for the group insurance fund
+    require_neq!(fund_index, 0, MangoError::SomeError);
+
+    let group = ctx.accounts.group.load()?;
+
+    let group_seeds = group_seeds!(group);
+    token::transfer(
+        ctx.accounts.transfer_ctx().with_signer(&[group_seeds]),
+        amount.min(ctx.accounts.insurance_vault.amount),
+    )?;
+
+    Ok(())
+}
@@ -68,6 +68,17 @@ pub fn ix_gate_set(ctx: Context<IxGateSet>, ix_gate: u128) -> Result<()> {
     log_if_changed(&group, ix_gate, IxGate::TokenForceCloseBorrowsWithToken);
     log_if_changed(&group, ix_gate, IxGate::PerpForceClosePosition);
     log_if_changed(&group, ix_gate, IxGate::GroupWithdrawInsuranceFund);
+    log_if_changed(&group, ix_gate, IxGate::InsuranceFundCreate);
+    log_if_changed(&group, ix_gate, IxGate::InsuranceFundWithdraw);
+    log_if_changed(&group, ix_gate, IxGate::InsuranceFundClose);
+    log_if_changed(&group, ix_gate, IxGate::PerpPlaceTriggerOrder);
+    log_if_changed(&group, ix_gate, IxGate::PerpCancelTriggerOrder);
+    log_if_changed(&group, ix_gate, IxGate::PerpExecuteTriggerOrder);
+    log_if_changed(&group, ix_gate, IxGate::TokenTransfer);
+    log_if_changed(&group, ix_gate, IxGate::PerpCancelReplaceOrders);
+    log_if_changed(&group, ix_gate, IxGate::PerpCloseExpiredPosition);
+    log_if_changed(&group, ix_gate, IxGate::PerpPlaceOrderV2);
+    log_if_changed(&group, ix_gate, IxGate::PerpPlaceOrderPeggedV2);
 
     group.ix_gate = ix_gate;
 
@@ -0,0 +1,228 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::health::{new_fixed_order_account_retriever, new_health_cache};
+use crate::instructions::apply_reduce_only;
+use crate::state::*;
+
+/// Converts the instruction parameters to an Order, None if it's already expired
+fn new_order_to_order(
+    new_order: &PerpNewOrder,
+    self_trade_behavior: SelfTradeBehavior,
+) -> Result<Option<Order>> {
+    let time_in_force = match Order::tif_from_expiry(new_order.expiry_timestamp) {
+        Some(t) => t,
+        None => return Ok(None),
+    };
+    let params = match new_order.price {
+        PerpOrderPrice::Fixed { price_lots } => {
+            require_gte!(price_lots, 0);
+            match new_order.order_type {
+                PlaceOrderType::Market => OrderParams::Market,
+                PlaceOrderType::ImmediateOrCancel => OrderParams::ImmediateOrCancel { price_lots },
+                PlaceOrderType::FillOrKill => OrderParams::FillOrKill { price_lots },
+                _ => OrderParams::Fixed {
+                    price_lots,
+                    order_type: new_order.order_type.to_post_order_type()?,
+                },
+            }
+        }
+        PerpOrderPrice::OraclePegged {
+            price_offset_lots,
+            peg_limit,
+        } => {
+            require_gte!(peg_limit, -1);
+            OrderParams::OraclePegged {
+                price_offset_lots,
+                order_type: new_order.order_type.to_post_order_type()?,
+                peg_limit,
+                max_oracle_staleness_slots: -1,
+            }
+        }
+    };
+    require_gte!(new_order.max_base_lots, 0);
+    require_gte!(new_order.max_quote_lots, 0);
+    Ok(Some(Order {
+        side: new_order.side,
+        max_base_lots: new_order.max_base_lots,
+        max_quote_lots: new_order.max_quote_lots,
+        client_order_id: new_order.client_order_id,
+        reduce_only: new_order.reduce_only,
+        time_in_force,
+        self_trade_behavior,
+        params,
+    }))
+}
+
+pub fn perp_cancel_replace_orders(
+    ctx: Context<PerpCancelReplaceOrders>,
+    cancel: PerpCancelOrders,
+    new_orders: Vec<PerpNewOrder>,
+    limit: u8,
+    self_trade_behavior: SelfTradeBehavior,
+) -> Result<Vec<Option<u128>>> {
+    // Validate all orders before changing anything
+    let orders = new_orders
+        .iter()
+        .map(|new_order| new_order_to_order(new_order, self_trade_behavior))
+        .collect::<Result<Vec<_>>>()?;
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    let oracle_price;
+
+    // Update funding if possible, like in perp_place_order
+    {
+        let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+        let book = Orderbook {
+            bids: ctx.accounts.bids.load_mut()?,
+            asks: ctx.accounts.asks.load_mut()?,
+        };
+
+        let oracle_slot;
+        (oracle_price, oracle_slot) = perp_market.oracle_price_and_slot_with_fallback(
+            &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
+            fallback_oracle_account(ctx.remaining_accounts, &perp_market.fallback_oracle)?.as_ref(),
+            None, // staleness checked in health
+        )?;
+
+        perp_market.update_funding_and_stable_price(&book, oracle_price, oracle_slot, now_ts)?;
+    }
+
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    // account constraint #1
+    require!(
+        account.fixed.is_owner_or_delegate(ctx.accounts.owner.key()),
+        MangoError::SomeError
+    );
+
+    let account_pk = ctx.accounts.account.key();
+
+    let (perp_market_index, settle_token_index) = {
+        let perp_market = ctx.accounts.perp_market.load()?;
+        (
+            perp_market.perp_market_index,
+            perp_market.settle_token_index,
+        )
+    };
+
+    account.ensure_perp_position(perp_market_index, settle_token_index)?;
+
+    //
+    // Pre-health computation, _after_ perp position is created
+    //
+    let pre_health_opt = if !account.fixed.is_in_health_region() {
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        let health_cache = new_health_cache(&account.borrow(), &retriever)
+            .context("pre-cancel-replace init health")?;
+        let pre_init_health = account.check_health_pre(&health_cache)?;
+        Some((health_cache, pre_init_health))
+    } else {
+        None
+    };
+
+    let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+    let mut book = Orderbook {
+        bids: ctx.accounts.bids.load_mut()?,
+        asks: ctx.accounts.asks.load_mut()?,
+    };
+
+    let mut event_queue = ctx.accounts.event_queue.load_mut()?;
+    let group = ctx.accounts.group.load()?;
+
+    account
+        .fixed
+        .expire_buyback_fees(now_ts, group.buyback_fees_expiry_interval);
+
+    //
+    // Cancel
+    //
+    let order_ids_to_cancel = match cancel {
+        PerpCancelOrders::None => vec![],
+        PerpCancelOrders::All { side_option } => {
+            book.cancel_all_orders(
+                &mut account.borrow_mut(),
+                &mut perp_market,
+                u8::MAX,
+                side_option,
+            )?;
+            vec![]
+        }
+        PerpCancelOrders::ByOrderIds { order_ids } => order_ids
+            .iter()
+            .filter_map(|&order_id| {
+                account
+                    .perp_find_order_with_order_id(perp_market_index, order_id)
+                    .map(|oo| (oo.id, oo.side_and_tree()))
+            })
+            .collect::<Vec<_>>(),
+        PerpCancelOrders::ByClientOrderIds { client_order_ids } => client_order_ids
+            .iter()
+            .filter_map(|&client_order_id| {
+                account
+                    .perp_find_order_with_client_order_id(perp_market_index, client_order_id)
+                    .map(|oo| (oo.id, oo.side_and_tree()))
+            })
+            .collect::<Vec<_>>(),
+    };
+    for (order_id, side_and_tree) in order_ids_to_cancel {
+        let cancel_result = book.cancel_order(
+            &mut account.borrow_mut(),
+            order_id,
+            side_and_tree,
+            Some(account_pk),
+        );
+        if cancel_result.is_anchor_error_with_code(MangoError::PerpOrderIdNotFound.into()) {
+            // Filled or expired already, the event on the queue frees the order slot
+            msg!(
+                "order {} was not found on orderbook, expired or filled already",
+                order_id
+            );
+        } else {
+            cancel_result?;
+        }
+    }
+
+    //
+    // Place
+    //
+    let mut order_ids = Vec::with_capacity(orders.len());
+    for order_opt in orders {
+        let mut order = match order_opt {
+            Some(order) => order,
+            None => {
+                msg!("Order is already expired");
+                order_ids.push(None);
+                continue;
+            }
+        };
+
+        let pp = account.perp_position(perp_market_index)?;
+        apply_reduce_only(pp, &mut order, perp_market.is_reduce_only())?;
+
+        let order_id_opt = book.new_order(
+            order,
+            &mut perp_market,
+            &mut event_queue,
+            oracle_price,
+            &mut account.borrow_mut(),
+            &account_pk,
+            now_ts,
+            limit,
+        )?;
+        order_ids.push(order_id_opt);
+    }
+
+    //
+    // Health check
+    //
+    if let Some((mut health_cache, pre_init_health)) = pre_health_opt {
+        let perp_position = account.perp_position(perp_market_index)?;
+        health_cache.recompute_perp_info(perp_position, &perp_market)?;
+        account.check_health_post(&health_cache, pre_init_health)?;
+    }
+
+    Ok(order_ids)
+}
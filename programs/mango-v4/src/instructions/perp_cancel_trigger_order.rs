@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::state::*;
+
+pub fn perp_cancel_trigger_order(
+    ctx: Context<PerpCancelTriggerOrder>,
+    trigger_order_id: u64,
+) -> Result<()> {
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    // account constraint #1
+    require!(
+        account.fixed.is_owner_or_delegate(ctx.accounts.owner.key()),
+        MangoError::SomeError
+    );
+
+    let (_, raw_index) = account.perp_trigger_order_and_raw_index(trigger_order_id)?;
+    account.remove_perp_trigger_order(raw_index);
+
+    Ok(())
+}
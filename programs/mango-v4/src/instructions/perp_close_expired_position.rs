@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+
+use crate::accounts_zerocopy::AccountInfoRef;
+use crate::error::MangoError;
+use crate::instructions::close_perp_positions_at_price;
+use crate::state::*;
+
+pub fn perp_close_expired_position(ctx: Context<PerpCloseExpiredPosition>) -> Result<()> {
+    let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+    let perp_market_index = perp_market.perp_market_index;
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    require!(
+        perp_market.is_expired(now_ts),
+        MangoError::PerpMarketNotExpired
+    );
+
+    let mut account_a = ctx.accounts.account_a.load_full_mut()?;
+    let mut account_b = ctx.accounts.account_b.load_full_mut()?;
+
+    // The oracle is only needed for fixing the settlement price on the first call
+    let settlement_price = if perp_market.has_settlement_price == 1 {
+        perp_market.settlement_price
+    } else {
+        let now_slot = Clock::get()?.slot;
+        let oracle_price = perp_market.oracle_price_with_fallback(
+            &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
+            fallback_oracle_account(ctx.remaining_accounts, &perp_market.fallback_oracle)?.as_ref(),
+            Some(now_slot),
+        )?;
+        let settlement_price = perp_market.fix_settlement_price(now_ts, oracle_price);
+        msg!("Settlement price: {}", settlement_price);
+        settlement_price
+    };
+
+    close_perp_positions_at_price(
+        ctx.accounts.group.key(),
+        &mut perp_market,
+        (
+            ctx.accounts.account_a.key(),
+            account_a.perp_position_mut(perp_market_index)?,
+        ),
+        (
+            ctx.accounts.account_b.key(),
+            account_b.perp_position_mut(perp_market_index)?,
+        ),
+        settlement_price,
+    )
+}
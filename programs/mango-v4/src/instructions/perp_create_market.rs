@@ -102,7 +102,28 @@ pub fn perp_create_market(
         maint_overall_asset_weight: I80F48::from_num(maint_overall_asset_weight),
         init_overall_asset_weight: I80F48::from_num(init_overall_asset_weight),
         positive_pnl_liquidation_fee: I80F48::from_num(positive_pnl_liquidation_fee),
-        reserved: [0; 1888],
+        fallback_oracle: Pubkey::default(),
+        insurance_fund_index: 0,
+        padding5: Default::default(),
+        expiry_timestamp: 0,
+        settlement_window_ts: 0,
+        settlement_price_sum: I80F48::ZERO,
+        settlement_price: I80F48::ZERO,
+        settlement_last_price: I80F48::ZERO,
+        settlement_price_weight_ts: 0,
+        settlement_uses_stable_price: 0,
+        has_settlement_price: 0,
+        padding6: Default::default(),
+        funding_interval_ts: 0,
+        funding_interval_start_ts: 0,
+        funding_premium_sum: I80F48::ZERO,
+        prev_funding_premium_sum: I80F48::ZERO,
+        funding_last_premium: I80F48::ZERO,
+        funding_premium_weight_ts: 0,
+        prev_funding_premium_weight_ts: 0,
+        funding_model: 0,
+        padding7: Default::default(),
+        reserved: [0; 1680],
     };
 
     let oracle_price =
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+
+pub fn perp_edit_insurance_fund(
+    ctx: Context<PerpEditInsuranceFund>,
+    fund_index: u16,
+) -> Result<()> {
+    let mut group = ctx.accounts.group.load_mut()?;
+    let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+
+    msg!(
+        "Insurance fund index: old - {:?}, new - {:?}",
+        perp_market.insurance_fund_index,
+        fund_index
+    );
+    group.verify_insurance_vault(
+        &ctx.accounts.group.key(),
+        fund_index,
+        ctx.remaining_accounts,
+    )?;
+    group.move_insurance_fund_reference(perp_market.insurance_fund_index, fund_index)?;
+    perp_market.insurance_fund_index = fund_index;
+
+    Ok(())
+}
@@ -39,6 +39,12 @@ pub fn perp_edit_market(
     positive_pnl_liquidation_fee_opt: Option<f32>,
     name_opt: Option<String>,
     force_close_opt: Option<bool>,
+    fallback_oracle_opt: Option<Pubkey>,
+    expiry_timestamp_opt: Option<u64>,
+    settlement_window_ts_opt: Option<u64>,
+    settlement_uses_stable_price_opt: Option<bool>,
+    funding_model_opt: Option<PerpFundingModel>,
+    funding_interval_ts_opt: Option<u64>,
 ) -> Result<()> {
     let group = ctx.accounts.group.load()?;
 
@@ -62,6 +68,15 @@ pub fn perp_edit_market(
         perp_market.oracle = oracle;
         require_group_admin = true;
     }
+    if let Some(fallback_oracle) = fallback_oracle_opt {
+        msg!(
+            "Fallback oracle: old - {:?}, new - {:?}",
+            perp_market.fallback_oracle,
+            fallback_oracle
+        );
+        perp_market.fallback_oracle = fallback_oracle;
+        require_group_admin = true;
+    }
     if reset_stable_price {
         msg!("Stable price reset");
         require_keys_eq!(perp_market.oracle, ctx.accounts.oracle.key());
@@ -344,6 +359,79 @@ pub fn perp_edit_market(
         require_group_admin = true;
     };
 
+    if let Some(expiry_timestamp) = expiry_timestamp_opt {
+        // Once the settlement price is fixed, the market can't be reopened
+        require_eq!(perp_market.has_settlement_price, 0);
+        if expiry_timestamp != 0 {
+            let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+            require_gt!(expiry_timestamp, now_ts);
+        }
+        msg!(
+            "Expiry timestamp: old - {:?}, new - {:?}",
+            perp_market.expiry_timestamp,
+            expiry_timestamp
+        );
+        perp_market.expiry_timestamp = expiry_timestamp;
+        perp_market.settlement_price_sum = I80F48::ZERO;
+        perp_market.settlement_price_weight_ts = 0;
+        require_group_admin = true;
+    };
+
+    if let Some(settlement_window_ts) = settlement_window_ts_opt {
+        require_eq!(perp_market.has_settlement_price, 0);
+        msg!(
+            "Settlement window: old - {:?}, new - {:?}",
+            perp_market.settlement_window_ts,
+            settlement_window_ts
+        );
+        perp_market.settlement_window_ts = settlement_window_ts;
+        require_group_admin = true;
+    };
+
+    if let Some(settlement_uses_stable_price) = settlement_uses_stable_price_opt {
+        require_eq!(perp_market.has_settlement_price, 0);
+        msg!(
+            "Settlement uses stable price: old - {:?}, new - {:?}",
+            perp_market.settlement_uses_stable_price,
+            u8::from(settlement_uses_stable_price)
+        );
+        perp_market.settlement_uses_stable_price = u8::from(settlement_uses_stable_price);
+        // The last observed price may be of the other kind
+        perp_market.settlement_last_price = I80F48::ZERO;
+        require_group_admin = true;
+    };
+
+    let mut reset_funding_premium_samples = false;
+    if let Some(funding_model) = funding_model_opt {
+        msg!(
+            "Funding model: old - {:?}, new - {:?}",
+            perp_market.funding_model(),
+            funding_model
+        );
+        perp_market.funding_model = funding_model.into();
+        reset_funding_premium_samples = true;
+        require_group_admin = true;
+    };
+
+    if let Some(funding_interval_ts) = funding_interval_ts_opt {
+        msg!(
+            "Funding interval: old - {:?}, new - {:?}",
+            perp_market.funding_interval_ts,
+            funding_interval_ts
+        );
+        perp_market.funding_interval_ts = funding_interval_ts;
+        reset_funding_premium_samples = true;
+        require_group_admin = true;
+    };
+
+    if reset_funding_premium_samples {
+        if perp_market.funding_model() == PerpFundingModel::PremiumTwap {
+            require_gt!(perp_market.funding_interval_ts, 0);
+        }
+        let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+        perp_market.reset_funding_premium_samples(now_ts);
+    }
+
     // account constraint #1
     if require_group_admin {
         require!(
@@ -0,0 +1,243 @@
+use anchor_lang::prelude::*;
+use fixed::types::I80F48;
+
+use crate::accounts_ix::*;
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::health::{new_fixed_order_account_retriever, new_health_cache};
+use crate::instructions::apply_reduce_only;
+use crate::logs::{PerpExecuteTriggerOrderLog, TokenBalanceLog};
+use crate::state::*;
+
+/// The executor can't pick a match limit below this. Otherwise it could execute
+/// trigger orders without matching anything, just to collect the incentive.
+pub const TRIGGER_ORDER_MIN_MATCH_LIMIT: u8 = 4;
+
+pub fn perp_execute_trigger_order(
+    ctx: Context<PerpExecuteTriggerOrder>,
+    trigger_order_id: u64,
+    limit: u8,
+) -> Result<()> {
+    require_keys_neq!(ctx.accounts.account.key(), ctx.accounts.executor.key());
+    require_msg!(
+        limit >= TRIGGER_ORDER_MIN_MATCH_LIMIT,
+        "limit must be at least {}",
+        TRIGGER_ORDER_MIN_MATCH_LIMIT
+    );
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    let now_slot = Clock::get()?.slot;
+    let oracle_price;
+
+    // Update funding if possible, like when placing a regular order.
+    {
+        let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+        let book = Orderbook {
+            bids: ctx.accounts.bids.load_mut()?,
+            asks: ctx.accounts.asks.load_mut()?,
+        };
+
+        // The trigger condition must be evaluated on a fresh oracle price
+        let oracle_slot;
+        (oracle_price, oracle_slot) = perp_market.oracle_price_and_slot_with_fallback(
+            &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
+            fallback_oracle_account(ctx.remaining_accounts, &perp_market.fallback_oracle)?.as_ref(),
+            Some(now_slot),
+        )?;
+
+        perp_market.update_funding_and_stable_price(&book, oracle_price, oracle_slot, now_ts)?;
+    }
+
+    let (perp_market_index, settle_token_index) = {
+        let perp_market = ctx.accounts.perp_market.load()?;
+        (
+            perp_market.perp_market_index,
+            perp_market.settle_token_index,
+        )
+    };
+
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    let account_pk = ctx.accounts.account.key();
+
+    let (trigger_order, trigger_order_raw_index) = {
+        let (order, raw_index) = account.perp_trigger_order_and_raw_index(trigger_order_id)?;
+        (*order, raw_index)
+    };
+    require_msg!(
+        trigger_order.market == perp_market_index,
+        "trigger order {} is for perp market {}, not {}",
+        trigger_order_id,
+        trigger_order.market,
+        perp_market_index
+    );
+
+    // Trigger orders are one-shot: if this instruction succeeds, the order is gone.
+    // Errors below, like the order not being triggered yet, revert the removal.
+    account.remove_perp_trigger_order(trigger_order_raw_index);
+
+    if trigger_order.is_expired(now_ts) {
+        msg!("trigger order {} is expired, removing", trigger_order_id);
+        return Ok(());
+    }
+
+    require_msg_typed!(
+        trigger_order.is_triggered(oracle_price),
+        MangoError::PerpTriggerOrderNotTriggered,
+        "oracle price {} does not meet the {:?} condition with trigger price {}",
+        oracle_price,
+        trigger_order.trigger_condition(),
+        trigger_order.trigger_price
+    );
+
+    let mut order = Order {
+        side: trigger_order.side(),
+        max_base_lots: trigger_order.max_base_lots,
+        max_quote_lots: trigger_order.max_quote_lots,
+        client_order_id: trigger_order.client_order_id,
+        reduce_only: trigger_order.is_reduce_only(),
+        time_in_force: 0,
+        self_trade_behavior: SelfTradeBehavior::DecrementTake,
+        params: OrderParams::ImmediateOrCancel {
+            price_lots: trigger_order.price_lots,
+        },
+    };
+
+    account.ensure_perp_position(perp_market_index, settle_token_index)?;
+
+    //
+    // Pre-health computation, _after_ perp position is created
+    //
+    let mut health_cache = {
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        new_health_cache(&account.borrow(), &retriever).context("pre-execute init health")?
+    };
+    let pre_init_health = account.check_health_pre(&health_cache)?;
+
+    let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+    let mut book = Orderbook {
+        bids: ctx.accounts.bids.load_mut()?,
+        asks: ctx.accounts.asks.load_mut()?,
+    };
+    let mut event_queue = ctx.accounts.event_queue.load_mut()?;
+    let group = ctx.accounts.group.load()?;
+
+    account
+        .fixed
+        .expire_buyback_fees(now_ts, group.buyback_fees_expiry_interval);
+
+    let pp = account.perp_position(perp_market_index)?;
+    apply_reduce_only(pp, &mut order, perp_market.is_reduce_only())?;
+    if order.max_base_lots == 0 {
+        // For example a stop loss for a position that was closed in the meantime:
+        // nothing to do and no incentive is paid.
+        msg!(
+            "trigger order {} has nothing left to execute",
+            trigger_order_id
+        );
+        return Ok(());
+    }
+
+    let pre_base_lots = account
+        .perp_position(perp_market_index)?
+        .effective_base_position_lots();
+
+    book.new_order(
+        order,
+        &mut perp_market,
+        &mut event_queue,
+        oracle_price,
+        &mut account.borrow_mut(),
+        &account_pk,
+        now_ts,
+        limit,
+    )?;
+
+    // The incentive is only paid for orders that actually traded. If nothing matched,
+    // for example because the book is empty, fail so the trigger order stays around
+    // and the account isn't charged the IOC penalty.
+    let perp_position = account.perp_position(perp_market_index)?;
+    let filled_base_lots = (perp_position.effective_base_position_lots() - pre_base_lots).abs();
+    require_msg!(
+        filled_base_lots > 0,
+        "trigger order {} did not match any orders",
+        trigger_order_id
+    );
+
+    health_cache.recompute_perp_info(perp_position, &perp_market)?;
+
+    //
+    // Pay the incentive from the account to the executor
+    //
+    let mut settle_bank = ctx.accounts.settle_bank.load_mut()?;
+    // Verify that the bank is the perp market's settle token bank (#2)
+    require!(
+        settle_bank.token_index == settle_token_index,
+        MangoError::InvalidBank
+    );
+
+    let incentive = I80F48::from(trigger_order.incentive);
+    if incentive.is_positive() {
+        let token_position = account.token_position_mut(settle_token_index)?.0;
+        settle_bank.withdraw_without_fee(token_position, incentive, now_ts)?;
+        health_cache.adjust_token_balance(&settle_bank, -incentive)?;
+
+        emit!(TokenBalanceLog {
+            mango_group: ctx.accounts.group.key(),
+            mango_account: account_pk,
+            token_index: settle_token_index,
+            indexed_position: token_position.indexed_position.to_bits(),
+            deposit_index: settle_bank.deposit_index.to_bits(),
+            borrow_index: settle_bank.borrow_index.to_bits(),
+        });
+    }
+
+    //
+    // Health check
+    //
+    account.check_health_post(&health_cache, pre_init_health)?;
+
+    if incentive.is_positive() {
+        let mut executor = ctx.accounts.executor.load_full_mut()?;
+        // account constraint #1
+        require!(
+            executor
+                .fixed
+                .is_owner_or_delegate(ctx.accounts.executor_owner.key()),
+            MangoError::SomeError
+        );
+
+        let (executor_token_position, executor_token_raw_index, _) =
+            executor.ensure_token_position(settle_token_index)?;
+        let executor_token_position_active =
+            settle_bank.deposit(executor_token_position, incentive, now_ts)?;
+
+        emit!(TokenBalanceLog {
+            mango_group: ctx.accounts.group.key(),
+            mango_account: ctx.accounts.executor.key(),
+            token_index: settle_token_index,
+            indexed_position: executor_token_position.indexed_position.to_bits(),
+            deposit_index: settle_bank.deposit_index.to_bits(),
+            borrow_index: settle_bank.borrow_index.to_bits(),
+        });
+
+        if !executor_token_position_active {
+            executor.deactivate_token_position_and_log(
+                executor_token_raw_index,
+                ctx.accounts.executor.key(),
+            );
+        }
+    }
+
+    emit!(PerpExecuteTriggerOrderLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: account_pk,
+        perp_market_index,
+        trigger_order_id,
+        executor: ctx.accounts.executor.key(),
+        incentive: trigger_order.incentive,
+        oracle_price: oracle_price.to_bits(),
+    });
+
+    Ok(())
+}
@@ -15,9 +15,37 @@ pub fn perp_force_close_position(ctx: Context<PerpForceClosePosition>) -> Result
     let mut account_a = ctx.accounts.account_a.load_full_mut()?;
     let mut account_b = ctx.accounts.account_b.load_full_mut()?;
 
-    let account_a_perp_position = account_a.perp_position_mut(perp_market_index)?;
-    let account_b_perp_position = account_b.perp_position_mut(perp_market_index)?;
+    let now_slot = Clock::get()?.slot;
+    let oracle_price = perp_market.oracle_price_with_fallback(
+        &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
+        fallback_oracle_account(ctx.remaining_accounts, &perp_market.fallback_oracle)?.as_ref(),
+        Some(now_slot),
+    )?;
 
+    close_perp_positions_at_price(
+        ctx.accounts.group.key(),
+        &mut perp_market,
+        (
+            ctx.accounts.account_a.key(),
+            account_a.perp_position_mut(perp_market_index)?,
+        ),
+        (
+            ctx.accounts.account_b.key(),
+            account_b.perp_position_mut(perp_market_index)?,
+        ),
+        oracle_price,
+    )
+}
+
+/// Reduces the long position of account_a and the short position of account_b
+/// by as many base lots as possible, trading them at `price`.
+pub(crate) fn close_perp_positions_at_price(
+    group: Pubkey,
+    perp_market: &mut PerpMarket,
+    (account_a_key, account_a_perp_position): (Pubkey, &mut PerpPosition),
+    (account_b_key, account_b_perp_position): (Pubkey, &mut PerpPosition),
+    price: I80F48,
+) -> Result<()> {
     require_gt!(
         account_a_perp_position.base_position_lots(),
         0,
@@ -33,37 +61,22 @@ pub fn perp_force_close_position(ctx: Context<PerpForceClosePosition>) -> Result
         .base_position_lots()
         .min(account_b_perp_position.base_position_lots().abs())
         .max(0);
-    let now_slot = Clock::get()?.slot;
-    let oracle_price = perp_market.oracle_price(
-        &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
-        Some(now_slot),
-    )?;
-    let quote_transfer = I80F48::from(base_transfer * perp_market.base_lot_size) * oracle_price;
+    let quote_transfer = I80F48::from(base_transfer * perp_market.base_lot_size) * price;
 
-    account_a_perp_position.record_trade(&mut perp_market, -base_transfer, quote_transfer);
-    account_b_perp_position.record_trade(&mut perp_market, base_transfer, -quote_transfer);
+    account_a_perp_position.record_trade(perp_market, -base_transfer, quote_transfer);
+    account_b_perp_position.record_trade(perp_market, base_transfer, -quote_transfer);
 
-    emit_perp_balances(
-        ctx.accounts.group.key(),
-        ctx.accounts.account_a.key(),
-        account_a_perp_position,
-        &perp_market,
-    );
-    emit_perp_balances(
-        ctx.accounts.group.key(),
-        ctx.accounts.account_b.key(),
-        &account_b_perp_position,
-        &perp_market,
-    );
+    emit_perp_balances(group, account_a_key, account_a_perp_position, perp_market);
+    emit_perp_balances(group, account_b_key, account_b_perp_position, perp_market);
 
     emit!(PerpForceClosePositionLog {
-        mango_group: ctx.accounts.group.key(),
+        mango_group: group,
         perp_market_index: perp_market.perp_market_index,
-        account_a: ctx.accounts.account_a.key(),
-        account_b: ctx.accounts.account_b.key(),
+        account_a: account_a_key,
+        account_b: account_b_key,
         base_transfer: base_transfer,
         quote_transfer: quote_transfer.to_bits(),
-        price: oracle_price.to_bits(),
+        price: price.to_bits(),
     });
 
     Ok(())
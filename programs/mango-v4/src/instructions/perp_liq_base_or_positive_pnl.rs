@@ -66,6 +66,14 @@ pub fn perp_liq_base_or_positive_pnl(
     let perp_market_index = perp_market.perp_market_index;
     let settle_token_index = perp_market.settle_token_index;
 
+    // Health uses the oracle price for an expired market until its settlement price is fixed
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    require_msg_typed!(
+        !perp_market.is_expired(now_ts) || perp_market.has_settlement_price == 1,
+        MangoError::PerpMarketExpired,
+        "the settlement price of the expired market is not fixed yet"
+    );
+
     let mut settle_bank = ctx.accounts.settle_bank.load_mut()?;
     // account constraint #2
     require!(
@@ -73,11 +81,13 @@ pub fn perp_liq_base_or_positive_pnl(
         MangoError::InvalidBank
     );
 
-    // Get oracle price for market. Price is validated inside
-    let oracle_price = perp_market.oracle_price(
-        &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
-        None, // checked in health
-    )?;
+    // Get oracle price for market. Price is validated inside, and may come from
+    // the fallback oracle if the primary one was rejected
+    require_keys_eq!(perp_market.oracle, ctx.accounts.oracle.key());
+    let oracle_price = liqee_health_cache
+        .perp_info(perp_market_index)?
+        .prices
+        .oracle;
 
     // Fetch perp positions for accounts, creating for the liqor if needed
     let liqee_perp_position = liqee.perp_position_mut(perp_market_index)?;
@@ -93,7 +103,6 @@ pub fn perp_liq_base_or_positive_pnl(
     // Settle funding, update limit
     liqee_perp_position.settle_funding(&perp_market);
     liqor_perp_position.settle_funding(&perp_market);
-    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
     liqee_perp_position.update_settle_limit(&perp_market, now_ts);
 
     //
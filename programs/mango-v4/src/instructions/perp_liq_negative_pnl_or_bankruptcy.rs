@@ -159,10 +159,27 @@ pub fn perp_liq_negative_pnl_or_bankruptcy(
             .max(I80F48::ZERO);
 
         // Available insurance fund coverage
-        let insurance_vault_amount = if perp_market.elligible_for_group_insurance_fund() {
-            ctx.accounts.insurance_vault.amount
-        } else {
-            0
+        //
+        // Isolated insurance funds only cover the markets they are attached to, so risky
+        // markets can't drain the group insurance fund. Insurance funds can only cover
+        // markets that settle in their token.
+        let insurance_vault_amount = match perp_market.covering_insurance_fund() {
+            Some(fund_index) => {
+                // account constraint #3
+                require_keys_eq!(
+                    ctx.accounts.insurance_vault.key(),
+                    ctx.accounts
+                        .group
+                        .load()?
+                        .insurance_fund_vault(&mango_group, fund_index)?
+                );
+                if settle_bank.mint == ctx.accounts.insurance_vault.mint {
+                    ctx.accounts.insurance_vault.amount
+                } else {
+                    0
+                }
+            }
+            None => 0,
         };
 
         let liquidation_fee_factor = I80F48::ONE + perp_market.base_liquidation_fee;
@@ -8,8 +8,9 @@ use crate::state::*;
 
 // TODO
 #[allow(clippy::too_many_arguments)]
-pub fn perp_place_order(
-    ctx: Context<PerpPlaceOrder>,
+pub fn perp_place_order<'info>(
+    accounts: &PerpPlaceOrder<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
     mut order: Order,
     limit: u8,
 ) -> Result<Option<u128>> {
@@ -24,32 +25,33 @@ pub fn perp_place_order(
     // Doing this automatically here makes it impossible for attackers to add orders to the orderbook
     // before triggering the funding computation.
     {
-        let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+        let mut perp_market = accounts.perp_market.load_mut()?;
         let book = Orderbook {
-            bids: ctx.accounts.bids.load_mut()?,
-            asks: ctx.accounts.asks.load_mut()?,
+            bids: accounts.bids.load_mut()?,
+            asks: accounts.asks.load_mut()?,
         };
 
         let oracle_slot;
-        (oracle_price, oracle_slot) = perp_market.oracle_price_and_slot(
-            &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
+        (oracle_price, oracle_slot) = perp_market.oracle_price_and_slot_with_fallback(
+            &AccountInfoRef::borrow(accounts.oracle.as_ref())?,
+            fallback_oracle_account(remaining_accounts, &perp_market.fallback_oracle)?.as_ref(),
             None, // staleness checked in health
         )?;
 
         perp_market.update_funding_and_stable_price(&book, oracle_price, oracle_slot, now_ts)?;
     }
 
-    let mut account = ctx.accounts.account.load_full_mut()?;
+    let mut account = accounts.account.load_full_mut()?;
     // account constraint #1
     require!(
-        account.fixed.is_owner_or_delegate(ctx.accounts.owner.key()),
+        account.fixed.is_owner_or_delegate(accounts.owner.key()),
         MangoError::SomeError
     );
 
-    let account_pk = ctx.accounts.account.key();
+    let account_pk = accounts.account.key();
 
     let (perp_market_index, settle_token_index) = {
-        let perp_market = ctx.accounts.perp_market.load()?;
+        let perp_market = accounts.perp_market.load()?;
         (
             perp_market.perp_market_index,
             perp_market.settle_token_index,
@@ -65,8 +67,7 @@ pub fn perp_place_order(
     // Pre-health computation, _after_ perp position is created
     //
     let pre_health_opt = if !account.fixed.is_in_health_region() {
-        let retriever =
-            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        let retriever = new_fixed_order_account_retriever(remaining_accounts, &account.borrow())?;
         let health_cache =
             new_health_cache(&account.borrow(), &retriever).context("pre-withdraw init health")?;
         let pre_init_health = account.check_health_pre(&health_cache)?;
@@ -75,14 +76,14 @@ pub fn perp_place_order(
         None
     };
 
-    let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+    let mut perp_market = accounts.perp_market.load_mut()?;
     let mut book = Orderbook {
-        bids: ctx.accounts.bids.load_mut()?,
-        asks: ctx.accounts.asks.load_mut()?,
+        bids: accounts.bids.load_mut()?,
+        asks: accounts.asks.load_mut()?,
     };
 
-    let mut event_queue = ctx.accounts.event_queue.load_mut()?;
-    let group = ctx.accounts.group.load()?;
+    let mut event_queue = accounts.event_queue.load_mut()?;
+    let group = accounts.group.load()?;
 
     let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
     account
@@ -90,19 +91,7 @@ pub fn perp_place_order(
         .expire_buyback_fees(now_ts, group.buyback_fees_expiry_interval);
 
     let pp = account.perp_position(perp_market_index)?;
-    let effective_pos = pp.effective_base_position_lots();
-    let max_base_lots = if order.reduce_only || perp_market.is_reduce_only() {
-        reduce_only_max_base_lots(pp, &order, perp_market.is_reduce_only())
-    } else {
-        order.max_base_lots
-    };
-    if perp_market.is_reduce_only() {
-        require!(
-            order.reduce_only || max_base_lots == order.max_base_lots,
-            MangoError::MarketInReduceOnlyMode
-        )
-    };
-    order.max_base_lots = max_base_lots;
+    apply_reduce_only(pp, &mut order, perp_market.is_reduce_only())?;
 
     let order_id_opt = book.new_order(
         order,
@@ -127,7 +116,43 @@ pub fn perp_place_order(
     Ok(order_id_opt)
 }
 
-fn reduce_only_max_base_lots(pp: &PerpPosition, order: &Order, market_reduce_only: bool) -> i64 {
+/// Limits the order's max_base_lots if the order or the market is reduce only.
+///
+/// Fails if the market is reduce only but the order isn't and would increase the
+/// position, or if a fill-or-kill order would be cut down.
+pub(crate) fn apply_reduce_only(
+    pp: &PerpPosition,
+    order: &mut Order,
+    market_reduce_only: bool,
+) -> Result<()> {
+    if !order.reduce_only && !market_reduce_only {
+        return Ok(());
+    }
+    let max_base_lots = reduce_only_max_base_lots(pp, order, market_reduce_only);
+    if market_reduce_only {
+        require!(
+            order.reduce_only || max_base_lots == order.max_base_lots,
+            MangoError::MarketInReduceOnlyMode
+        )
+    };
+    if order.is_fill_or_kill() {
+        require_msg_typed!(
+            max_base_lots == order.max_base_lots,
+            MangoError::FillOrKillNotFilled,
+            "reduce only allows only {} of {} base lots",
+            max_base_lots,
+            order.max_base_lots
+        );
+    }
+    order.max_base_lots = max_base_lots;
+    Ok(())
+}
+
+pub(crate) fn reduce_only_max_base_lots(
+    pp: &PerpPosition,
+    order: &Order,
+    market_reduce_only: bool,
+) -> i64 {
     let effective_pos = pp.effective_base_position_lots();
     msg!(
         "reduce only: current effective position: {} lots",
@@ -210,6 +235,7 @@ mod tests {
                 client_order_id: 0,
                 reduce_only: true,
                 time_in_force: 0,
+                self_trade_behavior: SelfTradeBehavior::DecrementTake,
                 params: OrderParams::Market,
             };
 
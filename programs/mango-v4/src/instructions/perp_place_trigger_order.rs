@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::state::*;
+
+pub fn perp_place_trigger_order(
+    ctx: Context<PerpPlaceTriggerOrder>,
+    mut trigger_order: PerpTriggerOrder,
+) -> Result<u64> {
+    require_gt!(trigger_order.max_base_lots, 0);
+    require_gt!(trigger_order.max_quote_lots, 0);
+    require_gt!(trigger_order.price_lots, 0);
+    require_msg!(
+        trigger_order.trigger_price.is_positive(),
+        "trigger price must be positive"
+    );
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    require_msg!(
+        !trigger_order.is_expired(now_ts),
+        "trigger order is already expired"
+    );
+
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    // account constraint #1
+    require!(
+        account.fixed.is_owner_or_delegate(ctx.accounts.owner.key()),
+        MangoError::SomeError
+    );
+
+    let perp_market = ctx.accounts.perp_market.load()?;
+    trigger_order.market = perp_market.perp_market_index;
+
+    let id = account.add_perp_trigger_order(trigger_order)?;
+    msg!(
+        "placed perp trigger order {} on market {}",
+        id,
+        perp_market.perp_market_index
+    );
+
+    Ok(id)
+}
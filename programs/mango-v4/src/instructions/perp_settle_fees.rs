@@ -29,12 +29,14 @@ pub fn perp_settle_fees(ctx: Context<PerpSettleFees>, max_settle_amount: u64) ->
     );
 
     // Get oracle prices
-    let oracle_price = perp_market.oracle_price(
+    let oracle_price = perp_market.oracle_price_with_fallback(
         &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
+        fallback_oracle_account(ctx.remaining_accounts, &perp_market.fallback_oracle)?.as_ref(),
         None, // staleness checked in health
     )?;
-    let settle_token_oracle_price = settle_bank.oracle_price(
+    let settle_token_oracle_price = settle_bank.oracle_price_with_fallback(
         &AccountInfoRef::borrow(ctx.accounts.settle_oracle.as_ref())?,
+        fallback_oracle_account(ctx.remaining_accounts, &settle_bank.fallback_oracle)?.as_ref(),
         None, // staleness checked in health
     )?;
 
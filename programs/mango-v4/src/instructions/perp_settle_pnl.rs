@@ -17,8 +17,15 @@ pub fn perp_settle_pnl(ctx: Context<PerpSettlePnl>) -> Result<()> {
         MangoError::CannotSettleWithSelf
     );
 
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
     let (perp_market_index, settle_token_index) = {
         let perp_market = ctx.accounts.perp_market.load()?;
+        // Health uses the oracle price for an expired market until its settlement price is fixed
+        require_msg_typed!(
+            !perp_market.is_expired(now_ts) || perp_market.has_settlement_price == 1,
+            MangoError::PerpMarketExpired,
+            "the settlement price of the expired market is not fixed yet"
+        );
         (
             perp_market.perp_market_index,
             perp_market.settle_token_index,
@@ -39,11 +46,15 @@ pub fn perp_settle_pnl(ctx: Context<PerpSettlePnl>) -> Result<()> {
     let a_liq_end_health;
     let a_maint_health;
     let b_settle_health;
+    let oracle_price;
     {
         let retriever =
             ScanningAccountRetriever::new(ctx.remaining_accounts, &ctx.accounts.group.key())
                 .context("create account retriever")?;
-        b_settle_health = new_health_cache(&account_b.borrow(), &retriever)?.perp_settle_health();
+        let b_cache = new_health_cache(&account_b.borrow(), &retriever)?;
+        b_settle_health = b_cache.perp_settle_health();
+        // validated in health, possibly from the fallback oracle
+        oracle_price = b_cache.perp_info(perp_market_index)?.prices.oracle;
         let a_cache = new_health_cache(&account_a.borrow(), &retriever)?;
         a_liq_end_health = a_cache.health(HealthType::LiquidationEnd);
         a_maint_health = a_cache.health(HealthType::Maint);
@@ -58,13 +69,16 @@ pub fn perp_settle_pnl(ctx: Context<PerpSettlePnl>) -> Result<()> {
         MangoError::InvalidBank
     );
 
-    // Get oracle prices
-    let oracle_price = perp_market.oracle_price(
+    // Validate the oracles. The perp price itself is taken from health, which also
+    // uses the settlement price of an expired market.
+    perp_market.oracle_price_with_fallback(
         &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
+        fallback_oracle_account(ctx.remaining_accounts, &perp_market.fallback_oracle)?.as_ref(),
         None, // staleness checked in health
     )?;
-    let settle_token_oracle_price = settle_bank.oracle_price(
+    settle_bank.oracle_price_with_fallback(
         &AccountInfoRef::borrow(ctx.accounts.settle_oracle.as_ref())?,
+        fallback_oracle_account(ctx.remaining_accounts, &settle_bank.fallback_oracle)?.as_ref(),
         None, // staleness checked in health
     )?;
 
@@ -92,7 +106,6 @@ pub fn perp_settle_pnl(ctx: Context<PerpSettlePnl>) -> Result<()> {
     );
 
     // Apply pnl settle limits
-    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
     a_perp_position.update_settle_limit(&perp_market, now_ts);
     let a_settleable_pnl = a_perp_position.apply_pnl_settle_limit(&perp_market, a_pnl);
     b_perp_position.update_settle_limit(&perp_market, now_ts);
@@ -14,8 +14,10 @@ pub fn perp_update_funding(ctx: Context<PerpUpdateFunding>) -> Result<()> {
     };
 
     let now_slot = Clock::get()?.slot;
-    let (oracle_price, oracle_slot) = perp_market.oracle_price_and_slot(
+    // The fallback oracle may be passed as a remaining account
+    let (oracle_price, oracle_slot) = perp_market.oracle_price_and_slot_with_fallback(
         &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
+        fallback_oracle_account(ctx.remaining_accounts, &perp_market.fallback_oracle)?.as_ref(),
         Some(now_slot),
     )?;
 
@@ -286,8 +286,11 @@ pub fn serum3_place_order(
             MangoError::TokenInReduceOnlyMode,
             "the payer tokens cannot be borrowed"
         );
-        let oracle_price =
-            payer_bank.oracle_price(&AccountInfoRef::borrow(&ctx.accounts.payer_oracle)?, None)?;
+        // the health cache has the validated price, possibly from the fallback oracle
+        let oracle_price = health_cache
+            .token_info(payer_bank.token_index)?
+            .prices
+            .oracle;
         payer_bank.enforce_min_vault_to_deposits_ratio((*ctx.accounts.payer_vault).as_ref())?;
         payer_bank.check_net_borrows(oracle_price)?;
     }
@@ -87,8 +87,9 @@ impl<'a, 'info> DepositCommon<'a, 'info> {
         token::transfer(self.transfer_ctx(), amount_i80f48.to_num::<u64>())?;
 
         let indexed_position = position.indexed_position;
-        let oracle_price = bank.oracle_price(
+        let oracle_price = bank.oracle_price_with_fallback(
             &AccountInfoRef::borrow(self.oracle.as_ref())?,
+            fallback_oracle_account(remaining_accounts, &bank.fallback_oracle)?.as_ref(),
             None, // staleness checked in health
         )?;
 
@@ -39,6 +39,7 @@ pub fn token_edit(
     reduce_only_opt: Option<u8>,
     name_opt: Option<String>,
     force_close_opt: Option<bool>,
+    fallback_oracle_opt: Option<Pubkey>,
 ) -> Result<()> {
     let group = ctx.accounts.group.load()?;
 
@@ -66,6 +67,15 @@ pub fn token_edit(
             mint_info.oracle = oracle;
             require_group_admin = true;
         }
+        if let Some(fallback_oracle) = fallback_oracle_opt {
+            msg!(
+                "Fallback oracle: old - {:?}, new - {:?}",
+                bank.fallback_oracle,
+                fallback_oracle
+            );
+            bank.fallback_oracle = fallback_oracle;
+            require_group_admin = true;
+        }
         if reset_stable_price {
             msg!("Stable price reset");
             require_keys_eq!(bank.oracle, ctx.accounts.oracle.key());
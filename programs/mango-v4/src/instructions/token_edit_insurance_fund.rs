@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+
+pub fn token_edit_insurance_fund(
+    ctx: Context<TokenEditInsuranceFund>,
+    fund_index: u16,
+) -> Result<()> {
+    let mut group = ctx.accounts.group.load_mut()?;
+    let mut mint_info = ctx.accounts.mint_info.load_mut()?;
+
+    msg!(
+        "Insurance fund index: old - {:?}, new - {:?}",
+        mint_info.insurance_fund_index,
+        fund_index
+    );
+    group.verify_insurance_vault(
+        &ctx.accounts.group.key(),
+        fund_index,
+        ctx.remaining_accounts,
+    )?;
+    group.move_insurance_fund_reference(mint_info.insurance_fund_index, fund_index)?;
+    mint_info.insurance_fund_index = fund_index;
+
+    Ok(())
+}
@@ -73,10 +73,18 @@ pub fn token_liq_bankruptcy(
 
     let liab_transfer_unrounded = remaining_liab_loss.min(max_liab_transfer);
 
-    let insurance_vault_amount = if liab_mint_info.elligible_for_group_insurance_fund() {
-        ctx.accounts.insurance_vault.amount
-    } else {
-        0
+    // Isolated insurance funds only cover the tokens they are attached to, so risky
+    // tokens can't drain the group insurance fund.
+    let insurance_vault_amount = match liab_mint_info.covering_insurance_fund() {
+        Some(fund_index) => {
+            // account constraint #3
+            require_keys_eq!(
+                ctx.accounts.insurance_vault.key(),
+                group.insurance_fund_vault(group_pk, fund_index)?
+            );
+            ctx.accounts.insurance_vault.amount
+        }
+        None => 0,
     };
 
     let insurance_transfer = (liab_transfer_unrounded * liab_to_quote_with_fee)
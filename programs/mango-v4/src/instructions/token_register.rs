@@ -89,7 +89,8 @@ pub fn token_register(
         deposit_weight_scale_start_quote: f64::MAX,
         reduce_only: 0,
         force_close: 0,
-        reserved: [0; 2118],
+        fallback_oracle: Pubkey::default(),
+        reserved: [0; 2086],
     };
     require_gt!(bank.max_rate, MINIMUM_MAX_RATE);
 
@@ -109,7 +110,9 @@ pub fn token_register(
         vaults: Default::default(),
         oracle: ctx.accounts.oracle.key(),
         registration_time: Clock::get()?.unix_timestamp.try_into().unwrap(),
-        reserved: [0; 2560],
+        insurance_fund_index: 0,
+        padding2: Default::default(),
+        reserved: [0; 2552],
     };
 
     mint_info.banks[0] = ctx.accounts.bank.key();
@@ -75,7 +75,8 @@ pub fn token_register_trustless(
         deposit_weight_scale_start_quote: 5_000_000_000.0, // $5k
         reduce_only: 2,                                   // deposit-only
         force_close: 0,
-        reserved: [0; 2118],
+        fallback_oracle: Pubkey::default(),
+        reserved: [0; 2086],
     };
     require_gt!(bank.max_rate, MINIMUM_MAX_RATE);
 
@@ -95,7 +96,9 @@ pub fn token_register_trustless(
         vaults: Default::default(),
         oracle: ctx.accounts.oracle.key(),
         registration_time: Clock::get()?.unix_timestamp.try_into().unwrap(),
-        reserved: [0; 2560],
+        insurance_fund_index: 0,
+        padding2: Default::default(),
+        reserved: [0; 2552],
     };
 
     mint_info.banks[0] = ctx.accounts.bank.key();
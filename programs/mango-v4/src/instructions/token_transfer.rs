@@ -0,0 +1,168 @@
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::health::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use fixed::types::I80F48;
+
+use crate::accounts_ix::*;
+use crate::logs::{
+    LoanOriginationFeeInstruction, TokenBalanceLog, TokenTransferLog, WithdrawLoanOriginationFeeLog,
+};
+
+pub fn token_transfer(ctx: Context<TokenTransfer>, amount: u64, allow_borrow: bool) -> Result<()> {
+    require_msg!(amount > 0, "transfer amount must be positive");
+    require_keys_neq!(
+        ctx.accounts.source_account.key(),
+        ctx.accounts.destination_account.key()
+    );
+
+    let token_index = ctx.accounts.bank.load()?.token_index;
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+
+    // Create the source account's position for that token index
+    let mut source = ctx.accounts.source_account.load_full_mut()?;
+    let (_, source_raw_token_index, _) = source.ensure_token_position(token_index)?;
+
+    // Health check _after_ the token position is guaranteed to exist
+    let pre_health_opt = if !source.fixed.is_in_health_region() {
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &source.borrow())?;
+        let health_cache =
+            new_health_cache(&source.borrow(), &retriever).context("pre-transfer init health")?;
+        let pre_init_health = source.check_health_pre(&health_cache)?;
+        Some((health_cache, pre_init_health))
+    } else {
+        None
+    };
+
+    let mut bank = ctx.accounts.bank.load_mut()?;
+    let source_position = source.token_position_mut_by_raw_index(source_raw_token_index);
+    let source_native_position = source_position.native(&bank);
+
+    // Handle amount special case for transferring everything
+    let amount = if amount == u64::MAX && !allow_borrow {
+        if source_native_position.is_positive() {
+            source_native_position.floor().to_num::<u64>()
+        } else {
+            return Ok(());
+        }
+    } else {
+        amount
+    };
+
+    let is_borrow = amount > source_native_position;
+    require!(allow_borrow || !is_borrow, MangoError::SomeError);
+    if bank.are_borrows_reduce_only() {
+        require!(!is_borrow, MangoError::TokenInReduceOnlyMode);
+    }
+
+    let amount_i80f48 = I80F48::from(amount);
+
+    let now_slot = Clock::get()?.slot;
+    let oracle_price = bank.oracle_price_with_fallback(
+        &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
+        fallback_oracle_account(ctx.remaining_accounts, &bank.fallback_oracle)?.as_ref(),
+        Some(now_slot),
+    )?;
+
+    //
+    // Withdraw from the source account
+    //
+    let (source_position_is_active, loan_origination_fee) =
+        bank.withdraw_with_fee(source_position, amount_i80f48, now_ts)?;
+    let source_native_position_after = source_position.native(&bank);
+
+    emit!(TokenBalanceLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.source_account.key(),
+        token_index,
+        indexed_position: source_position.indexed_position.to_bits(),
+        deposit_index: bank.deposit_index.to_bits(),
+        borrow_index: bank.borrow_index.to_bits(),
+    });
+
+    //
+    // Deposit into the destination account
+    //
+    let mut destination = ctx.accounts.destination_account.load_full_mut()?;
+    let (destination_position, destination_raw_token_index, _) =
+        destination.ensure_token_position(token_index)?;
+    if bank.are_deposits_reduce_only() {
+        // Only allow transfers that repay the destination's borrows
+        require!(
+            destination_position.native(&bank) + amount_i80f48 < 1,
+            MangoError::TokenInReduceOnlyMode
+        );
+    }
+    let destination_position_is_active =
+        bank.deposit(destination_position, amount_i80f48, now_ts)?;
+
+    emit!(TokenBalanceLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.destination_account.key(),
+        token_index,
+        indexed_position: destination_position.indexed_position.to_bits(),
+        deposit_index: bank.deposit_index.to_bits(),
+        borrow_index: bank.borrow_index.to_bits(),
+    });
+
+    // Update the net deposits - adjust by price so different tokens are on the same basis (in USD terms)
+    let amount_usd = (amount_i80f48 * oracle_price).to_num::<i64>();
+    source.fixed.net_deposits -= amount_usd;
+    destination.fixed.net_deposits += amount_usd;
+
+    //
+    // Health check on the source account; the destination only gains tokens
+    //
+    if let Some((mut health_cache, pre_init_health)) = pre_health_opt {
+        health_cache
+            .adjust_token_balance(&bank, source_native_position_after - source_native_position)?;
+        source.check_health_post(&health_cache, pre_init_health)?;
+    }
+
+    //
+    // Deactivate the positions only after the health check because the user passed in
+    // remaining_accounts for all banks/oracles, including the account that will now be
+    // deactivated.
+    //
+    if !source_position_is_active {
+        source.deactivate_token_position_and_log(
+            source_raw_token_index,
+            ctx.accounts.source_account.key(),
+        );
+    }
+    if !destination_position_is_active {
+        destination.deactivate_token_position_and_log(
+            destination_raw_token_index,
+            ctx.accounts.destination_account.key(),
+        );
+    }
+
+    emit!(TokenTransferLog {
+        mango_group: ctx.accounts.group.key(),
+        source_account: ctx.accounts.source_account.key(),
+        destination_account: ctx.accounts.destination_account.key(),
+        signer: ctx.accounts.owner.key(),
+        token_index,
+        quantity: amount,
+        price: oracle_price.to_bits(),
+    });
+
+    if loan_origination_fee.is_positive() {
+        emit!(WithdrawLoanOriginationFeeLog {
+            mango_group: ctx.accounts.group.key(),
+            mango_account: ctx.accounts.source_account.key(),
+            token_index,
+            loan_origination_fee: loan_origination_fee.to_bits(),
+            instruction: LoanOriginationFeeInstruction::TokenTransfer,
+        });
+    }
+
+    // Borrowing doesn't move tokens out of the vault, but it still counts towards net borrows
+    if is_borrow {
+        bank.check_net_borrows(oracle_price)?;
+    }
+
+    Ok(())
+}
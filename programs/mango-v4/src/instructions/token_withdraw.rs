@@ -59,8 +59,9 @@ pub fn token_withdraw(ctx: Context<TokenWithdraw>, amount: u64, allow_borrow: bo
     let amount_i80f48 = I80F48::from(amount);
 
     let now_slot = Clock::get()?.slot;
-    let oracle_price = bank.oracle_price(
+    let oracle_price = bank.oracle_price_with_fallback(
         &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
+        fallback_oracle_account(ctx.remaining_accounts, &bank.fallback_oracle)?.as_ref(),
         Some(now_slot),
     )?;
 
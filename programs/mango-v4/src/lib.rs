@@ -31,7 +31,8 @@ pub mod instructions;
 compile_error!("compiling the program entrypoint without 'enable-gpl' makes no sense, enable it or use the 'cpi' or 'client' features");
 
 use state::{
-    OracleConfigParams, PerpMarketIndex, PlaceOrderType, Serum3MarketIndex, Side, TokenIndex,
+    OracleConfigParams, PerpFundingModel, PerpMarketIndex, PerpTriggerCondition, PlaceOrderType,
+    SelfTradeBehavior, Serum3MarketIndex, Side, TokenIndex,
 };
 
 declare_id!("4MangoMjqJ2firMokCjjGgoK8d4MXcrgL7XJaL3w6fVg");
@@ -94,6 +95,46 @@ pub mod mango_v4 {
         Ok(())
     }
 
+    pub fn insurance_fund_create(ctx: Context<InsuranceFundCreate>, fund_index: u16) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::insurance_fund_create(ctx, fund_index)?;
+        Ok(())
+    }
+
+    pub fn insurance_fund_withdraw(
+        ctx: Context<InsuranceFundWithdraw>,
+        fund_index: u16,
+        amount: u64,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::insurance_fund_withdraw(ctx, fund_index, amount)?;
+        Ok(())
+    }
+
+    pub fn insurance_fund_close(ctx: Context<InsuranceFundClose>, fund_index: u16) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::insurance_fund_close(ctx, fund_index)?;
+        Ok(())
+    }
+
+    pub fn token_edit_insurance_fund(
+        ctx: Context<TokenEditInsuranceFund>,
+        fund_index: u16,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::token_edit_insurance_fund(ctx, fund_index)?;
+        Ok(())
+    }
+
+    pub fn perp_edit_insurance_fund(
+        ctx: Context<PerpEditInsuranceFund>,
+        fund_index: u16,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_edit_insurance_fund(ctx, fund_index)?;
+        Ok(())
+    }
+
     pub fn ix_gate_set(ctx: Context<IxGateSet>, ix_gate: u128) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::ix_gate_set(ctx, ix_gate)?;
@@ -182,6 +223,7 @@ pub mod mango_v4 {
         reduce_only_opt: Option<u8>,
         name_opt: Option<String>,
         force_close_opt: Option<bool>,
+        fallback_oracle_opt: Option<Pubkey>,
     ) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::token_edit(
@@ -210,6 +252,7 @@ pub mod mango_v4 {
             reduce_only_opt,
             name_opt,
             force_close_opt,
+            fallback_oracle_opt,
         )?;
         Ok(())
     }
@@ -269,7 +312,34 @@ pub mod mango_v4 {
         perp_oo_count: u8,
     ) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
-        instructions::account_expand(ctx, token_count, serum3_count, perp_count, perp_oo_count)?;
+        instructions::account_expand(
+            ctx,
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn account_expand_v2(
+        ctx: Context<AccountExpand>,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        perp_trigger_order_count: u8,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::account_expand(
+            ctx,
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            Some(perp_trigger_order_count),
+        )?;
         Ok(())
     }
 
@@ -353,6 +423,18 @@ pub mod mango_v4 {
         Ok(())
     }
 
+    /// Move tokens between two accounts of the same owner, without going
+    /// through a wallet token account.
+    pub fn token_transfer(
+        ctx: Context<TokenTransfer>,
+        amount: u64,
+        allow_borrow: bool,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::token_transfer(ctx, amount, allow_borrow)?;
+        Ok(())
+    }
+
     pub fn flash_loan_begin<'key, 'accounts, 'remaining, 'info>(
         ctx: Context<'key, 'accounts, 'remaining, 'info, FlashLoanBegin<'info>>,
         loan_amounts: Vec<u64>,
@@ -688,6 +770,12 @@ pub mod mango_v4 {
         positive_pnl_liquidation_fee_opt: Option<f32>,
         name_opt: Option<String>,
         force_close_opt: Option<bool>,
+        fallback_oracle_opt: Option<Pubkey>,
+        expiry_timestamp_opt: Option<u64>,
+        settlement_window_ts_opt: Option<u64>,
+        settlement_uses_stable_price_opt: Option<bool>,
+        funding_model_opt: Option<PerpFundingModel>,
+        funding_interval_ts_opt: Option<u64>,
     ) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::perp_edit_market(
@@ -722,6 +810,12 @@ pub mod mango_v4 {
             positive_pnl_liquidation_fee_opt,
             name_opt,
             force_close_opt,
+            fallback_oracle_opt,
+            expiry_timestamp_opt,
+            settlement_window_ts_opt,
+            settlement_uses_stable_price_opt,
+            funding_model_opt,
+            funding_interval_ts_opt,
         )?;
         Ok(())
     }
@@ -785,9 +879,11 @@ pub mod mango_v4 {
             client_order_id,
             reduce_only,
             time_in_force,
+            self_trade_behavior: SelfTradeBehavior::DecrementTake,
             params: match order_type {
                 PlaceOrderType::Market => OrderParams::Market,
                 PlaceOrderType::ImmediateOrCancel => OrderParams::ImmediateOrCancel { price_lots },
+                PlaceOrderType::FillOrKill => OrderParams::FillOrKill { price_lots },
                 _ => OrderParams::Fixed {
                     price_lots,
                     order_type: order_type.to_post_order_type()?,
@@ -795,7 +891,7 @@ pub mod mango_v4 {
             },
         };
         #[cfg(feature = "enable-gpl")]
-        return instructions::perp_place_order(ctx, order, limit);
+        return instructions::perp_place_order(ctx.accounts, ctx.remaining_accounts, order, limit);
 
         #[cfg(not(feature = "enable-gpl"))]
         Ok(None)
@@ -858,6 +954,158 @@ pub mod mango_v4 {
             client_order_id,
             reduce_only,
             time_in_force,
+            self_trade_behavior: SelfTradeBehavior::DecrementTake,
+            params: OrderParams::OraclePegged {
+                price_offset_lots,
+                order_type: order_type.to_post_order_type()?,
+                peg_limit,
+                max_oracle_staleness_slots,
+            },
+        };
+        #[cfg(feature = "enable-gpl")]
+        return instructions::perp_place_order(ctx.accounts, ctx.remaining_accounts, order, limit);
+
+        #[cfg(not(feature = "enable-gpl"))]
+        Ok(None)
+    }
+
+    /// Like PerpPlaceOrder, but `self_trade_behavior` determines what happens when the
+    /// order would match against an order of the same account.
+    #[allow(clippy::too_many_arguments)]
+    pub fn perp_place_order_v2(
+        ctx: Context<PerpPlaceOrderV2>,
+        side: Side,
+
+        // The price in lots (quote lots per base lots)
+        // - fill orders on the book up to this price or
+        // - place an order on the book at this price.
+        // - ignored for Market orders and potentially adjusted for PostOnlySlide orders.
+        price_lots: i64,
+
+        max_base_lots: i64,
+        max_quote_lots: i64,
+        client_order_id: u64,
+        order_type: PlaceOrderType,
+        reduce_only: bool,
+
+        // Timestamp of when order expires
+        //
+        // Send 0 if you want the order to never expire.
+        // Timestamps in the past mean the instruction is skipped.
+        // Timestamps in the future are reduced to now + 65535s.
+        expiry_timestamp: u64,
+
+        // Maximum number of orders from the book to fill.
+        //
+        // Use this to limit compute used during order matching.
+        // When the limit is reached, processing stops and the instruction succeeds.
+        limit: u8,
+
+        // What to do when the order would match against an order of the same account.
+        self_trade_behavior: Serum3SelfTradeBehavior,
+    ) -> Result<Option<u128>> {
+        require_gte!(price_lots, 0);
+
+        use crate::state::{Order, OrderParams};
+        let time_in_force = match Order::tif_from_expiry(expiry_timestamp) {
+            Some(t) => t,
+            None => {
+                msg!("Order is already expired");
+                return Ok(None);
+            }
+        };
+        let order = Order {
+            side,
+            max_base_lots,
+            max_quote_lots,
+            client_order_id,
+            reduce_only,
+            time_in_force,
+            self_trade_behavior,
+            params: match order_type {
+                PlaceOrderType::Market => OrderParams::Market,
+                PlaceOrderType::ImmediateOrCancel => OrderParams::ImmediateOrCancel { price_lots },
+                PlaceOrderType::FillOrKill => OrderParams::FillOrKill { price_lots },
+                _ => OrderParams::Fixed {
+                    price_lots,
+                    order_type: order_type.to_post_order_type()?,
+                },
+            },
+        };
+        #[cfg(feature = "enable-gpl")]
+        return instructions::perp_place_order(
+            &ctx.accounts.v1,
+            ctx.remaining_accounts,
+            order,
+            limit,
+        );
+
+        #[cfg(not(feature = "enable-gpl"))]
+        Ok(None)
+    }
+
+    /// Like PerpPlaceOrderPegged, with the `self_trade_behavior` of PerpPlaceOrderV2.
+    #[allow(clippy::too_many_arguments)]
+    pub fn perp_place_order_pegged_v2(
+        ctx: Context<PerpPlaceOrderPeggedV2>,
+        side: Side,
+
+        // The adjustment from the oracle price, in lots (quote lots per base lots).
+        // Orders on the book may be filled at oracle + adjustment (depends on order type).
+        price_offset_lots: i64,
+
+        // The limit at which the pegged order shall expire.
+        // May be -1 to denote no peg limit.
+        //
+        // Example: An bid pegged to -20 with peg_limit 100 would expire if the oracle hits 121.
+        peg_limit: i64,
+
+        max_base_lots: i64,
+        max_quote_lots: i64,
+        client_order_id: u64,
+        order_type: PlaceOrderType,
+        reduce_only: bool,
+
+        // Timestamp of when order expires
+        //
+        // Send 0 if you want the order to never expire.
+        // Timestamps in the past mean the instruction is skipped.
+        // Timestamps in the future are reduced to now + 65535s.
+        expiry_timestamp: u64,
+
+        // Maximum number of orders from the book to fill.
+        //
+        // Use this to limit compute used during order matching.
+        // When the limit is reached, processing stops and the instruction succeeds.
+        limit: u8,
+
+        // Oracle staleness limit, in slots. Set to -1 to disable.
+        //
+        // WARNING: Not currently implemented.
+        max_oracle_staleness_slots: i32,
+
+        // What to do when the order would match against an order of the same account.
+        self_trade_behavior: Serum3SelfTradeBehavior,
+    ) -> Result<Option<u128>> {
+        require_gte!(peg_limit, -1);
+        require_eq!(max_oracle_staleness_slots, -1); // unimplemented
+
+        use crate::state::{Order, OrderParams};
+        let time_in_force = match Order::tif_from_expiry(expiry_timestamp) {
+            Some(t) => t,
+            None => {
+                msg!("Order is already expired");
+                return Ok(None);
+            }
+        };
+        let order = Order {
+            side,
+            max_base_lots,
+            max_quote_lots,
+            client_order_id,
+            reduce_only,
+            time_in_force,
+            self_trade_behavior,
             params: OrderParams::OraclePegged {
                 price_offset_lots,
                 order_type: order_type.to_post_order_type()?,
@@ -866,12 +1114,117 @@ pub mod mango_v4 {
             },
         };
         #[cfg(feature = "enable-gpl")]
-        return instructions::perp_place_order(ctx, order, limit);
+        return instructions::perp_place_order(
+            &ctx.accounts.v1,
+            ctx.remaining_accounts,
+            order,
+            limit,
+        );
 
         #[cfg(not(feature = "enable-gpl"))]
         Ok(None)
     }
 
+    /// Cancels orders and places new ones on a perp market with a single health check.
+    ///
+    /// Returns the order ids of the new orders that were posted to the book, in the
+    /// order of `new_orders`.
+    pub fn perp_cancel_replace_orders(
+        ctx: Context<PerpCancelReplaceOrders>,
+        cancel: PerpCancelOrders,
+        new_orders: Vec<PerpNewOrder>,
+
+        // Maximum number of orders from the book to fill, for each new order.
+        limit: u8,
+
+        // What to do when a new order would match against an order of the same account.
+        self_trade_behavior: Serum3SelfTradeBehavior,
+    ) -> Result<Vec<Option<u128>>> {
+        #[cfg(feature = "enable-gpl")]
+        return instructions::perp_cancel_replace_orders(
+            ctx,
+            cancel,
+            new_orders,
+            limit,
+            self_trade_behavior,
+        );
+
+        #[cfg(not(feature = "enable-gpl"))]
+        Ok(vec![])
+    }
+
+    /// Stores a conditional order on the account. Returns the trigger order id.
+    #[allow(clippy::too_many_arguments)]
+    pub fn perp_place_trigger_order(
+        ctx: Context<PerpPlaceTriggerOrder>,
+        side: Side,
+        trigger_condition: PerpTriggerCondition,
+
+        // The oracle price threshold, in native quote per native base
+        trigger_price: f64,
+
+        // The limit price of the immediate-or-cancel order that is placed
+        // when the trigger order is executed, in lots (quote lots per base lots)
+        price_lots: i64,
+
+        max_base_lots: i64,
+        max_quote_lots: i64,
+        client_order_id: u64,
+        reduce_only: bool,
+
+        // Timestamp after which the trigger order can no longer be executed
+        //
+        // Send 0 if you want the trigger order to never expire.
+        expiry_timestamp: u64,
+
+        // Native amount of the perp market's settle token that is paid to
+        // whoever executes the trigger order
+        incentive: u64,
+    ) -> Result<u64> {
+        use crate::state::PerpTriggerOrder;
+        let trigger_order = PerpTriggerOrder {
+            side: side.into(),
+            trigger_condition: trigger_condition.into(),
+            reduce_only: u8::from(reduce_only),
+            trigger_price: I80F48::from_num(trigger_price),
+            price_lots,
+            max_base_lots,
+            max_quote_lots,
+            client_order_id,
+            expiry_timestamp,
+            incentive,
+            ..PerpTriggerOrder::default()
+        };
+        #[cfg(feature = "enable-gpl")]
+        return instructions::perp_place_trigger_order(ctx, trigger_order);
+
+        #[cfg(not(feature = "enable-gpl"))]
+        Ok(0)
+    }
+
+    pub fn perp_cancel_trigger_order(
+        ctx: Context<PerpCancelTriggerOrder>,
+        trigger_order_id: u64,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_cancel_trigger_order(ctx, trigger_order_id)?;
+        Ok(())
+    }
+
+    /// Permissionless: places the trigger order on the book once its condition is met.
+    pub fn perp_execute_trigger_order(
+        ctx: Context<PerpExecuteTriggerOrder>,
+        trigger_order_id: u64,
+
+        // Maximum number of orders from the book to fill, at least
+        // TRIGGER_ORDER_MIN_MATCH_LIMIT.
+        limit: u8,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_execute_trigger_order(ctx, trigger_order_id, limit)?;
+        Ok(())
+    }
+
     pub fn perp_cancel_order(ctx: Context<PerpCancelOrder>, order_id: u128) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::perp_cancel_order(ctx, order_id)?;
@@ -927,6 +1280,14 @@ pub mod mango_v4 {
         Ok(())
     }
 
+    /// Closes the positions of a long and a short account at the settlement price
+    /// of an expired dated futures market.
+    pub fn perp_close_expired_position(ctx: Context<PerpCloseExpiredPosition>) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_close_expired_position(ctx)?;
+        Ok(())
+    }
+
     pub fn perp_settle_fees(ctx: Context<PerpSettleFees>, max_settle_amount: u64) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::perp_settle_fees(ctx, max_settle_amount)?;
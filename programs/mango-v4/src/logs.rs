@@ -86,6 +86,17 @@ pub struct DepositLog {
     pub price: i128, // I80F48
 }
 
+#[event]
+pub struct TokenTransferLog {
+    pub mango_group: Pubkey,
+    pub source_account: Pubkey,
+    pub destination_account: Pubkey,
+    pub signer: Pubkey,
+    pub token_index: u16,
+    pub quantity: u64,
+    pub price: i128, // I80F48
+}
+
 #[event]
 pub struct FillLog {
     pub mango_group: Pubkey,
@@ -152,6 +163,18 @@ pub struct PerpUpdateFundingLog {
     pub instantaneous_funding_rate: i128,
 }
 
+// Emitted by the funding update that closes a funding interval of a PremiumTwap market.
+// The funding rate in PerpUpdateFundingLog is the twap over this and the previous interval.
+#[event]
+pub struct PerpFundingPremiumIntervalLog {
+    pub mango_group: Pubkey,
+    pub market_index: u16,
+    pub start_ts: u64,
+    pub end_ts: u64,
+    pub premium_sum: i128, // I80F48, sum of book premium * seconds
+    pub premium_weight_ts: u64,
+}
+
 #[event]
 pub struct UpdateIndexLog {
     pub mango_group: Pubkey,
@@ -229,6 +252,7 @@ pub enum LoanOriginationFeeInstruction {
     Serum3PlaceOrder,
     Serum3SettleFunds,
     TokenWithdraw,
+    TokenTransfer,
 }
 
 #[event]
@@ -380,6 +404,17 @@ pub struct FilledPerpOrderLog {
     pub seq_num: u64,
 }
 
+#[event]
+pub struct PerpExecuteTriggerOrderLog {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub perp_market_index: u16,
+    pub trigger_order_id: u64,
+    pub executor: Pubkey,
+    pub incentive: u64,
+    pub oracle_price: i128,
+}
+
 #[event]
 pub struct PerpForceClosePositionLog {
     pub mango_group: Pubkey,
@@ -135,8 +135,12 @@ pub struct Bank {
     pub reduce_only: u8,
     pub force_close: u8,
 
+    /// Oracle to use when the primary oracle is stale or not confident enough,
+    /// Pubkey::default() if there is none
+    pub fallback_oracle: Pubkey,
+
     #[derivative(Debug = "ignore")]
-    pub reserved: [u8; 2118],
+    pub reserved: [u8; 2086],
 }
 const_assert_eq!(
     size_of::<Bank>(),
@@ -165,7 +169,8 @@ const_assert_eq!(
         + 8
         + 1
         + 1
-        + 2118
+        + 32
+        + 2086
 );
 const_assert_eq!(size_of::<Bank>(), 3064);
 const_assert_eq!(size_of::<Bank>() % 8, 0);
@@ -196,6 +201,7 @@ impl Bank {
             group: existing_bank.group,
             mint: existing_bank.mint,
             oracle: existing_bank.oracle,
+            fallback_oracle: existing_bank.fallback_oracle,
             deposit_index: existing_bank.deposit_index,
             borrow_index: existing_bank.borrow_index,
             index_last_updated: existing_bank.index_last_updated,
@@ -227,7 +233,7 @@ impl Bank {
             deposit_weight_scale_start_quote: f64::MAX,
             reduce_only: 0,
             force_close: 0,
-            reserved: [0; 2118],
+            reserved: [0; 2086],
         }
     }
 
@@ -799,6 +805,27 @@ impl Bank {
         Ok(price)
     }
 
+    /// Like oracle_price(), but uses the fallback oracle if the primary one is
+    /// rejected and `fallback_oracle_acc` is provided.
+    pub fn oracle_price_with_fallback<T: KeyedAccountReader>(
+        &self,
+        oracle_acc: &T,
+        fallback_oracle_acc: Option<&T>,
+        staleness_slot: Option<u64>,
+    ) -> Result<I80F48> {
+        require_keys_eq!(self.oracle, *oracle_acc.key());
+        let (price, _) = oracle::oracle_price_and_slot_with_fallback(
+            oracle_acc,
+            &self.fallback_oracle,
+            fallback_oracle_acc,
+            &self.oracle_config,
+            self.mint_decimals,
+            staleness_slot,
+        )?;
+
+        Ok(price)
+    }
+
     pub fn stable_price(&self) -> I80F48 {
         I80F48::from_num(self.stable_price_model.stable_price)
     }
@@ -4,7 +4,7 @@ use anchor_lang::prelude::*;
 
 use fixed::types::I80F48;
 
-use crate::events::{Equity, TokenEquity};
+use crate::events::{Equity, PerpEquity, TokenEquity};
 use crate::health::ScanningAccountRetriever;
 
 use super::MangoAccountRef;
@@ -57,8 +57,82 @@ pub fn compute_equity(
         })
         .collect::<Vec<TokenEquity>>();
 
-    // TODO: perp contributions
-    let perps = Vec::new();
+    // perp contributions
+    let mut perps = Vec::with_capacity(account.active_perp_positions().count());
+    for perp_position in account.active_perp_positions() {
+        let (perp_market, oracle_price) =
+            retriever.scanned_perp_market_and_oracle(perp_position.market_index)?;
+        let stable_price = perp_market.stable_price();
+
+        let unsettled_funding = perp_position.unsettled_funding(perp_market);
+        let quote = perp_position.quote_position_native() - unsettled_funding;
+        let base_native = perp_position.base_position_native(perp_market);
+        let base_value = base_native * oracle_price;
+        let stable_base_value = base_native * stable_price;
+
+        perps.push(PerpEquity {
+            perp_market_index: perp_market.perp_market_index,
+            value: base_value + quote,
+            stable_value: stable_base_value + quote,
+            base_value,
+            stable_base_value,
+            unsettled_funding,
+        });
+    }
 
     Ok(Equity { tokens, perps })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::health::test::*;
+    use crate::state::*;
+
+    #[test]
+    fn test_perp_equity() {
+        let buffer = MangoAccount::default_for_tests().try_to_vec().unwrap();
+        let mut account = MangoAccountValue::from_bytes(&buffer).unwrap();
+
+        let group = Pubkey::new_unique();
+
+        let (mut bank1, mut oracle1) = mock_bank_and_oracle(group, 0, 1.0, 0.2, 0.1);
+        bank1
+            .data()
+            .deposit(
+                account.ensure_token_position(0).unwrap().0,
+                I80F48::from(100),
+                DUMMY_NOW_TS,
+            )
+            .unwrap();
+
+        let mut oracle2 = TestAccount::<StubOracle>::new_zeroed();
+        oracle2.data().price = I80F48::from(5);
+        let mut perp1 = mock_perp_market(group, oracle2.pubkey, 5.0, 9, (0.2, 0.1), (0.05, 0.02));
+        perp1.data().stable_price_model.reset_to_price(4.0, 0);
+        let perpaccount = account.ensure_perp_position(9, 0).unwrap().0;
+        perpaccount.record_trade(perp1.data(), 3, -I80F48::from(100));
+        perp1.data().long_funding = I80F48::from(2);
+
+        let ais = vec![
+            bank1.as_account_info(),
+            oracle1.as_account_info(),
+            perp1.as_account_info(),
+            oracle2.as_account_info(),
+        ];
+        let retriever = ScanningAccountRetriever::new_with_staleness(&ais, &group, None).unwrap();
+
+        let equity = compute_equity(&account.borrow(), &retriever).unwrap();
+        assert_eq!(equity.tokens.len(), 1);
+        assert_eq!(equity.tokens[0].value, I80F48::from(100));
+
+        assert_eq!(equity.perps.len(), 1);
+        let perp = &equity.perps[0];
+        assert_eq!(perp.perp_market_index, 9);
+        assert_eq!(perp.base_value, I80F48::from(3 * 10 * 5));
+        assert_eq!(perp.stable_base_value, I80F48::from(3 * 10 * 4));
+        assert_eq!(perp.unsettled_funding, I80F48::from(3 * 2));
+        assert_eq!(perp.value, I80F48::from(150 - 100 - 6));
+        assert_eq!(perp.stable_value, I80F48::from(120 - 100 - 6));
+    }
+}
@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
 use static_assertions::const_assert_eq;
 use std::mem::size_of;
 
+use crate::error::*;
+
 // TODO: Assuming we allow up to 65536 different tokens
 pub type TokenIndex = u16;
 
@@ -23,6 +26,10 @@ pub const INSURANCE_TOKEN_INDEX: TokenIndex = 0;
 /// configurable.
 pub const PERP_SETTLE_TOKEN_INDEX: TokenIndex = 0;
 
+/// Isolated insurance funds have indexes 1..MAX_INSURANCE_FUNDS, index 0 is the
+/// group insurance fund.
+pub const MAX_INSURANCE_FUNDS: usize = 16;
+
 /// The token index used in AccountBuybackFeesWithMngo to exchange for MNGO
 pub const FEE_BUYBACK_QUOTE_TOKEN_INDEX: TokenIndex = 0;
 
@@ -88,11 +95,21 @@ pub struct Group {
     /// When set to 0, there's no expiry of buyback fees.
     pub buyback_fees_expiry_interval: u64,
 
-    pub reserved: [u8; 1824],
+    /// Bumps of the isolated insurance fund vaults, by fund index.
+    ///
+    /// Set by InsuranceFundCreate. Index 0 is unused, see insurance_vault.
+    pub insurance_fund_vault_bumps: [u8; MAX_INSURANCE_FUNDS],
+
+    /// Number of tokens and perp markets each isolated insurance fund covers, by fund index.
+    ///
+    /// Insurance funds can't be closed while they are still referenced.
+    pub insurance_fund_references: [u16; MAX_INSURANCE_FUNDS],
+
+    pub reserved: [u8; 1776],
 }
 const_assert_eq!(
     size_of::<Group>(),
-    32 + 4 + 32 * 2 + 4 + 32 * 2 + 4 + 4 + 20 * 32 + 32 + 8 + 16 + 32 + 8 + 1824
+    32 + 4 + 32 * 2 + 4 + 32 * 2 + 4 + 4 + 20 * 32 + 32 + 8 + 16 + 32 + 8 + 16 + 16 * 2 + 1776
 );
 const_assert_eq!(size_of::<Group>(), 2736);
 const_assert_eq!(size_of::<Group>() % 8, 0);
@@ -121,13 +138,96 @@ impl Group {
     pub fn is_ix_enabled(&self, ix: IxGate) -> bool {
         self.ix_gate & (1 << ix as u128) == 0
     }
+
+    /// Address of the vault of the insurance fund with `fund_index`.
+    ///
+    /// Index 0 is the group insurance fund, all others are isolated insurance
+    /// funds created with InsuranceFundCreate.
+    pub fn insurance_fund_vault(&self, group_key: &Pubkey, fund_index: u16) -> Result<Pubkey> {
+        if fund_index == 0 {
+            return Ok(self.insurance_vault);
+        }
+        let bump = *self
+            .insurance_fund_vault_bumps
+            .get(fund_index as usize)
+            .ok_or_else(|| error_msg!("insurance fund index {} out of range", fund_index))?;
+        Pubkey::create_program_address(
+            &[
+                b"InsuranceVault".as_ref(),
+                group_key.as_ref(),
+                &fund_index.to_le_bytes(),
+                &[bump],
+            ],
+            &crate::id(),
+        )
+        .map_err(|_| error_msg!("no vault for insurance fund {}", fund_index))
+    }
+
+    /// Whether `vault` is the group insurance vault or the vault of the insurance
+    /// fund `fund_index`.
+    pub fn is_insurance_vault(
+        &self,
+        group_key: &Pubkey,
+        fund_index: u16,
+        vault: &Pubkey,
+    ) -> Result<bool> {
+        Ok(*vault == self.insurance_vault
+            || *vault == self.insurance_fund_vault(group_key, fund_index)?)
+    }
+
+    /// Checks that the vault of the insurance fund `fund_index` exists and is the
+    /// first of `ais`. Nothing needs to be passed for the group insurance fund.
+    pub fn verify_insurance_vault<'info>(
+        &self,
+        group_key: &Pubkey,
+        fund_index: u16,
+        ais: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        if fund_index == 0 {
+            return Ok(());
+        }
+        let vault_ai = ais
+            .first()
+            .ok_or_else(|| error_msg!("the vault of insurance fund {} is missing", fund_index))?;
+        require_keys_eq!(
+            *vault_ai.key,
+            self.insurance_fund_vault(group_key, fund_index)?
+        );
+        // fails if the vault was never created or has been closed
+        let vault = Account::<TokenAccount>::try_from(vault_ai)?;
+        require_keys_eq!(vault.mint, self.insurance_mint);
+        Ok(())
+    }
+
+    /// Updates the reference counts when a token or perp market switches from
+    /// the insurance fund `old_index` to `new_index`.
+    pub fn move_insurance_fund_reference(&mut self, old_index: u16, new_index: u16) -> Result<()> {
+        if old_index == new_index {
+            return Ok(());
+        }
+        if old_index != 0 {
+            let references = &mut self.insurance_fund_references[old_index as usize];
+            *references = references
+                .checked_sub(1)
+                .ok_or_else(|| error_msg!("insurance fund {} isn't referenced", old_index))?;
+        }
+        if new_index != 0 {
+            require_msg!(
+                (new_index as usize) < MAX_INSURANCE_FUNDS,
+                "insurance fund index {} out of range",
+                new_index
+            );
+            self.insurance_fund_references[new_index as usize] += 1;
+        }
+        Ok(())
+    }
 }
 
 /// Enum for lookup into ix gate
 /// note:
-/// total ix files 56,
+/// total ix files 58,
 /// ix files included 48,
-/// ix files not included 8,
+/// ix files not included 10,
 /// - Benchmark,
 /// - ComputeAccountData,
 /// - GroupCreate
@@ -135,7 +235,9 @@ impl Group {
 /// - IxGateSet,
 /// - PerpZeroOut,
 /// - PerpEditMarket,
+/// - PerpEditInsuranceFund,
 /// - TokenEdit,
+/// - TokenEditInsuranceFund,
 #[derive(Copy, Clone, Debug)]
 pub enum IxGate {
     AccountClose = 0,
@@ -190,6 +292,17 @@ pub enum IxGate {
     TokenForceCloseBorrowsWithToken = 49,
     PerpForceClosePosition = 50,
     GroupWithdrawInsuranceFund = 51,
+    InsuranceFundCreate = 52,
+    InsuranceFundWithdraw = 53,
+    InsuranceFundClose = 54,
+    PerpPlaceTriggerOrder = 55,
+    PerpCancelTriggerOrder = 56,
+    PerpExecuteTriggerOrder = 57,
+    TokenTransfer = 58,
+    PerpCancelReplaceOrders = 59,
+    PerpCloseExpiredPosition = 60,
+    PerpPlaceOrderV2 = 61,
+    PerpPlaceOrderPeggedV2 = 62,
     // NOTE: Adding new variants requires matching changes in ts and the ix_gate_set instruction.
 }
 
@@ -21,6 +21,7 @@ use super::LeafNode;
 use super::PerpMarket;
 use super::PerpMarketIndex;
 use super::PerpOpenOrder;
+use super::PerpTriggerOrder;
 use super::Serum3MarketIndex;
 use super::TokenIndex;
 use super::FREE_ORDER_SLOT;
@@ -104,7 +105,10 @@ pub struct MangoAccount {
     /// End timestamp of the current expiry interval of the buyback fees amount.
     pub buyback_fees_expiry_timestamp: u64,
 
-    pub reserved: [u8; 208],
+    /// Incremented for each new perp trigger order, the new value is used as its id.
+    pub perp_trigger_order_id_counter: u64,
+
+    pub reserved: [u8; 200],
 
     // dynamic
     pub header_version: u8,
@@ -122,6 +126,10 @@ pub struct MangoAccount {
     pub perps: Vec<PerpPosition>,
     pub padding7: u32,
     pub perp_open_orders: Vec<PerpOpenOrder>,
+    // Accounts created before trigger orders were introduced don't have
+    // this section, it's added on expansion
+    pub padding8: u32,
+    pub perp_trigger_orders: Vec<PerpTriggerOrder>,
 }
 
 impl MangoAccount {
@@ -142,7 +150,8 @@ impl MangoAccount {
             buyback_fees_accrued_current: 0,
             buyback_fees_accrued_previous: 0,
             buyback_fees_expiry_timestamp: 0,
-            reserved: [0; 208],
+            perp_trigger_order_id_counter: 0,
+            reserved: [0; 200],
             header_version: DEFAULT_MANGO_ACCOUNT_VERSION,
             padding3: Default::default(),
             padding4: Default::default(),
@@ -153,6 +162,8 @@ impl MangoAccount {
             perps: vec![PerpPosition::default(); 4],
             padding7: Default::default(),
             perp_open_orders: vec![PerpOpenOrder::default(); 6],
+            padding8: Default::default(),
+            perp_trigger_orders: vec![PerpTriggerOrder::default(); 2],
             perp_spot_transfers: 0,
         }
     }
@@ -163,14 +174,22 @@ impl MangoAccount {
         serum3_count: u8,
         perp_count: u8,
         perp_oo_count: u8,
+        perp_trigger_order_count: u8,
     ) -> Result<usize> {
         require_gte!(16, token_count);
         require_gte!(8, serum3_count);
         require_gte!(8, perp_count);
         require_gte!(64, perp_oo_count);
+        require_gte!(16, perp_trigger_order_count);
 
         Ok(8 + size_of::<MangoAccountFixed>()
-            + Self::dynamic_size(token_count, serum3_count, perp_count, perp_oo_count))
+            + Self::dynamic_size(
+                token_count,
+                serum3_count,
+                perp_count,
+                perp_oo_count,
+                perp_trigger_order_count,
+            ))
     }
 
     pub fn dynamic_token_vec_offset() -> usize {
@@ -196,7 +215,7 @@ impl MangoAccount {
             + BORSH_VEC_PADDING_BYTES
     }
 
-    pub fn dynamic_size(
+    pub fn dynamic_perp_trigger_order_vec_offset(
         token_count: u8,
         serum3_count: u8,
         perp_count: u8,
@@ -204,6 +223,23 @@ impl MangoAccount {
     ) -> usize {
         Self::dynamic_perp_oo_vec_offset(token_count, serum3_count, perp_count)
             + (BORSH_VEC_SIZE_BYTES + size_of::<PerpOpenOrder>() * usize::from(perp_oo_count))
+            + BORSH_VEC_PADDING_BYTES
+    }
+
+    pub fn dynamic_size(
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        perp_trigger_order_count: u8,
+    ) -> usize {
+        Self::dynamic_perp_trigger_order_vec_offset(
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+        ) + (BORSH_VEC_SIZE_BYTES
+            + size_of::<PerpTriggerOrder>() * usize::from(perp_trigger_order_count))
     }
 }
 
@@ -227,9 +263,10 @@ pub struct MangoAccountFixed {
     pub buyback_fees_accrued_current: u64,
     pub buyback_fees_accrued_previous: u64,
     pub buyback_fees_expiry_timestamp: u64,
-    pub reserved: [u8; 208],
+    pub perp_trigger_order_id_counter: u64,
+    pub reserved: [u8; 200],
 }
-const_assert_eq!(size_of::<MangoAccountFixed>(), 32 * 4 + 8 + 7 * 8 + 208);
+const_assert_eq!(size_of::<MangoAccountFixed>(), 32 * 4 + 8 + 8 * 8 + 200);
 const_assert_eq!(size_of::<MangoAccountFixed>(), 400);
 const_assert_eq!(size_of::<MangoAccountFixed>() % 8, 0);
 
@@ -338,6 +375,7 @@ pub struct MangoAccountDynamicHeader {
     pub serum3_count: u8,
     pub perp_count: u8,
     pub perp_oo_count: u8,
+    pub perp_trigger_order_count: u8,
 }
 
 impl DynamicHeader for MangoAccountDynamicHeader {
@@ -374,11 +412,33 @@ impl DynamicHeader for MangoAccountDynamicHeader {
                 ]))
                 .unwrap();
 
+                // The trigger order section was added later: accounts that were not
+                // expanded since then end right after the perp open orders.
+                let perp_trigger_order_vec_offset =
+                    MangoAccount::dynamic_perp_trigger_order_vec_offset(
+                        token_count,
+                        serum3_count,
+                        perp_count,
+                        perp_oo_count,
+                    );
+                let perp_trigger_order_count =
+                    if dynamic_data.len() >= perp_trigger_order_vec_offset + BORSH_VEC_SIZE_BYTES {
+                        u8::try_from(BorshVecLength::from_le_bytes(*array_ref![
+                            dynamic_data,
+                            perp_trigger_order_vec_offset,
+                            BORSH_VEC_SIZE_BYTES
+                        ]))
+                        .unwrap()
+                    } else {
+                        0
+                    };
+
                 Ok(Self {
                     token_count,
                     serum3_count,
                     perp_count,
                     perp_oo_count,
+                    perp_trigger_order_count,
                 })
             }
             _ => err!(MangoError::NotImplementedError).context("unexpected header version number"),
@@ -433,6 +493,16 @@ impl MangoAccountDynamicHeader {
             + raw_index * size_of::<PerpOpenOrder>()
     }
 
+    fn perp_trigger_order_offset(&self, raw_index: usize) -> usize {
+        MangoAccount::dynamic_perp_trigger_order_vec_offset(
+            self.token_count,
+            self.serum3_count,
+            self.perp_count,
+            self.perp_oo_count,
+        ) + BORSH_VEC_SIZE_BYTES
+            + raw_index * size_of::<PerpTriggerOrder>()
+    }
+
     pub fn token_count(&self) -> usize {
         self.token_count.into()
     }
@@ -445,6 +515,9 @@ impl MangoAccountDynamicHeader {
     pub fn perp_oo_count(&self) -> usize {
         self.perp_oo_count.into()
     }
+    pub fn perp_trigger_order_count(&self) -> usize {
+        self.perp_trigger_order_count.into()
+    }
 }
 
 /// Fully owned MangoAccount, useful for tests
@@ -622,6 +695,36 @@ impl<
             .find(|&oo| oo.is_active_for_market(market_index) && oo.id == order_id)
     }
 
+    pub fn perp_trigger_order_by_raw_index(&self, raw_index: usize) -> &PerpTriggerOrder {
+        get_helper(
+            self.dynamic(),
+            self.header().perp_trigger_order_offset(raw_index),
+        )
+    }
+
+    pub fn all_perp_trigger_orders(&self) -> impl Iterator<Item = &PerpTriggerOrder> {
+        (0..self.header().perp_trigger_order_count())
+            .map(|i| self.perp_trigger_order_by_raw_index(i))
+    }
+
+    pub fn active_perp_trigger_orders(&self) -> impl Iterator<Item = &PerpTriggerOrder> {
+        self.all_perp_trigger_orders().filter(|o| o.is_active())
+    }
+
+    /// Returns the trigger order with `id` and its raw index
+    pub fn perp_trigger_order_and_raw_index(&self, id: u64) -> Result<(&PerpTriggerOrder, usize)> {
+        self.all_perp_trigger_orders()
+            .enumerate()
+            .find_map(|(raw_index, o)| (o.is_active() && o.id == id).then_some((o, raw_index)))
+            .ok_or_else(|| {
+                error_msg_typed!(
+                    MangoError::PerpTriggerOrderNotFound,
+                    "perp trigger order with id {} not found",
+                    id
+                )
+            })
+    }
+
     pub fn being_liquidated(&self) -> bool {
         self.fixed().being_liquidated()
     }
@@ -811,6 +914,33 @@ impl<
         get_helper_mut(self.dynamic_mut(), offset)
     }
 
+    pub fn perp_trigger_order_mut_by_raw_index(
+        &mut self,
+        raw_index: usize,
+    ) -> &mut PerpTriggerOrder {
+        let offset = self.header().perp_trigger_order_offset(raw_index);
+        get_helper_mut(self.dynamic_mut(), offset)
+    }
+
+    /// Stores `order` in a free trigger order slot, assigning a new id. Returns the id.
+    pub fn add_perp_trigger_order(&mut self, mut order: PerpTriggerOrder) -> Result<u64> {
+        let raw_index = self
+            .all_perp_trigger_orders()
+            .position(|o| !o.is_active())
+            .ok_or_else(|| error_msg!("no free perp trigger order index"))?;
+
+        let fixed = self.fixed_mut();
+        fixed.perp_trigger_order_id_counter += 1;
+        order.id = fixed.perp_trigger_order_id_counter;
+
+        *self.perp_trigger_order_mut_by_raw_index(raw_index) = order;
+        Ok(order.id)
+    }
+
+    pub fn remove_perp_trigger_order(&mut self, raw_index: usize) {
+        *self.perp_trigger_order_mut_by_raw_index(raw_index) = PerpTriggerOrder::default();
+    }
+
     pub fn perp_position_mut(
         &mut self,
         market_index: PerpMarketIndex,
@@ -1099,6 +1229,13 @@ impl<
         dst.copy_from_slice(&BorshVecLength::from(count).to_le_bytes());
     }
 
+    fn write_perp_trigger_order_length(&mut self) {
+        let offset = self.header().perp_trigger_order_offset(0);
+        let count = self.header().perp_trigger_order_count;
+        let dst: &mut [u8] = &mut self.dynamic_mut()[offset - BORSH_VEC_SIZE_BYTES..offset];
+        dst.copy_from_slice(&BorshVecLength::from(count).to_le_bytes());
+    }
+
     fn write_perp_oo_length(&mut self) {
         let perp_oo_offset = self.header().perp_oo_offset(0);
         // msg!(
@@ -1117,11 +1254,16 @@ impl<
         new_serum3_count: u8,
         new_perp_count: u8,
         new_perp_oo_count: u8,
+        new_perp_trigger_order_count: u8,
     ) -> Result<()> {
         require_gte!(new_token_count, self.header().token_count);
         require_gte!(new_serum3_count, self.header().serum3_count);
         require_gte!(new_perp_count, self.header().perp_count);
         require_gte!(new_perp_oo_count, self.header().perp_oo_count);
+        require_gte!(
+            new_perp_trigger_order_count,
+            self.header().perp_trigger_order_count
+        );
 
         // create a temp copy to compute new starting offsets
         let new_header = MangoAccountDynamicHeader {
@@ -1129,12 +1271,28 @@ impl<
             serum3_count: new_serum3_count,
             perp_count: new_perp_count,
             perp_oo_count: new_perp_oo_count,
+            perp_trigger_order_count: new_perp_trigger_order_count,
         };
         let old_header = self.header().clone();
         let dynamic = self.dynamic_mut();
 
         // expand dynamic components by first moving existing positions, and then setting new ones to defaults
 
+        // perp trigger orders
+        if old_header.perp_trigger_order_count() > 0 {
+            unsafe {
+                sol_memmove(
+                    &mut dynamic[new_header.perp_trigger_order_offset(0)],
+                    &mut dynamic[old_header.perp_trigger_order_offset(0)],
+                    size_of::<PerpTriggerOrder>() * old_header.perp_trigger_order_count(),
+                );
+            }
+        }
+        for i in old_header.perp_trigger_order_count..new_perp_trigger_order_count {
+            *get_helper_mut(dynamic, new_header.perp_trigger_order_offset(i.into())) =
+                PerpTriggerOrder::default();
+        }
+
         // perp oo
         if old_header.perp_oo_count() > 0 {
             unsafe {
@@ -1200,6 +1358,7 @@ impl<
         self.write_serum3_length();
         self.write_perp_length();
         self.write_perp_oo_length();
+        self.write_perp_trigger_order_length();
 
         Ok(())
     }
@@ -1289,17 +1448,22 @@ mod tests {
         account.buyback_fees_accrued_current = 10;
         account.buyback_fees_accrued_previous = 11;
         account.buyback_fees_expiry_timestamp = 12;
+        account.perp_trigger_order_id_counter = 13;
         account.tokens.resize(8, TokenPosition::default());
         account.tokens[0].token_index = 8;
         account.serum3.resize(8, Serum3Orders::default());
         account.perps.resize(8, PerpPosition::default());
         account.perps[0].market_index = 9;
         account.perp_open_orders.resize(8, PerpOpenOrder::default());
+        account
+            .perp_trigger_orders
+            .resize(4, PerpTriggerOrder::default());
+        account.perp_trigger_orders[0].id = 14;
 
         let account_bytes = AnchorSerialize::try_to_vec(&account).unwrap();
         assert_eq!(
             8 + account_bytes.len(),
-            MangoAccount::space(8, 8, 8, 8).unwrap()
+            MangoAccount::space(8, 8, 8, 8, 4).unwrap()
         );
 
         let account2 = MangoAccountValue::from_bytes(&account_bytes).unwrap();
@@ -1344,6 +1508,58 @@ mod tests {
             account.perps[0].market_index,
             account2.perp_position_by_raw_index(0).market_index
         );
+        assert_eq!(
+            account.perp_trigger_order_id_counter,
+            account2.fixed.perp_trigger_order_id_counter
+        );
+        assert_eq!(
+            account.perp_trigger_orders[0].id,
+            account2.perp_trigger_order_by_raw_index(0).id
+        );
+    }
+
+    #[test]
+    fn test_perp_trigger_orders() {
+        // Accounts from before the trigger order section existed end after the perp open orders
+        let mut account = MangoAccount::default_for_tests();
+        account.perp_trigger_orders = vec![];
+        let mut bytes = AnchorSerialize::try_to_vec(&account).unwrap();
+        bytes.truncate(bytes.len() - 8);
+        let mut account = MangoAccountValue::from_bytes(&bytes).unwrap();
+        assert_eq!(account.header.perp_trigger_order_count(), 0);
+        assert!(account
+            .add_perp_trigger_order(PerpTriggerOrder::default())
+            .is_err());
+
+        // Expanding adds the section
+        account
+            .dynamic
+            .resize(MangoAccount::dynamic_size(3, 5, 4, 6, 2), 0);
+        account.expand_dynamic_content(3, 5, 4, 6, 2).unwrap();
+        assert_eq!(account.header.perp_trigger_order_count(), 2);
+        let mut expanded_bytes = bytes[..size_of::<MangoAccountFixed>()].to_vec();
+        expanded_bytes.extend_from_slice(&account.dynamic);
+        let reparsed = MangoAccountValue::from_bytes(&expanded_bytes).unwrap();
+        assert_eq!(reparsed.header.perp_trigger_order_count(), 2);
+
+        let order = PerpTriggerOrder {
+            market: 1,
+            ..PerpTriggerOrder::default()
+        };
+        assert_eq!(account.add_perp_trigger_order(order).unwrap(), 1);
+        assert_eq!(account.add_perp_trigger_order(order).unwrap(), 2);
+        assert!(account.add_perp_trigger_order(order).is_err());
+        assert_eq!(account.active_perp_trigger_orders().count(), 2);
+
+        let (found, raw_index) = account.perp_trigger_order_and_raw_index(2).unwrap();
+        assert_eq!(found.market, 1);
+        assert_eq!(raw_index, 1);
+        assert!(account.perp_trigger_order_and_raw_index(3).is_err());
+
+        account.remove_perp_trigger_order(0);
+        assert!(account.perp_trigger_order_and_raw_index(1).is_err());
+        assert_eq!(account.add_perp_trigger_order(order).unwrap(), 3);
+        assert_eq!(account.perp_trigger_order_by_raw_index(0).id, 3);
     }
 
     #[test]
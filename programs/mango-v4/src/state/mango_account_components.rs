@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 
 use derivative::Derivative;
 use fixed::types::I80F48;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
 use static_assertions::const_assert_eq;
 use std::cmp::Ordering;
 use std::mem::size_of;
@@ -820,6 +821,114 @@ impl PerpOpenOrder {
     }
 }
 
+#[derive(
+    Eq,
+    PartialEq,
+    Copy,
+    Clone,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Debug,
+    AnchorSerialize,
+    AnchorDeserialize,
+)]
+#[repr(u8)]
+pub enum PerpTriggerCondition {
+    /// Trigger when the oracle price is >= the trigger price
+    OraclePriceAbove = 0,
+    /// Trigger when the oracle price is <= the trigger price
+    OraclePriceBelow = 1,
+}
+
+/// A conditional perp order, usable for stop-loss and take-profit.
+///
+/// Once the oracle price crosses trigger_price, anyone may execute it: it is then sent
+/// to the orderbook as an immediate-or-cancel order and the executor receives the incentive.
+#[zero_copy]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PerpTriggerOrder {
+    /// Id unique for the account, zero marks an unused slot
+    pub id: u64,
+    pub market: PerpMarketIndex,
+    pub side: u8,              // Side -- enums aren't POD
+    pub trigger_condition: u8, // PerpTriggerCondition
+    pub reduce_only: u8,
+    pub padding: [u8; 3],
+
+    /// Oracle price threshold, in native quote per native base
+    pub trigger_price: I80F48,
+
+    /// Limit price of the order that gets placed on execution, in quote lots per base lots
+    pub price_lots: i64,
+    pub max_base_lots: i64,
+    pub max_quote_lots: i64,
+    pub client_order_id: u64,
+
+    /// Timestamp after which the trigger order can't be executed anymore, 0 for never
+    pub expiry_timestamp: u64,
+
+    /// Native settle token amount that the account pays to the executor
+    pub incentive: u64,
+
+    pub reserved: [u8; 64],
+}
+const_assert_eq!(
+    size_of::<PerpTriggerOrder>(),
+    8 + 2 + 1 + 1 + 1 + 3 + 16 + 8 * 6 + 64
+);
+const_assert_eq!(size_of::<PerpTriggerOrder>(), 144);
+const_assert_eq!(size_of::<PerpTriggerOrder>() % 8, 0);
+
+impl Default for PerpTriggerOrder {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            market: FREE_ORDER_SLOT,
+            side: Side::Bid.into(),
+            trigger_condition: PerpTriggerCondition::OraclePriceAbove.into(),
+            reduce_only: 0,
+            padding: Default::default(),
+            trigger_price: I80F48::ZERO,
+            price_lots: 0,
+            max_base_lots: 0,
+            max_quote_lots: 0,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            incentive: 0,
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl PerpTriggerOrder {
+    pub fn is_active(&self) -> bool {
+        self.id != 0
+    }
+
+    pub fn side(&self) -> Side {
+        Side::try_from(self.side).unwrap()
+    }
+
+    pub fn trigger_condition(&self) -> PerpTriggerCondition {
+        PerpTriggerCondition::try_from(self.trigger_condition).unwrap()
+    }
+
+    pub fn is_reduce_only(&self) -> bool {
+        self.reduce_only == 1
+    }
+
+    pub fn is_expired(&self, now_ts: u64) -> bool {
+        self.expiry_timestamp != 0 && now_ts > self.expiry_timestamp
+    }
+
+    pub fn is_triggered(&self, oracle_price: I80F48) -> bool {
+        match self.trigger_condition() {
+            PerpTriggerCondition::OraclePriceAbove => oracle_price >= self.trigger_price,
+            PerpTriggerCondition::OraclePriceBelow => oracle_price <= self.trigger_price,
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! account_seeds {
     ( $account:expr ) => {
@@ -30,11 +30,17 @@ pub struct MintInfo {
 
     pub registration_time: u64,
 
-    pub reserved: [u8; 2560],
+    /// Isolated insurance fund that covers bankruptcies in this token.
+    ///
+    /// 0 means the group insurance fund is used, if group_insurance_fund is set.
+    pub insurance_fund_index: u16,
+    pub padding2: [u8; 6],
+
+    pub reserved: [u8; 2552],
 }
 const_assert_eq!(
     size_of::<MintInfo>(),
-    32 + 2 + 1 + 5 + 32 + MAX_BANKS * 2 * 32 + 32 + 8 + 2560
+    32 + 2 + 1 + 5 + 32 + MAX_BANKS * 2 * 32 + 32 + 8 + 2 + 6 + 2552
 );
 const_assert_eq!(size_of::<MintInfo>(), 3056);
 const_assert_eq!(size_of::<MintInfo>() % 8, 0);
@@ -73,4 +79,17 @@ impl MintInfo {
     pub fn elligible_for_group_insurance_fund(&self) -> bool {
         self.group_insurance_fund == 1
     }
+
+    /// Index of the insurance fund that covers bankruptcies in this token, if any.
+    ///
+    /// Index 0 is the group insurance fund.
+    pub fn covering_insurance_fund(&self) -> Option<u16> {
+        if self.insurance_fund_index != 0 {
+            Some(self.insurance_fund_index)
+        } else if self.elligible_for_group_insurance_fund() {
+            Some(0)
+        } else {
+            None
+        }
+    }
 }
@@ -83,7 +83,7 @@ impl OracleConfigParams {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum OracleType {
     Pyth,
     Stub,
@@ -105,27 +105,226 @@ const_assert_eq!(size_of::<StubOracle>(), 32 + 32 + 16 + 8 + 128);
 const_assert_eq!(size_of::<StubOracle>(), 216);
 const_assert_eq!(size_of::<StubOracle>() % 8, 0);
 
-pub fn determine_oracle_type(acc_info: &impl KeyedAccountReader) -> Result<OracleType> {
-    let data = acc_info.data();
+/// The parts of an oracle account that adapters may look at.
+pub struct OracleAccount<'a> {
+    pub key: &'a Pubkey,
+    pub owner: &'a Pubkey,
+    pub data: &'a [u8],
+}
+
+impl<'a> OracleAccount<'a> {
+    pub fn new(acc_info: &'a impl KeyedAccountReader) -> Self {
+        Self {
+            key: acc_info.key(),
+            owner: acc_info.owner(),
+            data: acc_info.data(),
+        }
+    }
+}
+
+/// A price as read from an oracle account, before any OracleConfig checks.
+pub struct OracleReading {
+    /// The price, in units determined by `exponent`
+    pub price: I80F48,
+
+    /// Decimal exponent of `price`: ui price = price * 10^exponent
+    ///
+    /// None means that `price` already is in native quote per native base.
+    pub exponent: Option<i8>,
+
+    /// Uncertainty of the price, in the same units as `price`.
+    ///
+    /// Checked against OracleConfig::conf_filter. None if the oracle has no
+    /// notion of confidence.
+    pub deviation: Option<I80F48>,
+
+    /// Slot at which the price was last updated.
+    ///
+    /// Checked against OracleConfig::max_staleness_slots. None if the oracle
+    /// has no notion of staleness.
+    pub last_update_slot: Option<u64>,
+}
+
+/// An oracle account format that can be used for pricing.
+///
+/// Adding a new format means adding an OracleType variant, implementing this
+/// trait and listing the adapter in ORACLE_ADAPTERS. Detection, pricing and the
+/// OracleConfig checks all go through it.
+pub trait OracleAdapter: Sync {
+    fn oracle_type(&self) -> OracleType;
+
+    /// Name used in log messages
+    fn name(&self) -> &'static str;
+
+    /// Does the account contain an oracle of this format?
+    fn matches(&self, account: &OracleAccount) -> bool;
+
+    /// Read the price from an account for which `matches()` returned true.
+    fn read(&self, account: &OracleAccount) -> Result<OracleReading>;
+}
+
+/// All supported oracle formats, in order of detection priority.
+pub const ORACLE_ADAPTERS: [&dyn OracleAdapter; 4] = [
+    &PythOracle,
+    &StubOracleAdapter,
+    &SwitchboardV2Oracle,
+    &SwitchboardV1Oracle,
+];
+
+fn from_foreign_error(e: impl std::fmt::Display) -> Error {
+    error_msg!("{}", e)
+}
+
+pub struct PythOracle;
+
+impl OracleAdapter for PythOracle {
+    fn oracle_type(&self) -> OracleType {
+        OracleType::Pyth
+    }
+
+    fn name(&self) -> &'static str {
+        "Pyth"
+    }
+
+    fn matches(&self, account: &OracleAccount) -> bool {
+        account.data.len() >= 4
+            && u32::from_le_bytes(account.data[0..4].try_into().unwrap())
+                == pyth_sdk_solana::state::MAGIC
+    }
+
+    fn read(&self, account: &OracleAccount) -> Result<OracleReading> {
+        let price_account =
+            pyth_sdk_solana::state::load_price_account(account.data).map_err(from_foreign_error)?;
+        let price_data = price_account.to_price();
+        Ok(OracleReading {
+            price: I80F48::from_num(price_data.price),
+            exponent: Some(price_account.expo as i8),
+            deviation: Some(I80F48::from_num(price_data.conf)),
+            // The last_slot is when the price was actually updated
+            last_update_slot: Some(price_account.last_slot),
+        })
+    }
+}
+
+pub struct StubOracleAdapter;
+
+impl OracleAdapter for StubOracleAdapter {
+    fn oracle_type(&self) -> OracleType {
+        OracleType::Stub
+    }
+
+    fn name(&self) -> &'static str {
+        "Stub"
+    }
+
+    fn matches(&self, account: &OracleAccount) -> bool {
+        account.data.len() >= 8 && account.data[0..8] == StubOracle::discriminator()
+    }
+
+    fn read(&self, account: &OracleAccount) -> Result<OracleReading> {
+        require_keys_eq!(
+            *account.owner,
+            crate::id(),
+            ErrorCode::AccountOwnedByWrongProgram
+        );
+        require_gte!(
+            account.data.len(),
+            8 + size_of::<StubOracle>(),
+            ErrorCode::AccountDidNotDeserialize
+        );
+        let stub =
+            bytemuck::from_bytes::<StubOracle>(&account.data[8..8 + size_of::<StubOracle>()]);
+        // Stub prices are stored in native units and never go stale
+        Ok(OracleReading {
+            price: stub.price,
+            exponent: None,
+            deviation: None,
+            last_update_slot: None,
+        })
+    }
+}
+
+pub struct SwitchboardV2Oracle;
+
+impl OracleAdapter for SwitchboardV2Oracle {
+    fn oracle_type(&self) -> OracleType {
+        OracleType::SwitchboardV2
+    }
 
-    if u32::from_le_bytes(data[0..4].try_into().unwrap()) == pyth_sdk_solana::state::MAGIC {
-        return Ok(OracleType::Pyth);
-    } else if data[0..8] == StubOracle::discriminator() {
-        return Ok(OracleType::Stub);
+    fn name(&self) -> &'static str {
+        "Switchboard v2"
     }
-    // https://github.com/switchboard-xyz/switchboard-v2/blob/main/libraries/rs/src/aggregator.rs#L114
-    // note: disc is not public, hence the copy pasta
-    else if data[0..8] == [217, 230, 65, 101, 201, 162, 27, 125] {
-        return Ok(OracleType::SwitchboardV2);
+
+    fn matches(&self, account: &OracleAccount) -> bool {
+        // https://github.com/switchboard-xyz/switchboard-v2/blob/main/libraries/rs/src/aggregator.rs#L114
+        // note: disc is not public, hence the copy pasta
+        account.data.len() >= 8 && account.data[0..8] == [217, 230, 65, 101, 201, 162, 27, 125]
+    }
+
+    fn read(&self, account: &OracleAccount) -> Result<OracleReading> {
+        let feed = bytemuck::try_from_bytes::<AggregatorAccountData>(&account.data[8..])
+            .map_err(from_foreign_error)?;
+        let feed_result = feed.get_result().map_err(from_foreign_error)?;
+        let price_decimal: f64 = feed_result.try_into().map_err(from_foreign_error)?;
+        let std_deviation_decimal: f64 = feed
+            .latest_confirmed_round
+            .std_deviation
+            .try_into()
+            .map_err(from_foreign_error)?;
+        Ok(OracleReading {
+            price: I80F48::from_num(price_decimal),
+            exponent: Some(0),
+            deviation: Some(I80F48::from_num(std_deviation_decimal)),
+            // The round_open_slot is an overestimate of the oracle staleness: Reporters will see
+            // the round opening and only then start executing the price tasks.
+            last_update_slot: Some(feed.latest_confirmed_round.round_open_slot),
+        })
+    }
+}
+
+pub struct SwitchboardV1Oracle;
+
+impl OracleAdapter for SwitchboardV1Oracle {
+    fn oracle_type(&self) -> OracleType {
+        OracleType::SwitchboardV1
+    }
+
+    fn name(&self) -> &'static str {
+        "Switchboard v1"
     }
-    // note: this is the only known way of checking this
-    else if acc_info.owner() == &switchboard_v1_devnet_oracle::ID
-        || acc_info.owner() == &switchboard_v2_mainnet_oracle::ID
-    {
-        return Ok(OracleType::SwitchboardV1);
+
+    fn matches(&self, account: &OracleAccount) -> bool {
+        // note: this is the only known way of checking this
+        account.owner == &switchboard_v1_devnet_oracle::ID
+            || account.owner == &switchboard_v2_mainnet_oracle::ID
+    }
+
+    fn read(&self, account: &OracleAccount) -> Result<OracleReading> {
+        let result =
+            FastRoundResultAccountData::deserialize(account.data).map_err(from_foreign_error)?;
+        let min_response = I80F48::from_num(result.result.min_response);
+        let max_response = I80F48::from_num(result.result.max_response);
+        Ok(OracleReading {
+            price: I80F48::from_num(result.result.result),
+            exponent: Some(0),
+            // The min-max response gap serves as the confidence interval
+            deviation: Some(max_response - min_response),
+            last_update_slot: Some(result.result.round_open_slot),
+        })
     }
+}
 
-    Err(MangoError::UnknownOracleType.into())
+pub fn oracle_adapter(acc_info: &impl KeyedAccountReader) -> Result<&'static dyn OracleAdapter> {
+    let account = OracleAccount::new(acc_info);
+    ORACLE_ADAPTERS
+        .iter()
+        .find(|adapter| adapter.matches(&account))
+        .copied()
+        .ok_or_else(|| MangoError::UnknownOracleType.into())
+}
+
+pub fn determine_oracle_type(acc_info: &impl KeyedAccountReader) -> Result<OracleType> {
+    Ok(oracle_adapter(acc_info)?.oracle_type())
 }
 
 /// Returns the price of one native base token, in native quote tokens
@@ -141,137 +340,102 @@ pub fn oracle_price_and_slot(
     base_decimals: u8,
     staleness_slot: Option<u64>,
 ) -> Result<(I80F48, u64)> {
-    let data = &acc_info.data();
-    let oracle_type = determine_oracle_type(acc_info)?;
+    let adapter = oracle_adapter(acc_info)?;
+    let account = OracleAccount::new(acc_info);
+    let reading = adapter.read(&account)?;
     let staleness_slot = staleness_slot.unwrap_or(0);
-
-    Ok(match oracle_type {
-        OracleType::Stub => (acc_info.load::<StubOracle>()?.price, 0),
-        OracleType::Pyth => {
-            let price_account = pyth_sdk_solana::state::load_price_account(data).unwrap();
-            let price_data = price_account.to_price();
-            let price = I80F48::from_num(price_data.price);
-
-            // Filter out bad prices
-            if I80F48::from_num(price_data.conf) > (config.conf_filter * price) {
-                msg!(
-                    "Pyth conf interval too high; pubkey {} price: {} price_data.conf: {}",
-                    acc_info.key(),
-                    price.to_num::<f64>(),
-                    price_data.conf
-                );
-
-                // future: in v3, we had pricecache, and in case of luna, when there were no updates, we used last known value from cache
-                // we'll have to add a CachedOracle that is based on one of the oracle types, needs a separate keeper and supports
-                // maintaining this "last known good value"
-                return Err(MangoError::OracleConfidence.into());
-            }
-
-            // The last_slot is when the price was actually updated
-            let last_slot = price_account.last_slot;
-            if config.max_staleness_slots >= 0
-                && price_account
-                    .last_slot
-                    .saturating_add(config.max_staleness_slots as u64)
-                    < staleness_slot
-            {
-                msg!(
-                    "Pyth price too stale; pubkey {} price: {} last slot: {}",
-                    acc_info.key(),
-                    price.to_num::<f64>(),
-                    last_slot,
-                );
-
-                return Err(MangoError::OracleStale.into());
-            }
-
-            let decimals = (price_account.expo as i8) + QUOTE_DECIMALS - (base_decimals as i8);
-            let decimal_adj = power_of_ten(decimals);
-            (price * decimal_adj, last_slot)
+    let price = reading.price;
+
+    // Filter out bad prices
+    if let Some(deviation) = reading.deviation {
+        if deviation > config.conf_filter * price {
+            msg!(
+                "{} confidence interval too wide; pubkey {} price: {} deviation: {}",
+                adapter.name(),
+                account.key,
+                price.to_num::<f64>(),
+                deviation.to_num::<f64>(),
+            );
+
+            // future: in v3, we had pricecache, and in case of luna, when there were no updates, we used last known value from cache
+            // we'll have to add a CachedOracle that is based on one of the oracle types, needs a separate keeper and supports
+            // maintaining this "last known good value"
+            return Err(MangoError::OracleConfidence.into());
         }
-        OracleType::SwitchboardV2 => {
-            fn from_foreign_error(e: impl std::fmt::Display) -> Error {
-                error_msg!("{}", e)
-            }
-
-            let feed = bytemuck::from_bytes::<AggregatorAccountData>(&data[8..]);
-            let feed_result = feed.get_result().map_err(from_foreign_error)?;
-            let price_decimal: f64 = feed_result.try_into().map_err(from_foreign_error)?;
-            let price = I80F48::from_num(price_decimal);
-
-            // Filter out bad prices
-            let std_deviation_decimal: f64 = feed
-                .latest_confirmed_round
-                .std_deviation
-                .try_into()
-                .map_err(from_foreign_error)?;
-            if I80F48::from_num(std_deviation_decimal) > (config.conf_filter * price) {
-                msg!(
-                    "Switchboard v2 std deviation too high; pubkey {} price: {} latest_confirmed_round.std_deviation: {}",
-                    acc_info.key(),
-                    price.to_num::<f64>(),
-                    std_deviation_decimal
-                );
-                return Err(MangoError::OracleConfidence.into());
-            }
+    }
 
-            // The round_open_slot is an overestimate of the oracle staleness: Reporters will see
-            // the round opening and only then start executing the price tasks.
-            let round_open_slot = feed.latest_confirmed_round.round_open_slot;
-            if config.max_staleness_slots >= 0
-                && round_open_slot.saturating_add(config.max_staleness_slots as u64)
-                    < staleness_slot
-            {
-                msg!(
-                    "Switchboard v2 price too stale; pubkey {} price: {} latest_confirmed_round.round_open_slot: {}",
-                    acc_info.key(),
-                    price.to_num::<f64>(),
-                    round_open_slot,
-                );
-                return Err(MangoError::OracleConfidence.into());
-            }
-
-            let decimals = QUOTE_DECIMALS - (base_decimals as i8);
-            let decimal_adj = power_of_ten(decimals);
-            (price * decimal_adj, round_open_slot)
+    if let Some(last_update_slot) = reading.last_update_slot {
+        if config.max_staleness_slots >= 0
+            && last_update_slot.saturating_add(config.max_staleness_slots as u64) < staleness_slot
+        {
+            msg!(
+                "{} price too stale; pubkey {} price: {} last update slot: {}",
+                adapter.name(),
+                account.key,
+                price.to_num::<f64>(),
+                last_update_slot,
+            );
+            return Err(MangoError::OracleStale.into());
         }
-        OracleType::SwitchboardV1 => {
-            let result = FastRoundResultAccountData::deserialize(data).unwrap();
-            let price = I80F48::from_num(result.result.result);
-
-            // Filter out bad prices
-            let min_response = I80F48::from_num(result.result.min_response);
-            let max_response = I80F48::from_num(result.result.max_response);
-            if (max_response - min_response) > (config.conf_filter * price) {
-                msg!(
-                    "Switchboard v1 min-max response gap too wide; pubkey {} price: {} min_response: {} max_response {}",
-                    acc_info.key(),
-                    price.to_num::<f64>(),
-                    min_response,
-                    max_response
-                );
-                return Err(MangoError::OracleConfidence.into());
-            }
-
-            let round_open_slot = result.result.round_open_slot;
-            if config.max_staleness_slots >= 0
-                && round_open_slot.saturating_add(config.max_staleness_slots as u64)
-                    < staleness_slot
-            {
-                msg!(
-                    "Switchboard v1 price too stale; pubkey {} price: {} round_open_slot: {}",
-                    acc_info.key(),
-                    price.to_num::<f64>(),
-                    round_open_slot,
-                );
-                return Err(MangoError::OracleConfidence.into());
-            }
-
-            let decimals = QUOTE_DECIMALS - (base_decimals as i8);
-            let decimal_adj = power_of_ten(decimals);
-            (price * decimal_adj, round_open_slot)
+    }
+
+    let native_price = match reading.exponent {
+        Some(exponent) => {
+            let decimals = exponent + QUOTE_DECIMALS - (base_decimals as i8);
+            price * power_of_ten(decimals)
         }
-    })
+        None => price,
+    };
+    Ok((native_price, reading.last_update_slot.unwrap_or(0)))
+}
+
+/// Like oracle_price_and_slot(), but uses `fallback_acc` if the primary oracle is
+/// stale or not confident enough.
+///
+/// The fallback is only used if `fallback_key` is set and `fallback_acc` is that
+/// account. Otherwise the primary oracle's error is returned.
+pub fn oracle_price_and_slot_with_fallback<T: KeyedAccountReader>(
+    oracle_acc: &T,
+    fallback_key: &Pubkey,
+    fallback_acc: Option<&T>,
+    config: &OracleConfig,
+    base_decimals: u8,
+    staleness_slot: Option<u64>,
+) -> Result<(I80F48, u64)> {
+    let primary = oracle_price_and_slot(oracle_acc, config, base_decimals, staleness_slot);
+    let primary_rejected = primary.is_anchor_error_with_code(MangoError::OracleStale.error_code())
+        || primary.is_anchor_error_with_code(MangoError::OracleConfidence.error_code());
+    if !primary_rejected || *fallback_key == Pubkey::default() {
+        return primary;
+    }
+    let fallback_acc = match fallback_acc {
+        Some(acc) if acc.key() == fallback_key => acc,
+        _ => return primary,
+    };
+
+    msg!(
+        "oracle {} rejected, using fallback oracle {}",
+        oracle_acc.key(),
+        fallback_key
+    );
+    oracle_price_and_slot(fallback_acc, config, base_decimals, staleness_slot)
+}
+
+/// Finds the account of the fallback oracle `key` in `ais`, usually the remaining
+/// accounts of an instruction.
+///
+/// Returns None if no fallback oracle is configured or it was not passed.
+pub fn fallback_oracle_account<'a, 'info>(
+    ais: &'a [AccountInfo<'info>],
+    key: &Pubkey,
+) -> Result<Option<AccountInfoRef<'a, 'info>>> {
+    if *key == Pubkey::default() {
+        return Ok(None);
+    }
+    ais.iter()
+        .find(|ai| ai.key == key)
+        .map(AccountInfoRef::borrow)
+        .transpose()
 }
 
 #[cfg(test)]
@@ -319,6 +483,78 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    pub fn test_oracle_fallback() -> Result<()> {
+        let pyth_key = Pubkey::from_str("J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix").unwrap();
+        let mut pyth_data =
+            read_file(find_file(&format!("resources/test/{}.bin", pyth_key)).unwrap());
+        let pyth_owner = Pubkey::default();
+
+        let stub_key = Pubkey::new_unique();
+        let stub_owner = crate::id();
+        let mut stub_data = StubOracle::discriminator().to_vec();
+        stub_data.extend_from_slice(bytemuck::bytes_of(&StubOracle {
+            group: Pubkey::default(),
+            mint: Pubkey::default(),
+            price: I80F48::from_num(2.5),
+            last_updated: 0,
+            reserved: [0; 128],
+        }));
+
+        let pyth_cell = RefCell::new(&mut pyth_data[..]);
+        let pyth = AccountInfoRef {
+            key: &pyth_key,
+            owner: &pyth_owner,
+            data: pyth_cell.borrow(),
+        };
+        let stub_cell = RefCell::new(&mut stub_data[..]);
+        let stub = AccountInfoRef {
+            key: &stub_key,
+            owner: &stub_owner,
+            data: stub_cell.borrow(),
+        };
+
+        // a negative conf_filter rejects every pyth price
+        let config = OracleConfig {
+            conf_filter: I80F48::from_num(-1),
+            max_staleness_slots: -1,
+            reserved: [0; 72],
+        };
+        assert!(oracle_price_and_slot(&pyth, &config, 6, None)
+            .is_anchor_error_with_code(MangoError::OracleConfidence.error_code()));
+
+        // no fallback configured or passed: primary error
+        let no_fallback = oracle_price_and_slot_with_fallback(
+            &pyth,
+            &Pubkey::default(),
+            Some(&stub),
+            &config,
+            6,
+            None,
+        );
+        assert!(no_fallback.is_anchor_error_with_code(MangoError::OracleConfidence.error_code()));
+        let not_passed =
+            oracle_price_and_slot_with_fallback(&pyth, &stub_key, None, &config, 6, None);
+        assert!(not_passed.is_anchor_error_with_code(MangoError::OracleConfidence.error_code()));
+
+        // wrong account passed as fallback
+        let wrong = oracle_price_and_slot_with_fallback(
+            &pyth,
+            &Pubkey::new_unique(),
+            Some(&stub),
+            &config,
+            6,
+            None,
+        );
+        assert!(wrong.is_anchor_error_with_code(MangoError::OracleConfidence.error_code()));
+
+        let (price, _) =
+            oracle_price_and_slot_with_fallback(&pyth, &stub_key, Some(&stub), &config, 6, None)?;
+        assert_eq!(price, I80F48::from_num(2.5));
+
+        Ok(())
+    }
+
     #[test]
     pub fn lookup_test() {
         for idx in -12..0 {
@@ -55,6 +55,7 @@ impl<'a> Orderbook<'a> {
         let side = order.side;
         let other_side = side.invert_side();
         let market = perp_market;
+        require!(!market.is_expired(now_ts), MangoError::PerpMarketExpired);
         let oracle_price_lots = market.native_price_to_lot(oracle_price);
         let post_only = order.is_post_only();
         let mut post_target = order.post_target();
@@ -63,7 +64,7 @@ impl<'a> Orderbook<'a> {
         // generate new order id
         let order_id = market.gen_order_id(side, price_data);
 
-        // IOC orders have a fee penalty applied regardless of match
+        // IOC and FOK orders have a fee penalty applied regardless of match
         if order.needs_penalty_fee() {
             apply_penalty(market, mango_account)?;
         }
@@ -119,6 +120,34 @@ impl<'a> Orderbook<'a> {
                 break;
             }
 
+            if best_opposing.node.owner == *mango_account_pk {
+                match order.self_trade_behavior {
+                    SelfTradeBehavior::DecrementTake => {}
+                    SelfTradeBehavior::CancelProvide => {
+                        let event = OutEvent::new(
+                            other_side,
+                            best_opposing.node.owner_slot,
+                            now_ts,
+                            event_queue.header.seq_num,
+                            best_opposing.node.owner,
+                            best_opposing.node.quantity,
+                        );
+                        event_queue.push_back(cast(event)).unwrap();
+                        matched_order_deletes
+                            .push((best_opposing.handle.order_tree, best_opposing.node.key));
+                        limit -= 1;
+                        continue;
+                    }
+                    SelfTradeBehavior::AbortTransaction => {
+                        return Err(error_msg_typed!(
+                            MangoError::WouldSelfTrade,
+                            "order would match against own order {}",
+                            best_opposing.node.key
+                        ));
+                    }
+                }
+            }
+
             let max_match_by_quote = remaining_quote_lots / best_opposing_price;
             let match_base_lots = remaining_base_lots
                 .min(best_opposing.node.quantity)
@@ -164,6 +193,15 @@ impl<'a> Orderbook<'a> {
                 seq_num: seq_num,
             });
         }
+        if order.is_fill_or_kill() && remaining_base_lots > 0 {
+            return Err(error_msg_typed!(
+                MangoError::FillOrKillNotFilled,
+                "only {} of {} base lots could be matched",
+                order.max_base_lots - remaining_base_lots,
+                order.max_base_lots
+            ));
+        }
+
         let total_quote_lots_taken = order.max_quote_lots - remaining_quote_lots;
         let total_base_lots_taken = order.max_base_lots - remaining_base_lots;
         assert!(total_quote_lots_taken >= 0);
@@ -21,6 +21,7 @@ mod queue;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::*;
     use crate::state::{MangoAccount, MangoAccountValue, PerpMarket, FREE_ORDER_SLOT};
     use anchor_lang::prelude::*;
     use bytemuck::Zeroable;
@@ -125,6 +126,7 @@ mod tests {
                     max_quote_lots: i64::MAX,
                     client_order_id: 0,
                     time_in_force,
+                    self_trade_behavior: SelfTradeBehavior::DecrementTake,
                     reduce_only: false,
                     params: OrderParams::Fixed {
                         price_lots,
@@ -273,6 +275,7 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 42,
                 time_in_force: 0,
+                self_trade_behavior: SelfTradeBehavior::DecrementTake,
                 reduce_only: false,
                 params: OrderParams::Fixed {
                     price_lots,
@@ -334,6 +337,7 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 43,
                 time_in_force: 0,
+                self_trade_behavior: SelfTradeBehavior::DecrementTake,
                 reduce_only: false,
                 params: OrderParams::Fixed {
                     price_lots,
@@ -453,6 +457,7 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 43,
                 time_in_force: 0,
+                self_trade_behavior: SelfTradeBehavior::DecrementTake,
                 reduce_only: false,
                 params: OrderParams::Fixed {
                     price_lots: 1000,
@@ -477,6 +482,7 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 43,
                 time_in_force: 0,
+                self_trade_behavior: SelfTradeBehavior::DecrementTake,
                 reduce_only: false,
                 params: OrderParams::Fixed {
                     price_lots: 1000,
@@ -515,6 +521,7 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 43,
                 time_in_force: 0,
+                self_trade_behavior: SelfTradeBehavior::DecrementTake,
                 reduce_only: false,
                 params: OrderParams::ImmediateOrCancel { price_lots: 1000 },
             },
@@ -544,4 +551,312 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_fee_penalty_with_distinct_maker_and_taker() -> Result<()> {
+        let (mut market, oracle_price, mut event_queue, book_accs) = test_setup(1000.0);
+        let mut book = book_accs.orderbook();
+
+        let buffer = MangoAccount::default_for_tests().try_to_vec().unwrap();
+        let mut maker = MangoAccountValue::from_bytes(&buffer).unwrap();
+        let mut taker = MangoAccountValue::from_bytes(&buffer).unwrap();
+        let maker_pk = Pubkey::new_unique();
+        let taker_pk = Pubkey::new_unique();
+        let now_ts = 1000000;
+
+        market.maker_fee = I80F48::from_num(0.001);
+        market.taker_fee = I80F48::from_num(0.01);
+        market.fee_penalty = 5.0;
+        maker.ensure_perp_position(market.perp_market_index, 0)?;
+        taker.ensure_perp_position(market.perp_market_index, 0)?;
+
+        let new_order = |book: &mut Orderbook,
+                         event_queue: &mut EventQueue,
+                         market: &mut PerpMarket,
+                         account: &mut MangoAccountValue,
+                         account_pk: &Pubkey,
+                         side: Side,
+                         params: OrderParams| {
+            book.new_order(
+                Order {
+                    side,
+                    max_base_lots: if side == Side::Ask { 2 } else { 1 },
+                    max_quote_lots: i64::MAX,
+                    client_order_id: 43,
+                    time_in_force: 0,
+                    self_trade_behavior: SelfTradeBehavior::DecrementTake,
+                    reduce_only: false,
+                    params,
+                },
+                market,
+                event_queue,
+                oracle_price,
+                &mut account.borrow_mut(),
+                account_pk,
+                now_ts,
+                u8::MAX,
+            )
+            .unwrap();
+        };
+        let limit = || OrderParams::Fixed {
+            price_lots: 1000,
+            order_type: PostOrderType::Limit,
+        };
+
+        // Passive order
+        new_order(
+            &mut book,
+            &mut event_queue,
+            &mut market,
+            &mut maker,
+            &maker_pk,
+            Side::Ask,
+            limit(),
+        );
+
+        // Partial taker
+        new_order(
+            &mut book,
+            &mut event_queue,
+            &mut market,
+            &mut taker,
+            &taker_pk,
+            Side::Bid,
+            limit(),
+        );
+        assert_eq!(
+            taker
+                .perp_position(market.perp_market_index)?
+                .quote_position_native()
+                .round(),
+            I80F48::from_num(-10),
+            "Regular taker fees applied on limit order"
+        );
+        assert_eq!(
+            market.fees_accrued.round(),
+            I80F48::from_num(11), // 10 + 1
+            "Maker and taker fees moved to market"
+        );
+
+        // Full taker
+        new_order(
+            &mut book,
+            &mut event_queue,
+            &mut market,
+            &mut taker,
+            &taker_pk,
+            Side::Bid,
+            OrderParams::ImmediateOrCancel { price_lots: 1000 },
+        );
+        assert_eq!(
+            taker
+                .perp_position(market.perp_market_index)?
+                .quote_position_native()
+                .round(),
+            I80F48::from_num(-25), // -10 - 10 - 5
+            "Regular fees + fixed penalty applied on IOC order"
+        );
+        assert_eq!(
+            market.fees_accrued.round(),
+            I80F48::from_num(27), // 11 + 11 + 5
+            "Fees moved to market"
+        );
+
+        // The maker pays its fees when the fills are processed
+        assert_eq!(event_queue.len(), 2);
+        while let Ok(event) = event_queue.pop_front() {
+            let fill: &FillEvent = bytemuck::cast_ref(&event);
+            assert_eq!(fill.maker, maker_pk);
+            assert_eq!(fill.taker, taker_pk);
+            assert_eq!(fill.maker_fee, market.maker_fee.to_num::<f32>());
+            assert_eq!(fill.taker_fee, market.taker_fee.to_num::<f32>());
+            maker.execute_perp_maker(market.perp_market_index, &mut market, fill)?;
+        }
+        assert_eq!(
+            maker
+                .perp_position(market.perp_market_index)?
+                .quote_position_native()
+                .round(),
+            I80F48::from_num(2000 - 2),
+            "Maker fees applied on fill"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn book_self_trade_behavior() {
+        let run = |self_trade_behavior: SelfTradeBehavior| {
+            let (mut market, oracle_price, mut event_queue, book_accs) = test_setup(1000.0);
+            let mut book = book_accs.orderbook();
+            market.maker_fee = I80F48::from_num(0.001);
+            market.taker_fee = I80F48::from_num(0.01);
+
+            let buffer = MangoAccount::default_for_tests().try_to_vec().unwrap();
+            let mut account = MangoAccountValue::from_bytes(&buffer).unwrap();
+            account
+                .ensure_perp_position(market.perp_market_index, 0)
+                .unwrap();
+            let account_pk = Pubkey::new_unique();
+            let now_ts = 1000000;
+
+            let mut new_order = |book: &mut Orderbook,
+                                 event_queue: &mut EventQueue,
+                                 market: &mut PerpMarket,
+                                 side: Side,
+                                 client_order_id: u64| {
+                book.new_order(
+                    Order {
+                        side,
+                        max_base_lots: 2,
+                        max_quote_lots: i64::MAX,
+                        client_order_id,
+                        time_in_force: 0,
+                        self_trade_behavior,
+                        reduce_only: false,
+                        params: OrderParams::Fixed {
+                            price_lots: 1000,
+                            order_type: PostOrderType::Limit,
+                        },
+                    },
+                    market,
+                    event_queue,
+                    oracle_price,
+                    &mut account.borrow_mut(),
+                    &account_pk,
+                    now_ts,
+                    u8::MAX,
+                )
+            };
+
+            new_order(&mut book, &mut event_queue, &mut market, Side::Ask, 1).unwrap();
+            let result = new_order(&mut book, &mut event_queue, &mut market, Side::Bid, 2);
+            drop(book);
+            (result, market, event_queue, book_accs)
+        };
+
+        // matches against the own order like against any other, fees included
+        let (result, market, event_queue, book_accs) = run(SelfTradeBehavior::DecrementTake);
+        assert_eq!(result.unwrap(), None);
+        let quote_native = I80F48::from_num(2 * 1000);
+        assert_eq!(
+            market.fees_accrued,
+            quote_native * market.taker_fee + quote_native * market.maker_fee
+        );
+        assert_eq!(book_accs.asks.borrow().roots[0].leaf_count, 0);
+        assert_eq!(book_accs.bids.borrow().roots[0].leaf_count, 0);
+        assert_eq!(event_queue.len(), 1);
+        let event = event_queue.peek_front().unwrap();
+        assert_eq!(event.event_type, EventType::Fill as u8);
+        let fill: &FillEvent = bytemuck::cast_ref(event);
+        assert_eq!(fill.maker, fill.taker);
+        assert_eq!(fill.quantity, 2);
+        assert_eq!(fill.maker_fee, market.maker_fee.to_num::<f32>());
+        assert_eq!(fill.taker_fee, market.taker_fee.to_num::<f32>());
+
+        // the own ask is canceled and the bid is posted instead
+        let (result, market, event_queue, book_accs) = run(SelfTradeBehavior::CancelProvide);
+        assert!(result.unwrap().is_some());
+        assert_eq!(market.fees_accrued, 0);
+        assert_eq!(book_accs.asks.borrow().roots[0].leaf_count, 0);
+        assert_eq!(book_accs.bids.borrow().roots[0].leaf_count, 1);
+        assert_eq!(event_queue.len(), 1);
+        let event = event_queue.peek_front().unwrap();
+        assert_eq!(event.event_type, EventType::Out as u8);
+
+        let (result, _, _, _) = run(SelfTradeBehavior::AbortTransaction);
+        assert!(result.is_anchor_error_with_code(MangoError::WouldSelfTrade.into()));
+    }
+
+    #[test]
+    fn book_fill_or_kill() {
+        let run = |price_lots: i64, max_base_lots: i64, limit: u8| {
+            let (mut market, oracle_price, mut event_queue, book_accs) = test_setup(1000.0);
+            let mut book = book_accs.orderbook();
+            market.fee_penalty = 5.0;
+
+            let buffer = MangoAccount::default_for_tests().try_to_vec().unwrap();
+            let mut maker = MangoAccountValue::from_bytes(&buffer).unwrap();
+            maker
+                .ensure_perp_position(market.perp_market_index, 0)
+                .unwrap();
+            let maker_pk = Pubkey::new_unique();
+            let mut taker = MangoAccountValue::from_bytes(&buffer).unwrap();
+            taker
+                .ensure_perp_position(market.perp_market_index, 0)
+                .unwrap();
+            let taker_pk = Pubkey::new_unique();
+            let now_ts = 1000000;
+
+            // two asks of 2 lots each, at 1000 and 1010
+            for (client_order_id, ask_price_lots) in [(1, 1000), (2, 1010)] {
+                book.new_order(
+                    Order {
+                        side: Side::Ask,
+                        max_base_lots: 2,
+                        max_quote_lots: i64::MAX,
+                        client_order_id,
+                        time_in_force: 0,
+                        self_trade_behavior: SelfTradeBehavior::DecrementTake,
+                        reduce_only: false,
+                        params: OrderParams::Fixed {
+                            price_lots: ask_price_lots,
+                            order_type: PostOrderType::Limit,
+                        },
+                    },
+                    &mut market,
+                    &mut event_queue,
+                    oracle_price,
+                    &mut maker.borrow_mut(),
+                    &maker_pk,
+                    now_ts,
+                    u8::MAX,
+                )
+                .unwrap();
+            }
+
+            let result = book.new_order(
+                Order {
+                    side: Side::Bid,
+                    max_base_lots,
+                    max_quote_lots: i64::MAX,
+                    client_order_id: 3,
+                    time_in_force: 0,
+                    self_trade_behavior: SelfTradeBehavior::DecrementTake,
+                    reduce_only: false,
+                    params: OrderParams::FillOrKill { price_lots },
+                },
+                &mut market,
+                &mut event_queue,
+                oracle_price,
+                &mut taker.borrow_mut(),
+                &taker_pk,
+                now_ts,
+                limit,
+            );
+            let taker_base_lots = taker
+                .perp_position(market.perp_market_index)
+                .unwrap()
+                .taker_base_lots;
+            (result, taker_base_lots, event_queue, market.fees_accrued)
+        };
+
+        // not enough liquidity within the price limit
+        let (result, _, _, _) = run(1000, 3, u8::MAX);
+        assert!(result.is_anchor_error_with_code(MangoError::FillOrKillNotFilled.into()));
+
+        // matching limit reached before the order is filled
+        let (result, _, _, _) = run(1010, 3, 1);
+        assert!(result.is_anchor_error_with_code(MangoError::FillOrKillNotFilled.into()));
+
+        // filled completely, nothing is posted, the penalty is paid like for IOC orders
+        let (result, taker_base_lots, event_queue, fees_accrued) = run(1010, 3, u8::MAX);
+        assert_eq!(result.unwrap(), None);
+        assert_eq!(taker_base_lots, 3);
+        assert_eq!(fees_accrued, I80F48::from_num(5));
+        assert_eq!(event_queue.len(), 2);
+        assert!(event_queue
+            .iter()
+            .all(|event| event.event_type == EventType::Fill as u8));
+    }
 }
@@ -21,6 +21,9 @@ pub struct Order {
     /// Number of seconds the order shall live, 0 meaning forever
     pub time_in_force: u16,
 
+    /// What to do when the order would match against an order of the same account
+    pub self_trade_behavior: SelfTradeBehavior,
+
     /// Order type specific params
     pub params: OrderParams,
 }
@@ -30,6 +33,9 @@ pub enum OrderParams {
     ImmediateOrCancel {
         price_lots: i64,
     },
+    FillOrKill {
+        price_lots: i64,
+    },
     Fixed {
         price_lots: i64,
         order_type: PostOrderType,
@@ -62,10 +68,18 @@ impl Order {
 
     /// Should this order be penalized with an extra fee?
     ///
-    /// Some programs opportunistically call ioc orders, wasting lots of compute. This
+    /// Some programs opportunistically call ioc and fok orders, wasting lots of compute. This
     /// is intended to encourage people to be smarter about it.
     pub fn needs_penalty_fee(&self) -> bool {
-        matches!(self.params, OrderParams::ImmediateOrCancel { .. })
+        matches!(
+            self.params,
+            OrderParams::ImmediateOrCancel { .. } | OrderParams::FillOrKill { .. }
+        )
+    }
+
+    /// Must this order match its full max_base_lots? It will fail otherwise.
+    pub fn is_fill_or_kill(&self) -> bool {
+        matches!(self.params, OrderParams::FillOrKill { .. })
     }
 
     /// Is this order required to be posted to the orderbook? It will fail if it would take.
@@ -122,6 +136,7 @@ impl Order {
         let price_lots = match self.params {
             OrderParams::Market => market_order_limit_for_side(self.side),
             OrderParams::ImmediateOrCancel { price_lots } => price_lots,
+            OrderParams::FillOrKill { price_lots } => price_lots,
             OrderParams::Fixed {
                 price_lots,
                 order_type,
@@ -39,6 +39,11 @@ pub enum PlaceOrderType {
     /// If existing orders match with this order, adjust the price to just barely
     /// not match. Always places an order on the book.
     PostOnlySlide = 4,
+
+    /// Take existing orders up to price, max_base_quantity and max_quote_quantity.
+    /// Fails unless max_base_quantity is matched completely. Never place an order
+    /// on the book.
+    FillOrKill = 5,
 }
 
 impl PlaceOrderType {
@@ -46,6 +51,7 @@ impl PlaceOrderType {
         match *self {
             Self::Market => Err(error_msg!("Market is not a PostOrderType")),
             Self::ImmediateOrCancel => Err(error_msg!("ImmediateOrCancel is not a PostOrderType")),
+            Self::FillOrKill => Err(error_msg!("FillOrKill is not a PostOrderType")),
             Self::Limit => Ok(PostOrderType::Limit),
             Self::PostOnly => Ok(PostOrderType::PostOnly),
             Self::PostOnlySlide => Ok(PostOrderType::PostOnlySlide),
@@ -79,6 +85,16 @@ pub enum PostOrderType {
     PostOnlySlide = 4,
 }
 
+/// What happens when a taker order would match against a resting order
+/// of the same mango account. The options are the same as for serum3 orders:
+/// - DecrementTake: both the taker and the resting order are decremented by the
+///   matched quantity, as with a regular fill, and maker and taker fees are charged.
+///   This is what PerpPlaceOrder and PerpPlaceOrderPegged always do.
+/// - CancelProvide: the resting order is canceled and matching continues with the
+///   next order.
+/// - AbortTransaction: the instruction fails.
+pub use crate::accounts_ix::Serum3SelfTradeBehavior as SelfTradeBehavior;
+
 #[derive(
     Eq,
     PartialEq,
@@ -2,12 +2,13 @@ use std::mem::size_of;
 
 use anchor_lang::prelude::*;
 use fixed::types::I80F48;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
 
 use static_assertions::const_assert_eq;
 
 use crate::accounts_zerocopy::KeyedAccountReader;
 use crate::error::MangoError;
-use crate::logs::PerpUpdateFundingLog;
+use crate::logs::{PerpFundingPremiumIntervalLog, PerpUpdateFundingLog};
 use crate::state::orderbook::Side;
 use crate::state::{oracle, TokenIndex};
 
@@ -15,6 +16,34 @@ use super::{orderbook, OracleConfig, Orderbook, StablePriceModel, DAY_I80F48};
 
 pub type PerpMarketIndex = u16;
 
+/// How the funding rate of a perp market is computed.
+///
+/// In all cases the resulting rate is clamped to min_funding and max_funding.
+#[derive(
+    Eq,
+    PartialEq,
+    Copy,
+    Clone,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Debug,
+    AnchorSerialize,
+    AnchorDeserialize,
+)]
+#[repr(u8)]
+pub enum PerpFundingModel {
+    /// Premium of the mid of the book's impact prices over the oracle price,
+    /// taken at the time of the funding update.
+    ImpactPrice = 0,
+
+    /// Time-weighted average of the impact price premium, sampled over the
+    /// previous and the current funding interval.
+    PremiumTwap = 1,
+
+    /// Premium of the stable price over the oracle price.
+    StablePrice = 2,
+}
+
 #[account(zero_copy(safe_bytemuck_derives))]
 #[derive(Debug)]
 pub struct PerpMarket {
@@ -167,7 +196,58 @@ pub struct PerpMarket {
 
     pub positive_pnl_liquidation_fee: I80F48,
 
-    pub reserved: [u8; 1888],
+    /// Oracle to use when the primary oracle is stale or not confident enough,
+    /// Pubkey::default() if there is none
+    pub fallback_oracle: Pubkey,
+
+    /// Isolated insurance fund that covers bankruptcies in this market.
+    ///
+    /// 0 means the group insurance fund is used, if group_insurance_fund is set.
+    pub insurance_fund_index: u16,
+    pub padding5: [u8; 6],
+
+    // Dated futures
+    /// Timestamp at which the market expires, 0 for perpetual markets.
+    ///
+    /// Dated markets pay no funding. Once expired, no orders can be placed and
+    /// positions are closed at settlement_price with perp_close_expired_position.
+    pub expiry_timestamp: u64,
+    /// Length of the window before expiry_timestamp in which prices are sampled
+    /// for the settlement price.
+    pub settlement_window_ts: u64,
+    /// Sum of sampled prices, weighted by the seconds they were valid for.
+    pub settlement_price_sum: I80F48,
+    /// Final price at which positions are closed, valid if has_settlement_price is set.
+    pub settlement_price: I80F48,
+    /// Price observed at funding_last_updated, it's valid until the next update.
+    pub settlement_last_price: I80F48,
+    /// Number of seconds covered by settlement_price_sum.
+    pub settlement_price_weight_ts: u64,
+    /// If set, the stable price is sampled for the settlement price, otherwise the oracle price.
+    pub settlement_uses_stable_price: u8,
+    pub has_settlement_price: u8,
+    pub padding6: [u8; 6],
+
+    // Funding models
+    /// Length of a funding interval for the PremiumTwap funding model.
+    pub funding_interval_ts: u64,
+    /// Start of the current funding interval.
+    pub funding_interval_start_ts: u64,
+    /// Sum of book premiums in the current funding interval, weighted by seconds.
+    pub funding_premium_sum: I80F48,
+    /// Sum of book premiums in the previous funding interval, weighted by seconds.
+    pub prev_funding_premium_sum: I80F48,
+    /// Book premium observed at funding_last_updated, it's valid until the next update.
+    pub funding_last_premium: I80F48,
+    /// Number of seconds covered by funding_premium_sum.
+    pub funding_premium_weight_ts: u64,
+    /// Number of seconds covered by prev_funding_premium_sum.
+    pub prev_funding_premium_weight_ts: u64,
+    /// See PerpFundingModel.
+    pub funding_model: u8,
+    pub padding7: [u8; 7],
+
+    pub reserved: [u8; 1680],
 }
 
 const_assert_eq!(
@@ -203,7 +283,21 @@ const_assert_eq!(
         + 1
         + 7
         + 3 * 16
-        + 1888
+        + 32
+        + 2
+        + 6
+        + 8 * 2
+        + 16 * 3
+        + 8
+        + 1
+        + 1
+        + 6
+        + 8 * 2
+        + 16 * 3
+        + 8 * 2
+        + 1
+        + 7
+        + 1680
 );
 const_assert_eq!(size_of::<PerpMarket>(), 2808);
 const_assert_eq!(size_of::<PerpMarket>() % 8, 0);
@@ -223,6 +317,15 @@ impl PerpMarket {
         self.force_close == 1
     }
 
+    /// Is this a dated futures market, instead of a perpetual one?
+    pub fn is_dated(&self) -> bool {
+        self.expiry_timestamp != 0
+    }
+
+    pub fn is_expired(&self, now_ts: u64) -> bool {
+        self.is_dated() && now_ts >= self.expiry_timestamp
+    }
+
     pub fn elligible_for_group_insurance_fund(&self) -> bool {
         self.group_insurance_fund == 1
     }
@@ -231,6 +334,19 @@ impl PerpMarket {
         self.group_insurance_fund = u8::from(v);
     }
 
+    /// Index of the insurance fund that covers bankruptcies in this market, if any.
+    ///
+    /// Index 0 is the group insurance fund.
+    pub fn covering_insurance_fund(&self) -> Option<u16> {
+        if self.insurance_fund_index != 0 {
+            Some(self.insurance_fund_index)
+        } else if self.elligible_for_group_insurance_fund() {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
     pub fn settle_pnl_limit_factor(&self) -> I80F48 {
         I80F48::from_num(self.settle_pnl_limit_factor)
     }
@@ -270,10 +386,49 @@ impl PerpMarket {
         )
     }
 
+    /// Like oracle_price(), but uses the fallback oracle if the primary one is
+    /// rejected and `fallback_oracle_acc` is provided.
+    pub fn oracle_price_with_fallback<T: KeyedAccountReader>(
+        &self,
+        oracle_acc: &T,
+        fallback_oracle_acc: Option<&T>,
+        staleness_slot: Option<u64>,
+    ) -> Result<I80F48> {
+        let (price, _) = self.oracle_price_and_slot_with_fallback(
+            oracle_acc,
+            fallback_oracle_acc,
+            staleness_slot,
+        )?;
+        Ok(price)
+    }
+
+    /// Like oracle_price_and_slot(), but uses the fallback oracle if the primary one is
+    /// rejected and `fallback_oracle_acc` is provided.
+    pub fn oracle_price_and_slot_with_fallback<T: KeyedAccountReader>(
+        &self,
+        oracle_acc: &T,
+        fallback_oracle_acc: Option<&T>,
+        staleness_slot: Option<u64>,
+    ) -> Result<(I80F48, u64)> {
+        require_keys_eq!(self.oracle, *oracle_acc.key());
+        oracle::oracle_price_and_slot_with_fallback(
+            oracle_acc,
+            &self.fallback_oracle,
+            fallback_oracle_acc,
+            &self.oracle_config,
+            self.base_decimals,
+            staleness_slot,
+        )
+    }
+
     pub fn stable_price(&self) -> I80F48 {
         I80F48::from_num(self.stable_price_model.stable_price)
     }
 
+    pub fn funding_model(&self) -> PerpFundingModel {
+        PerpFundingModel::try_from(self.funding_model).unwrap()
+    }
+
     /// Use current order book price and index price to update the instantaneous funding
     pub fn update_funding_and_stable_price(
         &mut self,
@@ -288,37 +443,63 @@ impl PerpMarket {
 
         let index_price = oracle_price;
         let oracle_price_lots = self.native_price_to_lot(oracle_price);
+        let funding_model = self.funding_model();
 
-        // Get current book price & compare it to index price
-        let bid =
-            book.bookside(Side::Bid)
-                .impact_price(self.impact_quantity, now_ts, oracle_price_lots);
-        let ask =
-            book.bookside(Side::Ask)
-                .impact_price(self.impact_quantity, now_ts, oracle_price_lots);
+        // Limit the maximal time interval that funding is applied for. This means we won't use
+        // the funding rate computed from a single orderbook snapshot for a very long time period
+        // in exceptional circumstances, like a solana downtime or the security council disabling
+        // funding updates.
+        let max_funding_timestep = 3600; // one hour
+        let funding_timestep =
+            (now_ts - self.funding_last_updated as u64).min(max_funding_timestep);
 
-        let funding_rate = match (bid, ask) {
+        // Get current book price, only needed for the book based funding models
+        let (impact_bid, impact_ask) =
+            if self.is_dated() || funding_model == PerpFundingModel::StablePrice {
+                (None, None)
+            } else {
+                let bid = book.bookside(Side::Bid).impact_price(
+                    self.impact_quantity,
+                    now_ts,
+                    oracle_price_lots,
+                );
+                let ask = book.bookside(Side::Ask).impact_price(
+                    self.impact_quantity,
+                    now_ts,
+                    oracle_price_lots,
+                );
+                (bid, ask)
+            };
+
+        // The premium of the book over the index price
+        let book_premium = match (impact_bid, impact_ask) {
             (Some(bid), Some(ask)) => {
                 // calculate mid-market rate
                 let mid_price = (bid + ask) / 2;
                 let book_price = self.lot_to_native_price(mid_price);
-                let diff = book_price / index_price - I80F48::ONE;
-                diff.clamp(self.min_funding, self.max_funding)
+                book_price / index_price - I80F48::ONE
             }
             (Some(_bid), None) => self.max_funding,
             (None, Some(_ask)) => self.min_funding,
             (None, None) => I80F48::ZERO,
         };
 
-        // Limit the maximal time interval that funding is applied for. This means we won't use
-        // the funding rate computed from a single orderbook snapshot for a very long time period
-        // in exceptional circumstances, like a solana downtime or the security council disabling
-        // funding updates.
-        let max_funding_timestep = 3600; // one hour
-        let diff_ts =
-            I80F48::from_num((now_ts - self.funding_last_updated as u64).min(max_funding_timestep));
+        let premium = if self.is_dated() {
+            // Dated futures converge to the index price at expiry and pay no funding
+            I80F48::ZERO
+        } else {
+            match funding_model {
+                PerpFundingModel::ImpactPrice => book_premium,
+                PerpFundingModel::PremiumTwap => {
+                    self.sample_funding_premium(book_premium, now_ts, funding_timestep);
+                    self.funding_premium_twap()
+                }
+                PerpFundingModel::StablePrice => self.stable_price() / index_price - I80F48::ONE,
+            }
+        };
+        let funding_rate = premium.clamp(self.min_funding, self.max_funding);
 
-        let time_factor = diff_ts / DAY_I80F48;
+        let time_factor = I80F48::from_num(funding_timestep) / DAY_I80F48;
         let base_lot_size = I80F48::from_num(self.base_lot_size);
 
         // The number of native quote that one base lot should pay in funding
@@ -326,11 +507,19 @@ impl PerpMarket {
 
         self.long_funding += funding_delta;
         self.short_funding += funding_delta;
-        self.funding_last_updated = now_ts;
 
         self.stable_price_model
             .update(now_ts, oracle_price.to_num());
 
+        if self.is_dated() {
+            if self.is_expired(now_ts) {
+                self.fix_settlement_price(now_ts, oracle_price);
+            } else {
+                self.sample_settlement_price(self.funding_last_updated, now_ts, oracle_price);
+            }
+        }
+        self.funding_last_updated = now_ts;
+
         emit!(PerpUpdateFundingLog {
             mango_group: self.group,
             market_index: self.perp_market_index,
@@ -348,6 +537,115 @@ impl PerpMarket {
         Ok(())
     }
 
+    /// Add the premium observed at the last update, weighted by the `duration` seconds it
+    /// was valid for, to the samples of the current funding interval. Then start a new
+    /// interval if the current one is over and remember `book_premium` for the next update.
+    ///
+    /// Time before the samples were last reset isn't sampled, the premium observed back
+    /// then may belong to a different funding model.
+    fn sample_funding_premium(&mut self, book_premium: I80F48, now_ts: u64, duration: u64) {
+        if now_ts - duration >= self.funding_interval_start_ts {
+            self.funding_premium_sum += self.funding_last_premium * I80F48::from_num(duration);
+            self.funding_premium_weight_ts += duration;
+        }
+        self.funding_last_premium = book_premium;
+
+        let interval_end = self.funding_interval_start_ts + self.funding_interval_ts;
+        if now_ts >= interval_end {
+            emit!(PerpFundingPremiumIntervalLog {
+                mango_group: self.group,
+                market_index: self.perp_market_index,
+                start_ts: self.funding_interval_start_ts,
+                end_ts: now_ts,
+                premium_sum: self.funding_premium_sum.to_bits(),
+                premium_weight_ts: self.funding_premium_weight_ts,
+            });
+
+            // Samples older than the previous interval are dropped
+            if now_ts >= interval_end + self.funding_interval_ts {
+                self.prev_funding_premium_sum = I80F48::ZERO;
+                self.prev_funding_premium_weight_ts = 0;
+            } else {
+                self.prev_funding_premium_sum = self.funding_premium_sum;
+                self.prev_funding_premium_weight_ts = self.funding_premium_weight_ts;
+            }
+            self.funding_premium_sum = I80F48::ZERO;
+            self.funding_premium_weight_ts = 0;
+            self.funding_interval_start_ts = now_ts;
+        }
+    }
+
+    /// Time-weighted average of the book premium over the previous and the current
+    /// funding interval.
+    pub fn funding_premium_twap(&self) -> I80F48 {
+        let weight = self.prev_funding_premium_weight_ts + self.funding_premium_weight_ts;
+        if weight == 0 {
+            return I80F48::ZERO;
+        }
+        (self.prev_funding_premium_sum + self.funding_premium_sum) / I80F48::from_num(weight)
+    }
+
+    /// Resets the premium samples, for when the funding model or interval changes.
+    pub fn reset_funding_premium_samples(&mut self, now_ts: u64) {
+        self.funding_premium_sum = I80F48::ZERO;
+        self.funding_premium_weight_ts = 0;
+        self.prev_funding_premium_sum = I80F48::ZERO;
+        self.prev_funding_premium_weight_ts = 0;
+        self.funding_interval_start_ts = now_ts;
+    }
+
+    /// Add the price observed at `last_ts` to the settlement price samples, for the part
+    /// of the interval `[last_ts, now_ts]` that is inside the settlement window, and
+    /// remember the price observed at `now_ts` for the next interval.
+    ///
+    /// Prices observed at or after expiry are never sampled.
+    fn sample_settlement_price(&mut self, last_ts: u64, now_ts: u64, oracle_price: I80F48) {
+        if self.has_settlement_price == 1 {
+            return;
+        }
+        if self.settlement_last_price.is_positive() {
+            let window_start = self
+                .expiry_timestamp
+                .saturating_sub(self.settlement_window_ts);
+            let start = last_ts.max(window_start);
+            let end = now_ts.min(self.expiry_timestamp);
+            if end > start {
+                let duration = end - start;
+                self.settlement_price_sum +=
+                    self.settlement_last_price * I80F48::from_num(duration);
+                self.settlement_price_weight_ts += duration;
+            }
+        }
+        if now_ts < self.expiry_timestamp {
+            self.settlement_last_price = if self.settlement_uses_stable_price == 1 {
+                self.stable_price()
+            } else {
+                oracle_price
+            };
+        }
+    }
+
+    /// Fixes the price at which positions in an expired dated market are closed.
+    ///
+    /// It's the time-weighted average of the prices sampled during the settlement window,
+    /// including the time between the last update and expiry, or the current price if
+    /// there are no samples. Once fixed, health and pnl settlement use it instead of the
+    /// oracle price.
+    pub fn fix_settlement_price(&mut self, now_ts: u64, oracle_price: I80F48) -> I80F48 {
+        if self.has_settlement_price == 0 {
+            self.sample_settlement_price(self.funding_last_updated, now_ts, oracle_price);
+            self.settlement_price = if self.settlement_price_weight_ts > 0 {
+                self.settlement_price_sum / I80F48::from_num(self.settlement_price_weight_ts)
+            } else if self.settlement_uses_stable_price == 1 {
+                self.stable_price()
+            } else {
+                oracle_price
+            };
+            self.has_settlement_price = 1;
+        }
+        self.settlement_price
+    }
+
     /// Convert from the price stored on the book to the price used in value calculations
     pub fn lot_to_native_price(&self, price: i64) -> I80F48 {
         I80F48::from_num(price) * I80F48::from_num(self.quote_lot_size)
@@ -488,7 +786,153 @@ impl PerpMarket {
             maint_overall_asset_weight: I80F48::ONE,
             init_overall_asset_weight: I80F48::ONE,
             positive_pnl_liquidation_fee: I80F48::ZERO,
-            reserved: [0; 1888],
+            fallback_oracle: Pubkey::default(),
+            insurance_fund_index: 0,
+            padding5: Default::default(),
+            expiry_timestamp: 0,
+            settlement_window_ts: 0,
+            settlement_price_sum: I80F48::ZERO,
+            settlement_price: I80F48::ZERO,
+            settlement_last_price: I80F48::ZERO,
+            settlement_price_weight_ts: 0,
+            settlement_uses_stable_price: 0,
+            has_settlement_price: 0,
+            padding6: Default::default(),
+            funding_interval_ts: 0,
+            funding_interval_start_ts: 0,
+            funding_premium_sum: I80F48::ZERO,
+            prev_funding_premium_sum: I80F48::ZERO,
+            funding_last_premium: I80F48::ZERO,
+            funding_premium_weight_ts: 0,
+            prev_funding_premium_weight_ts: 0,
+            funding_model: 0,
+            padding7: Default::default(),
+            reserved: [0; 1680],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::orderbook::{
+        BookSide, EventQueue, Order, OrderParams, OrderTreeType, PostOrderType, SelfTradeBehavior,
+    };
+    use crate::state::{MangoAccount, MangoAccountValue};
+    use bytemuck::Zeroable;
+    use std::cell::RefCell;
+
+    #[test]
+    fn funding_models() {
+        let bids = RefCell::new(BookSide::zeroed());
+        let asks = RefCell::new(BookSide::zeroed());
+        bids.borrow_mut().nodes.order_tree_type = OrderTreeType::Bids.into();
+        asks.borrow_mut().nodes.order_tree_type = OrderTreeType::Asks.into();
+        let orderbook = || Orderbook {
+            bids: bids.borrow_mut(),
+            asks: asks.borrow_mut(),
+        };
+        let mut event_queue = EventQueue::zeroed();
+        let oracle_price = I80F48::from(1000);
+
+        let mut market = PerpMarket::zeroed();
+        market.quote_lot_size = 1;
+        market.base_lot_size = 1;
+        market.init_base_asset_weight = I80F48::ONE;
+        market.init_base_liab_weight = I80F48::ONE;
+        market.min_funding = I80F48::from(-1);
+        market.max_funding = I80F48::from(1);
+        market.impact_quantity = 1;
+        // allow posting orders away from the oracle price
+        market.maint_base_asset_weight = I80F48::from_num(0.8);
+        market.maint_base_liab_weight = I80F48::from_num(1.2);
+        market.stable_price_model = StablePriceModel::default();
+        market.stable_price_model.reset_to_price(1000.0, 0);
+
+        let buffer = MangoAccount::default_for_tests().try_to_vec().unwrap();
+        let mut account = MangoAccountValue::from_bytes(&buffer).unwrap();
+        account
+            .ensure_perp_position(market.perp_market_index, 0)
+            .unwrap();
+        let account_pk = Pubkey::new_unique();
+
+        // Returns the funding rate that was applied between the last update and now_ts
+        let update_funding = |market: &mut PerpMarket, now_ts: u64| {
+            let before = market.long_funding;
+            let dt = now_ts - market.funding_last_updated;
+            market
+                .update_funding_and_stable_price(&orderbook(), oracle_price, 0, now_ts)
+                .unwrap();
+            let rate = (market.long_funding - before) * I80F48::from(86400)
+                / (oracle_price * I80F48::from(dt));
+            rate.to_num::<f64>()
+        };
+
+        // mid price of 1100, which is 10% above the oracle price
+        for (side, price_lots) in [(Side::Bid, 1080), (Side::Ask, 1120)] {
+            orderbook()
+                .new_order(
+                    Order {
+                        side,
+                        max_base_lots: 1,
+                        max_quote_lots: i64::MAX,
+                        client_order_id: 0,
+                        time_in_force: 0,
+                        self_trade_behavior: SelfTradeBehavior::DecrementTake,
+                        reduce_only: false,
+                        params: OrderParams::Fixed {
+                            price_lots,
+                            order_type: PostOrderType::Limit,
+                        },
+                    },
+                    &mut market,
+                    &mut event_queue,
+                    oracle_price,
+                    &mut account.borrow_mut(),
+                    &account_pk,
+                    900,
+                    u8::MAX,
+                )
+                .unwrap();
         }
+
+        // TWAP: the premium observed at an update is sampled until the next update
+        market.funding_model = PerpFundingModel::PremiumTwap.into();
+        market.funding_interval_ts = 1000;
+        market.reset_funding_premium_samples(0);
+        assert_eq!(update_funding(&mut market, 900), 0.0);
+        assert_eq!(market.funding_premium_weight_ts, 900);
+
+        // the 10% premium observed at 900 is weighted by the 100s until this update
+        let rate = update_funding(&mut market, 1000);
+        assert!((rate - 0.01).abs() < 1e-6, "{}", rate);
+
+        // the previous interval keeps smoothing the rate in the next one
+        let rate = update_funding(&mut market, 1100);
+        assert!((rate - 20.0 / 1100.0).abs() < 1e-6, "{}", rate);
+
+        // time from before a reset isn't sampled
+        market.reset_funding_premium_samples(1150);
+        assert_eq!(update_funding(&mut market, 1200), 0.0);
+        assert_eq!(market.funding_premium_weight_ts, 0);
+        let rate = update_funding(&mut market, 1300);
+        assert!((rate - 0.1).abs() < 1e-6, "{}", rate);
+
+        // impact price: the premium at the time of the update
+        market.funding_model = PerpFundingModel::ImpactPrice.into();
+        let rate = update_funding(&mut market, 1400);
+        assert!((rate - 0.1).abs() < 1e-6, "{}", rate);
+
+        // funding is clamped
+        market.max_funding = I80F48::from_num(0.03);
+        let rate = update_funding(&mut market, 1500);
+        assert!((rate - 0.03).abs() < 1e-6, "{}", rate);
+        market.max_funding = I80F48::from(1);
+
+        // stable price vs oracle: the stable price is 5% above the oracle price
+        market.funding_model = PerpFundingModel::StablePrice.into();
+        market.stable_price_model.reset_to_price(1050.0, 1500);
+        let rate = update_funding(&mut market, 1600);
+        assert!((rate - 0.05).abs() < 1e-6, "{}", rate);
     }
 }
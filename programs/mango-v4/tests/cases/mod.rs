@@ -28,10 +28,12 @@ mod test_liq_perps_force_cancel;
 mod test_liq_perps_positive_pnl;
 mod test_liq_tokens;
 mod test_margin_trade;
+mod test_oracle_fallback;
 mod test_perp;
 mod test_perp_settle;
 mod test_perp_settle_fees;
 mod test_position_lifetime;
 mod test_reduce_only;
 mod test_serum;
+mod test_token_transfer;
 mod test_token_update_index_and_rate;
@@ -617,3 +617,218 @@ async fn test_bankrupt_tokens_insurance_fund() -> Result<(), TransportError> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_bankrupt_tokens_isolated_insurance_fund() -> Result<(), TransportError> {
+    let mut test_builder = TestContextBuilder::new();
+    test_builder.test().set_compute_max_units(85_000); // TokenLiqWithToken needs 84k
+    let context = test_builder.start_default().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..3];
+    let payer_mint_accounts = &context.users[1].token_accounts[0..3];
+
+    //
+    // SETUP: Create a group with an isolated insurance fund for the borrow token
+    //
+
+    let mango_setup::GroupWithTokens {
+        group,
+        tokens,
+        insurance_vault,
+        ..
+    } = mango_setup::GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let quote_token = &tokens[0];
+    let borrow_token = &tokens[1];
+    let collateral_token = &tokens[2];
+
+    let isolated_vault = send_tx(
+        solana,
+        InsuranceFundCreateInstruction {
+            group,
+            admin,
+            payer,
+            fund_index: 1,
+        },
+    )
+    .await
+    .unwrap()
+    .insurance_vault;
+
+    send_tx(
+        solana,
+        TokenEditInsuranceFund {
+            group,
+            admin,
+            mint: mints[1].pubkey,
+            insurance_fund_index: 1,
+        },
+    )
+    .await
+    .unwrap();
+
+    // fund both insurance vaults
+    let group_insurance_amount = 1000;
+    let isolated_insurance_amount = 30;
+    for (vault, amount) in [
+        (insurance_vault, group_insurance_amount),
+        (isolated_vault, isolated_insurance_amount),
+    ] {
+        let mut tx = ClientTransaction::new(solana);
+        tx.add_instruction_direct(
+            spl_token::instruction::transfer(
+                &spl_token::ID,
+                &payer_mint_accounts[0],
+                &vault,
+                &payer.pubkey(),
+                &[&payer.pubkey()],
+                amount,
+            )
+            .unwrap(),
+        );
+        tx.add_signer(payer);
+        tx.send().await.unwrap();
+    }
+
+    // deposit some funds, to the vaults aren't empty
+    let vault_account = create_funded_account(
+        &solana,
+        group,
+        owner,
+        2,
+        &context.users[1],
+        mints,
+        100000,
+        0,
+    )
+    .await;
+
+    //
+    // SETUP: Make an account with some collateral and a borrow
+    //
+    let account = create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        &mints[2..3],
+        100,
+        0,
+    )
+    .await;
+    send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: 50,
+            allow_borrow: true,
+            account,
+            owner,
+            token_account: payer_mint_accounts[1],
+            bank_index: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    //
+    // SETUP: Change the oracle to make health go very negative and liquidate the collateral
+    //
+    set_bank_stub_oracle_price(solana, group, borrow_token, admin, 20.0).await;
+
+    send_tx(
+        solana,
+        TokenLiqWithTokenInstruction {
+            liqee: account,
+            liqor: vault_account,
+            liqor_owner: owner,
+            asset_token_index: collateral_token.index,
+            asset_bank_index: 0,
+            liab_token_index: borrow_token.index,
+            liab_bank_index: 0,
+            max_liab_transfer: I80F48::from_num(100000.0),
+        },
+    )
+    .await
+    .unwrap();
+    assert!(account_position_closed(solana, account, collateral_token.bank).await);
+    let liqee = get_mango_account(solana, account).await;
+    assert!(liqee.being_liquidated());
+
+    //
+    // TEST: bankruptcy only draws from the isolated insurance fund
+    //
+    let liqor_before = account_position(solana, vault_account, quote_token.bank).await;
+    send_tx(
+        solana,
+        TokenLiqBankruptcyInstruction {
+            liqee: account,
+            liqor: vault_account,
+            liqor_owner: owner,
+            liab_mint_info: borrow_token.mint_info,
+            max_liab_transfer: I80F48::from_num(100000.0),
+        },
+    )
+    .await
+    .unwrap();
+    let liqee = get_mango_account(solana, account).await;
+    assert!(!liqee.being_liquidated());
+    assert!(account_position_closed(solana, account, borrow_token.bank).await);
+    assert_eq!(solana.token_account_balance(isolated_vault).await, 0);
+    assert_eq!(
+        solana.token_account_balance(insurance_vault).await,
+        group_insurance_amount
+    );
+    assert_eq!(
+        account_position(solana, vault_account, quote_token.bank).await,
+        liqor_before + isolated_insurance_amount as i64
+    );
+
+    //
+    // TEST: the isolated insurance fund can only be closed once no token uses it
+    //
+    let close_fund = || InsuranceFundCloseInstruction {
+        group,
+        admin,
+        fund_index: 1,
+        sol_destination: payer.pubkey(),
+    };
+    assert!(send_tx(solana, close_fund()).await.is_err());
+
+    send_tx(
+        solana,
+        TokenEditInsuranceFund {
+            group,
+            admin,
+            mint: mints[1].pubkey,
+            insurance_fund_index: 0,
+        },
+    )
+    .await
+    .unwrap();
+    send_tx(solana, close_fund()).await.unwrap();
+
+    let res = send_tx(
+        solana,
+        TokenEditInsuranceFund {
+            group,
+            admin,
+            mint: mints[1].pubkey,
+            insurance_fund_index: 1,
+        },
+    )
+    .await;
+    assert!(res.is_err());
+
+    Ok(())
+}
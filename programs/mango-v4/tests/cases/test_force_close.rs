@@ -440,3 +440,249 @@ async fn test_force_close_perp() -> Result<(), TransportError> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_perp_close_expired() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group, two accounts and a dated perp market
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+
+    let deposit_amount = 1000;
+    let account_0 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+    let account_1 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        1,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+
+    let mango_v4::accounts::PerpCreateMarket { perp_market, .. } = send_tx(
+        solana,
+        PerpCreateMarketInstruction {
+            group,
+            admin,
+            payer,
+            perp_market_index: 0,
+            quote_lot_size: 10,
+            base_lot_size: 100,
+            maint_base_asset_weight: 0.975,
+            init_base_asset_weight: 0.95,
+            maint_base_liab_weight: 1.025,
+            init_base_liab_weight: 1.05,
+            base_liquidation_fee: 0.012,
+            maker_fee: -0.0001,
+            taker_fee: 0.0002,
+            settle_pnl_limit_factor: -1.0,
+            settle_pnl_limit_window_size_ts: 24 * 60 * 60,
+            ..PerpCreateMarketInstruction::with_new_book_and_queue(&solana, &tokens[0]).await
+        },
+    )
+    .await
+    .unwrap();
+
+    let now_ts = solana.get_clock().await.unix_timestamp as u64;
+    let expiry_timestamp = now_ts + 1000;
+    send_tx(
+        solana,
+        PerpSetExpiry {
+            group,
+            admin,
+            perp_market,
+            expiry_timestamp,
+            settlement_window_ts: 500,
+        },
+    )
+    .await
+    .unwrap();
+
+    let price_lots = {
+        let perp_market = solana.get_account::<PerpMarket>(perp_market).await;
+        perp_market.native_price_to_lot(I80F48::ONE)
+    };
+
+    //
+    // Place a bid, corresponding ask, and consume event
+    //
+    let place_order = |account: Pubkey, side: Side| PerpPlaceOrderInstruction {
+        account,
+        perp_market,
+        owner,
+        side,
+        price_lots,
+        max_base_lots: 1,
+        max_quote_lots: i64::MAX,
+        reduce_only: false,
+        client_order_id: 0,
+    };
+    send_tx(solana, place_order(account_0, Side::Bid))
+        .await
+        .unwrap();
+    send_tx(solana, place_order(account_1, Side::Ask))
+        .await
+        .unwrap();
+    send_tx(
+        solana,
+        PerpConsumeEventsInstruction {
+            perp_market,
+            mango_accounts: vec![account_0, account_1],
+        },
+    )
+    .await
+    .unwrap();
+
+    let mango_account_0 = solana.get_account::<MangoAccount>(account_0).await;
+    assert_eq!(mango_account_0.perps[0].base_position_lots(), 1);
+    let mango_account_1 = solana.get_account::<MangoAccount>(account_1).await;
+    assert_eq!(mango_account_1.perps[0].base_position_lots(), -1);
+
+    let close_expired = PerpCloseExpiredPositionInstruction {
+        account_a: account_0,
+        account_b: account_1,
+        perp_market,
+    };
+
+    // Market needs to be expired
+    assert!(send_tx(solana, close_expired.clone()).await.is_err());
+
+    //
+    // Observe a price of 1.5 just before the settlement window starts
+    //
+    let update_funding = PerpUpdateFundingInstruction {
+        perp_market,
+        bank: tokens[0].bank,
+        oracle: tokens[0].oracle,
+    };
+    solana.advance_clock_to(expiry_timestamp as i64 - 530).await;
+    set_perp_stub_oracle_price(solana, group, perp_market, &tokens[0], admin, 1.5).await;
+    send_tx(solana, update_funding.clone()).await.unwrap();
+
+    // Nothing is sampled before the window
+    let perp_market_data = solana.get_account::<PerpMarket>(perp_market).await;
+    assert_eq!(perp_market_data.settlement_price_weight_ts, 0);
+
+    solana.advance_clock_to(expiry_timestamp as i64 - 200).await;
+    send_tx(solana, update_funding).await.unwrap();
+
+    let perp_market_data = solana.get_account::<PerpMarket>(perp_market).await;
+    assert!(perp_market_data.settlement_price_weight_ts >= 300);
+    assert_eq!(perp_market_data.long_funding, I80F48::ZERO);
+
+    //
+    // After expiry, no new orders can be placed and positions close at the settlement price
+    //
+    solana.advance_clock_to(expiry_timestamp as i64).await;
+    set_perp_stub_oracle_price(solana, group, perp_market, &tokens[0], admin, 3.0).await;
+
+    let result = send_tx(solana, place_order(account_0, Side::Bid)).await;
+    assert_mango_error(
+        &result,
+        MangoError::PerpMarketExpired.into(),
+        "market expired".into(),
+    );
+
+    // Pnl can't be settled before the settlement price is fixed
+    let settler =
+        create_funded_account(&solana, group, owner, 251, &context.users[1], &[], 0, 0).await;
+    let settle_pnl = PerpSettlePnlInstruction {
+        settler,
+        settler_owner: owner,
+        account_a: account_0,
+        account_b: account_1,
+        perp_market,
+        settle_bank: tokens[0].bank,
+    };
+    let result = send_tx(solana, settle_pnl.clone()).await;
+    assert_mango_error(
+        &result,
+        MangoError::PerpMarketExpired.into(),
+        "settlement price not fixed".into(),
+    );
+
+    // account_a needs to be long, and account_b needs to be short
+    assert!(send_tx(
+        solana,
+        PerpCloseExpiredPositionInstruction {
+            account_a: account_1,
+            account_b: account_0,
+            perp_market,
+        },
+    )
+    .await
+    .is_err());
+
+    send_tx(solana, close_expired).await.unwrap();
+
+    // The price observed at expiry is not sampled
+    let perp_market_data = solana.get_account::<PerpMarket>(perp_market).await;
+    assert_eq!(perp_market_data.has_settlement_price, 1);
+    assert_eq!(perp_market_data.settlement_price_weight_ts, 500);
+    assert!(assert_equal(perp_market_data.settlement_price, 1.5, 0.0001));
+    assert_eq!(perp_market_data.open_interest, 0);
+
+    let mango_account_0 = solana.get_account::<MangoAccount>(account_0).await;
+    assert_eq!(mango_account_0.perps[0].base_position_lots(), 0);
+    assert!(assert_equal(
+        mango_account_0.perps[0].quote_position_native(),
+        50.01,
+        0.001
+    ));
+    let mango_account_1 = solana.get_account::<MangoAccount>(account_1).await;
+    assert_eq!(mango_account_1.perps[0].base_position_lots(), 0);
+    assert!(assert_equal(
+        mango_account_1.perps[0].quote_position_native(),
+        -50.02,
+        0.001
+    ));
+
+    //
+    // The closed positions' pnl can be settled
+    //
+    send_tx(solana, settle_pnl).await.unwrap();
+
+    let mango_account_0 = solana.get_account::<MangoAccount>(account_0).await;
+    assert!(assert_equal(
+        mango_account_0.perps[0].quote_position_native(),
+        0.0,
+        0.001
+    ));
+    let mango_account_1 = solana.get_account::<MangoAccount>(account_1).await;
+    assert!(assert_equal(
+        mango_account_1.perps[0].quote_position_native(),
+        -0.01,
+        0.001
+    ));
+
+    Ok(())
+}
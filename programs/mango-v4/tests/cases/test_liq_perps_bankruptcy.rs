@@ -457,5 +457,108 @@ async fn test_liq_perps_bankruptcy() -> Result<(), TransportError> {
         assert_eq!(liq_event_amounts(), (5.0, 42, 0.0));
     }
 
+    // isolated insurance fund: only the market's own fund covers the bankruptcy
+    {
+        let isolated_vault = send_tx(
+            solana,
+            InsuranceFundCreateInstruction {
+                group,
+                admin,
+                payer,
+                fund_index: 1,
+            },
+        )
+        .await
+        .unwrap()
+        .insurance_vault;
+
+        let (perp_market, account) = setup_perp(-28, -50, -10).await;
+        send_tx(
+            solana,
+            PerpEditInsuranceFund {
+                group,
+                admin,
+                perp_market,
+                insurance_fund_index: 1,
+            },
+        )
+        .await
+        .unwrap();
+
+        fund_insurance(100).await;
+        let mut tx = ClientTransaction::new(solana);
+        tx.add_instruction_direct(
+            spl_token::instruction::transfer(
+                &spl_token::ID,
+                &payer_mint_accounts[0],
+                &isolated_vault,
+                &payer.pubkey(),
+                &[&payer.pubkey()],
+                5,
+            )
+            .unwrap(),
+        );
+        tx.add_signer(payer);
+        tx.send().await.unwrap();
+        let group_insurance_before = solana.token_account_balance(insurance_vault).await;
+
+        send_tx(
+            solana,
+            PerpLiqNegativePnlOrBankruptcyInstruction {
+                liqor,
+                liqor_owner: owner,
+                liqee: account,
+                perp_market,
+                max_liab_transfer: u64::MAX,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            liq_event_amounts(),
+            (10.0, 5, limit_prec(28.0 - 5.0 / 1.05))
+        );
+        assert_eq!(solana.token_account_balance(isolated_vault).await, 0);
+        assert_eq!(
+            solana.token_account_balance(insurance_vault).await,
+            group_insurance_before
+        );
+
+        // the fund can't be closed while the market uses it
+        let close_fund = || InsuranceFundCloseInstruction {
+            group,
+            admin,
+            fund_index: 1,
+            sol_destination: payer.pubkey(),
+        };
+        assert!(send_tx(solana, close_fund()).await.is_err());
+
+        send_tx(
+            solana,
+            PerpEditInsuranceFund {
+                group,
+                admin,
+                perp_market,
+                insurance_fund_index: 0,
+            },
+        )
+        .await
+        .unwrap();
+        send_tx(solana, close_fund()).await.unwrap();
+
+        // and markets can't be switched to a fund that doesn't exist
+        let res = send_tx(
+            solana,
+            PerpEditInsuranceFund {
+                group,
+                admin,
+                perp_market,
+                insurance_fund_index: 1,
+            },
+        )
+        .await;
+        assert!(res.is_err());
+    }
+
     Ok(())
 }
@@ -0,0 +1,211 @@
+use super::*;
+
+use solana_program_test::{read_file, ProgramTest};
+
+// Adds the pyth price account from the oracle test fixtures under a new address
+fn add_pyth_oracle(test: &mut ProgramTest) -> Pubkey {
+    let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("resources/test/J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix.bin");
+    let data = read_file(path);
+    let pubkey = Pubkey::new_unique();
+    test.add_account(
+        pubkey,
+        solana_sdk::account::Account {
+            lamports: u32::MAX as u64,
+            data,
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    pubkey
+}
+
+#[tokio::test]
+async fn test_oracle_fallback() -> Result<(), TransportError> {
+    let mut test_builder = TestContextBuilder::new();
+    test_builder.test().set_compute_max_units(95_000); // LiqTokenWithToken with fallbacks
+    let pyth_oracle = add_pyth_oracle(test_builder.test());
+    let context = test_builder.start_default().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..3];
+    let fallback_mint = &context.mints[3];
+    let payer_mint_accounts = &context.users[1].token_accounts[0..3];
+
+    //
+    // SETUP: Create a group, an account to fill the vaults and a stub oracle
+    // for a mint that isn't a token, to be used as the fallback
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let collateral_token = &tokens[1];
+    let borrow_token = &tokens[2];
+
+    let vault_account = create_funded_account(
+        &solana,
+        group,
+        owner,
+        2,
+        &context.users[1],
+        &mints[1..3],
+        100000,
+        0,
+    )
+    .await;
+
+    let fallback_oracle = send_tx(
+        solana,
+        StubOracleCreate {
+            group,
+            mint: fallback_mint.pubkey,
+            admin,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .oracle;
+
+    //
+    // SETUP: Make an account with some collateral and some borrows
+    //
+    let account = create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        &mints[1..2],
+        1000,
+        0,
+    )
+    .await;
+
+    send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: 400,
+            allow_borrow: true,
+            account,
+            owner,
+            token_account: payer_mint_accounts[2],
+            bank_index: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    //
+    // TEST: With a primary oracle that is never confident enough, health can't be computed
+    //
+    send_tx(
+        solana,
+        TokenEditOracles {
+            group,
+            admin,
+            mint: collateral_token.mint.pubkey,
+            oracle: pyth_oracle,
+            conf_filter: -1.0,
+            fallback_oracle: Pubkey::default(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let withdraw_collateral = || TokenWithdrawInstruction {
+        amount: 1,
+        allow_borrow: false,
+        account,
+        owner,
+        token_account: payer_mint_accounts[1],
+        bank_index: 0,
+    };
+    let res = send_tx(solana, withdraw_collateral()).await;
+    assert_mango_error(&res, MangoError::OracleConfidence.into(), "".into());
+
+    //
+    // TEST: With a fallback oracle the withdraw passes the health check
+    //
+    send_tx(
+        solana,
+        TokenEditOracles {
+            group,
+            admin,
+            mint: collateral_token.mint.pubkey,
+            oracle: pyth_oracle,
+            conf_filter: -1.0,
+            fallback_oracle,
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(solana, withdraw_collateral()).await.unwrap();
+    assert_eq!(
+        account_position(solana, account, collateral_token.bank).await,
+        1000 - 1
+    );
+
+    //
+    // TEST: A drop in the fallback price makes the account liquidatable
+    //
+    send_tx(
+        solana,
+        StubOracleSetInstruction {
+            group,
+            admin,
+            mint: fallback_mint.pubkey,
+            price: 0.5,
+        },
+    )
+    .await
+    .unwrap();
+
+    let res = send_tx(solana, withdraw_collateral()).await;
+    assert_mango_error(
+        &res,
+        MangoError::HealthMustBePositiveOrIncrease.into(),
+        "".into(),
+    );
+
+    send_tx(
+        solana,
+        TokenLiqWithTokenInstruction {
+            liqee: account,
+            liqor: vault_account,
+            liqor_owner: owner,
+            asset_token_index: collateral_token.index,
+            liab_token_index: borrow_token.index,
+            asset_bank_index: 0,
+            liab_bank_index: 0,
+            max_liab_transfer: I80F48::from_num(10.0),
+        },
+    )
+    .await
+    .unwrap();
+
+    // the asset cost for 10 borrow is 10 / 0.5 * 1.02 = 20.4 collateral, valued at the fallback price
+    assert_eq!(
+        account_position(solana, account, borrow_token.bank).await,
+        -400 + 10
+    );
+    assert_eq!(
+        account_position(solana, account, collateral_token.bank).await,
+        1000 - 1 - 20
+    );
+    let liqee = get_mango_account(solana, account).await;
+    assert!(liqee.being_liquidated());
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use super::*;
+use mango_v4::accounts_ix::{PerpCancelOrders, PerpNewOrder, PerpOrderPrice};
 
 #[tokio::test]
 async fn test_perp_fixed() -> Result<(), TransportError> {
@@ -1055,6 +1056,606 @@ async fn test_perp_realize_partially() -> Result<(), TransportError> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_perp_trigger_orders() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group, accounts and a perp market
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let settle_bank = tokens[0].bank;
+
+    let deposit_amount = 1000;
+    let account_0 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+    let account_1 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        1,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+    let executor =
+        create_funded_account(&solana, group, owner, 2, &context.users[1], &[], 0, 0).await;
+
+    let mango_v4::accounts::PerpCreateMarket { perp_market, .. } = send_tx(
+        solana,
+        PerpCreateMarketInstruction {
+            group,
+            admin,
+            payer,
+            perp_market_index: 0,
+            quote_lot_size: 10,
+            base_lot_size: 100,
+            maint_base_asset_weight: 0.975,
+            init_base_asset_weight: 0.95,
+            maint_base_liab_weight: 1.025,
+            init_base_liab_weight: 1.05,
+            base_liquidation_fee: 0.012,
+            maker_fee: 0.0000,
+            taker_fee: 0.0000,
+            settle_pnl_limit_factor: -1.0,
+            settle_pnl_limit_window_size_ts: 24 * 60 * 60,
+            ..PerpCreateMarketInstruction::with_new_book_and_queue(&solana, &tokens[1]).await
+        },
+    )
+    .await
+    .unwrap();
+
+    let perp_market_data = solana.get_account::<PerpMarket>(perp_market).await;
+    set_perp_stub_oracle_price(solana, group, perp_market, &tokens[1], admin, 1000.0).await;
+
+    //
+    // TEST: New accounts have no trigger order slots, expand to get some
+    //
+    let place_stop_loss = PerpPlaceTriggerOrderInstruction {
+        account: account_0,
+        perp_market,
+        owner,
+        side: Side::Ask,
+        trigger_condition: PerpTriggerCondition::OraclePriceBelow,
+        trigger_price: 960.0,
+        price_lots: perp_market_data.native_price_to_lot(I80F48::from(900)),
+        max_base_lots: 1,
+        reduce_only: false,
+        expiry_timestamp: 0,
+        incentive: 100,
+    };
+    assert!(send_tx(solana, place_stop_loss.clone()).await.is_err());
+
+    send_tx(
+        solana,
+        AccountExpandV2Instruction {
+            account_num: 0,
+            group,
+            owner,
+            payer,
+            token_count: 16,
+            serum3_count: 8,
+            perp_count: 8,
+            perp_oo_count: 8,
+            perp_trigger_order_count: 2,
+        },
+    )
+    .await
+    .unwrap();
+
+    //
+    // TEST: Place trigger orders until the slots are full, cancel one
+    //
+    send_tx(solana, place_stop_loss.clone()).await.unwrap();
+    let take_profit = PerpPlaceTriggerOrderInstruction {
+        side: Side::Ask,
+        trigger_condition: PerpTriggerCondition::OraclePriceAbove,
+        trigger_price: 2000.0,
+        ..place_stop_loss.clone()
+    };
+    send_tx(solana, take_profit.clone()).await.unwrap();
+    assert!(send_tx(solana, take_profit.clone()).await.is_err());
+
+    let mango_account_0 = get_mango_account(solana, account_0).await;
+    assert_eq!(mango_account_0.active_perp_trigger_orders().count(), 2);
+    let stop_loss_id = mango_account_0
+        .active_perp_trigger_orders()
+        .find(|o| o.trigger_condition() == PerpTriggerCondition::OraclePriceBelow)
+        .unwrap()
+        .id;
+    let take_profit_id = mango_account_0
+        .active_perp_trigger_orders()
+        .find(|o| o.trigger_condition() == PerpTriggerCondition::OraclePriceAbove)
+        .unwrap()
+        .id;
+
+    send_tx(
+        solana,
+        PerpCancelTriggerOrderInstruction {
+            account: account_0,
+            owner,
+            trigger_order_id: take_profit_id,
+        },
+    )
+    .await
+    .unwrap();
+    let mango_account_0 = get_mango_account(solana, account_0).await;
+    assert_eq!(mango_account_0.active_perp_trigger_orders().count(), 1);
+
+    //
+    // TEST: Executing before the trigger price is reached fails
+    //
+    let execute = PerpExecuteTriggerOrderInstruction {
+        account: account_0,
+        perp_market,
+        executor,
+        executor_owner: owner,
+        settle_bank,
+        trigger_order_id: stop_loss_id,
+        limit: 10,
+    };
+    let res = send_tx(solana, execute.clone()).await;
+    assert_mango_error(
+        &res,
+        MangoError::PerpTriggerOrderNotTriggered.into(),
+        "oracle price above trigger price".into(),
+    );
+
+    //
+    // TEST: Executing against an empty book fails, the trigger order stays and
+    // no incentive or penalty is paid
+    //
+    set_perp_stub_oracle_price(solana, group, perp_market, &tokens[1], admin, 950.0).await;
+    assert!(send_tx(solana, execute.clone()).await.is_err());
+
+    let mango_account_0 = get_mango_account(solana, account_0).await;
+    assert_eq!(mango_account_0.active_perp_trigger_orders().count(), 1);
+    assert_eq!(
+        account_position(solana, account_0, settle_bank).await,
+        deposit_amount as i64
+    );
+    assert!(account_position_closed(solana, executor, settle_bank).await);
+
+    //
+    // TEST: Once there is a bid, the executor places the order and earns the incentive,
+    // but it may not use a match limit that's too low to fill anything
+    //
+    send_tx(
+        solana,
+        PerpPlaceOrderInstruction {
+            account: account_1,
+            perp_market,
+            owner,
+            side: Side::Bid,
+            price_lots: perp_market_data.native_price_to_lot(I80F48::from(950)),
+            max_base_lots: 1,
+            max_quote_lots: i64::MAX,
+            reduce_only: false,
+            client_order_id: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    let res = send_tx(
+        solana,
+        PerpExecuteTriggerOrderInstruction {
+            limit: 0,
+            ..execute.clone()
+        },
+    )
+    .await;
+    assert!(res.is_err());
+    let mango_account_0 = get_mango_account(solana, account_0).await;
+    assert_eq!(mango_account_0.active_perp_trigger_orders().count(), 1);
+
+    send_tx(solana, execute.clone()).await.unwrap();
+    check_prev_instruction_post_health(&solana, account_0).await;
+
+    send_tx(
+        solana,
+        PerpConsumeEventsInstruction {
+            perp_market,
+            mango_accounts: vec![account_0, account_1],
+        },
+    )
+    .await
+    .unwrap();
+
+    let mango_account_0 = get_mango_account(solana, account_0).await;
+    assert_eq!(
+        mango_account_0
+            .perp_position(0)
+            .unwrap()
+            .base_position_lots(),
+        -1
+    );
+    assert_eq!(mango_account_0.active_perp_trigger_orders().count(), 0);
+    assert_eq!(
+        account_position(solana, account_0, settle_bank).await,
+        deposit_amount as i64 - 100
+    );
+    assert_eq!(account_position(solana, executor, settle_bank).await, 100);
+
+    // The trigger order was consumed
+    assert!(send_tx(solana, execute.clone()).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_perp_cancel_replace_orders() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group, an account and a perp market
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+
+    let account_0 =
+        create_funded_account(&solana, group, owner, 0, &context.users[1], mints, 1000, 0).await;
+
+    let mango_v4::accounts::PerpCreateMarket {
+        perp_market,
+        bids,
+        asks,
+        ..
+    } = send_tx(
+        solana,
+        PerpCreateMarketInstruction {
+            group,
+            admin,
+            payer,
+            perp_market_index: 0,
+            quote_lot_size: 10,
+            base_lot_size: 100,
+            maint_base_asset_weight: 0.975,
+            init_base_asset_weight: 0.95,
+            maint_base_liab_weight: 1.025,
+            init_base_liab_weight: 1.05,
+            base_liquidation_fee: 0.012,
+            maker_fee: -0.0001,
+            taker_fee: 0.0002,
+            ..PerpCreateMarketInstruction::with_new_book_and_queue(&solana, &tokens[0]).await
+        },
+    )
+    .await
+    .unwrap();
+
+    let price_lots = {
+        let perp_market = solana.get_account::<PerpMarket>(perp_market).await;
+        perp_market.native_price_to_lot(I80F48::ONE)
+    };
+
+    let new_order = |side: Side, price: PerpOrderPrice, client_order_id: u64| PerpNewOrder {
+        side,
+        price,
+        max_base_lots: 1,
+        max_quote_lots: i64::MAX,
+        client_order_id,
+        order_type: PlaceOrderType::Limit,
+        reduce_only: false,
+        expiry_timestamp: 0,
+    };
+
+    //
+    // TEST: Place several orders without cancelling
+    //
+    send_tx(
+        solana,
+        PerpCancelReplaceOrdersInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            cancel: PerpCancelOrders::None,
+            new_orders: vec![
+                new_order(Side::Bid, PerpOrderPrice::Fixed { price_lots }, 1),
+                new_order(
+                    Side::Bid,
+                    PerpOrderPrice::Fixed {
+                        price_lots: price_lots - 1,
+                    },
+                    2,
+                ),
+                new_order(
+                    Side::Ask,
+                    PerpOrderPrice::Fixed {
+                        price_lots: price_lots + 1,
+                    },
+                    3,
+                ),
+            ],
+        },
+    )
+    .await
+    .unwrap();
+    check_prev_instruction_post_health(&solana, account_0).await;
+
+    let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+    assert_eq!(bids_data.roots[0].leaf_count, 2);
+    let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+    assert_eq!(asks_data.roots[0].leaf_count, 1);
+
+    //
+    // TEST: Replace orders by client order id, unknown ids are skipped
+    //
+    send_tx(
+        solana,
+        PerpCancelReplaceOrdersInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            cancel: PerpCancelOrders::ByClientOrderIds {
+                client_order_ids: vec![1, 3, 99],
+            },
+            new_orders: vec![
+                new_order(
+                    Side::Bid,
+                    PerpOrderPrice::OraclePegged {
+                        price_offset_lots: -1,
+                        peg_limit: -1,
+                    },
+                    4,
+                ),
+                new_order(
+                    Side::Ask,
+                    PerpOrderPrice::Fixed {
+                        price_lots: price_lots + 2,
+                    },
+                    5,
+                ),
+            ],
+        },
+    )
+    .await
+    .unwrap();
+    check_prev_instruction_post_health(&solana, account_0).await;
+
+    let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+    assert_eq!(bids_data.roots[0].leaf_count, 1);
+    assert_eq!(bids_data.roots[1].leaf_count, 1);
+    let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+    assert_eq!(asks_data.roots[0].leaf_count, 1);
+
+    let mango_account_0 = get_mango_account(solana, account_0).await;
+    let mut client_ids = mango_account_0
+        .all_perp_orders()
+        .filter(|oo| oo.market != FREE_ORDER_SLOT)
+        .map(|oo| oo.client_id)
+        .collect::<Vec<_>>();
+    client_ids.sort();
+    assert_eq!(client_ids, vec![2, 4, 5]);
+
+    //
+    // TEST: Cancel all bids, keep the ask
+    //
+    send_tx(
+        solana,
+        PerpCancelReplaceOrdersInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            cancel: PerpCancelOrders::All {
+                side_option: Some(Side::Bid),
+            },
+            new_orders: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    let mango_account_0 = get_mango_account(solana, account_0).await;
+    let client_ids = mango_account_0
+        .all_perp_orders()
+        .filter(|oo| oo.market != FREE_ORDER_SLOT)
+        .map(|oo| oo.client_id)
+        .collect::<Vec<_>>();
+    assert_eq!(client_ids, vec![5]);
+
+    //
+    // TEST: Cancel everything
+    //
+    send_tx(
+        solana,
+        PerpCancelReplaceOrdersInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            cancel: PerpCancelOrders::All { side_option: None },
+            new_orders: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_no_perp_orders(solana, account_0).await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_perp_place_order_v2() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group, an account and a perp market
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+
+    let account_0 =
+        create_funded_account(&solana, group, owner, 0, &context.users[1], mints, 1000, 0).await;
+
+    let mango_v4::accounts::PerpCreateMarket {
+        perp_market,
+        bids,
+        asks,
+        ..
+    } = send_tx(
+        solana,
+        PerpCreateMarketInstruction {
+            group,
+            admin,
+            payer,
+            perp_market_index: 0,
+            quote_lot_size: 10,
+            base_lot_size: 100,
+            maint_base_asset_weight: 0.975,
+            init_base_asset_weight: 0.95,
+            maint_base_liab_weight: 1.025,
+            init_base_liab_weight: 1.05,
+            base_liquidation_fee: 0.012,
+            maker_fee: -0.0001,
+            taker_fee: 0.0002,
+            ..PerpCreateMarketInstruction::with_new_book_and_queue(&solana, &tokens[0]).await
+        },
+    )
+    .await
+    .unwrap();
+
+    let price_lots = {
+        let perp_market = solana.get_account::<PerpMarket>(perp_market).await;
+        perp_market.native_price_to_lot(I80F48::ONE)
+    };
+
+    send_tx(
+        solana,
+        PerpPlaceOrderInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots: i64::MAX,
+            reduce_only: false,
+            client_order_id: 1,
+        },
+    )
+    .await
+    .unwrap();
+
+    let place_ask = |self_trade_behavior: SelfTradeBehavior| PerpPlaceOrderV2Instruction {
+        account: account_0,
+        perp_market,
+        owner,
+        side: Side::Ask,
+        price_lots,
+        max_base_lots: 1,
+        max_quote_lots: i64::MAX,
+        reduce_only: false,
+        client_order_id: 2,
+        self_trade_behavior,
+    };
+
+    //
+    // TEST: Matching against the own bid can abort the transaction
+    //
+    let res = send_tx(solana, place_ask(SelfTradeBehavior::AbortTransaction)).await;
+    assert_mango_error(&res, MangoError::WouldSelfTrade.into(), "".into());
+
+    //
+    // TEST: Or cancel the own bid and post the ask instead
+    //
+    send_tx(solana, place_ask(SelfTradeBehavior::CancelProvide))
+        .await
+        .unwrap();
+    let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+    assert_eq!(bids_data.roots[0].leaf_count, 0);
+    let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+    assert_eq!(asks_data.roots[0].leaf_count, 1);
+
+    //
+    // TEST: The v2 instruction has its own ix gate
+    //
+    send_tx(
+        solana,
+        IxGateSetInstruction {
+            group,
+            admin,
+            ix_gate: 1 << IxGate::PerpPlaceOrderV2 as u128,
+        },
+    )
+    .await
+    .unwrap();
+
+    let res = send_tx(solana, place_ask(SelfTradeBehavior::DecrementTake)).await;
+    assert_mango_error(&res, MangoError::IxIsDisabled.into(), "".into());
+
+    send_tx(
+        solana,
+        PerpPlaceOrderInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            side: Side::Ask,
+            price_lots: price_lots + 1,
+            max_base_lots: 1,
+            max_quote_lots: i64::MAX,
+            reduce_only: false,
+            client_order_id: 3,
+        },
+    )
+    .await
+    .unwrap();
+    let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+    assert_eq!(asks_data.roots[0].leaf_count, 2);
+
+    Ok(())
+}
+
 async fn assert_no_perp_orders(solana: &SolanaCookie, account_0: Pubkey) {
     let mango_account_0 = solana.get_account::<MangoAccount>(account_0).await;
 
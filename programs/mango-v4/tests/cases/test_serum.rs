@@ -637,6 +637,104 @@ async fn test_serum_settle_v1() -> Result<(), TransportError> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_serum_ioc_swap() -> Result<(), TransportError> {
+    let mut test_builder = TestContextBuilder::new();
+    test_builder.test().set_compute_max_units(150_000); // Serum3PlaceOrder and Serum3SettleFunds
+    let context = test_builder.start_default().await;
+    let solana = &context.solana.clone();
+
+    //
+    // SETUP: Create a group, accounts, market etc
+    //
+    let deposit_amount = 160000;
+    let CommonSetup {
+        quote_token,
+        base_token,
+        order_placer,
+        mut order_placer2,
+        ..
+    } = common_setup(&context, deposit_amount).await;
+    let quote_bank = quote_token.bank;
+    let base_bank = base_token.bank;
+    let account = order_placer.account;
+
+    let serum_taker_fee = |amount: i64| (amount as f64 * 0.0004).trunc() as i64;
+
+    // An immediate-or-cancel ask and the settle in the same transaction, like
+    // the liquidator's serum3 rebalancing swap
+    let ioc_sell = |amount: u64| {
+        let mut tx = ClientTransaction::new(solana);
+        let place = Serum3PlaceOrderInstruction {
+            side: Serum3Side::Ask,
+            limit_price: (1.0 * 100.0 / 10.0) as u64, // in quote_lot (10) per base lot (100)
+            max_base_qty: amount / 100,               // in base lot (100)
+            max_native_quote_qty_including_fees: amount,
+            self_trade_behavior: Serum3SelfTradeBehavior::DecrementTake,
+            order_type: Serum3OrderType::ImmediateOrCancel,
+            client_order_id: 0,
+            limit: 10,
+            account,
+            owner: order_placer.owner,
+            serum_market: order_placer.serum_market,
+        };
+        let settle = Serum3SettleFundsInstruction {
+            account,
+            owner: order_placer.owner,
+            serum_market: order_placer.serum_market,
+        };
+        async move {
+            tx.add_instruction(place).await;
+            tx.add_instruction(settle).await;
+            tx.send().await.unwrap();
+        }
+    };
+
+    //
+    // TEST: Without liquidity on the book, nothing changes
+    //
+    let quote_start = account_position(solana, account, quote_bank).await;
+    let base_start = account_position(solana, account, base_bank).await;
+
+    ioc_sell(100000).await;
+
+    assert_eq!(
+        account_position(solana, account, quote_bank).await,
+        quote_start
+    );
+    assert_eq!(
+        account_position(solana, account, base_bank).await,
+        base_start
+    );
+
+    //
+    // TEST: Against a resting bid, the swap proceeds are available right away
+    //
+    let amount = 100000;
+    order_placer2.bid_maker(1.0, amount as u64).await.unwrap();
+
+    ioc_sell(amount as u64).await;
+
+    assert_eq!(
+        account_position(solana, account, base_bank).await,
+        base_start - amount
+    );
+    assert_eq!(
+        account_position(solana, account, quote_bank).await,
+        quote_start + amount - serum_taker_fee(amount)
+    );
+
+    // nothing is left in the open orders account
+    let open_orders = context
+        .serum
+        .load_open_orders(order_placer.open_orders)
+        .await;
+    assert_eq!(open_orders.native_coin_total, 0);
+    assert_eq!(open_orders.native_pc_total, 0);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_serum_settle_v2_to_dao() -> Result<(), TransportError> {
     let mut test_builder = TestContextBuilder::new();
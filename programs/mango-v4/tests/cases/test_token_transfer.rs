@@ -0,0 +1,214 @@
+use super::*;
+
+#[tokio::test]
+async fn test_token_transfer() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let other_owner = context.users[1].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group and accounts
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let bank0 = tokens[0].bank;
+
+    let account_0 =
+        create_funded_account(&solana, group, owner, 0, &context.users[1], mints, 1000, 0).await;
+    let account_1 =
+        create_funded_account(&solana, group, owner, 1, &context.users[1], &[], 0, 0).await;
+    let account_2 =
+        create_funded_account(&solana, group, owner, 2, &context.users[1], &[], 0, 0).await;
+    let other_account =
+        create_funded_account(&solana, group, other_owner, 0, &context.users[1], &[], 0, 0).await;
+
+    //
+    // TEST: Transfer part of a deposit
+    //
+    send_tx(
+        solana,
+        TokenTransferInstruction {
+            amount: 300,
+            allow_borrow: false,
+            source_account: account_0,
+            destination_account: account_1,
+            owner,
+            bank: bank0,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(account_position(solana, account_0, bank0).await, 700);
+    assert_eq!(account_position(solana, account_1, bank0).await, 300);
+
+    //
+    // TEST: Transferring to an account of a different owner fails
+    //
+    assert!(send_tx(
+        solana,
+        TokenTransferInstruction {
+            amount: 100,
+            allow_borrow: false,
+            source_account: account_0,
+            destination_account: other_account,
+            owner,
+            bank: bank0,
+        },
+    )
+    .await
+    .is_err());
+
+    //
+    // TEST: Transferring more than the deposit needs allow_borrow
+    //
+    assert!(send_tx(
+        solana,
+        TokenTransferInstruction {
+            amount: 800,
+            allow_borrow: false,
+            source_account: account_0,
+            destination_account: account_1,
+            owner,
+            bank: bank0,
+        },
+    )
+    .await
+    .is_err());
+
+    //
+    // TEST: Transfer everything, closing the source position
+    //
+    send_tx(
+        solana,
+        TokenTransferInstruction {
+            amount: u64::MAX,
+            allow_borrow: false,
+            source_account: account_0,
+            destination_account: account_1,
+            owner,
+            bank: bank0,
+        },
+    )
+    .await
+    .unwrap();
+    assert!(account_position_closed(solana, account_0, bank0).await);
+    assert_eq!(account_position(solana, account_1, bank0).await, 1000);
+
+    //
+    // TEST: Borrow into another account, backed by the mint1 collateral
+    //
+    send_tx(
+        solana,
+        TokenTransferInstruction {
+            amount: 100,
+            allow_borrow: true,
+            source_account: account_0,
+            destination_account: account_1,
+            owner,
+            bank: bank0,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(account_position(solana, account_0, bank0).await, -100);
+    assert_eq!(account_position(solana, account_1, bank0).await, 1100);
+
+    //
+    // TEST: Borrows that would make the source account unhealthy fail
+    //
+    assert_mango_error(
+        &send_tx(
+            solana,
+            TokenTransferInstruction {
+                amount: 10000,
+                allow_borrow: true,
+                source_account: account_0,
+                destination_account: account_1,
+                owner,
+                bank: bank0,
+            },
+        )
+        .await,
+        MangoError::HealthMustBePositiveOrIncrease.into(),
+        "transfer must not make the source unhealthy".to_string(),
+    );
+
+    //
+    // TEST: When deposits are reduce only, transfers may only repay borrows
+    //
+    send_tx(
+        solana,
+        TokenMakeReduceOnly {
+            group,
+            admin,
+            mint: mints[0].pubkey,
+            reduce_only: 1,
+            force_close: false,
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_mango_error(
+        &send_tx(
+            solana,
+            TokenTransferInstruction {
+                amount: 100,
+                allow_borrow: false,
+                source_account: account_1,
+                destination_account: account_2,
+                owner,
+                bank: bank0,
+            },
+        )
+        .await,
+        MangoError::TokenInReduceOnlyMode.into(),
+        "transfer must not create a deposit".to_string(),
+    );
+
+    assert_mango_error(
+        &send_tx(
+            solana,
+            TokenTransferInstruction {
+                amount: 200,
+                allow_borrow: false,
+                source_account: account_1,
+                destination_account: account_0,
+                owner,
+                bank: bank0,
+            },
+        )
+        .await,
+        MangoError::TokenInReduceOnlyMode.into(),
+        "transfer must not turn a borrow into a deposit".to_string(),
+    );
+
+    send_tx(
+        solana,
+        TokenTransferInstruction {
+            amount: 100,
+            allow_borrow: false,
+            source_account: account_1,
+            destination_account: account_0,
+            owner,
+            bank: bank0,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(account_position(solana, account_1, bank0).await, 1000);
+
+    Ok(())
+}
@@ -6,7 +6,8 @@ use anchor_spl::token::{Token, TokenAccount};
 use fixed::types::I80F48;
 use itertools::Itertools;
 use mango_v4::accounts_ix::{
-    InterestRateParams, Serum3OrderType, Serum3SelfTradeBehavior, Serum3Side,
+    InterestRateParams, PerpCancelOrders, PerpNewOrder, Serum3OrderType, Serum3SelfTradeBehavior,
+    Serum3Side,
 };
 use mango_v4::state::{MangoAccount, MangoAccountValue};
 use solana_program::instruction::Instruction;
@@ -173,6 +174,29 @@ async fn get_oracle_address_from_perp_market_address(
     perp_market.oracle
 }
 
+// the distinct fallback oracles of the given banks and perp markets, passed after
+// all other health accounts
+async fn derive_fallback_oracles(
+    account_loader: &impl ClientAccountLoader,
+    banks: &[Pubkey],
+    perp_markets: &[Pubkey],
+) -> Vec<Pubkey> {
+    let mut fallback_oracles = vec![];
+    for bank_address in banks {
+        let bank: Bank = account_loader.load(bank_address).await.unwrap();
+        fallback_oracles.push(bank.fallback_oracle);
+    }
+    for perp_market_address in perp_markets {
+        let perp_market: PerpMarket = account_loader.load(perp_market_address).await.unwrap();
+        fallback_oracles.push(perp_market.fallback_oracle);
+    }
+    fallback_oracles
+        .into_iter()
+        .filter(|oracle| *oracle != Pubkey::default())
+        .unique()
+        .collect()
+}
+
 // all the accounts that instructions like deposit/withdraw need to compute account health
 async fn derive_health_check_remaining_account_metas(
     account_loader: &impl ClientAccountLoader,
@@ -204,20 +228,20 @@ async fn derive_health_check_remaining_account_metas(
         oracles.push(mint_info.oracle);
     }
 
-    let perp_markets = adjusted_account
+    let perp_markets: Vec<Pubkey> = adjusted_account
         .active_perp_positions()
-        .map(|perp| get_perp_market_address_by_index(account.fixed.group, perp.market_index));
+        .map(|perp| get_perp_market_address_by_index(account.fixed.group, perp.market_index))
+        .collect();
 
     let mut perp_oracles = vec![];
-    for perp in adjusted_account
-        .active_perp_positions()
-        .map(|perp| get_perp_market_address_by_index(account.fixed.group, perp.market_index))
-    {
-        perp_oracles.push(get_oracle_address_from_perp_market_address(account_loader, &perp).await)
+    for perp in &perp_markets {
+        perp_oracles.push(get_oracle_address_from_perp_market_address(account_loader, perp).await)
     }
 
     let serum_oos = account.active_serum3_orders().map(|&s| s.open_orders);
 
+    let fallback_oracles = derive_fallback_oracles(account_loader, &banks, &perp_markets).await;
+
     let to_account_meta = |pubkey| AccountMeta {
         pubkey,
         is_writable: false,
@@ -232,9 +256,10 @@ async fn derive_health_check_remaining_account_metas(
             is_signer: false,
         })
         .chain(oracles.into_iter().map(to_account_meta))
-        .chain(perp_markets.map(to_account_meta))
+        .chain(perp_markets.into_iter().map(to_account_meta))
         .chain(perp_oracles.into_iter().map(to_account_meta))
         .chain(serum_oos.map(to_account_meta))
+        .chain(fallback_oracles.into_iter().map(to_account_meta))
         .collect()
 }
 
@@ -284,6 +309,10 @@ async fn derive_liquidation_remaining_account_metas(
         .chain(liqor.active_serum3_orders())
         .map(|&s| s.open_orders);
 
+    let bank_addresses: Vec<Pubkey> = banks.iter().map(|(pubkey, _)| *pubkey).collect();
+    let fallback_oracles =
+        derive_fallback_oracles(account_loader, &bank_addresses, &perp_markets).await;
+
     let to_account_meta = |pubkey| AccountMeta {
         pubkey,
         is_writable: false,
@@ -301,6 +330,7 @@ async fn derive_liquidation_remaining_account_metas(
         .chain(perp_markets.into_iter().map(to_account_meta))
         .chain(perp_oracles.into_iter().map(to_account_meta))
         .chain(serum_oos.map(to_account_meta))
+        .chain(fallback_oracles.into_iter().map(to_account_meta))
         .collect()
 }
 
@@ -627,6 +657,64 @@ impl ClientInstruction for TokenWithdrawInstruction {
     }
 }
 
+pub struct TokenTransferInstruction {
+    pub amount: u64,
+    pub allow_borrow: bool,
+
+    pub source_account: Pubkey,
+    pub destination_account: Pubkey,
+    pub owner: TestKeypair,
+    pub bank: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenTransferInstruction {
+    type Accounts = mango_v4::accounts::TokenTransfer;
+    type Instruction = mango_v4::instruction::TokenTransfer;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            amount: self.amount,
+            allow_borrow: self.allow_borrow,
+        };
+
+        let source_account = account_loader
+            .load_mango_account(&self.source_account)
+            .await
+            .unwrap();
+        let bank: Bank = account_loader.load(&self.bank).await.unwrap();
+
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &source_account,
+            Some(self.bank),
+            false,
+            None,
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: source_account.fixed.group,
+            source_account: self.source_account,
+            destination_account: self.destination_account,
+            owner: self.owner.pubkey(),
+            bank: self.bank,
+            oracle: bank.oracle,
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
 pub struct TokenDepositInstruction {
     pub amount: u64,
     pub reduce_only: bool,
@@ -1074,6 +1162,7 @@ fn token_edit_instruction_default() -> mango_v4::instruction::TokenEdit {
         reduce_only_opt: None,
         name_opt: None,
         force_close_opt: None,
+        fallback_oracle_opt: None,
     }
 }
 
@@ -1140,6 +1229,118 @@ impl ClientInstruction for TokenEditWeights {
     }
 }
 
+pub struct TokenEditInsuranceFund {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub mint: Pubkey,
+    pub insurance_fund_index: u16,
+}
+
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenEditInsuranceFund {
+    type Accounts = mango_v4::accounts::TokenEditInsuranceFund;
+    type Instruction = mango_v4::instruction::TokenEditInsuranceFund;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            fund_index: self.insurance_fund_index,
+        };
+
+        let mint_info = Pubkey::find_program_address(
+            &[
+                b"MintInfo".as_ref(),
+                self.group.as_ref(),
+                self.mint.as_ref(),
+            ],
+            &program_id,
+        )
+        .0;
+
+        let accounts = Self::Accounts {
+            group: self.group,
+            admin: self.admin.pubkey(),
+            mint_info,
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(insurance_fund_vault_meta(
+            self.group,
+            self.insurance_fund_index,
+        ));
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.admin]
+    }
+}
+
+pub struct TokenEditOracles {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub mint: Pubkey,
+    pub oracle: Pubkey,
+    pub conf_filter: f32,
+    pub fallback_oracle: Pubkey,
+}
+
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenEditOracles {
+    type Accounts = mango_v4::accounts::TokenEdit;
+    type Instruction = mango_v4::instruction::TokenEdit;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+
+        let mint_info_key = Pubkey::find_program_address(
+            &[
+                b"MintInfo".as_ref(),
+                self.group.as_ref(),
+                self.mint.as_ref(),
+            ],
+            &program_id,
+        )
+        .0;
+        let mint_info: MintInfo = account_loader.load(&mint_info_key).await.unwrap();
+
+        let instruction = Self::Instruction {
+            oracle_opt: Some(self.oracle),
+            oracle_config_opt: Some(OracleConfigParams {
+                conf_filter: self.conf_filter,
+                max_staleness_slots: None,
+            }),
+            fallback_oracle_opt: Some(self.fallback_oracle),
+            ..token_edit_instruction_default()
+        };
+
+        let accounts = Self::Accounts {
+            group: self.group,
+            admin: self.admin.pubkey(),
+            mint_info: mint_info_key,
+            oracle: self.oracle,
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction
+            .accounts
+            .extend(mint_info.banks().iter().map(|&k| AccountMeta {
+                pubkey: k,
+                is_signer: false,
+                is_writable: true,
+            }));
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.admin]
+    }
+}
+
 pub struct TokenResetStablePriceModel {
     pub group: Pubkey,
     pub admin: TestKeypair,
@@ -1625,24 +1826,32 @@ impl ClientInstruction for IxGateSetInstruction {
     }
 }
 
-pub struct GroupCloseInstruction {
-    pub admin: TestKeypair,
+pub struct InsuranceFundCreateInstruction {
     pub group: Pubkey,
-    pub sol_destination: Pubkey,
+    pub admin: TestKeypair,
+    pub payer: TestKeypair,
+    pub fund_index: u16,
 }
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for GroupCloseInstruction {
-    type Accounts = mango_v4::accounts::GroupClose;
-    type Instruction = mango_v4::instruction::GroupClose;
+impl ClientInstruction for InsuranceFundCreateInstruction {
+    type Accounts = mango_v4::accounts::InsuranceFundCreate;
+    type Instruction = mango_v4::instruction::InsuranceFundCreate;
     async fn to_instruction(
         &self,
-        _account_loader: impl ClientAccountLoader + 'async_trait,
+        account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
-        let instruction = Self::Instruction {};
+        let instruction = Self::Instruction {
+            fund_index: self.fund_index,
+        };
 
+        let group: Group = account_loader.load(&self.group).await.unwrap();
         let insurance_vault = Pubkey::find_program_address(
-            &[b"InsuranceVault".as_ref(), self.group.as_ref()],
+            &[
+                b"InsuranceVault".as_ref(),
+                self.group.as_ref(),
+                &self.fund_index.to_le_bytes(),
+            ],
             &program_id,
         )
         .0;
@@ -1650,9 +1859,12 @@ impl ClientInstruction for GroupCloseInstruction {
         let accounts = Self::Accounts {
             group: self.group,
             admin: self.admin.pubkey(),
+            insurance_mint: group.insurance_mint,
             insurance_vault,
-            sol_destination: self.sol_destination,
+            payer: self.payer.pubkey(),
             token_program: Token::id(),
+            system_program: System::id(),
+            rent: sysvar::rent::Rent::id(),
         };
 
         let instruction = make_instruction(program_id, &accounts, &instruction);
@@ -1660,55 +1872,47 @@ impl ClientInstruction for GroupCloseInstruction {
     }
 
     fn signers(&self) -> Vec<TestKeypair> {
-        vec![self.admin]
+        vec![self.admin, self.payer]
     }
 }
 
-pub struct AccountCreateInstruction {
-    pub account_num: u32,
-    pub token_count: u8,
-    pub serum3_count: u8,
-    pub perp_count: u8,
-    pub perp_oo_count: u8,
+pub struct InsuranceFundWithdrawInstruction {
     pub group: Pubkey,
-    pub owner: TestKeypair,
-    pub payer: TestKeypair,
+    pub admin: TestKeypair,
+    pub fund_index: u16,
+    pub destination: Pubkey,
+    pub amount: u64,
 }
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for AccountCreateInstruction {
-    type Accounts = mango_v4::accounts::AccountCreate;
-    type Instruction = mango_v4::instruction::AccountCreate;
+impl ClientInstruction for InsuranceFundWithdrawInstruction {
+    type Accounts = mango_v4::accounts::InsuranceFundWithdraw;
+    type Instruction = mango_v4::instruction::InsuranceFundWithdraw;
     async fn to_instruction(
         &self,
         _account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
-        let instruction = mango_v4::instruction::AccountCreate {
-            account_num: self.account_num,
-            token_count: self.token_count,
-            serum3_count: self.serum3_count,
-            perp_count: self.perp_count,
-            perp_oo_count: self.perp_oo_count,
-            name: "my_mango_account".to_string(),
+        let instruction = Self::Instruction {
+            fund_index: self.fund_index,
+            amount: self.amount,
         };
 
-        let account = Pubkey::find_program_address(
+        let insurance_vault = Pubkey::find_program_address(
             &[
-                b"MangoAccount".as_ref(),
+                b"InsuranceVault".as_ref(),
                 self.group.as_ref(),
-                self.owner.pubkey().as_ref(),
-                &self.account_num.to_le_bytes(),
+                &self.fund_index.to_le_bytes(),
             ],
             &program_id,
         )
         .0;
 
-        let accounts = mango_v4::accounts::AccountCreate {
+        let accounts = Self::Accounts {
             group: self.group,
-            owner: self.owner.pubkey(),
-            account,
-            payer: self.payer.pubkey(),
-            system_program: System::id(),
+            admin: self.admin.pubkey(),
+            insurance_vault,
+            destination: self.destination,
+            token_program: Token::id(),
         };
 
         let instruction = make_instruction(program_id, &accounts, &instruction);
@@ -1716,53 +1920,45 @@ impl ClientInstruction for AccountCreateInstruction {
     }
 
     fn signers(&self) -> Vec<TestKeypair> {
-        vec![self.owner, self.payer]
+        vec![self.admin]
     }
 }
 
-pub struct AccountExpandInstruction {
-    pub account_num: u32,
+pub struct InsuranceFundCloseInstruction {
     pub group: Pubkey,
-    pub owner: TestKeypair,
-    pub payer: TestKeypair,
-    pub token_count: u8,
-    pub serum3_count: u8,
-    pub perp_count: u8,
-    pub perp_oo_count: u8,
+    pub admin: TestKeypair,
+    pub fund_index: u16,
+    pub sol_destination: Pubkey,
 }
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for AccountExpandInstruction {
-    type Accounts = mango_v4::accounts::AccountExpand;
-    type Instruction = mango_v4::instruction::AccountExpand;
+impl ClientInstruction for InsuranceFundCloseInstruction {
+    type Accounts = mango_v4::accounts::InsuranceFundClose;
+    type Instruction = mango_v4::instruction::InsuranceFundClose;
     async fn to_instruction(
         &self,
         _account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
-        let instruction = mango_v4::instruction::AccountExpand {
-            token_count: self.token_count,
-            serum3_count: self.serum3_count,
-            perp_count: self.perp_count,
-            perp_oo_count: self.perp_oo_count,
+        let instruction = Self::Instruction {
+            fund_index: self.fund_index,
         };
 
-        let account = Pubkey::find_program_address(
+        let insurance_vault = Pubkey::find_program_address(
             &[
-                b"MangoAccount".as_ref(),
+                b"InsuranceVault".as_ref(),
                 self.group.as_ref(),
-                self.owner.pubkey().as_ref(),
-                &self.account_num.to_le_bytes(),
+                &self.fund_index.to_le_bytes(),
             ],
             &program_id,
         )
         .0;
 
-        let accounts = mango_v4::accounts::AccountExpand {
+        let accounts = Self::Accounts {
             group: self.group,
-            account,
-            owner: self.owner.pubkey(),
-            payer: self.payer.pubkey(),
-            system_program: System::id(),
+            admin: self.admin.pubkey(),
+            insurance_vault,
+            sol_destination: self.sol_destination,
+            token_program: Token::id(),
         };
 
         let instruction = make_instruction(program_id, &accounts, &instruction);
@@ -1770,21 +1966,248 @@ impl ClientInstruction for AccountExpandInstruction {
     }
 
     fn signers(&self) -> Vec<TestKeypair> {
-        vec![self.owner, self.payer]
+        vec![self.admin]
     }
 }
 
-pub struct AccountEditInstruction {
-    pub account_num: u32,
-    pub group: Pubkey,
-    pub owner: TestKeypair,
-    pub name: String,
-    pub delegate: Pubkey,
-}
-#[async_trait::async_trait(?Send)]
-impl ClientInstruction for AccountEditInstruction {
-    type Accounts = mango_v4::accounts::AccountEdit;
-    type Instruction = mango_v4::instruction::AccountEdit;
+// The vault of an isolated insurance fund, needed when switching a token or
+// perp market to it
+fn insurance_fund_vault_meta(group: Pubkey, fund_index: u16) -> Option<AccountMeta> {
+    if fund_index == 0 {
+        return None;
+    }
+    let insurance_vault = Pubkey::find_program_address(
+        &[
+            b"InsuranceVault".as_ref(),
+            group.as_ref(),
+            &fund_index.to_le_bytes(),
+        ],
+        &mango_v4::id(),
+    )
+    .0;
+    Some(AccountMeta {
+        pubkey: insurance_vault,
+        is_signer: false,
+        is_writable: false,
+    })
+}
+
+pub struct GroupCloseInstruction {
+    pub admin: TestKeypair,
+    pub group: Pubkey,
+    pub sol_destination: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for GroupCloseInstruction {
+    type Accounts = mango_v4::accounts::GroupClose;
+    type Instruction = mango_v4::instruction::GroupClose;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {};
+
+        let insurance_vault = Pubkey::find_program_address(
+            &[b"InsuranceVault".as_ref(), self.group.as_ref()],
+            &program_id,
+        )
+        .0;
+
+        let accounts = Self::Accounts {
+            group: self.group,
+            admin: self.admin.pubkey(),
+            insurance_vault,
+            sol_destination: self.sol_destination,
+            token_program: Token::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.admin]
+    }
+}
+
+pub struct AccountCreateInstruction {
+    pub account_num: u32,
+    pub token_count: u8,
+    pub serum3_count: u8,
+    pub perp_count: u8,
+    pub perp_oo_count: u8,
+    pub group: Pubkey,
+    pub owner: TestKeypair,
+    pub payer: TestKeypair,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for AccountCreateInstruction {
+    type Accounts = mango_v4::accounts::AccountCreate;
+    type Instruction = mango_v4::instruction::AccountCreate;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = mango_v4::instruction::AccountCreate {
+            account_num: self.account_num,
+            token_count: self.token_count,
+            serum3_count: self.serum3_count,
+            perp_count: self.perp_count,
+            perp_oo_count: self.perp_oo_count,
+            name: "my_mango_account".to_string(),
+        };
+
+        let account = Pubkey::find_program_address(
+            &[
+                b"MangoAccount".as_ref(),
+                self.group.as_ref(),
+                self.owner.pubkey().as_ref(),
+                &self.account_num.to_le_bytes(),
+            ],
+            &program_id,
+        )
+        .0;
+
+        let accounts = mango_v4::accounts::AccountCreate {
+            group: self.group,
+            owner: self.owner.pubkey(),
+            account,
+            payer: self.payer.pubkey(),
+            system_program: System::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner, self.payer]
+    }
+}
+
+pub struct AccountExpandInstruction {
+    pub account_num: u32,
+    pub group: Pubkey,
+    pub owner: TestKeypair,
+    pub payer: TestKeypair,
+    pub token_count: u8,
+    pub serum3_count: u8,
+    pub perp_count: u8,
+    pub perp_oo_count: u8,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for AccountExpandInstruction {
+    type Accounts = mango_v4::accounts::AccountExpand;
+    type Instruction = mango_v4::instruction::AccountExpand;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = mango_v4::instruction::AccountExpand {
+            token_count: self.token_count,
+            serum3_count: self.serum3_count,
+            perp_count: self.perp_count,
+            perp_oo_count: self.perp_oo_count,
+        };
+
+        let account = Pubkey::find_program_address(
+            &[
+                b"MangoAccount".as_ref(),
+                self.group.as_ref(),
+                self.owner.pubkey().as_ref(),
+                &self.account_num.to_le_bytes(),
+            ],
+            &program_id,
+        )
+        .0;
+
+        let accounts = mango_v4::accounts::AccountExpand {
+            group: self.group,
+            account,
+            owner: self.owner.pubkey(),
+            payer: self.payer.pubkey(),
+            system_program: System::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner, self.payer]
+    }
+}
+
+pub struct AccountExpandV2Instruction {
+    pub account_num: u32,
+    pub group: Pubkey,
+    pub owner: TestKeypair,
+    pub payer: TestKeypair,
+    pub token_count: u8,
+    pub serum3_count: u8,
+    pub perp_count: u8,
+    pub perp_oo_count: u8,
+    pub perp_trigger_order_count: u8,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for AccountExpandV2Instruction {
+    type Accounts = mango_v4::accounts::AccountExpand;
+    type Instruction = mango_v4::instruction::AccountExpandV2;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = mango_v4::instruction::AccountExpandV2 {
+            token_count: self.token_count,
+            serum3_count: self.serum3_count,
+            perp_count: self.perp_count,
+            perp_oo_count: self.perp_oo_count,
+            perp_trigger_order_count: self.perp_trigger_order_count,
+        };
+
+        let account = Pubkey::find_program_address(
+            &[
+                b"MangoAccount".as_ref(),
+                self.group.as_ref(),
+                self.owner.pubkey().as_ref(),
+                &self.account_num.to_le_bytes(),
+            ],
+            &program_id,
+        )
+        .0;
+
+        let accounts = mango_v4::accounts::AccountExpand {
+            group: self.group,
+            account,
+            owner: self.owner.pubkey(),
+            payer: self.payer.pubkey(),
+            system_program: System::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner, self.payer]
+    }
+}
+
+pub struct AccountEditInstruction {
+    pub account_num: u32,
+    pub group: Pubkey,
+    pub owner: TestKeypair,
+    pub name: String,
+    pub delegate: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for AccountEditInstruction {
+    type Accounts = mango_v4::accounts::AccountEdit;
+    type Instruction = mango_v4::instruction::AccountEdit;
     async fn to_instruction(
         &self,
         _account_loader: impl ClientAccountLoader + 'async_trait,
@@ -2811,11 +3234,10 @@ impl ClientInstruction for TokenLiqBankruptcyInstruction {
         .0;
         let quote_mint_info: MintInfo = account_loader.load(&quote_mint_info).await.unwrap();
 
-        let insurance_vault = Pubkey::find_program_address(
-            &[b"InsuranceVault".as_ref(), group_key.as_ref()],
-            &program_id,
-        )
-        .0;
+        let insurance_vault = liab_mint_info
+            .covering_insurance_fund()
+            .map(|fund_index| group.insurance_fund_vault(&group_key, fund_index).unwrap())
+            .unwrap_or(group.insurance_vault);
 
         let accounts = Self::Accounts {
             group: group_key,
@@ -3007,6 +3429,12 @@ fn perp_edit_instruction_default() -> mango_v4::instruction::PerpEditMarket {
         positive_pnl_liquidation_fee_opt: None,
         name_opt: None,
         force_close_opt: None,
+        fallback_oracle_opt: None,
+        expiry_timestamp_opt: None,
+        settlement_window_ts_opt: None,
+        settlement_uses_stable_price_opt: None,
+        funding_model_opt: None,
+        funding_interval_ts_opt: None,
     }
 }
 
@@ -3049,6 +3477,45 @@ impl ClientInstruction for PerpResetStablePriceModel {
     }
 }
 
+pub struct PerpEditInsuranceFund {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub perp_market: Pubkey,
+    pub insurance_fund_index: u16,
+}
+
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpEditInsuranceFund {
+    type Accounts = mango_v4::accounts::PerpEditInsuranceFund;
+    type Instruction = mango_v4::instruction::PerpEditInsuranceFund;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            fund_index: self.insurance_fund_index,
+        };
+
+        let accounts = Self::Accounts {
+            group: self.group,
+            admin: self.admin.pubkey(),
+            perp_market: self.perp_market,
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(insurance_fund_vault_meta(
+            self.group,
+            self.insurance_fund_index,
+        ));
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.admin]
+    }
+}
+
 pub struct PerpSetSettleLimitWindow {
     pub group: Pubkey,
     pub admin: TestKeypair,
@@ -3131,11 +3598,53 @@ impl ClientInstruction for PerpMakeReduceOnly {
     }
 }
 
-pub struct PerpChangeWeights {
+pub struct PerpSetExpiry {
     pub group: Pubkey,
     pub admin: TestKeypair,
     pub perp_market: Pubkey,
-    pub init_overall_asset_weight: f32,
+    pub expiry_timestamp: u64,
+    pub settlement_window_ts: u64,
+}
+
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpSetExpiry {
+    type Accounts = mango_v4::accounts::PerpEditMarket;
+    type Instruction = mango_v4::instruction::PerpEditMarket;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+
+        let perp_market: PerpMarket = account_loader.load(&self.perp_market).await.unwrap();
+
+        let instruction = Self::Instruction {
+            expiry_timestamp_opt: Some(self.expiry_timestamp),
+            settlement_window_ts_opt: Some(self.settlement_window_ts),
+            ..perp_edit_instruction_default()
+        };
+
+        let accounts = Self::Accounts {
+            group: self.group,
+            admin: self.admin.pubkey(),
+            perp_market: self.perp_market,
+            oracle: perp_market.oracle,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.admin]
+    }
+}
+
+pub struct PerpChangeWeights {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub perp_market: Pubkey,
+    pub init_overall_asset_weight: f32,
     pub maint_overall_asset_weight: f32,
 }
 
@@ -3310,6 +3819,77 @@ impl ClientInstruction for PerpPlaceOrderInstruction {
     }
 }
 
+pub struct PerpPlaceOrderV2Instruction {
+    pub account: Pubkey,
+    pub perp_market: Pubkey,
+    pub owner: TestKeypair,
+    pub side: Side,
+    pub price_lots: i64,
+    pub max_base_lots: i64,
+    pub max_quote_lots: i64,
+    pub reduce_only: bool,
+    pub client_order_id: u64,
+    pub self_trade_behavior: SelfTradeBehavior,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpPlaceOrderV2Instruction {
+    type Accounts = mango_v4::accounts::PerpPlaceOrderV2;
+    type Instruction = mango_v4::instruction::PerpPlaceOrderV2;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            side: self.side,
+            price_lots: self.price_lots,
+            max_base_lots: self.max_base_lots,
+            max_quote_lots: self.max_quote_lots,
+            client_order_id: self.client_order_id,
+            order_type: PlaceOrderType::Limit,
+            reduce_only: self.reduce_only,
+            expiry_timestamp: 0,
+            limit: 10,
+            self_trade_behavior: self.self_trade_behavior,
+        };
+
+        let perp_market: PerpMarket = account_loader.load(&self.perp_market).await.unwrap();
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            None,
+            false,
+            Some(perp_market.perp_market_index),
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            v1: mango_v4::accounts::PerpPlaceOrder {
+                group: account.fixed.group,
+                account: self.account,
+                perp_market: self.perp_market,
+                bids: perp_market.bids,
+                asks: perp_market.asks,
+                event_queue: perp_market.event_queue,
+                oracle: perp_market.oracle,
+                owner: self.owner.pubkey(),
+            },
+        };
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas);
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
 pub struct PerpPlaceOrderPeggedInstruction {
     pub account: Pubkey,
     pub perp_market: Pubkey,
@@ -3453,6 +4033,213 @@ impl ClientInstruction for PerpCancelOrderByClientOrderIdInstruction {
     }
 }
 
+pub struct PerpCancelReplaceOrdersInstruction {
+    pub account: Pubkey,
+    pub perp_market: Pubkey,
+    pub owner: TestKeypair,
+    pub cancel: PerpCancelOrders,
+    pub new_orders: Vec<PerpNewOrder>,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpCancelReplaceOrdersInstruction {
+    type Accounts = mango_v4::accounts::PerpCancelReplaceOrders;
+    type Instruction = mango_v4::instruction::PerpCancelReplaceOrders;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            cancel: self.cancel.clone(),
+            new_orders: self.new_orders.clone(),
+            limit: 10,
+            self_trade_behavior: SelfTradeBehavior::DecrementTake,
+        };
+
+        let perp_market: PerpMarket = account_loader.load(&self.perp_market).await.unwrap();
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            None,
+            false,
+            Some(perp_market.perp_market_index),
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            perp_market: self.perp_market,
+            bids: perp_market.bids,
+            asks: perp_market.asks,
+            event_queue: perp_market.event_queue,
+            oracle: perp_market.oracle,
+            owner: self.owner.pubkey(),
+        };
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas);
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+#[derive(Clone)]
+pub struct PerpPlaceTriggerOrderInstruction {
+    pub account: Pubkey,
+    pub perp_market: Pubkey,
+    pub owner: TestKeypair,
+    pub side: Side,
+    pub trigger_condition: PerpTriggerCondition,
+    pub trigger_price: f64,
+    pub price_lots: i64,
+    pub max_base_lots: i64,
+    pub reduce_only: bool,
+    pub expiry_timestamp: u64,
+    pub incentive: u64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpPlaceTriggerOrderInstruction {
+    type Accounts = mango_v4::accounts::PerpPlaceTriggerOrder;
+    type Instruction = mango_v4::instruction::PerpPlaceTriggerOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            side: self.side,
+            trigger_condition: self.trigger_condition,
+            trigger_price: self.trigger_price,
+            price_lots: self.price_lots,
+            max_base_lots: self.max_base_lots,
+            max_quote_lots: i64::MAX,
+            client_order_id: 0,
+            reduce_only: self.reduce_only,
+            expiry_timestamp: self.expiry_timestamp,
+            incentive: self.incentive,
+        };
+        let perp_market: PerpMarket = account_loader.load(&self.perp_market).await.unwrap();
+        let accounts = Self::Accounts {
+            group: perp_market.group,
+            account: self.account,
+            owner: self.owner.pubkey(),
+            perp_market: self.perp_market,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct PerpCancelTriggerOrderInstruction {
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+    pub trigger_order_id: u64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpCancelTriggerOrderInstruction {
+    type Accounts = mango_v4::accounts::PerpCancelTriggerOrder;
+    type Instruction = mango_v4::instruction::PerpCancelTriggerOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            trigger_order_id: self.trigger_order_id,
+        };
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            owner: self.owner.pubkey(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+#[derive(Clone)]
+pub struct PerpExecuteTriggerOrderInstruction {
+    pub account: Pubkey,
+    pub perp_market: Pubkey,
+    pub executor: Pubkey,
+    pub executor_owner: TestKeypair,
+    pub settle_bank: Pubkey,
+    pub trigger_order_id: u64,
+    pub limit: u8,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpExecuteTriggerOrderInstruction {
+    type Accounts = mango_v4::accounts::PerpExecuteTriggerOrder;
+    type Instruction = mango_v4::instruction::PerpExecuteTriggerOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            trigger_order_id: self.trigger_order_id,
+            limit: self.limit,
+        };
+
+        let perp_market: PerpMarket = account_loader.load(&self.perp_market).await.unwrap();
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            None,
+            false,
+            Some(perp_market.perp_market_index),
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            executor: self.executor,
+            executor_owner: self.executor_owner.pubkey(),
+            perp_market: self.perp_market,
+            bids: perp_market.bids,
+            asks: perp_market.asks,
+            event_queue: perp_market.event_queue,
+            oracle: perp_market.oracle,
+            settle_bank: self.settle_bank,
+        };
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas);
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.executor_owner]
+    }
+}
+
 pub struct PerpCancelAllOrdersInstruction {
     pub account: Pubkey,
     pub perp_market: Pubkey,
@@ -3525,6 +4312,7 @@ impl ClientInstruction for PerpConsumeEventsInstruction {
     }
 }
 
+#[derive(Clone)]
 pub struct PerpUpdateFundingInstruction {
     pub perp_market: Pubkey,
     pub bank: Pubkey,
@@ -3558,6 +4346,7 @@ impl ClientInstruction for PerpUpdateFundingInstruction {
     }
 }
 
+#[derive(Clone)]
 pub struct PerpSettlePnlInstruction {
     pub settler: Pubkey,
     pub settler_owner: TestKeypair,
@@ -3657,6 +4446,43 @@ impl ClientInstruction for PerpForceClosePositionInstruction {
     }
 }
 
+#[derive(Clone)]
+pub struct PerpCloseExpiredPositionInstruction {
+    pub account_a: Pubkey,
+    pub account_b: Pubkey,
+    pub perp_market: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpCloseExpiredPositionInstruction {
+    type Accounts = mango_v4::accounts::PerpCloseExpiredPosition;
+    type Instruction = mango_v4::instruction::PerpCloseExpiredPosition;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {};
+
+        let perp_market: PerpMarket = account_loader.load(&self.perp_market).await.unwrap();
+
+        let accounts = Self::Accounts {
+            group: perp_market.group,
+            perp_market: self.perp_market,
+            account_a: self.account_a,
+            account_b: self.account_b,
+            oracle: perp_market.oracle,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![]
+    }
+}
+
 pub struct PerpSettleFeesInstruction {
     pub account: Pubkey,
     pub perp_market: Pubkey,
@@ -3897,7 +4723,10 @@ impl ClientInstruction for PerpLiqNegativePnlOrBankruptcyInstruction {
             settle_bank: quote_mint_info.first_bank(),
             settle_vault: quote_mint_info.first_vault(),
             settle_oracle: quote_mint_info.oracle,
-            insurance_vault: group.insurance_vault,
+            insurance_vault: perp_market
+                .covering_insurance_fund()
+                .map(|fund_index| group.insurance_fund_vault(&group_key, fund_index).unwrap())
+                .unwrap_or(group.insurance_vault),
             token_program: Token::id(),
         };
         let mut instruction = make_instruction(program_id, &accounts, &instruction);
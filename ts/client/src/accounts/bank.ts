@@ -116,6 +116,7 @@ export class Bank implements BankForHealth {
       depositWeightScaleStartQuote: number;
       reduceOnly: number;
       forceClose: number;
+      fallbackOracle: PublicKey;
     },
   ): Bank {
     return new Bank(
@@ -163,6 +164,7 @@ export class Bank implements BankForHealth {
       obj.depositWeightScaleStartQuote,
       obj.reduceOnly,
       obj.forceClose == 1,
+      obj.fallbackOracle,
     );
   }
 
@@ -211,6 +213,7 @@ export class Bank implements BankForHealth {
     public depositWeightScaleStartQuote: number,
     public reduceOnly: number,
     public forceClose: boolean,
+    public fallbackOracle: PublicKey,
   ) {
     this.name = utf8.decode(new Uint8Array(name)).split('\x00')[0];
     this.oracleConfig = {
@@ -52,6 +52,7 @@ export class PerpMarket {
   public maintOverallAssetWeight: I80F48;
   public initOverallAssetWeight: I80F48;
   public positivePnlLiquidationFee: I80F48;
+  public settlementPrice: I80F48;
 
   public _price: I80F48;
   public _uiPrice: number;
@@ -111,6 +112,10 @@ export class PerpMarket {
       maintOverallAssetWeight: I80F48Dto;
       initOverallAssetWeight: I80F48Dto;
       positivePnlLiquidationFee: I80F48Dto;
+      fallbackOracle: PublicKey;
+      expiryTimestamp: BN;
+      settlementPrice: I80F48Dto;
+      hasSettlementPrice: number;
     },
   ): PerpMarket {
     return new PerpMarket(
@@ -158,6 +163,10 @@ export class PerpMarket {
       obj.maintOverallAssetWeight,
       obj.initOverallAssetWeight,
       obj.positivePnlLiquidationFee,
+      obj.fallbackOracle,
+      obj.expiryTimestamp,
+      obj.settlementPrice,
+      obj.hasSettlementPrice == 1,
     );
   }
 
@@ -206,6 +215,10 @@ export class PerpMarket {
     maintOverallAssetWeight: I80F48Dto,
     initOverallAssetWeight: I80F48Dto,
     positivePnlLiquidationFee: I80F48Dto,
+    public fallbackOracle: PublicKey,
+    public expiryTimestamp: BN,
+    settlementPrice: I80F48Dto,
+    public hasSettlementPrice: boolean,
   ) {
     this.name = utf8.decode(new Uint8Array(name)).split('\x00')[0];
     this.oracleConfig = {
@@ -228,6 +241,7 @@ export class PerpMarket {
     this.maintOverallAssetWeight = I80F48.from(maintOverallAssetWeight);
     this.initOverallAssetWeight = I80F48.from(initOverallAssetWeight);
     this.positivePnlLiquidationFee = I80F48.from(positivePnlLiquidationFee);
+    this.settlementPrice = I80F48.from(settlementPrice);
 
     this.priceLotsToUiConverter = new Big(10)
       .pow(baseDecimals - QUOTE_DECIMALS)
@@ -875,6 +889,13 @@ export class PerpOrderType {
   static postOnly = { postOnly: {} };
   static market = { market: {} };
   static postOnlySlide = { postOnlySlide: {} };
+  static fillOrKill = { fillOrKill: {} };
+}
+
+export class PerpFundingModel {
+  static impactPrice = { impactPrice: {} };
+  static premiumTwap = { premiumTwap: {} };
+  static stablePrice = { stablePrice: {} };
 }
 
 export class PerpOrder {
@@ -1,4 +1,10 @@
-import { AnchorProvider, BN, Program, Provider } from '@coral-xyz/anchor';
+import {
+  AnchorProvider,
+  BN,
+  IdlAccounts,
+  Program,
+  Provider,
+} from '@coral-xyz/anchor';
 import {
   createCloseAccountInstruction,
   createInitializeAccount3Instruction,
@@ -22,6 +28,7 @@ import {
 import bs58 from 'bs58';
 import cloneDeep from 'lodash/cloneDeep';
 import uniq from 'lodash/uniq';
+import uniqBy from 'lodash/uniqBy';
 import { Bank, MintInfo, TokenIndex } from './accounts/bank';
 import { Group } from './accounts/group';
 import {
@@ -231,6 +238,26 @@ export class MangoClient {
     return await this.sendAndConfirmTransactionForGroup(group, [ix]);
   }
 
+  /**
+   * The vault of an isolated insurance fund, which must be passed when a token
+   * or perp market is switched to it. Index 0 is the group insurance fund.
+   */
+  private insuranceFundVaultAccounts(
+    group: Group,
+    fundIndex: number,
+  ): AccountMeta[] {
+    if (fundIndex === 0) {
+      return [];
+    }
+    const fundIndexBuf = Buffer.alloc(2);
+    fundIndexBuf.writeUInt16LE(fundIndex);
+    const [insuranceVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from('InsuranceVault'), group.publicKey.toBuffer(), fundIndexBuf],
+      this.program.programId,
+    );
+    return [{ pubkey: insuranceVault, isWritable: false, isSigner: false }];
+  }
+
   public async getGroup(groupPk: PublicKey): Promise<Group> {
     const groupAccount = await this.program.account.group.fetch(groupPk);
     const group = Group.from(groupPk, groupAccount);
@@ -402,6 +429,7 @@ export class MangoClient {
         params.reduceOnly,
         params.name,
         params.forceClose,
+        params.fallbackOracle,
       )
       .accounts({
         group: group.publicKey,
@@ -417,7 +445,26 @@ export class MangoClient {
         } as AccountMeta,
       ])
       .instruction();
-    return await this.sendAndConfirmTransactionForGroup(group, [ix]);
+    const ixs = [ix];
+
+    // Switching the insurance fund is a separate instruction
+    if (params.insuranceFundIndex !== null) {
+      ixs.push(
+        await this.program.methods
+          .tokenEditInsuranceFund(params.insuranceFundIndex)
+          .accounts({
+            group: group.publicKey,
+            admin: (this.program.provider as AnchorProvider).wallet.publicKey,
+            mintInfo: mintInfo.publicKey,
+          })
+          .remainingAccounts(
+            this.insuranceFundVaultAccounts(group, params.insuranceFundIndex),
+          )
+          .instruction(),
+      );
+    }
+
+    return await this.sendAndConfirmTransactionForGroup(group, ixs);
   }
 
   public async tokenForceCloseBorrowsWithToken(
@@ -804,6 +851,36 @@ export class MangoClient {
     return await this.sendAndConfirmTransactionForGroup(group, [ix]);
   }
 
+  /**
+   * Accounts that were created before perp trigger orders existed end after
+   * the perp open orders. Like the program, decode them as having no trigger
+   * orders.
+   */
+  private decodeMangoAccount(
+    data: Buffer,
+  ): IdlAccounts<MangoV4>['mangoAccount'] {
+    // Trailing bytes are ignored, the appended zeros are only read as the
+    // padding and length of the trigger order vec when that section is missing
+    return this.program.coder.accounts.decode(
+      'mangoAccount',
+      Buffer.concat([data, Buffer.alloc(8)]),
+    );
+  }
+
+  private async getMangoAccountsWithFilters(
+    filters: MemcmpFilter[],
+  ): Promise<MangoAccount[]> {
+    const accounts = await this.connection.getProgramAccounts(this.programId, {
+      filters: [
+        { memcmp: this.program.coder.accounts.memcmp('mangoAccount') },
+        ...filters,
+      ],
+    });
+    return accounts.map((pa) =>
+      MangoAccount.from(pa.pubkey, this.decodeMangoAccount(pa.account.data)),
+    );
+  }
+
   public async getMangoAccount(
     mangoAccount: MangoAccount | PublicKey,
     loadSerum3Oo = false,
@@ -812,9 +889,13 @@ export class MangoClient {
       mangoAccount instanceof MangoAccount
         ? mangoAccount.publicKey
         : mangoAccount;
+    const ai = await this.connection.getAccountInfo(mangoAccountPk);
+    if (!ai) {
+      throw new Error(`MangoAccount ${mangoAccountPk} not found`);
+    }
     const mangoAccount_ = MangoAccount.from(
       mangoAccountPk,
-      await this.program.account.mangoAccount.fetch(mangoAccountPk),
+      this.decodeMangoAccount(ai.data),
     );
     if (loadSerum3Oo) {
       await mangoAccount_?.reloadSerum3OpenOrders(this);
@@ -831,10 +912,7 @@ export class MangoClient {
         mangoAccountPk,
       );
     if (!resp?.value) return;
-    const decodedMangoAccount = this.program.coder.accounts.decode(
-      'mangoAccount',
-      resp.value.data,
-    );
+    const decodedMangoAccount = this.decodeMangoAccount(resp.value.data);
     const mangoAccount = MangoAccount.from(mangoAccountPk, decodedMangoAccount);
     if (loadSerum3Oo) {
       await mangoAccount?.reloadSerum3OpenOrders(this);
@@ -865,24 +943,20 @@ export class MangoClient {
     ownerPk: PublicKey,
     loadSerum3Oo = false,
   ): Promise<MangoAccount[]> {
-    const accounts = (
-      await this.program.account.mangoAccount.all([
-        {
-          memcmp: {
-            bytes: group.publicKey.toBase58(),
-            offset: 8,
-          },
+    const accounts = await this.getMangoAccountsWithFilters([
+      {
+        memcmp: {
+          bytes: group.publicKey.toBase58(),
+          offset: 8,
         },
-        {
-          memcmp: {
-            bytes: ownerPk.toBase58(),
-            offset: 40,
-          },
+      },
+      {
+        memcmp: {
+          bytes: ownerPk.toBase58(),
+          offset: 40,
         },
-      ])
-    ).map((pa) => {
-      return MangoAccount.from(pa.publicKey, pa.account);
-    });
+      },
+    ]);
 
     if (loadSerum3Oo) {
       await Promise.all(
@@ -898,24 +972,20 @@ export class MangoClient {
     delegate: PublicKey,
     loadSerum3Oo = false,
   ): Promise<MangoAccount[]> {
-    const accounts = (
-      await this.program.account.mangoAccount.all([
-        {
-          memcmp: {
-            bytes: group.publicKey.toBase58(),
-            offset: 8,
-          },
+    const accounts = await this.getMangoAccountsWithFilters([
+      {
+        memcmp: {
+          bytes: group.publicKey.toBase58(),
+          offset: 8,
         },
-        {
-          memcmp: {
-            bytes: delegate.toBase58(),
-            offset: 104,
-          },
+      },
+      {
+        memcmp: {
+          bytes: delegate.toBase58(),
+          offset: 104,
         },
-      ])
-    ).map((pa) => {
-      return MangoAccount.from(pa.publicKey, pa.account);
-    });
+      },
+    ]);
 
     if (loadSerum3Oo) {
       await Promise.all(
@@ -930,18 +1000,14 @@ export class MangoClient {
     group: Group,
     loadSerum3Oo = false,
   ): Promise<MangoAccount[]> {
-    const accounts = (
-      await this.program.account.mangoAccount.all([
-        {
-          memcmp: {
-            bytes: group.publicKey.toBase58(),
-            offset: 8,
-          },
+    const accounts = await this.getMangoAccountsWithFilters([
+      {
+        memcmp: {
+          bytes: group.publicKey.toBase58(),
+          offset: 8,
         },
-      ])
-    ).map((pa) => {
-      return MangoAccount.from(pa.publicKey, pa.account);
-    });
+      },
+    ]);
 
     if (loadSerum3Oo) {
       await Promise.all(
@@ -2112,6 +2178,16 @@ export class MangoClient {
         params.positivePnlLiquidationFee,
         params.name,
         params.forceClose,
+        params.fallbackOracle,
+        params.expiryTimestamp !== null ? new BN(params.expiryTimestamp) : null,
+        params.settlementWindowTs !== null
+          ? new BN(params.settlementWindowTs)
+          : null,
+        params.settlementUsesStablePrice,
+        params.fundingModel,
+        params.fundingIntervalTs !== null
+          ? new BN(params.fundingIntervalTs)
+          : null,
       )
       .accounts({
         group: group.publicKey,
@@ -2120,7 +2196,26 @@ export class MangoClient {
         perpMarket: perpMarket.publicKey,
       })
       .instruction();
-    return await this.sendAndConfirmTransactionForGroup(group, [ix]);
+    const ixs = [ix];
+
+    // Switching the insurance fund is a separate instruction
+    if (params.insuranceFundIndex !== null) {
+      ixs.push(
+        await this.program.methods
+          .perpEditInsuranceFund(params.insuranceFundIndex)
+          .accounts({
+            group: group.publicKey,
+            admin: (this.program.provider as AnchorProvider).wallet.publicKey,
+            perpMarket: perpMarket.publicKey,
+          })
+          .remainingAccounts(
+            this.insuranceFundVaultAccounts(group, params.insuranceFundIndex),
+          )
+          .instruction(),
+      );
+    }
+
+    return await this.sendAndConfirmTransactionForGroup(group, ixs);
   }
 
   public async perpForceClosePosition(
@@ -2238,6 +2333,7 @@ export class MangoClient {
     reduceOnly?: boolean,
     expiryTimestamp?: number,
     limit?: number,
+    selfTradeBehavior?: Serum3SelfTradeBehavior,
   ): Promise<TransactionSignature> {
     const ix = await this.perpPlaceOrderIx(
       group,
@@ -2252,6 +2348,7 @@ export class MangoClient {
       reduceOnly,
       expiryTimestamp,
       limit,
+      selfTradeBehavior,
     );
 
     return await this.sendAndConfirmTransactionForGroup(group, [ix]);
@@ -2270,6 +2367,7 @@ export class MangoClient {
     reduceOnly?: boolean,
     expiryTimestamp?: number,
     limit?: number,
+    selfTradeBehavior?: Serum3SelfTradeBehavior,
   ): Promise<TransactionInstruction> {
     const perpMarket = group.getPerpMarketByMarketIndex(perpMarketIndex);
     const healthRemainingAccounts: PublicKey[] =
@@ -2280,6 +2378,42 @@ export class MangoClient {
         [group.getFirstBankForPerpSettlement()],
         [perpMarket],
       );
+    const accounts = {
+      group: group.publicKey,
+      account: mangoAccount.publicKey,
+      perpMarket: perpMarket.publicKey,
+      bids: perpMarket.bids,
+      asks: perpMarket.asks,
+      eventQueue: perpMarket.eventQueue,
+      oracle: perpMarket.oracle,
+      owner: (this.program.provider as AnchorProvider).wallet.publicKey,
+    };
+    const remainingAccounts = healthRemainingAccounts.map(
+      (pk) =>
+        ({ pubkey: pk, isWritable: false, isSigner: false } as AccountMeta),
+    );
+
+    // v2 is only needed for a self trade behavior other than the default
+    if (selfTradeBehavior) {
+      return await this.program.methods
+        .perpPlaceOrderV2(
+          side,
+          perpMarket.uiPriceToLots(price),
+          perpMarket.uiBaseToLots(quantity),
+          maxQuoteQuantity
+            ? perpMarket.uiQuoteToLots(maxQuoteQuantity)
+            : I64_MAX_BN,
+          new BN(clientOrderId ? clientOrderId : Date.now()),
+          orderType ? orderType : PerpOrderType.limit,
+          reduceOnly ? reduceOnly : false,
+          new BN(expiryTimestamp ? expiryTimestamp : 0),
+          limit ? limit : 10,
+          selfTradeBehavior,
+        )
+        .accounts({ v1: accounts })
+        .remainingAccounts(remainingAccounts)
+        .instruction();
+    }
     return await this.program.methods
       .perpPlaceOrder(
         side,
@@ -2294,22 +2428,8 @@ export class MangoClient {
         new BN(expiryTimestamp ? expiryTimestamp : 0),
         limit ? limit : 10,
       )
-      .accounts({
-        group: group.publicKey,
-        account: mangoAccount.publicKey,
-        perpMarket: perpMarket.publicKey,
-        bids: perpMarket.bids,
-        asks: perpMarket.asks,
-        eventQueue: perpMarket.eventQueue,
-        oracle: perpMarket.oracle,
-        owner: (this.program.provider as AnchorProvider).wallet.publicKey,
-      })
-      .remainingAccounts(
-        healthRemainingAccounts.map(
-          (pk) =>
-            ({ pubkey: pk, isWritable: false, isSigner: false } as AccountMeta),
-        ),
-      )
+      .accounts(accounts)
+      .remainingAccounts(remainingAccounts)
       .instruction();
   }
 
@@ -2327,6 +2447,7 @@ export class MangoClient {
     reduceOnly?: boolean,
     expiryTimestamp?: number,
     limit?: number,
+    selfTradeBehavior?: Serum3SelfTradeBehavior,
   ): Promise<TransactionSignature> {
     const ix = await this.perpPlaceOrderPeggedIx(
       group,
@@ -2342,6 +2463,7 @@ export class MangoClient {
       reduceOnly,
       expiryTimestamp,
       limit,
+      selfTradeBehavior,
     );
 
     return await this.sendAndConfirmTransactionForGroup(group, [ix]);
@@ -2361,6 +2483,7 @@ export class MangoClient {
     reduceOnly?: boolean,
     expiryTimestamp?: number,
     limit?: number,
+    selfTradeBehavior?: Serum3SelfTradeBehavior,
   ): Promise<TransactionInstruction> {
     const perpMarket = group.getPerpMarketByMarketIndex(perpMarketIndex);
     const healthRemainingAccounts: PublicKey[] =
@@ -2371,6 +2494,44 @@ export class MangoClient {
         [group.getFirstBankForPerpSettlement()],
         [perpMarket],
       );
+    const accounts = {
+      group: group.publicKey,
+      account: mangoAccount.publicKey,
+      perpMarket: perpMarket.publicKey,
+      bids: perpMarket.bids,
+      asks: perpMarket.asks,
+      eventQueue: perpMarket.eventQueue,
+      oracle: perpMarket.oracle,
+      owner: (this.program.provider as AnchorProvider).wallet.publicKey,
+    };
+    const remainingAccounts = healthRemainingAccounts.map(
+      (pk) =>
+        ({ pubkey: pk, isWritable: false, isSigner: false } as AccountMeta),
+    );
+
+    // v2 is only needed for a self trade behavior other than the default
+    if (selfTradeBehavior) {
+      return await this.program.methods
+        .perpPlaceOrderPeggedV2(
+          side,
+          perpMarket.uiPriceToLots(priceOffset),
+          pegLimit ? perpMarket.uiPriceToLots(pegLimit) : new BN(-1),
+          perpMarket.uiBaseToLots(quantity),
+          maxQuoteQuantity
+            ? perpMarket.uiQuoteToLots(maxQuoteQuantity)
+            : I64_MAX_BN,
+          new BN(clientOrderId ?? Date.now()),
+          orderType ? orderType : PerpOrderType.limit,
+          reduceOnly ? reduceOnly : false,
+          new BN(expiryTimestamp ?? 0),
+          limit ? limit : 10,
+          -1,
+          selfTradeBehavior,
+        )
+        .accounts({ v1: accounts })
+        .remainingAccounts(remainingAccounts)
+        .instruction();
+    }
     return await this.program.methods
       .perpPlaceOrderPegged(
         side,
@@ -2387,22 +2548,8 @@ export class MangoClient {
         limit ? limit : 10,
         -1,
       )
-      .accounts({
-        group: group.publicKey,
-        account: mangoAccount.publicKey,
-        perpMarket: perpMarket.publicKey,
-        bids: perpMarket.bids,
-        asks: perpMarket.asks,
-        eventQueue: perpMarket.eventQueue,
-        oracle: perpMarket.oracle,
-        owner: (this.program.provider as AnchorProvider).wallet.publicKey,
-      })
-      .remainingAccounts(
-        healthRemainingAccounts.map(
-          (pk) =>
-            ({ pubkey: pk, isWritable: false, isSigner: false } as AccountMeta),
-        ),
-      )
+      .accounts(accounts)
+      .remainingAccounts(remainingAccounts)
       .instruction();
   }
 
@@ -3205,6 +3352,18 @@ export class MangoClient {
         .map((serumPosition) => serumPosition.openOrders),
     );
 
+    // The distinct fallback oracles of all banks and perp markets above go last
+    const fallbackOracles = mintInfos
+      .map(
+        (mintInfo) =>
+          group.getFirstBankByTokenIndex(mintInfo.tokenIndex).fallbackOracle,
+      )
+      .concat(allPerpMarkets.map((perp) => perp.fallbackOracle))
+      .filter((oracle) => !oracle.equals(PublicKey.default));
+    healthRemainingAccounts.push(
+      ...uniqBy(fallbackOracles, (oracle) => oracle.toBase58()),
+    );
+
     return healthRemainingAccounts;
   }
 
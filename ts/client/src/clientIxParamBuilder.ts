@@ -1,5 +1,6 @@
 import { BN } from '@coral-xyz/anchor';
 import { PublicKey } from '@solana/web3.js';
+import { PerpFundingModel } from './accounts/perp';
 import { InterestRateParams, OracleConfigParams } from './types';
 
 export interface TokenEditParams {
@@ -27,6 +28,13 @@ export interface TokenEditParams {
   reduceOnly: number | null;
   name: string | null;
   forceClose: boolean | null;
+  fallbackOracle: PublicKey | null;
+  insuranceFundIndex: number | null;
+  expiryTimestamp: number | null;
+  settlementWindowTs: number | null;
+  settlementUsesStablePrice: boolean | null;
+  fundingModel: PerpFundingModel | null;
+  fundingIntervalTs: number | null;
 }
 
 export const NullTokenEditParams: TokenEditParams = {
@@ -54,6 +62,13 @@ export const NullTokenEditParams: TokenEditParams = {
   reduceOnly: null,
   name: null,
   forceClose: null,
+  fallbackOracle: null,
+  insuranceFundIndex: null,
+  expiryTimestamp: null,
+  settlementWindowTs: null,
+  settlementUsesStablePrice: null,
+  fundingModel: null,
+  fundingIntervalTs: null,
 };
 
 export interface PerpEditParams {
@@ -87,6 +102,8 @@ export interface PerpEditParams {
   positivePnlLiquidationFee: number | null;
   name: string | null;
   forceClose: boolean | null;
+  fallbackOracle: PublicKey | null;
+  insuranceFundIndex: number | null;
 }
 
 export const NullPerpEditParams: PerpEditParams = {
@@ -120,6 +137,8 @@ export const NullPerpEditParams: PerpEditParams = {
   positivePnlLiquidationFee: null,
   name: null,
   forceClose: null,
+  fallbackOracle: null,
+  insuranceFundIndex: null,
 };
 
 // Use with TrueIxGateParams and buildIxGate
@@ -179,6 +198,17 @@ export interface IxGateParams {
   TokenForceCloseBorrowsWithToken: boolean;
   PerpForceClosePosition: boolean;
   GroupWithdrawInsuranceFund: boolean;
+  InsuranceFundCreate: boolean;
+  InsuranceFundWithdraw: boolean;
+  InsuranceFundClose: boolean;
+  PerpPlaceTriggerOrder: boolean;
+  PerpCancelTriggerOrder: boolean;
+  PerpExecuteTriggerOrder: boolean;
+  TokenTransfer: boolean;
+  PerpCancelReplaceOrders: boolean;
+  PerpCloseExpiredPosition: boolean;
+  PerpPlaceOrderV2: boolean;
+  PerpPlaceOrderPeggedV2: boolean;
 }
 
 // Default with all ixs enabled, use with buildIxGate
@@ -238,6 +268,17 @@ export const TrueIxGateParams: IxGateParams = {
   TokenForceCloseBorrowsWithToken: true,
   PerpForceClosePosition: true,
   GroupWithdrawInsuranceFund: true,
+  InsuranceFundCreate: true,
+  InsuranceFundWithdraw: true,
+  InsuranceFundClose: true,
+  PerpPlaceTriggerOrder: true,
+  PerpCancelTriggerOrder: true,
+  PerpExecuteTriggerOrder: true,
+  TokenTransfer: true,
+  PerpCancelReplaceOrders: true,
+  PerpCloseExpiredPosition: true,
+  PerpPlaceOrderV2: true,
+  PerpPlaceOrderPeggedV2: true,
 };
 
 // build ix gate e.g. buildIxGate(Builder(TrueIxGateParams).TokenDeposit(false).build()).toNumber(),
@@ -307,6 +348,17 @@ export function buildIxGate(p: IxGateParams): BN {
   toggleIx(ixGate, p, 'TokenForceCloseBorrowsWithToken', 49);
   toggleIx(ixGate, p, 'PerpForceClosePosition', 50);
   toggleIx(ixGate, p, 'GroupWithdrawInsuranceFund', 51);
+  toggleIx(ixGate, p, 'InsuranceFundCreate', 52);
+  toggleIx(ixGate, p, 'InsuranceFundWithdraw', 53);
+  toggleIx(ixGate, p, 'InsuranceFundClose', 54);
+  toggleIx(ixGate, p, 'PerpPlaceTriggerOrder', 55);
+  toggleIx(ixGate, p, 'PerpCancelTriggerOrder', 56);
+  toggleIx(ixGate, p, 'PerpExecuteTriggerOrder', 57);
+  toggleIx(ixGate, p, 'TokenTransfer', 58);
+  toggleIx(ixGate, p, 'PerpCancelReplaceOrders', 59);
+  toggleIx(ixGate, p, 'PerpCloseExpiredPosition', 60);
+  toggleIx(ixGate, p, 'PerpPlaceOrderV2', 61);
+  toggleIx(ixGate, p, 'PerpPlaceOrderPeggedV2', 62);
 
   return ixGate;
 }
@@ -213,6 +213,204 @@ export type MangoV4 = {
         }
       ]
     },
+    {
+      "name": "insuranceFundCreate",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "insuranceMint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "insuranceVault",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "InsuranceVault"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "fund_index"
+              }
+            ]
+          }
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "fundIndex",
+          "type": "u16"
+        }
+      ]
+    },
+    {
+      "name": "insuranceFundWithdraw",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "insuranceVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "destination",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "fundIndex",
+          "type": "u16"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "insuranceFundClose",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "insuranceVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "solDestination",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "fundIndex",
+          "type": "u16"
+        }
+      ]
+    },
+    {
+      "name": "tokenEditInsuranceFund",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "mintInfo",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "fundIndex",
+          "type": "u16"
+        }
+      ]
+    },
+    {
+      "name": "perpEditInsuranceFund",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "fundIndex",
+          "type": "u16"
+        }
+      ]
+    },
     {
       "name": "ixGateSet",
       "accounts": [
@@ -765,6 +963,12 @@ export type MangoV4 = {
           "type": {
             "option": "bool"
           }
+        },
+        {
+          "name": "fallbackOracleOpt",
+          "type": {
+            "option": "publicKey"
+          }
         }
       ]
     },
@@ -1076,6 +1280,58 @@ export type MangoV4 = {
         }
       ]
     },
+    {
+      "name": "accountExpandV2",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "tokenCount",
+          "type": "u8"
+        },
+        {
+          "name": "serum3Count",
+          "type": "u8"
+        },
+        {
+          "name": "perpCount",
+          "type": "u8"
+        },
+        {
+          "name": "perpOoCount",
+          "type": "u8"
+        },
+        {
+          "name": "perpTriggerOrderCount",
+          "type": "u8"
+        }
+      ]
+    },
     {
       "name": "accountEdit",
       "accounts": [
@@ -1516,25 +1772,74 @@ export type MangoV4 = {
       ]
     },
     {
-      "name": "flashLoanBegin",
+      "name": "tokenTransfer",
+      "docs": [
+        "Move tokens between two accounts of the same owner, without going",
+        "through a wallet token account."
+      ],
       "accounts": [
         {
-          "name": "account",
+          "name": "group",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
+          "name": "sourceAccount",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "tokenProgram",
-          "isMut": false,
+          "name": "destinationAccount",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "instructions",
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "bank",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        },
+        {
+          "name": "allowBorrow",
+          "type": "bool"
+        }
+      ]
+    },
+    {
+      "name": "flashLoanBegin",
+      "accounts": [
+        {
+          "name": "account",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "instructions",
           "isMut": false,
           "isSigner": false,
           "docs": [
@@ -3140,6 +3445,44 @@ export type MangoV4 = {
           "type": {
             "option": "bool"
           }
+        },
+        {
+          "name": "fallbackOracleOpt",
+          "type": {
+            "option": "publicKey"
+          }
+        },
+        {
+          "name": "expiryTimestampOpt",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "settlementWindowTsOpt",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "settlementUsesStablePriceOpt",
+          "type": {
+            "option": "bool"
+          }
+        },
+        {
+          "name": "fundingModelOpt",
+          "type": {
+            "option": {
+              "defined": "PerpFundingModel"
+            }
+          }
+        },
+        {
+          "name": "fundingIntervalTsOpt",
+          "type": {
+            "option": "u64"
+          }
         }
       ]
     },
@@ -3404,179 +3747,230 @@ export type MangoV4 = {
       }
     },
     {
-      "name": "perpCancelOrder",
+      "name": "perpPlaceOrderV2",
+      "docs": [
+        "Like PerpPlaceOrder, but `self_trade_behavior` determines what happens when the",
+        "order would match against an order of the same account."
+      ],
       "accounts": [
         {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "account",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "perpMarket",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "bids",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "asks",
-          "isMut": true,
-          "isSigner": false
+          "name": "v1",
+          "accounts": [
+            {
+              "name": "group",
+              "isMut": false,
+              "isSigner": false
+            },
+            {
+              "name": "account",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "owner",
+              "isMut": false,
+              "isSigner": true
+            },
+            {
+              "name": "perpMarket",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "bids",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "asks",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "eventQueue",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "oracle",
+              "isMut": false,
+              "isSigner": false
+            }
+          ]
         }
       ],
       "args": [
         {
-          "name": "orderId",
-          "type": "u128"
-        }
-      ]
-    },
-    {
-      "name": "perpCancelOrderByClientOrderId",
-      "accounts": [
-        {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "account",
-          "isMut": true,
-          "isSigner": false
+          "name": "side",
+          "type": {
+            "defined": "Side"
+          }
         },
         {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
+          "name": "priceLots",
+          "type": "i64"
         },
         {
-          "name": "perpMarket",
-          "isMut": true,
-          "isSigner": false
+          "name": "maxBaseLots",
+          "type": "i64"
         },
         {
-          "name": "bids",
-          "isMut": true,
-          "isSigner": false
+          "name": "maxQuoteLots",
+          "type": "i64"
         },
-        {
-          "name": "asks",
-          "isMut": true,
-          "isSigner": false
-        }
-      ],
-      "args": [
         {
           "name": "clientOrderId",
           "type": "u64"
-        }
-      ]
-    },
-    {
-      "name": "perpCancelAllOrders",
-      "accounts": [
-        {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
         },
         {
-          "name": "account",
-          "isMut": true,
-          "isSigner": false
+          "name": "orderType",
+          "type": {
+            "defined": "PlaceOrderType"
+          }
         },
         {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
+          "name": "reduceOnly",
+          "type": "bool"
         },
         {
-          "name": "perpMarket",
-          "isMut": true,
-          "isSigner": false
+          "name": "expiryTimestamp",
+          "type": "u64"
         },
         {
-          "name": "bids",
-          "isMut": true,
-          "isSigner": false
+          "name": "limit",
+          "type": "u8"
         },
         {
-          "name": "asks",
-          "isMut": true,
-          "isSigner": false
+          "name": "selfTradeBehavior",
+          "type": {
+            "defined": "Serum3SelfTradeBehavior"
+          }
         }
       ],
-      "args": [
-        {
-          "name": "limit",
-          "type": "u8"
-        }
-      ]
+      "returns": {
+        "option": "u128"
+      }
     },
     {
-      "name": "perpCancelAllOrdersBySide",
+      "name": "perpPlaceOrderPeggedV2",
+      "docs": [
+        "Like PerpPlaceOrderPegged, with the `self_trade_behavior` of PerpPlaceOrderV2."
+      ],
       "accounts": [
         {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "account",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "perpMarket",
-          "isMut": true,
-          "isSigner": false
+          "name": "v1",
+          "accounts": [
+            {
+              "name": "group",
+              "isMut": false,
+              "isSigner": false
+            },
+            {
+              "name": "account",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "owner",
+              "isMut": false,
+              "isSigner": true
+            },
+            {
+              "name": "perpMarket",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "bids",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "asks",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "eventQueue",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "oracle",
+              "isMut": false,
+              "isSigner": false
+            }
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "side",
+          "type": {
+            "defined": "Side"
+          }
         },
         {
-          "name": "bids",
-          "isMut": true,
-          "isSigner": false
+          "name": "priceOffsetLots",
+          "type": "i64"
         },
         {
-          "name": "asks",
-          "isMut": true,
-          "isSigner": false
-        }
-      ],
-      "args": [
+          "name": "pegLimit",
+          "type": "i64"
+        },
         {
-          "name": "sideOption",
+          "name": "maxBaseLots",
+          "type": "i64"
+        },
+        {
+          "name": "maxQuoteLots",
+          "type": "i64"
+        },
+        {
+          "name": "clientOrderId",
+          "type": "u64"
+        },
+        {
+          "name": "orderType",
           "type": {
-            "option": {
-              "defined": "Side"
-            }
+            "defined": "PlaceOrderType"
           }
         },
+        {
+          "name": "reduceOnly",
+          "type": "bool"
+        },
+        {
+          "name": "expiryTimestamp",
+          "type": "u64"
+        },
         {
           "name": "limit",
           "type": "u8"
+        },
+        {
+          "name": "maxOracleStalenessSlots",
+          "type": "i32"
+        },
+        {
+          "name": "selfTradeBehavior",
+          "type": {
+            "defined": "Serum3SelfTradeBehavior"
+          }
         }
-      ]
+      ],
+      "returns": {
+        "option": "u128"
+      }
     },
     {
-      "name": "perpConsumeEvents",
+      "name": "perpCancelReplaceOrders",
+      "docs": [
+        "Cancels orders and places new ones on a perp market with a single health check.",
+        "",
+        "Returns the order ids of the new orders that were posted to the book, in the",
+        "order of `new_orders`."
+      ],
       "accounts": [
         {
           "name": "group",
@@ -3584,30 +3978,14 @@ export type MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "perpMarket",
+          "name": "account",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "eventQueue",
-          "isMut": true,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "limit",
-          "type": "u64"
-        }
-      ]
-    },
-    {
-      "name": "perpUpdateFunding",
-      "accounts": [
-        {
-          "name": "group",
+          "name": "owner",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         },
         {
           "name": "perpMarket",
@@ -3624,16 +4002,54 @@ export type MangoV4 = {
           "isMut": true,
           "isSigner": false
         },
+        {
+          "name": "eventQueue",
+          "isMut": true,
+          "isSigner": false
+        },
         {
           "name": "oracle",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": []
+      "args": [
+        {
+          "name": "cancel",
+          "type": {
+            "defined": "PerpCancelOrders"
+          }
+        },
+        {
+          "name": "newOrders",
+          "type": {
+            "vec": {
+              "defined": "PerpNewOrder"
+            }
+          }
+        },
+        {
+          "name": "limit",
+          "type": "u8"
+        },
+        {
+          "name": "selfTradeBehavior",
+          "type": {
+            "defined": "Serum3SelfTradeBehavior"
+          }
+        }
+      ],
+      "returns": {
+        "vec": {
+          "option": "u128"
+        }
+      }
     },
     {
-      "name": "perpSettlePnl",
+      "name": "perpPlaceTriggerOrder",
+      "docs": [
+        "Stores a conditional order on the account. Returns the trigger order id."
+      ],
       "accounts": [
         {
           "name": "group",
@@ -3641,12 +4057,12 @@ export type MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "settler",
+          "name": "account",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "settlerOwner",
+          "name": "owner",
           "isMut": false,
           "isSigner": true
         },
@@ -3654,109 +4070,87 @@ export type MangoV4 = {
           "name": "perpMarket",
           "isMut": false,
           "isSigner": false
-        },
+        }
+      ],
+      "args": [
         {
-          "name": "accountA",
-          "isMut": true,
-          "isSigner": false
+          "name": "side",
+          "type": {
+            "defined": "Side"
+          }
         },
         {
-          "name": "accountB",
-          "isMut": true,
-          "isSigner": false
+          "name": "triggerCondition",
+          "type": {
+            "defined": "PerpTriggerCondition"
+          }
         },
         {
-          "name": "oracle",
-          "isMut": false,
-          "isSigner": false
+          "name": "triggerPrice",
+          "type": "f64"
         },
         {
-          "name": "settleBank",
-          "isMut": true,
-          "isSigner": false
+          "name": "priceLots",
+          "type": "i64"
         },
         {
-          "name": "settleOracle",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": []
-    },
-    {
-      "name": "perpForceClosePosition",
-      "accounts": [
+          "name": "maxBaseLots",
+          "type": "i64"
+        },
         {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
+          "name": "maxQuoteLots",
+          "type": "i64"
         },
         {
-          "name": "perpMarket",
-          "isMut": true,
-          "isSigner": false
+          "name": "clientOrderId",
+          "type": "u64"
         },
         {
-          "name": "accountA",
-          "isMut": true,
-          "isSigner": false
+          "name": "reduceOnly",
+          "type": "bool"
         },
         {
-          "name": "accountB",
-          "isMut": true,
-          "isSigner": false
+          "name": "expiryTimestamp",
+          "type": "u64"
         },
         {
-          "name": "oracle",
-          "isMut": false,
-          "isSigner": false
+          "name": "incentive",
+          "type": "u64"
         }
       ],
-      "args": []
+      "returns": "u64"
     },
     {
-      "name": "perpSettleFees",
+      "name": "perpCancelTriggerOrder",
       "accounts": [
         {
           "name": "group",
           "isMut": false,
           "isSigner": false
         },
-        {
-          "name": "perpMarket",
-          "isMut": true,
-          "isSigner": false
-        },
         {
           "name": "account",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "oracle",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "settleBank",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "settleOracle",
+          "name": "owner",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         }
       ],
       "args": [
         {
-          "name": "maxSettleAmount",
+          "name": "triggerOrderId",
           "type": "u64"
         }
       ]
     },
     {
-      "name": "perpLiqBaseOrPositivePnl",
+      "name": "perpExecuteTriggerOrder",
+      "docs": [
+        "Permissionless: places the trigger order on the book once its condition is met."
+      ],
       "accounts": [
         {
           "name": "group",
@@ -3764,59 +4158,64 @@ export type MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "perpMarket",
+          "name": "account",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "oracle",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "liqor",
+          "name": "executor",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "liqorOwner",
+          "name": "executorOwner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "liqee",
+          "name": "perpMarket",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "settleBank",
+          "name": "bids",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "settleVault",
+          "name": "asks",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "settleOracle",
+          "name": "eventQueue",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
           "isMut": false,
           "isSigner": false
+        },
+        {
+          "name": "settleBank",
+          "isMut": true,
+          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "maxBaseTransfer",
-          "type": "i64"
+          "name": "triggerOrderId",
+          "type": "u64"
         },
         {
-          "name": "maxPnlTransfer",
-          "type": "u64"
+          "name": "limit",
+          "type": "u8"
         }
       ]
     },
     {
-      "name": "perpLiqForceCancelOrders",
+      "name": "perpCancelOrder",
       "accounts": [
         {
           "name": "group",
@@ -3828,6 +4227,11 @@ export type MangoV4 = {
           "isMut": true,
           "isSigner": false
         },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
         {
           "name": "perpMarket",
           "isMut": true,
@@ -3846,13 +4250,13 @@ export type MangoV4 = {
       ],
       "args": [
         {
-          "name": "limit",
-          "type": "u8"
+          "name": "orderId",
+          "type": "u128"
         }
       ]
     },
     {
-      "name": "perpLiqNegativePnlOrBankruptcy",
+      "name": "perpCancelOrderByClientOrderId",
       "accounts": [
         {
           "name": "group",
@@ -3860,91 +4264,130 @@ export type MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "liqor",
+          "name": "account",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "liqorOwner",
+          "name": "owner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "liqee",
+          "name": "perpMarket",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "perpMarket",
+          "name": "bids",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "oracle",
-          "isMut": false,
+          "name": "asks",
+          "isMut": true,
           "isSigner": false
-        },
+        }
+      ],
+      "args": [
         {
-          "name": "settleBank",
-          "isMut": true,
+          "name": "clientOrderId",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "perpCancelAllOrders",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "settleVault",
+          "name": "account",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "settleOracle",
+          "name": "owner",
           "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "insuranceVault",
+          "name": "bids",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "tokenProgram",
-          "isMut": false,
+          "name": "asks",
+          "isMut": true,
           "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "maxLiabTransfer",
-          "type": "u64"
+          "name": "limit",
+          "type": "u8"
         }
       ]
     },
     {
-      "name": "altSet",
+      "name": "perpCancelAllOrdersBySide",
       "accounts": [
         {
           "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "admin",
+          "name": "owner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "addressLookupTable",
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "asks",
           "isMut": true,
           "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "index",
+          "name": "sideOption",
+          "type": {
+            "option": {
+              "defined": "Side"
+            }
+          }
+        },
+        {
+          "name": "limit",
           "type": "u8"
         }
       ]
     },
     {
-      "name": "altExtend",
+      "name": "perpConsumeEvents",
       "accounts": [
         {
           "name": "group",
@@ -3952,36 +4395,25 @@ export type MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "admin",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "payer",
-          "isMut": false,
-          "isSigner": true
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "addressLookupTable",
+          "name": "eventQueue",
           "isMut": true,
           "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "index",
-          "type": "u8"
-        },
-        {
-          "name": "newAddresses",
-          "type": {
-            "vec": "publicKey"
-          }
+          "name": "limit",
+          "type": "u64"
         }
       ]
     },
     {
-      "name": "computeAccountData",
+      "name": "perpUpdateFunding",
       "accounts": [
         {
           "name": "group",
@@ -3989,7 +4421,22 @@ export type MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "account",
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "asks",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
           "isMut": false,
           "isSigner": false
         }
@@ -3997,30 +4444,429 @@ export type MangoV4 = {
       "args": []
     },
     {
-      "name": "benchmark",
-      "docs": [
-        "",
-        "benchmark",
-        ""
+      "name": "perpSettlePnl",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "settler",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "settlerOwner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "perpMarket",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "accountA",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "accountB",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "settleBank",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "settleOracle",
+          "isMut": false,
+          "isSigner": false
+        }
       ],
+      "args": []
+    },
+    {
+      "name": "perpForceClosePosition",
       "accounts": [
         {
-          "name": "dummy",
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "accountA",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "accountB",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
           "isMut": false,
           "isSigner": false
         }
       ],
       "args": []
-    }
-  ],
-  "accounts": [
+    },
     {
-      "name": "bank",
-      "type": {
-        "kind": "struct",
-        "fields": [
-          {
-            "name": "group",
+      "name": "perpCloseExpiredPosition",
+      "docs": [
+        "Closes the positions of a long and a short account at the settlement price",
+        "of an expired dated futures market."
+      ],
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "accountA",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "accountB",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "perpSettleFees",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "settleBank",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "settleOracle",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "maxSettleAmount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "perpLiqBaseOrPositivePnl",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "liqor",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "liqorOwner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "liqee",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "settleBank",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "settleVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "settleOracle",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "maxBaseTransfer",
+          "type": "i64"
+        },
+        {
+          "name": "maxPnlTransfer",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "perpLiqForceCancelOrders",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "asks",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "limit",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "perpLiqNegativePnlOrBankruptcy",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "liqor",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "liqorOwner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "liqee",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "settleBank",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "settleVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "settleOracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "insuranceVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "maxLiabTransfer",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "altSet",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "addressLookupTable",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "index",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "altExtend",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "addressLookupTable",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "index",
+          "type": "u8"
+        },
+        {
+          "name": "newAddresses",
+          "type": {
+            "vec": "publicKey"
+          }
+        }
+      ]
+    },
+    {
+      "name": "computeAccountData",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "benchmark",
+      "docs": [
+        "",
+        "benchmark",
+        ""
+      ],
+      "accounts": [
+        {
+          "name": "dummy",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    }
+  ],
+  "accounts": [
+    {
+      "name": "bank",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "group",
             "type": "publicKey"
           },
           {
@@ -4291,12 +5137,20 @@ export type MangoV4 = {
             "name": "forceClose",
             "type": "u8"
           },
+          {
+            "name": "fallbackOracle",
+            "docs": [
+              "Oracle to use when the primary oracle is stale or not confident enough,",
+              "Pubkey::default() if there is none"
+            ],
+            "type": "publicKey"
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                2118
+                2086
               ]
             }
           }
@@ -4402,12 +5256,40 @@ export type MangoV4 = {
             ],
             "type": "u64"
           },
+          {
+            "name": "insuranceFundVaultBumps",
+            "docs": [
+              "Bumps of the isolated insurance fund vaults, by fund index.",
+              "",
+              "Set by InsuranceFundCreate. Index 0 is unused, see insurance_vault."
+            ],
+            "type": {
+              "array": [
+                "u8",
+                16
+              ]
+            }
+          },
+          {
+            "name": "insuranceFundReferences",
+            "docs": [
+              "Number of tokens and perp markets each isolated insurance fund covers, by fund index.",
+              "",
+              "Insurance funds can't be closed while they are still referenced."
+            ],
+            "type": {
+              "array": [
+                "u16",
+                16
+              ]
+            }
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                1824
+                1776
               ]
             }
           }
@@ -4522,12 +5404,19 @@ export type MangoV4 = {
             ],
             "type": "u64"
           },
+          {
+            "name": "perpTriggerOrderIdCounter",
+            "docs": [
+              "Incremented for each new perp trigger order, the new value is used as its id."
+            ],
+            "type": "u64"
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                208
+                200
               ]
             }
           },
@@ -4591,6 +5480,18 @@ export type MangoV4 = {
                 "defined": "PerpOpenOrder"
               }
             }
+          },
+          {
+            "name": "padding8",
+            "type": "u32"
+          },
+          {
+            "name": "perpTriggerOrders",
+            "type": {
+              "vec": {
+                "defined": "PerpTriggerOrder"
+              }
+            }
           }
         ]
       }
@@ -4651,12 +5552,30 @@ export type MangoV4 = {
             "name": "registrationTime",
             "type": "u64"
           },
+          {
+            "name": "insuranceFundIndex",
+            "docs": [
+              "Isolated insurance fund that covers bankruptcies in this token.",
+              "",
+              "0 means the group insurance fund is used, if group_insurance_fund is set."
+            ],
+            "type": "u16"
+          },
+          {
+            "name": "padding2",
+            "type": {
+              "array": [
+                "u8",
+                6
+              ]
+            }
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                2560
+                2552
               ]
             }
           }
@@ -5138,33 +6057,202 @@ export type MangoV4 = {
             "type": "u8"
           },
           {
-            "name": "padding4",
+            "name": "padding4",
+            "type": {
+              "array": [
+                "u8",
+                6
+              ]
+            }
+          },
+          {
+            "name": "maintOverallAssetWeight",
+            "docs": [
+              "Weights for full perp market health, if positive"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "initOverallAssetWeight",
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "positivePnlLiquidationFee",
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "fallbackOracle",
+            "docs": [
+              "Oracle to use when the primary oracle is stale or not confident enough,",
+              "Pubkey::default() if there is none"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "insuranceFundIndex",
+            "docs": [
+              "Isolated insurance fund that covers bankruptcies in this market.",
+              "",
+              "0 means the group insurance fund is used, if group_insurance_fund is set."
+            ],
+            "type": "u16"
+          },
+          {
+            "name": "padding5",
+            "type": {
+              "array": [
+                "u8",
+                6
+              ]
+            }
+          },
+          {
+            "name": "expiryTimestamp",
+            "docs": [
+              "Timestamp at which the market expires, 0 for perpetual markets.",
+              "",
+              "Dated markets pay no funding. Once expired, no orders can be placed and",
+              "positions are closed at settlement_price with perp_close_expired_position."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "settlementWindowTs",
+            "docs": [
+              "Length of the window before expiry_timestamp in which prices are sampled",
+              "for the settlement price."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "settlementPriceSum",
+            "docs": [
+              "Sum of sampled prices, weighted by the seconds they were valid for."
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "settlementPrice",
+            "docs": [
+              "Final price at which positions are closed, valid if has_settlement_price is set."
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "settlementLastPrice",
+            "docs": [
+              "Price observed at funding_last_updated, it's valid until the next update."
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "settlementPriceWeightTs",
+            "docs": [
+              "Number of seconds covered by settlement_price_sum."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "settlementUsesStablePrice",
+            "docs": [
+              "If set, the stable price is sampled for the settlement price, otherwise the oracle price."
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "hasSettlementPrice",
+            "type": "u8"
+          },
+          {
+            "name": "padding6",
+            "type": {
+              "array": [
+                "u8",
+                6
+              ]
+            }
+          },
+          {
+            "name": "fundingIntervalTs",
+            "docs": [
+              "Length of a funding interval for the PremiumTwap funding model."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "fundingIntervalStartTs",
+            "docs": [
+              "Start of the current funding interval."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "fundingPremiumSum",
+            "docs": [
+              "Sum of book premiums in the current funding interval, weighted by seconds."
+            ],
             "type": {
-              "array": [
-                "u8",
-                6
-              ]
+              "defined": "I80F48"
             }
           },
           {
-            "name": "maintOverallAssetWeight",
+            "name": "prevFundingPremiumSum",
             "docs": [
-              "Weights for full perp market health, if positive"
+              "Sum of book premiums in the previous funding interval, weighted by seconds."
             ],
             "type": {
               "defined": "I80F48"
             }
           },
           {
-            "name": "initOverallAssetWeight",
+            "name": "fundingLastPremium",
+            "docs": [
+              "Book premium observed at funding_last_updated, it's valid until the next update."
+            ],
             "type": {
               "defined": "I80F48"
             }
           },
           {
-            "name": "positivePnlLiquidationFee",
+            "name": "fundingPremiumWeightTs",
+            "docs": [
+              "Number of seconds covered by funding_premium_sum."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "prevFundingPremiumWeightTs",
+            "docs": [
+              "Number of seconds covered by prev_funding_premium_sum."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "fundingModel",
+            "docs": [
+              "See PerpFundingModel."
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "padding7",
             "type": {
-              "defined": "I80F48"
+              "array": [
+                "u8",
+                7
+              ]
             }
           },
           {
@@ -5172,7 +6260,7 @@ export type MangoV4 = {
             "type": {
               "array": [
                 "u8",
-                1888
+                1680
               ]
             }
           }
@@ -5375,6 +6463,45 @@ export type MangoV4 = {
           },
           {
             "name": "value",
+            "docs": [
+              "Unsettled pnl at the oracle price, including unsettled funding"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "stableValue",
+            "docs": [
+              "Unsettled pnl at the stable price, including unsettled funding"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "baseValue",
+            "docs": [
+              "Value of the base position at the oracle price"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "stableBaseValue",
+            "docs": [
+              "Value of the base position at the stable price"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "unsettledFunding",
+            "docs": [
+              "Funding the account still needs to pay, negative if it is owed funding"
+            ],
             "type": {
               "defined": "I80F48"
             }
@@ -5666,6 +6793,58 @@ export type MangoV4 = {
         ]
       }
     },
+    {
+      "name": "PerpNewOrder",
+      "docs": [
+        "A new order in perp_cancel_replace_orders"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "side",
+            "type": {
+              "defined": "Side"
+            }
+          },
+          {
+            "name": "price",
+            "type": {
+              "defined": "PerpOrderPrice"
+            }
+          },
+          {
+            "name": "maxBaseLots",
+            "type": "i64"
+          },
+          {
+            "name": "maxQuoteLots",
+            "type": "i64"
+          },
+          {
+            "name": "clientOrderId",
+            "type": "u64"
+          },
+          {
+            "name": "orderType",
+            "type": {
+              "defined": "PlaceOrderType"
+            }
+          },
+          {
+            "name": "reduceOnly",
+            "type": "bool"
+          },
+          {
+            "name": "expiryTimestamp",
+            "docs": [
+              "Timestamp of when the order expires, 0 for never"
+            ],
+            "type": "u64"
+          }
+        ]
+      }
+    },
     {
       "name": "TokenPosition",
       "type": {
@@ -6070,6 +7249,117 @@ export type MangoV4 = {
         ]
       }
     },
+    {
+      "name": "PerpTriggerOrder",
+      "docs": [
+        "A conditional perp order, usable for stop-loss and take-profit.",
+        "",
+        "Once the oracle price crosses trigger_price, anyone may execute it: it is then sent",
+        "to the orderbook as an immediate-or-cancel order and the executor receives the incentive."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "id",
+            "docs": [
+              "Id unique for the account, zero marks an unused slot"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "market",
+            "type": "u16"
+          },
+          {
+            "name": "side",
+            "type": "u8"
+          },
+          {
+            "name": "triggerCondition",
+            "type": "u8"
+          },
+          {
+            "name": "reduceOnly",
+            "type": "u8"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                3
+              ]
+            }
+          },
+          {
+            "name": "triggerPrice",
+            "docs": [
+              "Oracle price threshold, in native quote per native base"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "priceLots",
+            "docs": [
+              "Limit price of the order that gets placed on execution, in quote lots per base lots"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "maxBaseLots",
+            "type": "i64"
+          },
+          {
+            "name": "maxQuoteLots",
+            "type": "i64"
+          },
+          {
+            "name": "clientOrderId",
+            "type": "u64"
+          },
+          {
+            "name": "expiryTimestamp",
+            "docs": [
+              "Timestamp after which the trigger order can't be executed anymore, 0 for never"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "incentive",
+            "docs": [
+              "Native settle token amount that the account pays to the executor"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                64
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "PerpTriggerCondition",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "OraclePriceAbove"
+          },
+          {
+            "name": "OraclePriceBelow"
+          }
+        ]
+      }
+    },
     {
       "name": "MangoAccountFixed",
       "type": {
@@ -6149,12 +7439,16 @@ export type MangoV4 = {
             "name": "buybackFeesExpiryTimestamp",
             "type": "u64"
           },
+          {
+            "name": "perpTriggerOrderIdCounter",
+            "type": "u64"
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                208
+                200
               ]
             }
           }
@@ -6871,7 +8165,86 @@ export type MangoV4 = {
             "name": "Unknown"
           },
           {
-            "name": "Swap"
+            "name": "Swap"
+          }
+        ]
+      }
+    },
+    {
+      "name": "PerpCancelOrders",
+      "docs": [
+        "Which of the account's orders on the market to cancel before placing new ones"
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "None"
+          },
+          {
+            "name": "All",
+            "fields": [
+              {
+                "name": "side_option",
+                "type": {
+                  "option": {
+                    "defined": "Side"
+                  }
+                }
+              }
+            ]
+          },
+          {
+            "name": "ByOrderIds",
+            "fields": [
+              {
+                "name": "order_ids",
+                "type": {
+                  "vec": "u128"
+                }
+              }
+            ]
+          },
+          {
+            "name": "ByClientOrderIds",
+            "fields": [
+              {
+                "name": "client_order_ids",
+                "type": {
+                  "vec": "u64"
+                }
+              }
+            ]
+          }
+        ]
+      }
+    },
+    {
+      "name": "PerpOrderPrice",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Fixed",
+            "fields": [
+              {
+                "name": "price_lots",
+                "type": "i64"
+              }
+            ]
+          },
+          {
+            "name": "OraclePegged",
+            "fields": [
+              {
+                "name": "price_offset_lots",
+                "type": "i64"
+              },
+              {
+                "name": "peg_limit",
+                "type": "i64"
+              }
+            ]
           }
         ]
       }
@@ -6985,6 +8358,9 @@ export type MangoV4 = {
           },
           {
             "name": "TokenWithdraw"
+          },
+          {
+            "name": "TokenTransfer"
           }
         ]
       }
@@ -7112,356 +8488,1211 @@ export type MangoV4 = {
             "name": "Serum3PlaceOrder"
           },
           {
-            "name": "Serum3RegisterMarket"
+            "name": "Serum3RegisterMarket"
+          },
+          {
+            "name": "Serum3SettleFunds"
+          },
+          {
+            "name": "StubOracleClose"
+          },
+          {
+            "name": "StubOracleCreate"
+          },
+          {
+            "name": "StubOracleSet"
+          },
+          {
+            "name": "TokenAddBank"
+          },
+          {
+            "name": "TokenDeposit"
+          },
+          {
+            "name": "TokenDeregister"
+          },
+          {
+            "name": "TokenLiqBankruptcy"
+          },
+          {
+            "name": "TokenLiqWithToken"
+          },
+          {
+            "name": "TokenRegister"
+          },
+          {
+            "name": "TokenRegisterTrustless"
+          },
+          {
+            "name": "TokenUpdateIndexAndRate"
+          },
+          {
+            "name": "TokenWithdraw"
+          },
+          {
+            "name": "AccountBuybackFeesWithMngo"
+          },
+          {
+            "name": "TokenForceCloseBorrowsWithToken"
+          },
+          {
+            "name": "PerpForceClosePosition"
+          },
+          {
+            "name": "GroupWithdrawInsuranceFund"
+          },
+          {
+            "name": "InsuranceFundCreate"
+          },
+          {
+            "name": "InsuranceFundWithdraw"
+          },
+          {
+            "name": "InsuranceFundClose"
+          },
+          {
+            "name": "PerpPlaceTriggerOrder"
+          },
+          {
+            "name": "PerpCancelTriggerOrder"
+          },
+          {
+            "name": "PerpExecuteTriggerOrder"
+          },
+          {
+            "name": "TokenTransfer"
+          },
+          {
+            "name": "PerpCancelReplaceOrders"
+          },
+          {
+            "name": "PerpCloseExpiredPosition"
+          },
+          {
+            "name": "PerpPlaceOrderV2"
+          },
+          {
+            "name": "PerpPlaceOrderPeggedV2"
+          }
+        ]
+      }
+    },
+    {
+      "name": "CheckLiquidatable",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "NotLiquidatable"
+          },
+          {
+            "name": "Liquidatable"
+          },
+          {
+            "name": "BecameNotLiquidatable"
+          }
+        ]
+      }
+    },
+    {
+      "name": "OracleType",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Pyth"
+          },
+          {
+            "name": "Stub"
+          },
+          {
+            "name": "SwitchboardV1"
+          },
+          {
+            "name": "SwitchboardV2"
+          }
+        ]
+      }
+    },
+    {
+      "name": "OrderState",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Valid"
+          },
+          {
+            "name": "Invalid"
+          },
+          {
+            "name": "Skipped"
+          }
+        ]
+      }
+    },
+    {
+      "name": "BookSideOrderTree",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Fixed"
+          },
+          {
+            "name": "OraclePegged"
+          }
+        ]
+      }
+    },
+    {
+      "name": "NodeTag",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Uninitialized"
+          },
+          {
+            "name": "InnerNode"
+          },
+          {
+            "name": "LeafNode"
+          },
+          {
+            "name": "FreeNode"
+          },
+          {
+            "name": "LastFreeNode"
+          }
+        ]
+      }
+    },
+    {
+      "name": "PlaceOrderType",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Limit"
+          },
+          {
+            "name": "ImmediateOrCancel"
+          },
+          {
+            "name": "PostOnly"
+          },
+          {
+            "name": "Market"
+          },
+          {
+            "name": "PostOnlySlide"
+          },
+          {
+            "name": "FillOrKill"
+          }
+        ]
+      }
+    },
+    {
+      "name": "PostOrderType",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Limit"
+          },
+          {
+            "name": "PostOnly"
+          },
+          {
+            "name": "PostOnlySlide"
+          }
+        ]
+      }
+    },
+    {
+      "name": "Side",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Bid"
+          },
+          {
+            "name": "Ask"
+          }
+        ]
+      }
+    },
+    {
+      "name": "SideAndOrderTree",
+      "docs": [
+        "SideAndOrderTree is a storage optimization, so we don't need two bytes for the data"
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "BidFixed"
+          },
+          {
+            "name": "AskFixed"
           },
           {
-            "name": "Serum3SettleFunds"
+            "name": "BidOraclePegged"
           },
           {
-            "name": "StubOracleClose"
-          },
+            "name": "AskOraclePegged"
+          }
+        ]
+      }
+    },
+    {
+      "name": "OrderParams",
+      "type": {
+        "kind": "enum",
+        "variants": [
           {
-            "name": "StubOracleCreate"
+            "name": "Market"
           },
           {
-            "name": "StubOracleSet"
+            "name": "ImmediateOrCancel",
+            "fields": [
+              {
+                "name": "price_lots",
+                "type": "i64"
+              }
+            ]
           },
           {
-            "name": "TokenAddBank"
+            "name": "FillOrKill",
+            "fields": [
+              {
+                "name": "price_lots",
+                "type": "i64"
+              }
+            ]
           },
           {
-            "name": "TokenDeposit"
+            "name": "Fixed",
+            "fields": [
+              {
+                "name": "price_lots",
+                "type": "i64"
+              },
+              {
+                "name": "order_type",
+                "type": {
+                  "defined": "PostOrderType"
+                }
+              }
+            ]
           },
           {
-            "name": "TokenDeregister"
-          },
+            "name": "OraclePegged",
+            "fields": [
+              {
+                "name": "price_offset_lots",
+                "type": "i64"
+              },
+              {
+                "name": "order_type",
+                "type": {
+                  "defined": "PostOrderType"
+                }
+              },
+              {
+                "name": "peg_limit",
+                "type": "i64"
+              },
+              {
+                "name": "max_oracle_staleness_slots",
+                "type": "i32"
+              }
+            ]
+          }
+        ]
+      }
+    },
+    {
+      "name": "OrderTreeType",
+      "type": {
+        "kind": "enum",
+        "variants": [
           {
-            "name": "TokenLiqBankruptcy"
+            "name": "Bids"
           },
           {
-            "name": "TokenLiqWithToken"
-          },
+            "name": "Asks"
+          }
+        ]
+      }
+    },
+    {
+      "name": "EventType",
+      "type": {
+        "kind": "enum",
+        "variants": [
           {
-            "name": "TokenRegister"
+            "name": "Fill"
           },
           {
-            "name": "TokenRegisterTrustless"
+            "name": "Out"
           },
           {
-            "name": "TokenUpdateIndexAndRate"
-          },
+            "name": "Liquidate"
+          }
+        ]
+      }
+    },
+    {
+      "name": "PerpFundingModel",
+      "docs": [
+        "How the funding rate of a perp market is computed.",
+        "",
+        "In all cases the resulting rate is clamped to min_funding and max_funding."
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
           {
-            "name": "TokenWithdraw"
+            "name": "ImpactPrice"
           },
           {
-            "name": "AccountBuybackFeesWithMngo"
+            "name": "PremiumTwap"
           },
           {
-            "name": "TokenForceCloseBorrowsWithToken"
+            "name": "StablePrice"
+          }
+        ]
+      }
+    }
+  ],
+  "events": [
+    {
+      "name": "MangoAccountData",
+      "fields": [
+        {
+          "name": "healthCache",
+          "type": {
+            "defined": "HealthCache"
+          },
+          "index": false
+        },
+        {
+          "name": "initHealth",
+          "type": {
+            "defined": "I80F48"
+          },
+          "index": false
+        },
+        {
+          "name": "maintHealth",
+          "type": {
+            "defined": "I80F48"
+          },
+          "index": false
+        },
+        {
+          "name": "equity",
+          "type": {
+            "defined": "Equity"
+          },
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "PerpBalanceLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "basePosition",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quotePosition",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "longSettledFunding",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "shortSettledFunding",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "longFunding",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "shortFunding",
+          "type": "i128",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TokenBalanceLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "indexedPosition",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "depositIndex",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "borrowIndex",
+          "type": "i128",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "FlashLoanLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenLoanDetails",
+          "type": {
+            "vec": {
+              "defined": "FlashLoanTokenDetail"
+            }
           },
-          {
-            "name": "PerpForceClosePosition"
+          "index": false
+        },
+        {
+          "name": "flashLoanType",
+          "type": {
+            "defined": "FlashLoanType"
           },
-          {
-            "name": "GroupWithdrawInsuranceFund"
-          }
-        ]
-      }
+          "index": false
+        }
+      ]
     },
     {
-      "name": "CheckLiquidatable",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "NotLiquidatable"
-          },
-          {
-            "name": "Liquidatable"
-          },
-          {
-            "name": "BecameNotLiquidatable"
-          }
-        ]
-      }
+      "name": "WithdrawLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "signer",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i128",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "OracleType",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Pyth"
-          },
-          {
-            "name": "Stub"
-          },
-          {
-            "name": "SwitchboardV1"
-          },
-          {
-            "name": "SwitchboardV2"
-          }
-        ]
-      }
+      "name": "DepositLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "signer",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i128",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "OrderState",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Valid"
-          },
-          {
-            "name": "Invalid"
-          },
-          {
-            "name": "Skipped"
-          }
-        ]
-      }
+      "name": "TokenTransferLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "sourceAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "destinationAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "signer",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i128",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "FillLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "takerSide",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "makerSlot",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "makerOut",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "seqNum",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "maker",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "makerOrderId",
+          "type": "u128",
+          "index": false
+        },
+        {
+          "name": "makerFee",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "makerTimestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "taker",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "takerOrderId",
+          "type": "u128",
+          "index": false
+        },
+        {
+          "name": "takerClientOrderId",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "takerFee",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "i64",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "BookSideOrderTree",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Fixed"
-          },
-          {
-            "name": "OraclePegged"
-          }
-        ]
-      }
+      "name": "FillLogV2",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "takerSide",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "makerSlot",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "makerOut",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "seqNum",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "maker",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "makerClientOrderId",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "makerFee",
+          "type": "f32",
+          "index": false
+        },
+        {
+          "name": "makerTimestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "taker",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "takerClientOrderId",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "takerFee",
+          "type": "f32",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "i64",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "NodeTag",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Uninitialized"
-          },
-          {
-            "name": "InnerNode"
-          },
-          {
-            "name": "LeafNode"
-          },
-          {
-            "name": "FreeNode"
-          },
-          {
-            "name": "LastFreeNode"
-          }
-        ]
-      }
+      "name": "PerpUpdateFundingLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "longFunding",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "shortFunding",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "oracleSlot",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stablePrice",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "feesAccrued",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "feesSettled",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "openInterest",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "instantaneousFundingRate",
+          "type": "i128",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "PlaceOrderType",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Limit"
-          },
-          {
-            "name": "ImmediateOrCancel"
-          },
-          {
-            "name": "PostOnly"
-          },
-          {
-            "name": "Market"
-          },
-          {
-            "name": "PostOnlySlide"
-          }
-        ]
-      }
+      "name": "PerpFundingPremiumIntervalLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "startTs",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "endTs",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "premiumSum",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "premiumWeightTs",
+          "type": "u64",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "PostOrderType",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Limit"
-          },
-          {
-            "name": "PostOnly"
-          },
-          {
-            "name": "PostOnlySlide"
-          }
-        ]
-      }
+      "name": "UpdateIndexLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "depositIndex",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "borrowIndex",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "avgUtilization",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "stablePrice",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "collectedFees",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "loanFeeRate",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "totalBorrows",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "totalDeposits",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "borrowRate",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "depositRate",
+          "type": "i128",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "Side",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Bid"
-          },
-          {
-            "name": "Ask"
-          }
-        ]
-      }
+      "name": "UpdateRateLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "rate0",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "rate1",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "maxRate",
+          "type": "i128",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "SideAndOrderTree",
-      "docs": [
-        "SideAndOrderTree is a storage optimization, so we don't need two bytes for the data"
-      ],
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "BidFixed"
-          },
-          {
-            "name": "AskFixed"
-          },
-          {
-            "name": "BidOraclePegged"
-          },
-          {
-            "name": "AskOraclePegged"
-          }
-        ]
-      }
+      "name": "TokenLiqWithTokenLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "liqee",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "liqor",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "assetTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "liabTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "assetTransfer",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "liabTransfer",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "assetPrice",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "liabPrice",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "bankruptcy",
+          "type": "bool",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "OrderParams",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Market"
-          },
-          {
-            "name": "ImmediateOrCancel",
-            "fields": [
-              {
-                "name": "price_lots",
-                "type": "i64"
-              }
-            ]
-          },
-          {
-            "name": "Fixed",
-            "fields": [
-              {
-                "name": "price_lots",
-                "type": "i64"
-              },
-              {
-                "name": "order_type",
-                "type": {
-                  "defined": "PostOrderType"
-                }
-              }
-            ]
-          },
-          {
-            "name": "OraclePegged",
-            "fields": [
-              {
-                "name": "price_offset_lots",
-                "type": "i64"
-              },
-              {
-                "name": "order_type",
-                "type": {
-                  "defined": "PostOrderType"
-                }
-              },
-              {
-                "name": "peg_limit",
-                "type": "i64"
-              },
-              {
-                "name": "max_oracle_staleness_slots",
-                "type": "i32"
-              }
-            ]
-          }
-        ]
-      }
+      "name": "Serum3OpenOrdersBalanceLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "baseTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "quoteTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "baseTotal",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "baseFree",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "quoteTotal",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "quoteFree",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "referrerRebatesAccrued",
+          "type": "u64",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "OrderTreeType",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Bids"
-          },
-          {
-            "name": "Asks"
-          }
-        ]
-      }
+      "name": "Serum3OpenOrdersBalanceLogV2",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "baseTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "quoteTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "baseTotal",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "baseFree",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "quoteTotal",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "quoteFree",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "referrerRebatesAccrued",
+          "type": "u64",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "EventType",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Fill"
-          },
-          {
-            "name": "Out"
-          },
-          {
-            "name": "Liquidate"
-          }
-        ]
-      }
-    }
-  ],
-  "events": [
-    {
-      "name": "MangoAccountData",
+      "name": "WithdrawLoanOriginationFeeLog",
       "fields": [
         {
-          "name": "healthCache",
-          "type": {
-            "defined": "HealthCache"
-          },
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "initHealth",
-          "type": {
-            "defined": "I80F48"
-          },
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "maintHealth",
-          "type": {
-            "defined": "I80F48"
-          },
+          "name": "tokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "equity",
+          "name": "loanOriginationFee",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "instruction",
           "type": {
-            "defined": "Equity"
+            "defined": "LoanOriginationFeeInstruction"
           },
           "index": false
         }
       ]
     },
     {
-      "name": "PerpBalanceLog",
+      "name": "TokenLiqBankruptcyLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -7469,49 +9700,59 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "liqee",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "marketIndex",
+          "name": "liqor",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "liabTokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "basePosition",
-          "type": "i64",
+          "name": "initialLiabNative",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "quotePosition",
+          "name": "liabPrice",
           "type": "i128",
           "index": false
         },
         {
-          "name": "longSettledFunding",
+          "name": "insuranceTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "insuranceTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "shortSettledFunding",
+          "name": "socializedLoss",
           "type": "i128",
           "index": false
         },
         {
-          "name": "longFunding",
+          "name": "startingLiabDepositIndex",
           "type": "i128",
           "index": false
         },
         {
-          "name": "shortFunding",
+          "name": "endingLiabDepositIndex",
           "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenBalanceLog",
+      "name": "DeactivateTokenPositionLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -7529,55 +9770,139 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "indexedPosition",
-          "type": "i128",
+          "name": "cumulativeDepositInterest",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "depositIndex",
-          "type": "i128",
+          "name": "cumulativeBorrowInterest",
+          "type": "f64",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "DeactivatePerpPositionLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "borrowIndex",
-          "type": "i128",
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "cumulativeLongFunding",
+          "type": "f64",
+          "index": false
+        },
+        {
+          "name": "cumulativeShortFunding",
+          "type": "f64",
+          "index": false
+        },
+        {
+          "name": "makerVolume",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "takerVolume",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "perpSpotTransfers",
+          "type": "i64",
           "index": false
         }
       ]
     },
     {
-      "name": "FlashLoanLog",
+      "name": "TokenMetaDataLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mint",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "mintDecimals",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "oracle",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mintInfo",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "PerpMarketMetaDataLog",
       "fields": [
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "perpMarket",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "baseDecimals",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "tokenLoanDetails",
-          "type": {
-            "vec": {
-              "defined": "FlashLoanTokenDetail"
-            }
-          },
+          "name": "baseLotSize",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "flashLoanType",
-          "type": {
-            "defined": "FlashLoanType"
-          },
+          "name": "quoteLotSize",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "oracle",
+          "type": "publicKey",
           "index": false
         }
       ]
     },
     {
-      "name": "WithdrawLog",
+      "name": "Serum3RegisterMarketLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -7585,34 +9910,39 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "serumMarket",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "signer",
-          "type": "publicKey",
+          "name": "marketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "tokenIndex",
+          "name": "baseTokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "quantity",
-          "type": "u64",
+          "name": "quoteTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i128",
+          "name": "serumProgram",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "serumProgramExternal",
+          "type": "publicKey",
           "index": false
         }
       ]
     },
     {
-      "name": "DepositLog",
+      "name": "PerpLiqBaseOrPositivePnlLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -7620,23 +9950,38 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "perpMarketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "liqor",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "signer",
+          "name": "liqee",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
+          "name": "baseTransfer",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "quantity",
-          "type": "u64",
+          "name": "quoteTransfer",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "pnlTransfer",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "pnlSettleLimitTransfer",
+          "type": "i128",
           "index": false
         },
         {
@@ -7647,7 +9992,7 @@ export type MangoV4 = {
       ]
     },
     {
-      "name": "FillLog",
+      "name": "PerpLiqBankruptcyLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -7655,89 +10000,84 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
+          "name": "liqee",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "takerSide",
-          "type": "u8",
+          "name": "liqor",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "makerSlot",
-          "type": "u8",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "makerOut",
-          "type": "bool",
+          "name": "insuranceTransfer",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "timestamp",
-          "type": "u64",
+          "name": "socializedLoss",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "seqNum",
-          "type": "u64",
+          "name": "startingLongFunding",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "maker",
-          "type": "publicKey",
+          "name": "startingShortFunding",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "makerOrderId",
-          "type": "u128",
+          "name": "endingLongFunding",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "makerFee",
+          "name": "endingShortFunding",
           "type": "i128",
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "PerpLiqNegativePnlOrBankruptcyLog",
+      "fields": [
         {
-          "name": "makerTimestamp",
-          "type": "u64",
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "taker",
+          "name": "liqee",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "takerOrderId",
-          "type": "u128",
+          "name": "liqor",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "takerClientOrderId",
-          "type": "u64",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "takerFee",
+          "name": "settlement",
           "type": "i128",
           "index": false
-        },
-        {
-          "name": "price",
-          "type": "i64",
-          "index": false
-        },
-        {
-          "name": "quantity",
-          "type": "i64",
-          "index": false
         }
       ]
     },
     {
-      "name": "FillLogV2",
+      "name": "PerpSettlePnlLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -7745,84 +10085,99 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
+          "name": "mangoAccountA",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "takerSide",
-          "type": "u8",
+          "name": "mangoAccountB",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "makerSlot",
-          "type": "u8",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "makerOut",
-          "type": "bool",
+          "name": "settlement",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "timestamp",
-          "type": "u64",
+          "name": "settler",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "seqNum",
-          "type": "u64",
+          "name": "fee",
+          "type": "i128",
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "PerpSettleFeesLog",
+      "fields": [
         {
-          "name": "maker",
+          "name": "mangoGroup",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "makerClientOrderId",
-          "type": "u64",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "makerFee",
-          "type": "f32",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "makerTimestamp",
-          "type": "u64",
+          "name": "settlement",
+          "type": "i128",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "AccountBuybackFeesWithMngoLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "taker",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "takerClientOrderId",
-          "type": "u64",
+          "name": "buybackFees",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerFee",
-          "type": "f32",
+          "name": "buybackMngo",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i64",
+          "name": "mngoBuybackPrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "quantity",
-          "type": "i64",
+          "name": "oraclePrice",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpUpdateFundingLog",
+      "name": "FilledPerpOrderLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -7830,59 +10185,59 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "marketIndex",
+          "name": "perpMarketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "longFunding",
-          "type": "i128",
-          "index": false
-        },
-        {
-          "name": "shortFunding",
-          "type": "i128",
+          "name": "seqNum",
+          "type": "u64",
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "PerpExecuteTriggerOrderLog",
+      "fields": [
         {
-          "name": "price",
-          "type": "i128",
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "oracleSlot",
-          "type": "u64",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "stablePrice",
-          "type": "i128",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "feesAccrued",
-          "type": "i128",
+          "name": "triggerOrderId",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "feesSettled",
-          "type": "i128",
+          "name": "executor",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "openInterest",
-          "type": "i64",
+          "name": "incentive",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "instantaneousFundingRate",
+          "name": "oraclePrice",
           "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "UpdateIndexLog",
+      "name": "PerpForceClosePositionLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -7890,22 +10245,27 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "tokenIndex",
+          "name": "perpMarketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "depositIndex",
-          "type": "i128",
+          "name": "accountA",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "borrowIndex",
-          "type": "i128",
+          "name": "accountB",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "avgUtilization",
+          "name": "baseTransfer",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quoteTransfer",
           "type": "i128",
           "index": false
         },
@@ -7913,1115 +10273,1338 @@ export type MangoV4 = {
           "name": "price",
           "type": "i128",
           "index": false
+        }
+      ]
+    },
+    {
+      "name": "TokenForceCloseBorrowsWithTokenLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
         },
         {
-          "name": "stablePrice",
-          "type": "i128",
+          "name": "liqor",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "collectedFees",
-          "type": "i128",
+          "name": "liqee",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "loanFeeRate",
+          "name": "assetTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "liabTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "assetTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "totalBorrows",
+          "name": "liabTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "totalDeposits",
+          "name": "assetPrice",
           "type": "i128",
           "index": false
         },
         {
-          "name": "borrowRate",
+          "name": "liabPrice",
           "type": "i128",
           "index": false
         },
         {
-          "name": "depositRate",
+          "name": "feeFactor",
           "type": "i128",
           "index": false
         }
       ]
+    }
+  ],
+  "errors": [
+    {
+      "code": 6000,
+      "name": "SomeError",
+      "msg": ""
+    },
+    {
+      "code": 6001,
+      "name": "NotImplementedError",
+      "msg": ""
+    },
+    {
+      "code": 6002,
+      "name": "MathError",
+      "msg": "checked math error"
+    },
+    {
+      "code": 6003,
+      "name": "UnexpectedOracle",
+      "msg": ""
+    },
+    {
+      "code": 6004,
+      "name": "UnknownOracleType",
+      "msg": "oracle type cannot be determined"
+    },
+    {
+      "code": 6005,
+      "name": "InvalidFlashLoanTargetCpiProgram",
+      "msg": ""
+    },
+    {
+      "code": 6006,
+      "name": "HealthMustBePositive",
+      "msg": "health must be positive"
+    },
+    {
+      "code": 6007,
+      "name": "HealthMustBePositiveOrIncrease",
+      "msg": "health must be positive or increase"
+    },
+    {
+      "code": 6008,
+      "name": "HealthMustBeNegative",
+      "msg": "health must be negative"
+    },
+    {
+      "code": 6009,
+      "name": "IsBankrupt",
+      "msg": "the account is bankrupt"
+    },
+    {
+      "code": 6010,
+      "name": "IsNotBankrupt",
+      "msg": "the account is not bankrupt"
+    },
+    {
+      "code": 6011,
+      "name": "NoFreeTokenPositionIndex",
+      "msg": "no free token position index"
+    },
+    {
+      "code": 6012,
+      "name": "NoFreeSerum3OpenOrdersIndex",
+      "msg": "no free serum3 open orders index"
+    },
+    {
+      "code": 6013,
+      "name": "NoFreePerpPositionIndex",
+      "msg": "no free perp position index"
+    },
+    {
+      "code": 6014,
+      "name": "Serum3OpenOrdersExistAlready",
+      "msg": "serum3 open orders exist already"
+    },
+    {
+      "code": 6015,
+      "name": "InsufficentBankVaultFunds",
+      "msg": "bank vault has insufficent funds"
+    },
+    {
+      "code": 6016,
+      "name": "BeingLiquidated",
+      "msg": "account is currently being liquidated"
+    },
+    {
+      "code": 6017,
+      "name": "InvalidBank",
+      "msg": "invalid bank"
+    },
+    {
+      "code": 6018,
+      "name": "ProfitabilityMismatch",
+      "msg": "account profitability is mismatched"
+    },
+    {
+      "code": 6019,
+      "name": "CannotSettleWithSelf",
+      "msg": "cannot settle with self"
+    },
+    {
+      "code": 6020,
+      "name": "PerpPositionDoesNotExist",
+      "msg": "perp position does not exist"
+    },
+    {
+      "code": 6021,
+      "name": "MaxSettleAmountMustBeGreaterThanZero",
+      "msg": "max settle amount must be greater than zero"
+    },
+    {
+      "code": 6022,
+      "name": "HasOpenPerpOrders",
+      "msg": "the perp position has open orders or unprocessed fill events"
+    },
+    {
+      "code": 6023,
+      "name": "OracleConfidence",
+      "msg": "an oracle does not reach the confidence threshold"
+    },
+    {
+      "code": 6024,
+      "name": "OracleStale",
+      "msg": "an oracle is stale"
+    },
+    {
+      "code": 6025,
+      "name": "SettlementAmountMustBePositive",
+      "msg": "settlement amount must always be positive"
+    },
+    {
+      "code": 6026,
+      "name": "BankBorrowLimitReached",
+      "msg": "bank utilization has reached limit"
+    },
+    {
+      "code": 6027,
+      "name": "BankNetBorrowsLimitReached",
+      "msg": "bank net borrows has reached limit - this is an intermittent error - the limit will reset regularly"
+    },
+    {
+      "code": 6028,
+      "name": "TokenPositionDoesNotExist",
+      "msg": "token position does not exist"
+    },
+    {
+      "code": 6029,
+      "name": "DepositsIntoLiquidatingMustRecover",
+      "msg": "token deposits into accounts that are being liquidated must bring their health above the init threshold"
+    },
+    {
+      "code": 6030,
+      "name": "TokenInReduceOnlyMode",
+      "msg": "token is in reduce only mode"
+    },
+    {
+      "code": 6031,
+      "name": "MarketInReduceOnlyMode",
+      "msg": "market is in reduce only mode"
+    },
+    {
+      "code": 6032,
+      "name": "GroupIsHalted",
+      "msg": "group is halted"
+    },
+    {
+      "code": 6033,
+      "name": "PerpHasBaseLots",
+      "msg": "the perp position has non-zero base lots"
+    },
+    {
+      "code": 6034,
+      "name": "HasOpenOrUnsettledSerum3Orders",
+      "msg": "there are open or unsettled serum3 orders"
+    },
+    {
+      "code": 6035,
+      "name": "HasLiquidatableTokenPosition",
+      "msg": "has liquidatable token position"
+    },
+    {
+      "code": 6036,
+      "name": "HasLiquidatablePerpBasePosition",
+      "msg": "has liquidatable perp base position"
+    },
+    {
+      "code": 6037,
+      "name": "HasLiquidatablePositivePerpPnl",
+      "msg": "has liquidatable positive perp pnl"
     },
     {
-      "name": "UpdateRateLog",
-      "fields": [
-        {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
-        },
-        {
-          "name": "tokenIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "rate0",
-          "type": "i128",
-          "index": false
-        },
-        {
-          "name": "rate1",
-          "type": "i128",
-          "index": false
-        },
-        {
-          "name": "maxRate",
-          "type": "i128",
-          "index": false
-        }
-      ]
+      "code": 6038,
+      "name": "AccountIsFrozen",
+      "msg": "account is frozen"
     },
     {
-      "name": "TokenLiqWithTokenLog",
-      "fields": [
+      "code": 6039,
+      "name": "InitAssetWeightCantBeNegative",
+      "msg": "Init Asset Weight can't be negative"
+    },
+    {
+      "code": 6040,
+      "name": "HasOpenPerpTakerFills",
+      "msg": "has open perp taker fills"
+    },
+    {
+      "code": 6041,
+      "name": "DepositLimit",
+      "msg": "deposit crosses the current group deposit limit"
+    },
+    {
+      "code": 6042,
+      "name": "IxIsDisabled",
+      "msg": "instruction is disabled"
+    },
+    {
+      "code": 6043,
+      "name": "NoLiquidatablePerpBasePosition",
+      "msg": "no liquidatable perp base position"
+    },
+    {
+      "code": 6044,
+      "name": "PerpOrderIdNotFound",
+      "msg": "perp order id not found on the orderbook"
+    },
+    {
+      "code": 6045,
+      "name": "HealthRegionBadInnerInstruction",
+      "msg": "HealthRegions allow only specific instructions between Begin and End"
+    },
+    {
+      "code": 6046,
+      "name": "TokenInForceClose",
+      "msg": "token is in force close"
+    },
+    {
+      "code": 6047,
+      "name": "InvalidHealthAccountCount",
+      "msg": "incorrect number of health accounts"
+    },
+    {
+      "code": 6048,
+      "name": "PerpTriggerOrderNotFound",
+      "msg": "perp trigger order not found"
+    },
+    {
+      "code": 6049,
+      "name": "PerpTriggerOrderNotTriggered",
+      "msg": "perp trigger order condition is not met"
+    },
+    {
+      "code": 6050,
+      "name": "WouldSelfTrade",
+      "msg": "the order would self trade"
+    },
+    {
+      "code": 6051,
+      "name": "FillOrKillNotFilled",
+      "msg": "the fill-or-kill order could not be filled completely"
+    },
+    {
+      "code": 6052,
+      "name": "PerpMarketExpired",
+      "msg": "the perp market has expired"
+    },
+    {
+      "code": 6053,
+      "name": "PerpMarketNotExpired",
+      "msg": "the perp market has not expired yet"
+    }
+  ]
+};
+
+export const IDL: MangoV4 = {
+  "version": "0.14.0",
+  "name": "mango_v4",
+  "instructions": [
+    {
+      "name": "groupCreate",
+      "accounts": [
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "group",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "Group"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "creator"
+              },
+              {
+                "kind": "arg",
+                "type": "u32",
+                "path": "group_num"
+              }
+            ]
+          }
         },
         {
-          "name": "liqee",
-          "type": "publicKey",
-          "index": false
+          "name": "creator",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "liqor",
-          "type": "publicKey",
-          "index": false
+          "name": "insuranceMint",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "assetTokenIndex",
-          "type": "u16",
-          "index": false
+          "name": "insuranceVault",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "InsuranceVault"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              }
+            ]
+          }
         },
         {
-          "name": "liabTokenIndex",
-          "type": "u16",
-          "index": false
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
         },
         {
-          "name": "assetTransfer",
-          "type": "i128",
-          "index": false
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "liabTransfer",
-          "type": "i128",
-          "index": false
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "assetPrice",
-          "type": "i128",
-          "index": false
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "groupNum",
+          "type": "u32"
         },
         {
-          "name": "liabPrice",
-          "type": "i128",
-          "index": false
+          "name": "testing",
+          "type": "u8"
         },
         {
-          "name": "bankruptcy",
-          "type": "bool",
-          "index": false
+          "name": "version",
+          "type": "u8"
         }
       ]
     },
     {
-      "name": "Serum3OpenOrdersBalanceLog",
-      "fields": [
-        {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
-        },
-        {
-          "name": "mangoAccount",
-          "type": "publicKey",
-          "index": false
-        },
-        {
-          "name": "baseTokenIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "quoteTokenIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "baseTotal",
-          "type": "u64",
-          "index": false
-        },
-        {
-          "name": "baseFree",
-          "type": "u64",
-          "index": false
-        },
-        {
-          "name": "quoteTotal",
-          "type": "u64",
-          "index": false
-        },
+      "name": "groupEdit",
+      "accounts": [
         {
-          "name": "quoteFree",
-          "type": "u64",
-          "index": false
+          "name": "group",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "referrerRebatesAccrued",
-          "type": "u64",
-          "index": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         }
-      ]
-    },
-    {
-      "name": "Serum3OpenOrdersBalanceLogV2",
-      "fields": [
+      ],
+      "args": [
+        {
+          "name": "adminOpt",
+          "type": {
+            "option": "publicKey"
+          }
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "fastListingAdminOpt",
+          "type": {
+            "option": "publicKey"
+          }
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
-          "index": false
+          "name": "securityAdminOpt",
+          "type": {
+            "option": "publicKey"
+          }
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
-          "index": false
+          "name": "testingOpt",
+          "type": {
+            "option": "u8"
+          }
         },
         {
-          "name": "baseTokenIndex",
-          "type": "u16",
-          "index": false
+          "name": "versionOpt",
+          "type": {
+            "option": "u8"
+          }
         },
         {
-          "name": "quoteTokenIndex",
-          "type": "u16",
-          "index": false
+          "name": "depositLimitQuoteOpt",
+          "type": {
+            "option": "u64"
+          }
         },
         {
-          "name": "baseTotal",
-          "type": "u64",
-          "index": false
+          "name": "buybackFeesOpt",
+          "type": {
+            "option": "bool"
+          }
         },
         {
-          "name": "baseFree",
-          "type": "u64",
-          "index": false
+          "name": "buybackFeesBonusFactorOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "quoteTotal",
-          "type": "u64",
-          "index": false
+          "name": "buybackFeesSwapMangoAccountOpt",
+          "type": {
+            "option": "publicKey"
+          }
         },
         {
-          "name": "quoteFree",
-          "type": "u64",
-          "index": false
+          "name": "mngoTokenIndexOpt",
+          "type": {
+            "option": "u16"
+          }
         },
         {
-          "name": "referrerRebatesAccrued",
-          "type": "u64",
-          "index": false
+          "name": "buybackFeesExpiryIntervalOpt",
+          "type": {
+            "option": "u64"
+          }
         }
       ]
     },
     {
-      "name": "WithdrawLoanOriginationFeeLog",
-      "fields": [
+      "name": "groupWithdrawInsuranceFund",
+      "accounts": [
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
-          "index": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
-          "index": false
+          "name": "insuranceVault",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "loanOriginationFee",
-          "type": "i128",
-          "index": false
+          "name": "destination",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "instruction",
-          "type": {
-            "defined": "LoanOriginationFeeInstruction"
-          },
-          "index": false
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
         }
       ]
     },
     {
-      "name": "TokenLiqBankruptcyLog",
-      "fields": [
+      "name": "insuranceFundCreate",
+      "accounts": [
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "group",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "liqee",
-          "type": "publicKey",
-          "index": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "liqor",
-          "type": "publicKey",
-          "index": false
+          "name": "insuranceMint",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "liabTokenIndex",
-          "type": "u16",
-          "index": false
+          "name": "insuranceVault",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "InsuranceVault"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "fund_index"
+              }
+            ]
+          }
         },
         {
-          "name": "initialLiabNative",
-          "type": "i128",
-          "index": false
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
         },
         {
-          "name": "liabPrice",
-          "type": "i128",
-          "index": false
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "insuranceTokenIndex",
-          "type": "u16",
-          "index": false
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "insuranceTransfer",
-          "type": "i128",
-          "index": false
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "fundIndex",
+          "type": "u16"
+        }
+      ]
+    },
+    {
+      "name": "insuranceFundWithdraw",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "socializedLoss",
-          "type": "i128",
-          "index": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "startingLiabDepositIndex",
-          "type": "i128",
-          "index": false
+          "name": "insuranceVault",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "endingLiabDepositIndex",
-          "type": "i128",
-          "index": false
+          "name": "destination",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "fundIndex",
+          "type": "u16"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
         }
       ]
     },
     {
-      "name": "DeactivateTokenPositionLog",
-      "fields": [
+      "name": "insuranceFundClose",
+      "accounts": [
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
-          "index": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
-          "index": false
+          "name": "insuranceVault",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "cumulativeDepositInterest",
-          "type": "f64",
-          "index": false
+          "name": "solDestination",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "cumulativeBorrowInterest",
-          "type": "f64",
-          "index": false
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "fundIndex",
+          "type": "u16"
         }
       ]
     },
     {
-      "name": "DeactivatePerpPositionLog",
-      "fields": [
+      "name": "tokenEditInsuranceFund",
+      "accounts": [
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "group",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
-          "index": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
-          "index": false
-        },
+          "name": "mintInfo",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
         {
-          "name": "cumulativeLongFunding",
-          "type": "f64",
-          "index": false
-        },
+          "name": "fundIndex",
+          "type": "u16"
+        }
+      ]
+    },
+    {
+      "name": "perpEditInsuranceFund",
+      "accounts": [
         {
-          "name": "cumulativeShortFunding",
-          "type": "f64",
-          "index": false
+          "name": "group",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "makerVolume",
-          "type": "u64",
-          "index": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "takerVolume",
-          "type": "u64",
-          "index": false
-        },
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
         {
-          "name": "perpSpotTransfers",
-          "type": "i64",
-          "index": false
+          "name": "fundIndex",
+          "type": "u16"
         }
       ]
     },
     {
-      "name": "TokenMetaDataLog",
-      "fields": [
+      "name": "ixGateSet",
+      "accounts": [
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "group",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "mint",
-          "type": "publicKey",
-          "index": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        }
+      ],
+      "args": [
+        {
+          "name": "ixGate",
+          "type": "u128"
+        }
+      ]
+    },
+    {
+      "name": "groupClose",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
-          "index": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "mintDecimals",
-          "type": "u8",
-          "index": false
+          "name": "insuranceVault",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "oracle",
-          "type": "publicKey",
-          "index": false
+          "name": "solDestination",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "mintInfo",
-          "type": "publicKey",
-          "index": false
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
         }
-      ]
+      ],
+      "args": []
     },
     {
-      "name": "PerpMarketMetaDataLog",
-      "fields": [
-        {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
-        },
-        {
-          "name": "perpMarket",
-          "type": "publicKey",
-          "index": false
-        },
+      "name": "tokenRegister",
+      "accounts": [
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
-          "index": false
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "baseDecimals",
-          "type": "u8",
-          "index": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "baseLotSize",
-          "type": "i64",
-          "index": false
+          "name": "mint",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "quoteLotSize",
-          "type": "i64",
-          "index": false
+          "name": "bank",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "Bank"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "token_index"
+              },
+              {
+                "kind": "const",
+                "type": "u32",
+                "value": 0
+              }
+            ]
+          }
         },
         {
-          "name": "oracle",
-          "type": "publicKey",
-          "index": false
-        }
-      ]
-    },
-    {
-      "name": "Serum3RegisterMarketLog",
-      "fields": [
-        {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "vault",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "Vault"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "token_index"
+              },
+              {
+                "kind": "const",
+                "type": "u32",
+                "value": 0
+              }
+            ]
+          }
         },
         {
-          "name": "serumMarket",
-          "type": "publicKey",
-          "index": false
+          "name": "mintInfo",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "MintInfo"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "account": "Mint",
+                "path": "mint"
+              }
+            ]
+          }
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
-          "index": false
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "baseTokenIndex",
-          "type": "u16",
-          "index": false
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
         },
         {
-          "name": "quoteTokenIndex",
-          "type": "u16",
-          "index": false
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "serumProgram",
-          "type": "publicKey",
-          "index": false
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "serumProgramExternal",
-          "type": "publicKey",
-          "index": false
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
         }
-      ]
-    },
-    {
-      "name": "PerpLiqBaseOrPositivePnlLog",
-      "fields": [
-        {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
-        },
-        {
-          "name": "perpMarketIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "liqor",
-          "type": "publicKey",
-          "index": false
-        },
-        {
-          "name": "liqee",
-          "type": "publicKey",
-          "index": false
-        },
+      ],
+      "args": [
         {
-          "name": "baseTransfer",
-          "type": "i64",
-          "index": false
+          "name": "tokenIndex",
+          "type": "u16"
         },
         {
-          "name": "quoteTransfer",
-          "type": "i128",
-          "index": false
+          "name": "name",
+          "type": "string"
         },
         {
-          "name": "pnlTransfer",
-          "type": "i128",
-          "index": false
+          "name": "oracleConfig",
+          "type": {
+            "defined": "OracleConfigParams"
+          }
         },
         {
-          "name": "pnlSettleLimitTransfer",
-          "type": "i128",
-          "index": false
+          "name": "interestRateParams",
+          "type": {
+            "defined": "InterestRateParams"
+          }
         },
         {
-          "name": "price",
-          "type": "i128",
-          "index": false
-        }
-      ]
-    },
-    {
-      "name": "PerpLiqBankruptcyLog",
-      "fields": [
-        {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "loanFeeRate",
+          "type": "f32"
         },
         {
-          "name": "liqee",
-          "type": "publicKey",
-          "index": false
+          "name": "loanOriginationFeeRate",
+          "type": "f32"
         },
         {
-          "name": "liqor",
-          "type": "publicKey",
-          "index": false
+          "name": "maintAssetWeight",
+          "type": "f32"
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
-          "index": false
+          "name": "initAssetWeight",
+          "type": "f32"
         },
         {
-          "name": "insuranceTransfer",
-          "type": "i128",
-          "index": false
+          "name": "maintLiabWeight",
+          "type": "f32"
         },
         {
-          "name": "socializedLoss",
-          "type": "i128",
-          "index": false
+          "name": "initLiabWeight",
+          "type": "f32"
         },
         {
-          "name": "startingLongFunding",
-          "type": "i128",
-          "index": false
+          "name": "liquidationFee",
+          "type": "f32"
         },
         {
-          "name": "startingShortFunding",
-          "type": "i128",
-          "index": false
+          "name": "minVaultToDepositsRatio",
+          "type": "f64"
         },
         {
-          "name": "endingLongFunding",
-          "type": "i128",
-          "index": false
+          "name": "netBorrowLimitWindowSizeTs",
+          "type": "u64"
         },
         {
-          "name": "endingShortFunding",
-          "type": "i128",
-          "index": false
+          "name": "netBorrowLimitPerWindowQuote",
+          "type": "i64"
         }
       ]
     },
     {
-      "name": "PerpLiqNegativePnlOrBankruptcyLog",
-      "fields": [
+      "name": "tokenRegisterTrustless",
+      "accounts": [
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "liqee",
-          "type": "publicKey",
-          "index": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "liqor",
-          "type": "publicKey",
-          "index": false
+          "name": "mint",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
-          "index": false
+          "name": "bank",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "Bank"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "token_index"
+              },
+              {
+                "kind": "const",
+                "type": "u32",
+                "value": 0
+              }
+            ]
+          }
         },
         {
-          "name": "settlement",
-          "type": "i128",
-          "index": false
-        }
-      ]
-    },
-    {
-      "name": "PerpSettlePnlLog",
-      "fields": [
+          "name": "vault",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "Vault"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "token_index"
+              },
+              {
+                "kind": "const",
+                "type": "u32",
+                "value": 0
+              }
+            ]
+          }
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "mintInfo",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "MintInfo"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "account": "Mint",
+                "path": "mint"
+              }
+            ]
+          }
         },
         {
-          "name": "mangoAccountA",
-          "type": "publicKey",
-          "index": false
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "mangoAccountB",
-          "type": "publicKey",
-          "index": false
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
-          "index": false
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "settlement",
-          "type": "i128",
-          "index": false
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "settler",
-          "type": "publicKey",
-          "index": false
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "tokenIndex",
+          "type": "u16"
         },
         {
-          "name": "fee",
-          "type": "i128",
-          "index": false
+          "name": "name",
+          "type": "string"
         }
       ]
     },
     {
-      "name": "PerpSettleFeesLog",
-      "fields": [
+      "name": "tokenEdit",
+      "accounts": [
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
-          "index": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
-          "index": false
+          "name": "mintInfo",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "settlement",
-          "type": "i128",
-          "index": false
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The oracle account is optional and only used when reset_stable_price is set.",
+            ""
+          ]
         }
-      ]
-    },
-    {
-      "name": "AccountBuybackFeesWithMngoLog",
-      "fields": [
+      ],
+      "args": [
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "oracleOpt",
+          "type": {
+            "option": "publicKey"
+          }
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
-          "index": false
+          "name": "oracleConfigOpt",
+          "type": {
+            "option": {
+              "defined": "OracleConfigParams"
+            }
+          }
         },
         {
-          "name": "buybackFees",
-          "type": "i128",
-          "index": false
+          "name": "groupInsuranceFundOpt",
+          "type": {
+            "option": "bool"
+          }
         },
         {
-          "name": "buybackMngo",
-          "type": "i128",
-          "index": false
+          "name": "interestRateParamsOpt",
+          "type": {
+            "option": {
+              "defined": "InterestRateParams"
+            }
+          }
         },
         {
-          "name": "mngoBuybackPrice",
-          "type": "i128",
-          "index": false
+          "name": "loanFeeRateOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "oraclePrice",
-          "type": "i128",
-          "index": false
-        }
-      ]
-    },
-    {
-      "name": "FilledPerpOrderLog",
-      "fields": [
-        {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "loanOriginationFeeRateOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
-          "index": false
+          "name": "maintAssetWeightOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "seqNum",
-          "type": "u64",
-          "index": false
-        }
-      ]
-    },
-    {
-      "name": "PerpForceClosePositionLog",
-      "fields": [
-        {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "initAssetWeightOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
-          "index": false
+          "name": "maintLiabWeightOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "accountA",
-          "type": "publicKey",
-          "index": false
+          "name": "initLiabWeightOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "accountB",
-          "type": "publicKey",
-          "index": false
+          "name": "liquidationFeeOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "baseTransfer",
-          "type": "i64",
-          "index": false
+          "name": "stablePriceDelayIntervalSecondsOpt",
+          "type": {
+            "option": "u32"
+          }
         },
         {
-          "name": "quoteTransfer",
-          "type": "i128",
-          "index": false
+          "name": "stablePriceDelayGrowthLimitOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "price",
-          "type": "i128",
-          "index": false
-        }
-      ]
-    },
-    {
-      "name": "TokenForceCloseBorrowsWithTokenLog",
-      "fields": [
-        {
-          "name": "mangoGroup",
-          "type": "publicKey",
-          "index": false
+          "name": "stablePriceGrowthLimitOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "liqor",
-          "type": "publicKey",
-          "index": false
+          "name": "minVaultToDepositsRatioOpt",
+          "type": {
+            "option": "f64"
+          }
         },
         {
-          "name": "liqee",
-          "type": "publicKey",
-          "index": false
+          "name": "netBorrowLimitPerWindowQuoteOpt",
+          "type": {
+            "option": "i64"
+          }
         },
         {
-          "name": "assetTokenIndex",
-          "type": "u16",
-          "index": false
+          "name": "netBorrowLimitWindowSizeTsOpt",
+          "type": {
+            "option": "u64"
+          }
         },
         {
-          "name": "liabTokenIndex",
-          "type": "u16",
-          "index": false
+          "name": "borrowWeightScaleStartQuoteOpt",
+          "type": {
+            "option": "f64"
+          }
         },
         {
-          "name": "assetTransfer",
-          "type": "i128",
-          "index": false
+          "name": "depositWeightScaleStartQuoteOpt",
+          "type": {
+            "option": "f64"
+          }
         },
         {
-          "name": "liabTransfer",
-          "type": "i128",
-          "index": false
+          "name": "resetStablePrice",
+          "type": "bool"
         },
         {
-          "name": "assetPrice",
-          "type": "i128",
-          "index": false
+          "name": "resetNetBorrowLimit",
+          "type": "bool"
         },
         {
-          "name": "liabPrice",
-          "type": "i128",
-          "index": false
+          "name": "reduceOnlyOpt",
+          "type": {
+            "option": "u8"
+          }
         },
         {
-          "name": "feeFactor",
-          "type": "i128",
-          "index": false
-        }
-      ]
-    }
-  ],
-  "errors": [
-    {
-      "code": 6000,
-      "name": "SomeError",
-      "msg": ""
-    },
-    {
-      "code": 6001,
-      "name": "NotImplementedError",
-      "msg": ""
-    },
-    {
-      "code": 6002,
-      "name": "MathError",
-      "msg": "checked math error"
-    },
-    {
-      "code": 6003,
-      "name": "UnexpectedOracle",
-      "msg": ""
-    },
-    {
-      "code": 6004,
-      "name": "UnknownOracleType",
-      "msg": "oracle type cannot be determined"
-    },
-    {
-      "code": 6005,
-      "name": "InvalidFlashLoanTargetCpiProgram",
-      "msg": ""
-    },
-    {
-      "code": 6006,
-      "name": "HealthMustBePositive",
-      "msg": "health must be positive"
-    },
-    {
-      "code": 6007,
-      "name": "HealthMustBePositiveOrIncrease",
-      "msg": "health must be positive or increase"
-    },
-    {
-      "code": 6008,
-      "name": "HealthMustBeNegative",
-      "msg": "health must be negative"
-    },
-    {
-      "code": 6009,
-      "name": "IsBankrupt",
-      "msg": "the account is bankrupt"
-    },
-    {
-      "code": 6010,
-      "name": "IsNotBankrupt",
-      "msg": "the account is not bankrupt"
-    },
-    {
-      "code": 6011,
-      "name": "NoFreeTokenPositionIndex",
-      "msg": "no free token position index"
-    },
-    {
-      "code": 6012,
-      "name": "NoFreeSerum3OpenOrdersIndex",
-      "msg": "no free serum3 open orders index"
-    },
-    {
-      "code": 6013,
-      "name": "NoFreePerpPositionIndex",
-      "msg": "no free perp position index"
-    },
-    {
-      "code": 6014,
-      "name": "Serum3OpenOrdersExistAlready",
-      "msg": "serum3 open orders exist already"
-    },
-    {
-      "code": 6015,
-      "name": "InsufficentBankVaultFunds",
-      "msg": "bank vault has insufficent funds"
-    },
-    {
-      "code": 6016,
-      "name": "BeingLiquidated",
-      "msg": "account is currently being liquidated"
-    },
-    {
-      "code": 6017,
-      "name": "InvalidBank",
-      "msg": "invalid bank"
-    },
-    {
-      "code": 6018,
-      "name": "ProfitabilityMismatch",
-      "msg": "account profitability is mismatched"
-    },
-    {
-      "code": 6019,
-      "name": "CannotSettleWithSelf",
-      "msg": "cannot settle with self"
-    },
-    {
-      "code": 6020,
-      "name": "PerpPositionDoesNotExist",
-      "msg": "perp position does not exist"
-    },
-    {
-      "code": 6021,
-      "name": "MaxSettleAmountMustBeGreaterThanZero",
-      "msg": "max settle amount must be greater than zero"
-    },
-    {
-      "code": 6022,
-      "name": "HasOpenPerpOrders",
-      "msg": "the perp position has open orders or unprocessed fill events"
-    },
-    {
-      "code": 6023,
-      "name": "OracleConfidence",
-      "msg": "an oracle does not reach the confidence threshold"
-    },
-    {
-      "code": 6024,
-      "name": "OracleStale",
-      "msg": "an oracle is stale"
-    },
-    {
-      "code": 6025,
-      "name": "SettlementAmountMustBePositive",
-      "msg": "settlement amount must always be positive"
-    },
-    {
-      "code": 6026,
-      "name": "BankBorrowLimitReached",
-      "msg": "bank utilization has reached limit"
-    },
-    {
-      "code": 6027,
-      "name": "BankNetBorrowsLimitReached",
-      "msg": "bank net borrows has reached limit - this is an intermittent error - the limit will reset regularly"
-    },
-    {
-      "code": 6028,
-      "name": "TokenPositionDoesNotExist",
-      "msg": "token position does not exist"
-    },
-    {
-      "code": 6029,
-      "name": "DepositsIntoLiquidatingMustRecover",
-      "msg": "token deposits into accounts that are being liquidated must bring their health above the init threshold"
-    },
-    {
-      "code": 6030,
-      "name": "TokenInReduceOnlyMode",
-      "msg": "token is in reduce only mode"
-    },
-    {
-      "code": 6031,
-      "name": "MarketInReduceOnlyMode",
-      "msg": "market is in reduce only mode"
-    },
-    {
-      "code": 6032,
-      "name": "GroupIsHalted",
-      "msg": "group is halted"
-    },
-    {
-      "code": 6033,
-      "name": "PerpHasBaseLots",
-      "msg": "the perp position has non-zero base lots"
-    },
-    {
-      "code": 6034,
-      "name": "HasOpenOrUnsettledSerum3Orders",
-      "msg": "there are open or unsettled serum3 orders"
-    },
-    {
-      "code": 6035,
-      "name": "HasLiquidatableTokenPosition",
-      "msg": "has liquidatable token position"
-    },
-    {
-      "code": 6036,
-      "name": "HasLiquidatablePerpBasePosition",
-      "msg": "has liquidatable perp base position"
-    },
-    {
-      "code": 6037,
-      "name": "HasLiquidatablePositivePerpPnl",
-      "msg": "has liquidatable positive perp pnl"
-    },
-    {
-      "code": 6038,
-      "name": "AccountIsFrozen",
-      "msg": "account is frozen"
-    },
-    {
-      "code": 6039,
-      "name": "InitAssetWeightCantBeNegative",
-      "msg": "Init Asset Weight can't be negative"
-    },
-    {
-      "code": 6040,
-      "name": "HasOpenPerpTakerFills",
-      "msg": "has open perp taker fills"
-    },
-    {
-      "code": 6041,
-      "name": "DepositLimit",
-      "msg": "deposit crosses the current group deposit limit"
-    },
-    {
-      "code": 6042,
-      "name": "IxIsDisabled",
-      "msg": "instruction is disabled"
-    },
-    {
-      "code": 6043,
-      "name": "NoLiquidatablePerpBasePosition",
-      "msg": "no liquidatable perp base position"
-    },
-    {
-      "code": 6044,
-      "name": "PerpOrderIdNotFound",
-      "msg": "perp order id not found on the orderbook"
-    },
-    {
-      "code": 6045,
-      "name": "HealthRegionBadInnerInstruction",
-      "msg": "HealthRegions allow only specific instructions between Begin and End"
+          "name": "nameOpt",
+          "type": {
+            "option": "string"
+          }
+        },
+        {
+          "name": "forceCloseOpt",
+          "type": {
+            "option": "bool"
+          }
+        },
+        {
+          "name": "fallbackOracleOpt",
+          "type": {
+            "option": "publicKey"
+          }
+        }
+      ]
     },
     {
-      "code": 6046,
-      "name": "TokenInForceClose",
-      "msg": "token is in force close"
-    }
-  ]
-};
-
-export const IDL: MangoV4 = {
-  "version": "0.14.0",
-  "name": "mango_v4",
-  "instructions": [
-    {
-      "name": "groupCreate",
+      "name": "tokenAddBank",
       "accounts": [
         {
           "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "mint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "existingBank",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "bank",
           "isMut": true,
           "isSigner": false,
           "pda": {
@@ -9029,33 +11612,28 @@ export const IDL: MangoV4 = {
               {
                 "kind": "const",
                 "type": "string",
-                "value": "Group"
+                "value": "Bank"
               },
               {
                 "kind": "account",
                 "type": "publicKey",
-                "path": "creator"
+                "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "token_index"
               },
               {
                 "kind": "arg",
                 "type": "u32",
-                "path": "group_num"
+                "path": "bank_num"
               }
             ]
           }
         },
         {
-          "name": "creator",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "insuranceMint",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "insuranceVault",
+          "name": "vault",
           "isMut": true,
           "isSigner": false,
           "pda": {
@@ -9063,16 +11641,31 @@ export const IDL: MangoV4 = {
               {
                 "kind": "const",
                 "type": "string",
-                "value": "InsuranceVault"
+                "value": "Vault"
               },
               {
                 "kind": "account",
                 "type": "publicKey",
                 "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "token_index"
+              },
+              {
+                "kind": "arg",
+                "type": "u32",
+                "path": "bank_num"
               }
             ]
           }
         },
+        {
+          "name": "mintInfo",
+          "isMut": true,
+          "isSigner": false
+        },
         {
           "name": "payer",
           "isMut": true,
@@ -9096,104 +11689,53 @@ export const IDL: MangoV4 = {
       ],
       "args": [
         {
-          "name": "groupNum",
-          "type": "u32"
-        },
-        {
-          "name": "testing",
-          "type": "u8"
+          "name": "tokenIndex",
+          "type": "u16"
         },
         {
-          "name": "version",
-          "type": "u8"
+          "name": "bankNum",
+          "type": "u32"
         }
       ]
     },
     {
-      "name": "groupEdit",
+      "name": "tokenDeregister",
       "accounts": [
         {
           "name": "group",
-          "isMut": true,
+          "isMut": false,
           "isSigner": false
         },
         {
           "name": "admin",
           "isMut": false,
           "isSigner": true
-        }
-      ],
-      "args": [
-        {
-          "name": "adminOpt",
-          "type": {
-            "option": "publicKey"
-          }
-        },
-        {
-          "name": "fastListingAdminOpt",
-          "type": {
-            "option": "publicKey"
-          }
-        },
-        {
-          "name": "securityAdminOpt",
-          "type": {
-            "option": "publicKey"
-          }
-        },
-        {
-          "name": "testingOpt",
-          "type": {
-            "option": "u8"
-          }
-        },
-        {
-          "name": "versionOpt",
-          "type": {
-            "option": "u8"
-          }
-        },
-        {
-          "name": "depositLimitQuoteOpt",
-          "type": {
-            "option": "u64"
-          }
-        },
-        {
-          "name": "buybackFeesOpt",
-          "type": {
-            "option": "bool"
-          }
         },
         {
-          "name": "buybackFeesBonusFactorOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "mintInfo",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "buybackFeesSwapMangoAccountOpt",
-          "type": {
-            "option": "publicKey"
-          }
+          "name": "dustVault",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "mngoTokenIndexOpt",
-          "type": {
-            "option": "u16"
-          }
+          "name": "solDestination",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "buybackFeesExpiryIntervalOpt",
-          "type": {
-            "option": "u64"
-          }
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
         }
-      ]
+      ],
+      "args": []
     },
     {
-      "name": "groupWithdrawInsuranceFund",
+      "name": "tokenUpdateIndexAndRate",
       "accounts": [
         {
           "name": "group",
@@ -9201,87 +11743,153 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "admin",
+          "name": "mintInfo",
           "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "insuranceVault",
-          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "destination",
-          "isMut": true,
+          "name": "oracle",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "tokenProgram",
+          "name": "instructions",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": [
-        {
-          "name": "amount",
-          "type": "u64"
-        }
-      ]
+      "args": []
     },
     {
-      "name": "ixGateSet",
+      "name": "accountCreate",
       "accounts": [
         {
           "name": "group",
-          "isMut": true,
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "admin",
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "MangoAccount"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "owner"
+              },
+              {
+                "kind": "arg",
+                "type": "u32",
+                "path": "account_num"
+              }
+            ]
+          }
+        },
+        {
+          "name": "owner",
           "isMut": false,
           "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "ixGate",
-          "type": "u128"
+          "name": "accountNum",
+          "type": "u32"
+        },
+        {
+          "name": "tokenCount",
+          "type": "u8"
+        },
+        {
+          "name": "serum3Count",
+          "type": "u8"
+        },
+        {
+          "name": "perpCount",
+          "type": "u8"
+        },
+        {
+          "name": "perpOoCount",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
         }
       ]
     },
     {
-      "name": "groupClose",
+      "name": "accountExpand",
       "accounts": [
         {
           "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "admin",
+          "name": "owner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "insuranceVault",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "solDestination",
+          "name": "payer",
           "isMut": true,
-          "isSigner": false
+          "isSigner": true
         },
         {
-          "name": "tokenProgram",
+          "name": "systemProgram",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": []
+      "args": [
+        {
+          "name": "tokenCount",
+          "type": "u8"
+        },
+        {
+          "name": "serum3Count",
+          "type": "u8"
+        },
+        {
+          "name": "perpCount",
+          "type": "u8"
+        },
+        {
+          "name": "perpOoCount",
+          "type": "u8"
+        }
+      ]
     },
     {
-      "name": "tokenRegister",
+      "name": "accountExpandV2",
       "accounts": [
         {
           "name": "group",
@@ -9289,189 +11897,147 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "admin",
-          "isMut": false,
-          "isSigner": true
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "mint",
+          "name": "owner",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         },
         {
-          "name": "bank",
+          "name": "payer",
           "isMut": true,
-          "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "Bank"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "group"
-              },
-              {
-                "kind": "arg",
-                "type": "u16",
-                "path": "token_index"
-              },
-              {
-                "kind": "const",
-                "type": "u32",
-                "value": 0
-              }
-            ]
-          }
+          "isSigner": true
         },
         {
-          "name": "vault",
-          "isMut": true,
-          "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "Vault"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "group"
-              },
-              {
-                "kind": "arg",
-                "type": "u16",
-                "path": "token_index"
-              },
-              {
-                "kind": "const",
-                "type": "u32",
-                "value": 0
-              }
-            ]
-          }
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "tokenCount",
+          "type": "u8"
         },
         {
-          "name": "mintInfo",
-          "isMut": true,
-          "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "MintInfo"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "group"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "account": "Mint",
-                "path": "mint"
-              }
-            ]
-          }
+          "name": "serum3Count",
+          "type": "u8"
         },
         {
-          "name": "oracle",
-          "isMut": false,
-          "isSigner": false
+          "name": "perpCount",
+          "type": "u8"
         },
         {
-          "name": "payer",
-          "isMut": true,
-          "isSigner": true
+          "name": "perpOoCount",
+          "type": "u8"
         },
         {
-          "name": "tokenProgram",
+          "name": "perpTriggerOrderCount",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "accountEdit",
+      "accounts": [
+        {
+          "name": "group",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "systemProgram",
-          "isMut": false,
+          "name": "account",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "rent",
+          "name": "owner",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         }
       ],
       "args": [
         {
-          "name": "tokenIndex",
-          "type": "u16"
-        },
-        {
-          "name": "name",
-          "type": "string"
-        },
-        {
-          "name": "oracleConfig",
+          "name": "nameOpt",
           "type": {
-            "defined": "OracleConfigParams"
+            "option": "string"
           }
         },
         {
-          "name": "interestRateParams",
+          "name": "delegateOpt",
           "type": {
-            "defined": "InterestRateParams"
+            "option": "publicKey"
           }
-        },
+        }
+      ]
+    },
+    {
+      "name": "accountToggleFreeze",
+      "accounts": [
         {
-          "name": "loanFeeRate",
-          "type": "f32"
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "loanOriginationFeeRate",
-          "type": "f32"
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "maintAssetWeight",
-          "type": "f32"
-        },
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        }
+      ],
+      "args": [
         {
-          "name": "initAssetWeight",
-          "type": "f32"
-        },
+          "name": "freeze",
+          "type": "bool"
+        }
+      ]
+    },
+    {
+      "name": "accountClose",
+      "accounts": [
         {
-          "name": "maintLiabWeight",
-          "type": "f32"
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "initLiabWeight",
-          "type": "f32"
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "liquidationFee",
-          "type": "f32"
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "minVaultToDepositsRatio",
-          "type": "f64"
+          "name": "solDestination",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "netBorrowLimitWindowSizeTs",
-          "type": "u64"
-        },
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
         {
-          "name": "netBorrowLimitPerWindowQuote",
-          "type": "i64"
+          "name": "forceClose",
+          "type": "bool"
         }
       ]
     },
     {
-      "name": "tokenRegisterTrustless",
+      "name": "accountBuybackFeesWithMngo",
       "accounts": [
         {
           "name": "group",
@@ -9479,75 +12045,58 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "admin",
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "owner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "mint",
-          "isMut": false,
+          "name": "daoAccount",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "bank",
+          "name": "mngoBank",
           "isMut": true,
-          "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "Bank"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "group"
-              },
-              {
-                "kind": "arg",
-                "type": "u16",
-                "path": "token_index"
-              },
-              {
-                "kind": "const",
-                "type": "u32",
-                "value": 0
-              }
-            ]
-          }
+          "isSigner": false
         },
         {
-          "name": "vault",
+          "name": "mngoOracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "feesBank",
           "isMut": true,
-          "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "Vault"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "group"
-              },
-              {
-                "kind": "arg",
-                "type": "u16",
-                "path": "token_index"
-              },
-              {
-                "kind": "const",
-                "type": "u32",
-                "value": 0
-              }
-            ]
-          }
+          "isSigner": false
         },
         {
-          "name": "mintInfo",
+          "name": "feesOracle",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "maxBuybackUsd",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "stubOracleCreate",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
           "isMut": true,
           "isSigner": false,
           "pda": {
@@ -9555,7 +12104,7 @@ export const IDL: MangoV4 = {
               {
                 "kind": "const",
                 "type": "string",
-                "value": "MintInfo"
+                "value": "StubOracle"
               },
               {
                 "kind": "account",
@@ -9572,7 +12121,12 @@ export const IDL: MangoV4 = {
           }
         },
         {
-          "name": "oracle",
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "mint",
           "isMut": false,
           "isSigner": false
         },
@@ -9582,34 +12136,81 @@ export const IDL: MangoV4 = {
           "isSigner": true
         },
         {
-          "name": "tokenProgram",
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "price",
+          "type": {
+            "defined": "I80F48"
+          }
+        }
+      ]
+    },
+    {
+      "name": "stubOracleClose",
+      "accounts": [
+        {
+          "name": "group",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "systemProgram",
+          "name": "admin",
           "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "oracle",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "rent",
+          "name": "solDestination",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": [
+      "args": []
+    },
+    {
+      "name": "stubOracleSet",
+      "accounts": [
         {
-          "name": "tokenIndex",
-          "type": "u16"
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "name",
-          "type": "string"
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "oracle",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "price",
+          "type": {
+            "defined": "I80F48"
+          }
         }
       ]
     },
     {
-      "name": "tokenEdit",
+      "name": "tokenDeposit",
       "accounts": [
         {
           "name": "group",
@@ -9617,362 +12218,377 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "admin",
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "owner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "mintInfo",
+          "name": "bank",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "vault",
           "isMut": true,
           "isSigner": false
         },
         {
           "name": "oracle",
           "isMut": false,
-          "isSigner": false,
-          "docs": [
-            "The oracle account is optional and only used when reset_stable_price is set.",
-            ""
-          ]
+          "isSigner": false
+        },
+        {
+          "name": "tokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenAuthority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "oracleOpt",
-          "type": {
-            "option": "publicKey"
-          }
+          "name": "amount",
+          "type": "u64"
         },
         {
-          "name": "oracleConfigOpt",
-          "type": {
-            "option": {
-              "defined": "OracleConfigParams"
-            }
-          }
+          "name": "reduceOnly",
+          "type": "bool"
+        }
+      ]
+    },
+    {
+      "name": "tokenDepositIntoExisting",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "groupInsuranceFundOpt",
-          "type": {
-            "option": "bool"
-          }
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "interestRateParamsOpt",
-          "type": {
-            "option": {
-              "defined": "InterestRateParams"
-            }
-          }
+          "name": "bank",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "vault",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "loanFeeRateOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "loanOriginationFeeRateOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "tokenAccount",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "maintAssetWeightOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "tokenAuthority",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "initAssetWeightOpt",
-          "type": {
-            "option": "f32"
-          }
-        },
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
         {
-          "name": "maintLiabWeightOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "amount",
+          "type": "u64"
         },
         {
-          "name": "initLiabWeightOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "reduceOnly",
+          "type": "bool"
+        }
+      ]
+    },
+    {
+      "name": "tokenWithdraw",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "liquidationFeeOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "stablePriceDelayIntervalSecondsOpt",
-          "type": {
-            "option": "u32"
-          }
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "stablePriceDelayGrowthLimitOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "bank",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "stablePriceGrowthLimitOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "vault",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "minVaultToDepositsRatioOpt",
-          "type": {
-            "option": "f64"
-          }
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "netBorrowLimitPerWindowQuoteOpt",
-          "type": {
-            "option": "i64"
-          }
+          "name": "tokenAccount",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "netBorrowLimitWindowSizeTsOpt",
-          "type": {
-            "option": "u64"
-          }
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
         },
         {
-          "name": "borrowWeightScaleStartQuoteOpt",
-          "type": {
-            "option": "f64"
-          }
+          "name": "allowBorrow",
+          "type": "bool"
+        }
+      ]
+    },
+    {
+      "name": "tokenTransfer",
+      "docs": [
+        "Move tokens between two accounts of the same owner, without going",
+        "through a wallet token account."
+      ],
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "depositWeightScaleStartQuoteOpt",
-          "type": {
-            "option": "f64"
-          }
+          "name": "sourceAccount",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "resetStablePrice",
-          "type": "bool"
+          "name": "destinationAccount",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "resetNetBorrowLimit",
-          "type": "bool"
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "reduceOnlyOpt",
-          "type": {
-            "option": "u8"
-          }
+          "name": "bank",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "nameOpt",
-          "type": {
-            "option": "string"
-          }
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
         },
         {
-          "name": "forceCloseOpt",
-          "type": {
-            "option": "bool"
-          }
+          "name": "allowBorrow",
+          "type": "bool"
         }
       ]
     },
     {
-      "name": "tokenAddBank",
+      "name": "flashLoanBegin",
       "accounts": [
         {
-          "name": "group",
+          "name": "account",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "admin",
+          "name": "owner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "mint",
+          "name": "tokenProgram",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "existingBank",
+          "name": "instructions",
           "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "bank",
-          "isMut": true,
           "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "Bank"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "group"
-              },
-              {
-                "kind": "arg",
-                "type": "u16",
-                "path": "token_index"
-              },
-              {
-                "kind": "arg",
-                "type": "u32",
-                "path": "bank_num"
-              }
-            ]
-          }
-        },
+          "docs": [
+            "Instructions Sysvar for instruction introspection"
+          ]
+        }
+      ],
+      "args": [
         {
-          "name": "vault",
-          "isMut": true,
-          "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "Vault"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "group"
-              },
-              {
-                "kind": "arg",
-                "type": "u16",
-                "path": "token_index"
-              },
-              {
-                "kind": "arg",
-                "type": "u32",
-                "path": "bank_num"
-              }
-            ]
+          "name": "loanAmounts",
+          "type": {
+            "vec": "u64"
           }
-        },
+        }
+      ]
+    },
+    {
+      "name": "flashLoanEnd",
+      "accounts": [
         {
-          "name": "mintInfo",
+          "name": "account",
           "isMut": true,
           "isSigner": false
         },
-        {
-          "name": "payer",
-          "isMut": true,
+        {
+          "name": "owner",
+          "isMut": false,
           "isSigner": true
         },
         {
           "name": "tokenProgram",
           "isMut": false,
           "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "flashLoanType",
+          "type": {
+            "defined": "FlashLoanType"
+          }
+        }
+      ]
+    },
+    {
+      "name": "flashLoanEndV2",
+      "accounts": [
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "systemProgram",
+          "name": "owner",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         },
         {
-          "name": "rent",
+          "name": "tokenProgram",
           "isMut": false,
           "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "tokenIndex",
-          "type": "u16"
+          "name": "numLoans",
+          "type": "u8"
         },
         {
-          "name": "bankNum",
-          "type": "u32"
+          "name": "flashLoanType",
+          "type": {
+            "defined": "FlashLoanType"
+          }
         }
       ]
     },
     {
-      "name": "tokenDeregister",
+      "name": "healthRegionBegin",
       "accounts": [
         {
-          "name": "group",
+          "name": "instructions",
           "isMut": false,
-          "isSigner": false
+          "isSigner": false,
+          "docs": [
+            "Instructions Sysvar for instruction introspection"
+          ]
         },
         {
-          "name": "admin",
+          "name": "group",
           "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "mintInfo",
-          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "dustVault",
+          "name": "account",
           "isMut": true,
           "isSigner": false
-        },
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "healthRegionEnd",
+      "accounts": [
         {
-          "name": "solDestination",
+          "name": "account",
           "isMut": true,
           "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
         }
       ],
       "args": []
     },
     {
-      "name": "tokenUpdateIndexAndRate",
+      "name": "serum3RegisterMarket",
+      "docs": [
+        "",
+        "Serum",
+        ""
+      ],
       "accounts": [
         {
           "name": "group",
-          "isMut": false,
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "mintInfo",
+          "name": "admin",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         },
         {
-          "name": "oracle",
+          "name": "serumProgram",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "instructions",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": []
-    },
-    {
-      "name": "accountCreate",
-      "accounts": [
-        {
-          "name": "group",
+          "name": "serumMarketExternal",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "account",
+          "name": "serumMarket",
           "isMut": true,
           "isSigner": false,
           "pda": {
@@ -9980,7 +12596,7 @@ export const IDL: MangoV4 = {
               {
                 "kind": "const",
                 "type": "string",
-                "value": "MangoAccount"
+                "value": "Serum3Market"
               },
               {
                 "kind": "account",
@@ -9990,20 +12606,44 @@ export const IDL: MangoV4 = {
               {
                 "kind": "account",
                 "type": "publicKey",
-                "path": "owner"
+                "path": "serum_market_external"
+              }
+            ]
+          }
+        },
+        {
+          "name": "indexReservation",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "Serum3Index"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
               },
               {
                 "kind": "arg",
-                "type": "u32",
-                "path": "account_num"
+                "type": "u16",
+                "path": "market_index"
               }
             ]
           }
         },
         {
-          "name": "owner",
+          "name": "quoteBank",
           "isMut": false,
-          "isSigner": true
+          "isSigner": false
+        },
+        {
+          "name": "baseBank",
+          "isMut": false,
+          "isSigner": false
         },
         {
           "name": "payer",
@@ -10018,24 +12658,8 @@ export const IDL: MangoV4 = {
       ],
       "args": [
         {
-          "name": "accountNum",
-          "type": "u32"
-        },
-        {
-          "name": "tokenCount",
-          "type": "u8"
-        },
-        {
-          "name": "serum3Count",
-          "type": "u8"
-        },
-        {
-          "name": "perpCount",
-          "type": "u8"
-        },
-        {
-          "name": "perpOoCount",
-          "type": "u8"
+          "name": "marketIndex",
+          "type": "u16"
         },
         {
           "name": "name",
@@ -10044,7 +12668,7 @@ export const IDL: MangoV4 = {
       ]
     },
     {
-      "name": "accountExpand",
+      "name": "serum3EditMarket",
       "accounts": [
         {
           "name": "group",
@@ -10052,89 +12676,36 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "account",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "owner",
+          "name": "admin",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "payer",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "tokenCount",
-          "type": "u8"
-        },
-        {
-          "name": "serum3Count",
-          "type": "u8"
-        },
-        {
-          "name": "perpCount",
-          "type": "u8"
-        },
-        {
-          "name": "perpOoCount",
-          "type": "u8"
-        }
-      ]
-    },
-    {
-      "name": "accountEdit",
-      "accounts": [
-        {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "account",
+          "name": "market",
           "isMut": true,
           "isSigner": false
-        },
-        {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
         }
       ],
       "args": [
         {
-          "name": "nameOpt",
+          "name": "reduceOnlyOpt",
           "type": {
-            "option": "string"
+            "option": "bool"
           }
         },
         {
-          "name": "delegateOpt",
+          "name": "forceCloseOpt",
           "type": {
-            "option": "publicKey"
+            "option": "bool"
           }
         }
       ]
     },
     {
-      "name": "accountToggleFreeze",
+      "name": "serum3DeregisterMarket",
       "accounts": [
         {
           "name": "group",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "account",
           "isMut": true,
           "isSigner": false
         },
@@ -10142,32 +12713,16 @@ export const IDL: MangoV4 = {
           "name": "admin",
           "isMut": false,
           "isSigner": true
-        }
-      ],
-      "args": [
-        {
-          "name": "freeze",
-          "type": "bool"
-        }
-      ]
-    },
-    {
-      "name": "accountClose",
-      "accounts": [
-        {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
         },
         {
-          "name": "account",
+          "name": "serumMarket",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
+          "name": "indexReservation",
+          "isMut": true,
+          "isSigner": false
         },
         {
           "name": "solDestination",
@@ -10180,15 +12735,10 @@ export const IDL: MangoV4 = {
           "isSigner": false
         }
       ],
-      "args": [
-        {
-          "name": "forceClose",
-          "type": "bool"
-        }
-      ]
+      "args": []
     },
     {
-      "name": "accountBuybackFeesWithMngo",
+      "name": "serum3CreateOpenOrders",
       "accounts": [
         {
           "name": "group",
@@ -10206,48 +12756,22 @@ export const IDL: MangoV4 = {
           "isSigner": true
         },
         {
-          "name": "daoAccount",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "mngoBank",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "mngoOracle",
+          "name": "serumMarket",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "feesBank",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "feesOracle",
+          "name": "serumProgram",
           "isMut": false,
           "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "maxBuybackUsd",
-          "type": "u64"
-        }
-      ]
-    },
-    {
-      "name": "stubOracleCreate",
-      "accounts": [
+        },
         {
-          "name": "group",
+          "name": "serumMarketExternal",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "oracle",
+          "name": "openOrders",
           "isMut": true,
           "isSigner": false,
           "pda": {
@@ -10255,54 +12779,41 @@ export const IDL: MangoV4 = {
               {
                 "kind": "const",
                 "type": "string",
-                "value": "StubOracle"
+                "value": "Serum3OO"
               },
               {
                 "kind": "account",
                 "type": "publicKey",
-                "path": "group"
+                "path": "account"
               },
               {
                 "kind": "account",
                 "type": "publicKey",
-                "account": "Mint",
-                "path": "mint"
+                "path": "serum_market"
               }
             ]
           }
         },
         {
-          "name": "admin",
-          "isMut": false,
+          "name": "payer",
+          "isMut": true,
           "isSigner": true
         },
         {
-          "name": "mint",
+          "name": "systemProgram",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "payer",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "systemProgram",
+          "name": "rent",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": [
-        {
-          "name": "price",
-          "type": {
-            "defined": "I80F48"
-          }
-        }
-      ]
+      "args": []
     },
     {
-      "name": "stubOracleClose",
+      "name": "serum3CloseOpenOrders",
       "accounts": [
         {
           "name": "group",
@@ -10310,58 +12821,45 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "admin",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "oracle",
+          "name": "account",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "solDestination",
-          "isMut": true,
-          "isSigner": false
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "tokenProgram",
+          "name": "serumMarket",
           "isMut": false,
           "isSigner": false
-        }
-      ],
-      "args": []
-    },
-    {
-      "name": "stubOracleSet",
-      "accounts": [
+        },
         {
-          "name": "group",
+          "name": "serumProgram",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "admin",
+          "name": "serumMarketExternal",
           "isMut": false,
-          "isSigner": true
+          "isSigner": false
         },
         {
-          "name": "oracle",
+          "name": "openOrders",
           "isMut": true,
           "isSigner": false
-        }
-      ],
-      "args": [
+        },
         {
-          "name": "price",
-          "type": {
-            "defined": "I80F48"
-          }
+          "name": "solDestination",
+          "isMut": true,
+          "isSigner": false
         }
-      ]
+      ],
+      "args": []
     },
     {
-      "name": "tokenDeposit",
+      "name": "serum3PlaceOrder",
       "accounts": [
         {
           "name": "group",
@@ -10379,84 +12877,83 @@ export const IDL: MangoV4 = {
           "isSigner": true
         },
         {
-          "name": "bank",
+          "name": "openOrders",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "vault",
-          "isMut": true,
+          "name": "serumMarket",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "oracle",
+          "name": "serumProgram",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "tokenAccount",
+          "name": "serumMarketExternal",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "tokenAuthority",
-          "isMut": false,
-          "isSigner": true
+          "name": "marketBids",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "tokenProgram",
-          "isMut": false,
+          "name": "marketAsks",
+          "isMut": true,
           "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "amount",
-          "type": "u64"
         },
         {
-          "name": "reduceOnly",
-          "type": "bool"
-        }
-      ]
-    },
-    {
-      "name": "tokenDepositIntoExisting",
-      "accounts": [
-        {
-          "name": "group",
-          "isMut": false,
+          "name": "marketEventQueue",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "account",
+          "name": "marketRequestQueue",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "bank",
+          "name": "marketBaseVault",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "vault",
+          "name": "marketQuoteVault",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "oracle",
+          "name": "marketVaultSigner",
           "isMut": false,
-          "isSigner": false
+          "isSigner": false,
+          "docs": [
+            "needed for the automatic settle_funds call"
+          ]
         },
         {
-          "name": "tokenAccount",
+          "name": "payerBank",
           "isMut": true,
-          "isSigner": false
+          "isSigner": false,
+          "docs": [
+            "The bank that pays for the order, if necessary"
+          ]
         },
         {
-          "name": "tokenAuthority",
+          "name": "payerVault",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The bank vault that pays for the order, if necessary"
+          ]
+        },
+        {
+          "name": "payerOracle",
           "isMut": false,
-          "isSigner": true
+          "isSigner": false
         },
         {
           "name": "tokenProgram",
@@ -10466,140 +12963,123 @@ export const IDL: MangoV4 = {
       ],
       "args": [
         {
-          "name": "amount",
-          "type": "u64"
-        },
-        {
-          "name": "reduceOnly",
-          "type": "bool"
-        }
-      ]
-    },
-    {
-      "name": "tokenWithdraw",
-      "accounts": [
-        {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "account",
-          "isMut": true,
-          "isSigner": false
+          "name": "side",
+          "type": {
+            "defined": "Serum3Side"
+          }
         },
         {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
+          "name": "limitPrice",
+          "type": "u64"
         },
         {
-          "name": "bank",
-          "isMut": true,
-          "isSigner": false
+          "name": "maxBaseQty",
+          "type": "u64"
         },
         {
-          "name": "vault",
-          "isMut": true,
-          "isSigner": false
+          "name": "maxNativeQuoteQtyIncludingFees",
+          "type": "u64"
         },
         {
-          "name": "oracle",
-          "isMut": false,
-          "isSigner": false
+          "name": "selfTradeBehavior",
+          "type": {
+            "defined": "Serum3SelfTradeBehavior"
+          }
         },
         {
-          "name": "tokenAccount",
-          "isMut": true,
-          "isSigner": false
+          "name": "orderType",
+          "type": {
+            "defined": "Serum3OrderType"
+          }
         },
         {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "amount",
+          "name": "clientOrderId",
           "type": "u64"
         },
         {
-          "name": "allowBorrow",
-          "type": "bool"
+          "name": "limit",
+          "type": "u16"
         }
       ]
     },
     {
-      "name": "flashLoanBegin",
+      "name": "serum3CancelOrder",
       "accounts": [
         {
-          "name": "account",
+          "name": "group",
           "isMut": false,
           "isSigner": false
         },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
+        },
         {
           "name": "owner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "tokenProgram",
+          "name": "openOrders",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "serumMarket",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "instructions",
+          "name": "serumProgram",
           "isMut": false,
-          "isSigner": false,
-          "docs": [
-            "Instructions Sysvar for instruction introspection"
-          ]
-        }
-      ],
-      "args": [
+          "isSigner": false
+        },
         {
-          "name": "loanAmounts",
-          "type": {
-            "vec": "u64"
-          }
-        }
-      ]
-    },
-    {
-      "name": "flashLoanEnd",
-      "accounts": [
+          "name": "serumMarketExternal",
+          "isMut": true,
+          "isSigner": false
+        },
         {
-          "name": "account",
+          "name": "marketBids",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
+          "name": "marketAsks",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "tokenProgram",
-          "isMut": false,
+          "name": "marketEventQueue",
+          "isMut": true,
           "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "flashLoanType",
+          "name": "side",
           "type": {
-            "defined": "FlashLoanType"
+            "defined": "Serum3Side"
           }
+        },
+        {
+          "name": "orderId",
+          "type": "u128"
         }
       ]
     },
     {
-      "name": "flashLoanEndV2",
+      "name": "serum3CancelAllOrders",
       "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
         {
           "name": "account",
-          "isMut": true,
+          "isMut": false,
           "isSigner": false
         },
         {
@@ -10608,226 +13088,126 @@ export const IDL: MangoV4 = {
           "isSigner": true
         },
         {
-          "name": "tokenProgram",
-          "isMut": false,
+          "name": "openOrders",
+          "isMut": true,
           "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "numLoans",
-          "type": "u8"
         },
         {
-          "name": "flashLoanType",
-          "type": {
-            "defined": "FlashLoanType"
-          }
-        }
-      ]
-    },
-    {
-      "name": "healthRegionBegin",
-      "accounts": [
-        {
-          "name": "instructions",
+          "name": "serumMarket",
           "isMut": false,
-          "isSigner": false,
-          "docs": [
-            "Instructions Sysvar for instruction introspection"
-          ]
+          "isSigner": false
         },
         {
-          "name": "group",
+          "name": "serumProgram",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "account",
+          "name": "serumMarketExternal",
           "isMut": true,
           "isSigner": false
-        }
-      ],
-      "args": []
-    },
-    {
-      "name": "healthRegionEnd",
-      "accounts": [
+        },
         {
-          "name": "account",
+          "name": "marketBids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "marketAsks",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "marketEventQueue",
           "isMut": true,
           "isSigner": false
         }
       ],
-      "args": []
+      "args": [
+        {
+          "name": "limit",
+          "type": "u8"
+        }
+      ]
     },
     {
-      "name": "serum3RegisterMarket",
+      "name": "serum3SettleFunds",
       "docs": [
+        "Settles all free funds from the OpenOrders account into the MangoAccount.",
         "",
-        "Serum",
-        ""
+        "Any serum \"referrer rebates\" (ui fees) are considered Mango fees."
       ],
       "accounts": [
         {
           "name": "group",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "admin",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "serumProgram",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "serumMarketExternal",
-          "isMut": false,
+          "name": "account",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "serumMarket",
-          "isMut": true,
-          "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "Serum3Market"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "group"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "serum_market_external"
-              }
-            ]
-          }
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "indexReservation",
+          "name": "openOrders",
           "isMut": true,
-          "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "Serum3Index"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "group"
-              },
-              {
-                "kind": "arg",
-                "type": "u16",
-                "path": "market_index"
-              }
-            ]
-          }
+          "isSigner": false
         },
         {
-          "name": "quoteBank",
+          "name": "serumMarket",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "baseBank",
+          "name": "serumProgram",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "payer",
+          "name": "serumMarketExternal",
           "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
           "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "marketIndex",
-          "type": "u16"
         },
         {
-          "name": "name",
-          "type": "string"
-        }
-      ]
-    },
-    {
-      "name": "serum3EditMarket",
-      "accounts": [
-        {
-          "name": "group",
-          "isMut": false,
+          "name": "marketBaseVault",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "admin",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "market",
+          "name": "marketQuoteVault",
           "isMut": true,
           "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "reduceOnlyOpt",
-          "type": {
-            "option": "bool"
-          }
         },
         {
-          "name": "forceCloseOpt",
-          "type": {
-            "option": "bool"
-          }
-        }
-      ]
-    },
-    {
-      "name": "serum3DeregisterMarket",
-      "accounts": [
+          "name": "marketVaultSigner",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "needed for the automatic settle_funds call"
+          ]
+        },
         {
-          "name": "group",
+          "name": "quoteBank",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "admin",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "serumMarket",
+          "name": "quoteVault",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "indexReservation",
+          "name": "baseBank",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "solDestination",
+          "name": "baseVault",
           "isMut": true,
           "isSigner": false
         },
@@ -10840,7 +13220,120 @@ export const IDL: MangoV4 = {
       "args": []
     },
     {
-      "name": "serum3CreateOpenOrders",
+      "name": "serum3SettleFundsV2",
+      "docs": [
+        "Like Serum3SettleFunds, but `fees_to_dao` determines if referrer rebates are considered fees",
+        "or are credited to the MangoAccount."
+      ],
+      "accounts": [
+        {
+          "name": "v1",
+          "accounts": [
+            {
+              "name": "group",
+              "isMut": false,
+              "isSigner": false
+            },
+            {
+              "name": "account",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "owner",
+              "isMut": false,
+              "isSigner": true
+            },
+            {
+              "name": "openOrders",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "serumMarket",
+              "isMut": false,
+              "isSigner": false
+            },
+            {
+              "name": "serumProgram",
+              "isMut": false,
+              "isSigner": false
+            },
+            {
+              "name": "serumMarketExternal",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "marketBaseVault",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "marketQuoteVault",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "marketVaultSigner",
+              "isMut": false,
+              "isSigner": false,
+              "docs": [
+                "needed for the automatic settle_funds call"
+              ]
+            },
+            {
+              "name": "quoteBank",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "quoteVault",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "baseBank",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "baseVault",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "tokenProgram",
+              "isMut": false,
+              "isSigner": false
+            }
+          ]
+        },
+        {
+          "name": "v2",
+          "accounts": [
+            {
+              "name": "quoteOracle",
+              "isMut": false,
+              "isSigner": false
+            },
+            {
+              "name": "baseOracle",
+              "isMut": false,
+              "isSigner": false
+            }
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "feesToDao",
+          "type": "bool"
+        }
+      ]
+    },
+    {
+      "name": "serum3LiqForceCancelOrders",
       "accounts": [
         {
           "name": "group",
@@ -10853,9 +13346,9 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
+          "name": "openOrders",
+          "isMut": true,
+          "isSigner": false
         },
         {
           "name": "serumMarket",
@@ -10869,99 +13362,74 @@ export const IDL: MangoV4 = {
         },
         {
           "name": "serumMarketExternal",
-          "isMut": false,
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "openOrders",
+          "name": "marketBids",
           "isMut": true,
-          "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "Serum3OO"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "account"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "serum_market"
-              }
-            ]
-          }
+          "isSigner": false
         },
         {
-          "name": "payer",
+          "name": "marketAsks",
           "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "rent",
-          "isMut": false,
+          "name": "marketEventQueue",
+          "isMut": true,
           "isSigner": false
-        }
-      ],
-      "args": []
-    },
-    {
-      "name": "serum3CloseOpenOrders",
-      "accounts": [
+        },
         {
-          "name": "group",
-          "isMut": false,
+          "name": "marketBaseVault",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "account",
+          "name": "marketQuoteVault",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "owner",
+          "name": "marketVaultSigner",
           "isMut": false,
-          "isSigner": true
+          "isSigner": false
         },
         {
-          "name": "serumMarket",
-          "isMut": false,
+          "name": "quoteBank",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "serumProgram",
-          "isMut": false,
+          "name": "quoteVault",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "serumMarketExternal",
-          "isMut": false,
+          "name": "baseBank",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "openOrders",
+          "name": "baseVault",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "solDestination",
-          "isMut": true,
+          "name": "tokenProgram",
+          "isMut": false,
           "isSigner": false
         }
       ],
-      "args": []
+      "args": [
+        {
+          "name": "limit",
+          "type": "u8"
+        }
+      ]
     },
     {
-      "name": "serum3PlaceOrder",
+      "name": "liqTokenWithToken",
       "accounts": [
         {
           "name": "group",
@@ -10969,92 +13437,74 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "account",
+          "name": "liqor",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "owner",
+          "name": "liqorOwner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "openOrders",
+          "name": "liqee",
           "isMut": true,
           "isSigner": false
-        },
-        {
-          "name": "serumMarket",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "serumProgram",
-          "isMut": false,
-          "isSigner": false
-        },
+        }
+      ],
+      "args": [
         {
-          "name": "serumMarketExternal",
-          "isMut": true,
-          "isSigner": false
+          "name": "assetTokenIndex",
+          "type": "u16"
         },
         {
-          "name": "marketBids",
-          "isMut": true,
-          "isSigner": false
+          "name": "liabTokenIndex",
+          "type": "u16"
         },
         {
-          "name": "marketAsks",
-          "isMut": true,
-          "isSigner": false
-        },
+          "name": "maxLiabTransfer",
+          "type": {
+            "defined": "I80F48"
+          }
+        }
+      ]
+    },
+    {
+      "name": "liqTokenBankruptcy",
+      "accounts": [
         {
-          "name": "marketEventQueue",
-          "isMut": true,
+          "name": "group",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "marketRequestQueue",
+          "name": "liqor",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "marketBaseVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "liqorOwner",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "marketQuoteVault",
+          "name": "liqee",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "marketVaultSigner",
+          "name": "liabMintInfo",
           "isMut": false,
-          "isSigner": false,
-          "docs": [
-            "needed for the automatic settle_funds call"
-          ]
+          "isSigner": false
         },
         {
-          "name": "payerBank",
+          "name": "quoteVault",
           "isMut": true,
-          "isSigner": false,
-          "docs": [
-            "The bank that pays for the order, if necessary"
-          ]
+          "isSigner": false
         },
         {
-          "name": "payerVault",
+          "name": "insuranceVault",
           "isMut": true,
-          "isSigner": false,
-          "docs": [
-            "The bank vault that pays for the order, if necessary"
-          ]
-        },
-        {
-          "name": "payerOracle",
-          "isMut": false,
           "isSigner": false
         },
         {
@@ -11065,47 +13515,56 @@ export const IDL: MangoV4 = {
       ],
       "args": [
         {
-          "name": "side",
+          "name": "maxLiabTransfer",
           "type": {
-            "defined": "Serum3Side"
+            "defined": "I80F48"
           }
-        },
+        }
+      ]
+    },
+    {
+      "name": "tokenLiqWithToken",
+      "accounts": [
         {
-          "name": "limitPrice",
-          "type": "u64"
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "maxBaseQty",
-          "type": "u64"
+          "name": "liqor",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "maxNativeQuoteQtyIncludingFees",
-          "type": "u64"
+          "name": "liqorOwner",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "selfTradeBehavior",
-          "type": {
-            "defined": "Serum3SelfTradeBehavior"
-          }
-        },
+          "name": "liqee",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
         {
-          "name": "orderType",
-          "type": {
-            "defined": "Serum3OrderType"
-          }
+          "name": "assetTokenIndex",
+          "type": "u16"
         },
         {
-          "name": "clientOrderId",
-          "type": "u64"
+          "name": "liabTokenIndex",
+          "type": "u16"
         },
         {
-          "name": "limit",
-          "type": "u16"
+          "name": "maxLiabTransfer",
+          "type": {
+            "defined": "I80F48"
+          }
         }
       ]
     },
     {
-      "name": "serum3CancelOrder",
+      "name": "tokenForceCloseBorrowsWithToken",
       "accounts": [
         {
           "name": "group",
@@ -11113,66 +13572,96 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "account",
+          "name": "liqor",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "owner",
+          "name": "liqorOwner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "openOrders",
+          "name": "liqee",
           "isMut": true,
           "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "assetTokenIndex",
+          "type": "u16"
         },
         {
-          "name": "serumMarket",
-          "isMut": false,
-          "isSigner": false
+          "name": "liabTokenIndex",
+          "type": "u16"
         },
         {
-          "name": "serumProgram",
+          "name": "maxLiabTransfer",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "tokenLiqBankruptcy",
+      "accounts": [
+        {
+          "name": "group",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "serumMarketExternal",
+          "name": "liqor",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "marketBids",
+          "name": "liqorOwner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "liqee",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "marketAsks",
+          "name": "liabMintInfo",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "quoteVault",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "marketEventQueue",
+          "name": "insuranceVault",
           "isMut": true,
           "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "side",
+          "name": "maxLiabTransfer",
           "type": {
-            "defined": "Serum3Side"
+            "defined": "I80F48"
           }
-        },
-        {
-          "name": "orderId",
-          "type": "u128"
         }
       ]
     },
     {
-      "name": "serum3CancelAllOrders",
+      "name": "perpCreateMarket",
+      "docs": [
+        "",
+        "Perps",
+        ""
+      ],
       "accounts": [
         {
           "name": "group",
@@ -11180,262 +13669,184 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "account",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "owner",
+          "name": "admin",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "openOrders",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "serumMarket",
+          "name": "oracle",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "serumProgram",
-          "isMut": false,
-          "isSigner": false
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "PerpMarket"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "perp_market_index"
+              }
+            ]
+          }
         },
         {
-          "name": "serumMarketExternal",
+          "name": "bids",
           "isMut": true,
-          "isSigner": false
+          "isSigner": false,
+          "docs": [
+            "Accounts are initialised by client,",
+            "anchor discriminator is set first when ix exits,"
+          ]
         },
         {
-          "name": "marketBids",
+          "name": "asks",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "marketAsks",
+          "name": "eventQueue",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "marketEventQueue",
+          "name": "payer",
           "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
           "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "limit",
+          "name": "perpMarketIndex",
+          "type": "u16"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "oracleConfig",
+          "type": {
+            "defined": "OracleConfigParams"
+          }
+        },
+        {
+          "name": "baseDecimals",
           "type": "u8"
-        }
-      ]
-    },
-    {
-      "name": "serum3SettleFunds",
-      "docs": [
-        "Settles all free funds from the OpenOrders account into the MangoAccount.",
-        "",
-        "Any serum \"referrer rebates\" (ui fees) are considered Mango fees."
-      ],
-      "accounts": [
+        },
+        {
+          "name": "quoteLotSize",
+          "type": "i64"
+        },
+        {
+          "name": "baseLotSize",
+          "type": "i64"
+        },
         {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
+          "name": "maintBaseAssetWeight",
+          "type": "f32"
         },
         {
-          "name": "account",
-          "isMut": true,
-          "isSigner": false
+          "name": "initBaseAssetWeight",
+          "type": "f32"
         },
         {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
+          "name": "maintBaseLiabWeight",
+          "type": "f32"
         },
         {
-          "name": "openOrders",
-          "isMut": true,
-          "isSigner": false
+          "name": "initBaseLiabWeight",
+          "type": "f32"
         },
         {
-          "name": "serumMarket",
-          "isMut": false,
-          "isSigner": false
+          "name": "maintOverallAssetWeight",
+          "type": "f32"
         },
         {
-          "name": "serumProgram",
-          "isMut": false,
-          "isSigner": false
+          "name": "initOverallAssetWeight",
+          "type": "f32"
         },
         {
-          "name": "serumMarketExternal",
-          "isMut": true,
-          "isSigner": false
+          "name": "baseLiquidationFee",
+          "type": "f32"
         },
         {
-          "name": "marketBaseVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "makerFee",
+          "type": "f32"
         },
         {
-          "name": "marketQuoteVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "takerFee",
+          "type": "f32"
         },
         {
-          "name": "marketVaultSigner",
-          "isMut": false,
-          "isSigner": false,
-          "docs": [
-            "needed for the automatic settle_funds call"
-          ]
+          "name": "minFunding",
+          "type": "f32"
         },
         {
-          "name": "quoteBank",
-          "isMut": true,
-          "isSigner": false
+          "name": "maxFunding",
+          "type": "f32"
         },
         {
-          "name": "quoteVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "impactQuantity",
+          "type": "i64"
         },
         {
-          "name": "baseBank",
-          "isMut": true,
-          "isSigner": false
+          "name": "groupInsuranceFund",
+          "type": "bool"
         },
         {
-          "name": "baseVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "feePenalty",
+          "type": "f32"
         },
         {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": []
-    },
-    {
-      "name": "serum3SettleFundsV2",
-      "docs": [
-        "Like Serum3SettleFunds, but `fees_to_dao` determines if referrer rebates are considered fees",
-        "or are credited to the MangoAccount."
-      ],
-      "accounts": [
+          "name": "settleFeeFlat",
+          "type": "f32"
+        },
         {
-          "name": "v1",
-          "accounts": [
-            {
-              "name": "group",
-              "isMut": false,
-              "isSigner": false
-            },
-            {
-              "name": "account",
-              "isMut": true,
-              "isSigner": false
-            },
-            {
-              "name": "owner",
-              "isMut": false,
-              "isSigner": true
-            },
-            {
-              "name": "openOrders",
-              "isMut": true,
-              "isSigner": false
-            },
-            {
-              "name": "serumMarket",
-              "isMut": false,
-              "isSigner": false
-            },
-            {
-              "name": "serumProgram",
-              "isMut": false,
-              "isSigner": false
-            },
-            {
-              "name": "serumMarketExternal",
-              "isMut": true,
-              "isSigner": false
-            },
-            {
-              "name": "marketBaseVault",
-              "isMut": true,
-              "isSigner": false
-            },
-            {
-              "name": "marketQuoteVault",
-              "isMut": true,
-              "isSigner": false
-            },
-            {
-              "name": "marketVaultSigner",
-              "isMut": false,
-              "isSigner": false,
-              "docs": [
-                "needed for the automatic settle_funds call"
-              ]
-            },
-            {
-              "name": "quoteBank",
-              "isMut": true,
-              "isSigner": false
-            },
-            {
-              "name": "quoteVault",
-              "isMut": true,
-              "isSigner": false
-            },
-            {
-              "name": "baseBank",
-              "isMut": true,
-              "isSigner": false
-            },
-            {
-              "name": "baseVault",
-              "isMut": true,
-              "isSigner": false
-            },
-            {
-              "name": "tokenProgram",
-              "isMut": false,
-              "isSigner": false
-            }
-          ]
+          "name": "settleFeeAmountThreshold",
+          "type": "f32"
         },
         {
-          "name": "v2",
-          "accounts": [
-            {
-              "name": "quoteOracle",
-              "isMut": false,
-              "isSigner": false
-            },
-            {
-              "name": "baseOracle",
-              "isMut": false,
-              "isSigner": false
-            }
-          ]
-        }
-      ],
-      "args": [
+          "name": "settleFeeFractionLowHealth",
+          "type": "f32"
+        },
         {
-          "name": "feesToDao",
-          "type": "bool"
+          "name": "settleTokenIndex",
+          "type": "u16"
+        },
+        {
+          "name": "settlePnlLimitFactor",
+          "type": "f32"
+        },
+        {
+          "name": "settlePnlLimitWindowSizeTs",
+          "type": "u64"
+        },
+        {
+          "name": "positivePnlLiquidationFee",
+          "type": "f32"
         }
       ]
     },
     {
-      "name": "serum3LiqForceCancelOrders",
+      "name": "perpEditMarket",
       "accounts": [
         {
           "name": "group",
@@ -11443,230 +13854,248 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "account",
-          "isMut": true,
-          "isSigner": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "openOrders",
+          "name": "perpMarket",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "serumMarket",
-          "isMut": false,
-          "isSigner": false
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The oracle account is optional and only used when reset_stable_price is set.",
+            ""
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "oracleOpt",
+          "type": {
+            "option": "publicKey"
+          }
+        },
+        {
+          "name": "oracleConfigOpt",
+          "type": {
+            "option": {
+              "defined": "OracleConfigParams"
+            }
+          }
+        },
+        {
+          "name": "baseDecimalsOpt",
+          "type": {
+            "option": "u8"
+          }
+        },
+        {
+          "name": "maintBaseAssetWeightOpt",
+          "type": {
+            "option": "f32"
+          }
+        },
+        {
+          "name": "initBaseAssetWeightOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "serumProgram",
-          "isMut": false,
-          "isSigner": false
+          "name": "maintBaseLiabWeightOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "serumMarketExternal",
-          "isMut": true,
-          "isSigner": false
+          "name": "initBaseLiabWeightOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "marketBids",
-          "isMut": true,
-          "isSigner": false
+          "name": "maintOverallAssetWeightOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "marketAsks",
-          "isMut": true,
-          "isSigner": false
+          "name": "initOverallAssetWeightOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "marketEventQueue",
-          "isMut": true,
-          "isSigner": false
+          "name": "baseLiquidationFeeOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "marketBaseVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "makerFeeOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "marketQuoteVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "takerFeeOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "marketVaultSigner",
-          "isMut": false,
-          "isSigner": false
+          "name": "minFundingOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "quoteBank",
-          "isMut": true,
-          "isSigner": false
+          "name": "maxFundingOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "quoteVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "impactQuantityOpt",
+          "type": {
+            "option": "i64"
+          }
         },
         {
-          "name": "baseBank",
-          "isMut": true,
-          "isSigner": false
+          "name": "groupInsuranceFundOpt",
+          "type": {
+            "option": "bool"
+          }
         },
         {
-          "name": "baseVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "feePenaltyOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "limit",
-          "type": "u8"
-        }
-      ]
-    },
-    {
-      "name": "liqTokenWithToken",
-      "accounts": [
-        {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
+          "name": "settleFeeFlatOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "liqor",
-          "isMut": true,
-          "isSigner": false
+          "name": "settleFeeAmountThresholdOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "liqorOwner",
-          "isMut": false,
-          "isSigner": true
+          "name": "settleFeeFractionLowHealthOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "liqee",
-          "isMut": true,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "assetTokenIndex",
-          "type": "u16"
+          "name": "stablePriceDelayIntervalSecondsOpt",
+          "type": {
+            "option": "u32"
+          }
         },
         {
-          "name": "liabTokenIndex",
-          "type": "u16"
+          "name": "stablePriceDelayGrowthLimitOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "maxLiabTransfer",
+          "name": "stablePriceGrowthLimitOpt",
           "type": {
-            "defined": "I80F48"
+            "option": "f32"
           }
-        }
-      ]
-    },
-    {
-      "name": "liqTokenBankruptcy",
-      "accounts": [
-        {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
         },
         {
-          "name": "liqor",
-          "isMut": true,
-          "isSigner": false
+          "name": "settlePnlLimitFactorOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "liqorOwner",
-          "isMut": false,
-          "isSigner": true
+          "name": "settlePnlLimitWindowSizeTsOpt",
+          "type": {
+            "option": "u64"
+          }
         },
         {
-          "name": "liqee",
-          "isMut": true,
-          "isSigner": false
+          "name": "reduceOnlyOpt",
+          "type": {
+            "option": "bool"
+          }
         },
         {
-          "name": "liabMintInfo",
-          "isMut": false,
-          "isSigner": false
+          "name": "resetStablePrice",
+          "type": "bool"
         },
         {
-          "name": "quoteVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "positivePnlLiquidationFeeOpt",
+          "type": {
+            "option": "f32"
+          }
         },
         {
-          "name": "insuranceVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "nameOpt",
+          "type": {
+            "option": "string"
+          }
         },
         {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "maxLiabTransfer",
+          "name": "forceCloseOpt",
           "type": {
-            "defined": "I80F48"
+            "option": "bool"
           }
-        }
-      ]
-    },
-    {
-      "name": "tokenLiqWithToken",
-      "accounts": [
-        {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
         },
         {
-          "name": "liqor",
-          "isMut": true,
-          "isSigner": false
+          "name": "fallbackOracleOpt",
+          "type": {
+            "option": "publicKey"
+          }
         },
         {
-          "name": "liqorOwner",
-          "isMut": false,
-          "isSigner": true
+          "name": "expiryTimestampOpt",
+          "type": {
+            "option": "u64"
+          }
         },
         {
-          "name": "liqee",
-          "isMut": true,
-          "isSigner": false
-        }
-      ],
-      "args": [
+          "name": "settlementWindowTsOpt",
+          "type": {
+            "option": "u64"
+          }
+        },
         {
-          "name": "assetTokenIndex",
-          "type": "u16"
+          "name": "settlementUsesStablePriceOpt",
+          "type": {
+            "option": "bool"
+          }
         },
         {
-          "name": "liabTokenIndex",
-          "type": "u16"
+          "name": "fundingModelOpt",
+          "type": {
+            "option": {
+              "defined": "PerpFundingModel"
+            }
+          }
         },
         {
-          "name": "maxLiabTransfer",
+          "name": "fundingIntervalTsOpt",
           "type": {
-            "defined": "I80F48"
+            "option": "u64"
           }
         }
       ]
     },
     {
-      "name": "tokenForceCloseBorrowsWithToken",
+      "name": "perpCloseMarket",
       "accounts": [
         {
           "name": "group",
@@ -11674,96 +14103,71 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "liqor",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "liqorOwner",
+          "name": "admin",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "liqee",
+          "name": "perpMarket",
           "isMut": true,
           "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "assetTokenIndex",
-          "type": "u16"
-        },
-        {
-          "name": "liabTokenIndex",
-          "type": "u16"
         },
         {
-          "name": "maxLiabTransfer",
-          "type": "u64"
-        }
-      ]
-    },
-    {
-      "name": "tokenLiqBankruptcy",
-      "accounts": [
-        {
-          "name": "group",
-          "isMut": false,
+          "name": "bids",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "liqor",
+          "name": "asks",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "liqorOwner",
-          "isMut": false,
-          "isSigner": true
+          "name": "eventQueue",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "liqee",
+          "name": "solDestination",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "liabMintInfo",
+          "name": "tokenProgram",
           "isMut": false,
           "isSigner": false
-        },
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "perpDeactivatePosition",
+      "accounts": [
         {
-          "name": "quoteVault",
-          "isMut": true,
+          "name": "group",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "insuranceVault",
+          "name": "account",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "tokenProgram",
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "perpMarket",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": [
-        {
-          "name": "maxLiabTransfer",
-          "type": {
-            "defined": "I80F48"
-          }
-        }
-      ]
+      "args": []
     },
     {
-      "name": "perpCreateMarket",
-      "docs": [
-        "",
-        "Perps",
-        ""
-      ],
+      "name": "perpPlaceOrder",
       "accounts": [
         {
           "name": "group",
@@ -11771,47 +14175,24 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "admin",
-          "isMut": false,
-          "isSigner": true
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "oracle",
+          "name": "owner",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         },
         {
           "name": "perpMarket",
           "isMut": true,
-          "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "PerpMarket"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "group"
-              },
-              {
-                "kind": "arg",
-                "type": "u16",
-                "path": "perp_market_index"
-              }
-            ]
-          }
+          "isSigner": false
         },
         {
           "name": "bids",
           "isMut": true,
-          "isSigner": false,
-          "docs": [
-            "Accounts are initialised by client,",
-            "anchor discriminator is set first when ix exits,"
-          ]
+          "isSigner": false
         },
         {
           "name": "asks",
@@ -11824,131 +14205,59 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "payer",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "systemProgram",
+          "name": "oracle",
           "isMut": false,
           "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "perpMarketIndex",
-          "type": "u16"
-        },
-        {
-          "name": "name",
-          "type": "string"
-        },
-        {
-          "name": "oracleConfig",
+          "name": "side",
           "type": {
-            "defined": "OracleConfigParams"
+            "defined": "Side"
           }
         },
         {
-          "name": "baseDecimals",
-          "type": "u8"
-        },
-        {
-          "name": "quoteLotSize",
+          "name": "priceLots",
           "type": "i64"
         },
         {
-          "name": "baseLotSize",
+          "name": "maxBaseLots",
           "type": "i64"
         },
         {
-          "name": "maintBaseAssetWeight",
-          "type": "f32"
-        },
-        {
-          "name": "initBaseAssetWeight",
-          "type": "f32"
-        },
-        {
-          "name": "maintBaseLiabWeight",
-          "type": "f32"
-        },
-        {
-          "name": "initBaseLiabWeight",
-          "type": "f32"
-        },
-        {
-          "name": "maintOverallAssetWeight",
-          "type": "f32"
-        },
-        {
-          "name": "initOverallAssetWeight",
-          "type": "f32"
-        },
-        {
-          "name": "baseLiquidationFee",
-          "type": "f32"
-        },
-        {
-          "name": "makerFee",
-          "type": "f32"
-        },
-        {
-          "name": "takerFee",
-          "type": "f32"
-        },
-        {
-          "name": "minFunding",
-          "type": "f32"
-        },
-        {
-          "name": "maxFunding",
-          "type": "f32"
-        },
-        {
-          "name": "impactQuantity",
+          "name": "maxQuoteLots",
           "type": "i64"
         },
         {
-          "name": "groupInsuranceFund",
-          "type": "bool"
-        },
-        {
-          "name": "feePenalty",
-          "type": "f32"
-        },
-        {
-          "name": "settleFeeFlat",
-          "type": "f32"
-        },
-        {
-          "name": "settleFeeAmountThreshold",
-          "type": "f32"
-        },
-        {
-          "name": "settleFeeFractionLowHealth",
-          "type": "f32"
+          "name": "clientOrderId",
+          "type": "u64"
         },
         {
-          "name": "settleTokenIndex",
-          "type": "u16"
+          "name": "orderType",
+          "type": {
+            "defined": "PlaceOrderType"
+          }
         },
         {
-          "name": "settlePnlLimitFactor",
-          "type": "f32"
+          "name": "reduceOnly",
+          "type": "bool"
         },
         {
-          "name": "settlePnlLimitWindowSizeTs",
+          "name": "expiryTimestamp",
           "type": "u64"
         },
         {
-          "name": "positivePnlLiquidationFee",
-          "type": "f32"
+          "name": "limit",
+          "type": "u8"
         }
-      ]
+      ],
+      "returns": {
+        "option": "u128"
+      }
     },
     {
-      "name": "perpEditMarket",
+      "name": "perpPlaceOrderPegged",
       "accounts": [
         {
           "name": "group",
@@ -11956,210 +14265,320 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "admin",
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "owner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "perpMarket",
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "asks",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "eventQueue",
           "isMut": true,
           "isSigner": false
         },
         {
           "name": "oracle",
           "isMut": false,
-          "isSigner": false,
-          "docs": [
-            "The oracle account is optional and only used when reset_stable_price is set.",
-            ""
-          ]
+          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "oracleOpt",
+          "name": "side",
           "type": {
-            "option": "publicKey"
+            "defined": "Side"
           }
         },
         {
-          "name": "oracleConfigOpt",
-          "type": {
-            "option": {
-              "defined": "OracleConfigParams"
-            }
-          }
+          "name": "priceOffsetLots",
+          "type": "i64"
         },
         {
-          "name": "baseDecimalsOpt",
-          "type": {
-            "option": "u8"
-          }
+          "name": "pegLimit",
+          "type": "i64"
         },
         {
-          "name": "maintBaseAssetWeightOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "maxBaseLots",
+          "type": "i64"
         },
         {
-          "name": "initBaseAssetWeightOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "maxQuoteLots",
+          "type": "i64"
         },
         {
-          "name": "maintBaseLiabWeightOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "clientOrderId",
+          "type": "u64"
         },
         {
-          "name": "initBaseLiabWeightOpt",
+          "name": "orderType",
           "type": {
-            "option": "f32"
+            "defined": "PlaceOrderType"
           }
         },
         {
-          "name": "maintOverallAssetWeightOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "reduceOnly",
+          "type": "bool"
         },
         {
-          "name": "initOverallAssetWeightOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "expiryTimestamp",
+          "type": "u64"
         },
         {
-          "name": "baseLiquidationFeeOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "limit",
+          "type": "u8"
         },
         {
-          "name": "makerFeeOpt",
+          "name": "maxOracleStalenessSlots",
+          "type": "i32"
+        }
+      ],
+      "returns": {
+        "option": "u128"
+      }
+    },
+    {
+      "name": "perpPlaceOrderV2",
+      "docs": [
+        "Like PerpPlaceOrder, but `self_trade_behavior` determines what happens when the",
+        "order would match against an order of the same account."
+      ],
+      "accounts": [
+        {
+          "name": "v1",
+          "accounts": [
+            {
+              "name": "group",
+              "isMut": false,
+              "isSigner": false
+            },
+            {
+              "name": "account",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "owner",
+              "isMut": false,
+              "isSigner": true
+            },
+            {
+              "name": "perpMarket",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "bids",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "asks",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "eventQueue",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "oracle",
+              "isMut": false,
+              "isSigner": false
+            }
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "side",
           "type": {
-            "option": "f32"
+            "defined": "Side"
           }
         },
         {
-          "name": "takerFeeOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "priceLots",
+          "type": "i64"
         },
         {
-          "name": "minFundingOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "maxBaseLots",
+          "type": "i64"
         },
         {
-          "name": "maxFundingOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "maxQuoteLots",
+          "type": "i64"
         },
         {
-          "name": "impactQuantityOpt",
-          "type": {
-            "option": "i64"
-          }
+          "name": "clientOrderId",
+          "type": "u64"
         },
         {
-          "name": "groupInsuranceFundOpt",
+          "name": "orderType",
           "type": {
-            "option": "bool"
+            "defined": "PlaceOrderType"
           }
         },
         {
-          "name": "feePenaltyOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "reduceOnly",
+          "type": "bool"
         },
         {
-          "name": "settleFeeFlatOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "expiryTimestamp",
+          "type": "u64"
         },
         {
-          "name": "settleFeeAmountThresholdOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "limit",
+          "type": "u8"
         },
         {
-          "name": "settleFeeFractionLowHealthOpt",
+          "name": "selfTradeBehavior",
           "type": {
-            "option": "f32"
+            "defined": "Serum3SelfTradeBehavior"
           }
-        },
+        }
+      ],
+      "returns": {
+        "option": "u128"
+      }
+    },
+    {
+      "name": "perpPlaceOrderPeggedV2",
+      "docs": [
+        "Like PerpPlaceOrderPegged, with the `self_trade_behavior` of PerpPlaceOrderV2."
+      ],
+      "accounts": [
         {
-          "name": "stablePriceDelayIntervalSecondsOpt",
+          "name": "v1",
+          "accounts": [
+            {
+              "name": "group",
+              "isMut": false,
+              "isSigner": false
+            },
+            {
+              "name": "account",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "owner",
+              "isMut": false,
+              "isSigner": true
+            },
+            {
+              "name": "perpMarket",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "bids",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "asks",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "eventQueue",
+              "isMut": true,
+              "isSigner": false
+            },
+            {
+              "name": "oracle",
+              "isMut": false,
+              "isSigner": false
+            }
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "side",
           "type": {
-            "option": "u32"
+            "defined": "Side"
           }
         },
         {
-          "name": "stablePriceDelayGrowthLimitOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "priceOffsetLots",
+          "type": "i64"
         },
         {
-          "name": "stablePriceGrowthLimitOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "pegLimit",
+          "type": "i64"
+        },
+        {
+          "name": "maxBaseLots",
+          "type": "i64"
+        },
+        {
+          "name": "maxQuoteLots",
+          "type": "i64"
         },
         {
-          "name": "settlePnlLimitFactorOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "clientOrderId",
+          "type": "u64"
         },
         {
-          "name": "settlePnlLimitWindowSizeTsOpt",
+          "name": "orderType",
           "type": {
-            "option": "u64"
+            "defined": "PlaceOrderType"
           }
         },
         {
-          "name": "reduceOnlyOpt",
-          "type": {
-            "option": "bool"
-          }
+          "name": "reduceOnly",
+          "type": "bool"
         },
         {
-          "name": "resetStablePrice",
-          "type": "bool"
+          "name": "expiryTimestamp",
+          "type": "u64"
         },
         {
-          "name": "positivePnlLiquidationFeeOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "limit",
+          "type": "u8"
         },
         {
-          "name": "nameOpt",
-          "type": {
-            "option": "string"
-          }
+          "name": "maxOracleStalenessSlots",
+          "type": "i32"
         },
         {
-          "name": "forceCloseOpt",
+          "name": "selfTradeBehavior",
           "type": {
-            "option": "bool"
+            "defined": "Serum3SelfTradeBehavior"
           }
         }
-      ]
+      ],
+      "returns": {
+        "option": "u128"
+      }
     },
     {
-      "name": "perpCloseMarket",
+      "name": "perpCancelReplaceOrders",
+      "docs": [
+        "Cancels orders and places new ones on a perp market with a single health check.",
+        "",
+        "Returns the order ids of the new orders that were posted to the book, in the",
+        "order of `new_orders`."
+      ],
       "accounts": [
         {
           "name": "group",
@@ -12167,7 +14586,12 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "admin",
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "owner",
           "isMut": false,
           "isSigner": true
         },
@@ -12192,46 +14616,48 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "solDestination",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
+          "name": "oracle",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": []
-    },
-    {
-      "name": "perpDeactivatePosition",
-      "accounts": [
+      "args": [
         {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
+          "name": "cancel",
+          "type": {
+            "defined": "PerpCancelOrders"
+          }
         },
         {
-          "name": "account",
-          "isMut": true,
-          "isSigner": false
+          "name": "newOrders",
+          "type": {
+            "vec": {
+              "defined": "PerpNewOrder"
+            }
+          }
         },
         {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
+          "name": "limit",
+          "type": "u8"
         },
         {
-          "name": "perpMarket",
-          "isMut": false,
-          "isSigner": false
+          "name": "selfTradeBehavior",
+          "type": {
+            "defined": "Serum3SelfTradeBehavior"
+          }
         }
       ],
-      "args": []
+      "returns": {
+        "vec": {
+          "option": "u128"
+        }
+      }
     },
     {
-      "name": "perpPlaceOrder",
+      "name": "perpPlaceTriggerOrder",
+      "docs": [
+        "Stores a conditional order on the account. Returns the trigger order id."
+      ],
       "accounts": [
         {
           "name": "group",
@@ -12250,26 +14676,6 @@ export const IDL: MangoV4 = {
         },
         {
           "name": "perpMarket",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "bids",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "asks",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "eventQueue",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "oracle",
           "isMut": false,
           "isSigner": false
         }
@@ -12281,6 +14687,16 @@ export const IDL: MangoV4 = {
             "defined": "Side"
           }
         },
+        {
+          "name": "triggerCondition",
+          "type": {
+            "defined": "PerpTriggerCondition"
+          }
+        },
+        {
+          "name": "triggerPrice",
+          "type": "f64"
+        },
         {
           "name": "priceLots",
           "type": "i64"
@@ -12297,12 +14713,6 @@ export const IDL: MangoV4 = {
           "name": "clientOrderId",
           "type": "u64"
         },
-        {
-          "name": "orderType",
-          "type": {
-            "defined": "PlaceOrderType"
-          }
-        },
         {
           "name": "reduceOnly",
           "type": "bool"
@@ -12312,16 +14722,14 @@ export const IDL: MangoV4 = {
           "type": "u64"
         },
         {
-          "name": "limit",
-          "type": "u8"
+          "name": "incentive",
+          "type": "u64"
         }
       ],
-      "returns": {
-        "option": "u128"
-      }
+      "returns": "u64"
     },
     {
-      "name": "perpPlaceOrderPegged",
+      "name": "perpCancelTriggerOrder",
       "accounts": [
         {
           "name": "group",
@@ -12337,6 +14745,40 @@ export const IDL: MangoV4 = {
           "name": "owner",
           "isMut": false,
           "isSigner": true
+        }
+      ],
+      "args": [
+        {
+          "name": "triggerOrderId",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "perpExecuteTriggerOrder",
+      "docs": [
+        "Permissionless: places the trigger order on the book once its condition is met."
+      ],
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "executor",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "executorOwner",
+          "isMut": false,
+          "isSigner": true
         },
         {
           "name": "perpMarket",
@@ -12362,61 +14804,23 @@ export const IDL: MangoV4 = {
           "name": "oracle",
           "isMut": false,
           "isSigner": false
+        },
+        {
+          "name": "settleBank",
+          "isMut": true,
+          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "side",
-          "type": {
-            "defined": "Side"
-          }
-        },
-        {
-          "name": "priceOffsetLots",
-          "type": "i64"
-        },
-        {
-          "name": "pegLimit",
-          "type": "i64"
-        },
-        {
-          "name": "maxBaseLots",
-          "type": "i64"
-        },
-        {
-          "name": "maxQuoteLots",
-          "type": "i64"
-        },
-        {
-          "name": "clientOrderId",
-          "type": "u64"
-        },
-        {
-          "name": "orderType",
-          "type": {
-            "defined": "PlaceOrderType"
-          }
-        },
-        {
-          "name": "reduceOnly",
-          "type": "bool"
-        },
-        {
-          "name": "expiryTimestamp",
+          "name": "triggerOrderId",
           "type": "u64"
         },
         {
           "name": "limit",
           "type": "u8"
-        },
-        {
-          "name": "maxOracleStalenessSlots",
-          "type": "i32"
         }
-      ],
-      "returns": {
-        "option": "u128"
-      }
+      ]
     },
     {
       "name": "perpCancelOrder",
@@ -12663,11 +15067,52 @@ export const IDL: MangoV4 = {
         {
           "name": "settlerOwner",
           "isMut": false,
-          "isSigner": true
+          "isSigner": true
+        },
+        {
+          "name": "perpMarket",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "accountA",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "accountB",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "settleBank",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "settleOracle",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "perpForceClosePosition",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
           "name": "perpMarket",
-          "isMut": false,
+          "isMut": true,
           "isSigner": false
         },
         {
@@ -12684,22 +15129,16 @@ export const IDL: MangoV4 = {
           "name": "oracle",
           "isMut": false,
           "isSigner": false
-        },
-        {
-          "name": "settleBank",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "settleOracle",
-          "isMut": false,
-          "isSigner": false
         }
       ],
       "args": []
     },
     {
-      "name": "perpForceClosePosition",
+      "name": "perpCloseExpiredPosition",
+      "docs": [
+        "Closes the positions of a long and a short account at the settlement price",
+        "of an expired dated futures market."
+      ],
       "accounts": [
         {
           "name": "group",
@@ -13306,12 +15745,20 @@ export const IDL: MangoV4 = {
             "name": "forceClose",
             "type": "u8"
           },
+          {
+            "name": "fallbackOracle",
+            "docs": [
+              "Oracle to use when the primary oracle is stale or not confident enough,",
+              "Pubkey::default() if there is none"
+            ],
+            "type": "publicKey"
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                2118
+                2086
               ]
             }
           }
@@ -13417,12 +15864,40 @@ export const IDL: MangoV4 = {
             ],
             "type": "u64"
           },
+          {
+            "name": "insuranceFundVaultBumps",
+            "docs": [
+              "Bumps of the isolated insurance fund vaults, by fund index.",
+              "",
+              "Set by InsuranceFundCreate. Index 0 is unused, see insurance_vault."
+            ],
+            "type": {
+              "array": [
+                "u8",
+                16
+              ]
+            }
+          },
+          {
+            "name": "insuranceFundReferences",
+            "docs": [
+              "Number of tokens and perp markets each isolated insurance fund covers, by fund index.",
+              "",
+              "Insurance funds can't be closed while they are still referenced."
+            ],
+            "type": {
+              "array": [
+                "u16",
+                16
+              ]
+            }
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                1824
+                1776
               ]
             }
           }
@@ -13537,12 +16012,19 @@ export const IDL: MangoV4 = {
             ],
             "type": "u64"
           },
+          {
+            "name": "perpTriggerOrderIdCounter",
+            "docs": [
+              "Incremented for each new perp trigger order, the new value is used as its id."
+            ],
+            "type": "u64"
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                208
+                200
               ]
             }
           },
@@ -13606,6 +16088,18 @@ export const IDL: MangoV4 = {
                 "defined": "PerpOpenOrder"
               }
             }
+          },
+          {
+            "name": "padding8",
+            "type": "u32"
+          },
+          {
+            "name": "perpTriggerOrders",
+            "type": {
+              "vec": {
+                "defined": "PerpTriggerOrder"
+              }
+            }
           }
         ]
       }
@@ -13666,12 +16160,30 @@ export const IDL: MangoV4 = {
             "name": "registrationTime",
             "type": "u64"
           },
+          {
+            "name": "insuranceFundIndex",
+            "docs": [
+              "Isolated insurance fund that covers bankruptcies in this token.",
+              "",
+              "0 means the group insurance fund is used, if group_insurance_fund is set."
+            ],
+            "type": "u16"
+          },
+          {
+            "name": "padding2",
+            "type": {
+              "array": [
+                "u8",
+                6
+              ]
+            }
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                2560
+                2552
               ]
             }
           }
@@ -14026,168 +16538,337 @@ export const IDL: MangoV4 = {
             }
           },
           {
-            "name": "fundingLastUpdated",
+            "name": "fundingLastUpdated",
+            "docs": [
+              "timestamp that funding was last updated in"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "baseLiquidationFee",
+            "docs": [
+              "Fees",
+              "Fee for base position liquidation"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "makerFee",
+            "docs": [
+              "Fee when matching maker orders. May be negative."
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "takerFee",
+            "docs": [
+              "Fee for taker orders, may not be negative."
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "feesAccrued",
+            "docs": [
+              "Fees accrued in native quote currency"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "feesSettled",
+            "docs": [
+              "Fees settled in native quote currency"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "feePenalty",
+            "docs": [
+              "Fee (in quote native) to charge for ioc orders"
+            ],
+            "type": "f32"
+          },
+          {
+            "name": "settleFeeFlat",
+            "docs": [
+              "In native units of settlement token, given to each settle call above the",
+              "settle_fee_amount_threshold."
+            ],
+            "type": "f32"
+          },
+          {
+            "name": "settleFeeAmountThreshold",
+            "docs": [
+              "Pnl settlement amount needed to be eligible for the flat fee."
+            ],
+            "type": "f32"
+          },
+          {
+            "name": "settleFeeFractionLowHealth",
+            "docs": [
+              "Fraction of pnl to pay out as fee if +pnl account has low health."
+            ],
+            "type": "f32"
+          },
+          {
+            "name": "settlePnlLimitFactor",
+            "docs": [
+              "Controls the strictness of the settle limit.",
+              "Set to a negative value to disable the limit.",
+              "",
+              "This factor applies to the settle limit in two ways",
+              "- for the unrealized pnl settle limit, the factor is multiplied with the stable perp base value",
+              "(i.e. limit_factor * base_native * stable_price)",
+              "- when increasing the realized pnl settle limit (stored per PerpPosition), the factor is",
+              "multiplied with the stable value of the perp pnl being realized",
+              "(i.e. limit_factor * reduced_native * stable_price)",
+              "",
+              "See also PerpPosition::settle_pnl_limit_realized_trade"
+            ],
+            "type": "f32"
+          },
+          {
+            "name": "padding3",
+            "type": {
+              "array": [
+                "u8",
+                4
+              ]
+            }
+          },
+          {
+            "name": "settlePnlLimitWindowSizeTs",
+            "docs": [
+              "Window size in seconds for the perp settlement limit"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "reduceOnly",
+            "docs": [
+              "If true, users may no longer increase their market exposure. Only actions",
+              "that reduce their position are still allowed."
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "forceClose",
+            "type": "u8"
+          },
+          {
+            "name": "padding4",
+            "type": {
+              "array": [
+                "u8",
+                6
+              ]
+            }
+          },
+          {
+            "name": "maintOverallAssetWeight",
+            "docs": [
+              "Weights for full perp market health, if positive"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "initOverallAssetWeight",
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "positivePnlLiquidationFee",
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "fallbackOracle",
+            "docs": [
+              "Oracle to use when the primary oracle is stale or not confident enough,",
+              "Pubkey::default() if there is none"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "insuranceFundIndex",
+            "docs": [
+              "Isolated insurance fund that covers bankruptcies in this market.",
+              "",
+              "0 means the group insurance fund is used, if group_insurance_fund is set."
+            ],
+            "type": "u16"
+          },
+          {
+            "name": "padding5",
+            "type": {
+              "array": [
+                "u8",
+                6
+              ]
+            }
+          },
+          {
+            "name": "expiryTimestamp",
+            "docs": [
+              "Timestamp at which the market expires, 0 for perpetual markets.",
+              "",
+              "Dated markets pay no funding. Once expired, no orders can be placed and",
+              "positions are closed at settlement_price with perp_close_expired_position."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "settlementWindowTs",
             "docs": [
-              "timestamp that funding was last updated in"
+              "Length of the window before expiry_timestamp in which prices are sampled",
+              "for the settlement price."
             ],
             "type": "u64"
           },
           {
-            "name": "baseLiquidationFee",
+            "name": "settlementPriceSum",
             "docs": [
-              "Fees",
-              "Fee for base position liquidation"
+              "Sum of sampled prices, weighted by the seconds they were valid for."
             ],
             "type": {
               "defined": "I80F48"
             }
           },
           {
-            "name": "makerFee",
+            "name": "settlementPrice",
             "docs": [
-              "Fee when matching maker orders. May be negative."
+              "Final price at which positions are closed, valid if has_settlement_price is set."
             ],
             "type": {
               "defined": "I80F48"
             }
           },
           {
-            "name": "takerFee",
+            "name": "settlementLastPrice",
             "docs": [
-              "Fee for taker orders, may not be negative."
+              "Price observed at funding_last_updated, it's valid until the next update."
             ],
             "type": {
               "defined": "I80F48"
             }
           },
           {
-            "name": "feesAccrued",
+            "name": "settlementPriceWeightTs",
             "docs": [
-              "Fees accrued in native quote currency"
+              "Number of seconds covered by settlement_price_sum."
             ],
-            "type": {
-              "defined": "I80F48"
-            }
+            "type": "u64"
           },
           {
-            "name": "feesSettled",
+            "name": "settlementUsesStablePrice",
             "docs": [
-              "Fees settled in native quote currency"
+              "If set, the stable price is sampled for the settlement price, otherwise the oracle price."
             ],
+            "type": "u8"
+          },
+          {
+            "name": "hasSettlementPrice",
+            "type": "u8"
+          },
+          {
+            "name": "padding6",
             "type": {
-              "defined": "I80F48"
+              "array": [
+                "u8",
+                6
+              ]
             }
           },
           {
-            "name": "feePenalty",
+            "name": "fundingIntervalTs",
             "docs": [
-              "Fee (in quote native) to charge for ioc orders"
+              "Length of a funding interval for the PremiumTwap funding model."
             ],
-            "type": "f32"
+            "type": "u64"
           },
           {
-            "name": "settleFeeFlat",
+            "name": "fundingIntervalStartTs",
             "docs": [
-              "In native units of settlement token, given to each settle call above the",
-              "settle_fee_amount_threshold."
+              "Start of the current funding interval."
             ],
-            "type": "f32"
+            "type": "u64"
           },
           {
-            "name": "settleFeeAmountThreshold",
+            "name": "fundingPremiumSum",
             "docs": [
-              "Pnl settlement amount needed to be eligible for the flat fee."
+              "Sum of book premiums in the current funding interval, weighted by seconds."
             ],
-            "type": "f32"
+            "type": {
+              "defined": "I80F48"
+            }
           },
           {
-            "name": "settleFeeFractionLowHealth",
+            "name": "prevFundingPremiumSum",
             "docs": [
-              "Fraction of pnl to pay out as fee if +pnl account has low health."
+              "Sum of book premiums in the previous funding interval, weighted by seconds."
             ],
-            "type": "f32"
+            "type": {
+              "defined": "I80F48"
+            }
           },
           {
-            "name": "settlePnlLimitFactor",
+            "name": "fundingLastPremium",
             "docs": [
-              "Controls the strictness of the settle limit.",
-              "Set to a negative value to disable the limit.",
-              "",
-              "This factor applies to the settle limit in two ways",
-              "- for the unrealized pnl settle limit, the factor is multiplied with the stable perp base value",
-              "(i.e. limit_factor * base_native * stable_price)",
-              "- when increasing the realized pnl settle limit (stored per PerpPosition), the factor is",
-              "multiplied with the stable value of the perp pnl being realized",
-              "(i.e. limit_factor * reduced_native * stable_price)",
-              "",
-              "See also PerpPosition::settle_pnl_limit_realized_trade"
+              "Book premium observed at funding_last_updated, it's valid until the next update."
             ],
-            "type": "f32"
-          },
-          {
-            "name": "padding3",
             "type": {
-              "array": [
-                "u8",
-                4
-              ]
+              "defined": "I80F48"
             }
           },
           {
-            "name": "settlePnlLimitWindowSizeTs",
+            "name": "fundingPremiumWeightTs",
             "docs": [
-              "Window size in seconds for the perp settlement limit"
+              "Number of seconds covered by funding_premium_sum."
             ],
             "type": "u64"
           },
           {
-            "name": "reduceOnly",
+            "name": "prevFundingPremiumWeightTs",
             "docs": [
-              "If true, users may no longer increase their market exposure. Only actions",
-              "that reduce their position are still allowed."
+              "Number of seconds covered by prev_funding_premium_sum."
             ],
-            "type": "u8"
+            "type": "u64"
           },
           {
-            "name": "forceClose",
+            "name": "fundingModel",
+            "docs": [
+              "See PerpFundingModel."
+            ],
             "type": "u8"
           },
           {
-            "name": "padding4",
+            "name": "padding7",
             "type": {
               "array": [
                 "u8",
-                6
+                7
               ]
             }
           },
-          {
-            "name": "maintOverallAssetWeight",
-            "docs": [
-              "Weights for full perp market health, if positive"
-            ],
-            "type": {
-              "defined": "I80F48"
-            }
-          },
-          {
-            "name": "initOverallAssetWeight",
-            "type": {
-              "defined": "I80F48"
-            }
-          },
-          {
-            "name": "positivePnlLiquidationFee",
-            "type": {
-              "defined": "I80F48"
-            }
-          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                1888
+                1680
               ]
             }
           }
@@ -14390,6 +17071,45 @@ export const IDL: MangoV4 = {
           },
           {
             "name": "value",
+            "docs": [
+              "Unsettled pnl at the oracle price, including unsettled funding"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "stableValue",
+            "docs": [
+              "Unsettled pnl at the stable price, including unsettled funding"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "baseValue",
+            "docs": [
+              "Value of the base position at the oracle price"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "stableBaseValue",
+            "docs": [
+              "Value of the base position at the stable price"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "unsettledFunding",
+            "docs": [
+              "Funding the account still needs to pay, negative if it is owed funding"
+            ],
             "type": {
               "defined": "I80F48"
             }
@@ -14681,6 +17401,58 @@ export const IDL: MangoV4 = {
         ]
       }
     },
+    {
+      "name": "PerpNewOrder",
+      "docs": [
+        "A new order in perp_cancel_replace_orders"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "side",
+            "type": {
+              "defined": "Side"
+            }
+          },
+          {
+            "name": "price",
+            "type": {
+              "defined": "PerpOrderPrice"
+            }
+          },
+          {
+            "name": "maxBaseLots",
+            "type": "i64"
+          },
+          {
+            "name": "maxQuoteLots",
+            "type": "i64"
+          },
+          {
+            "name": "clientOrderId",
+            "type": "u64"
+          },
+          {
+            "name": "orderType",
+            "type": {
+              "defined": "PlaceOrderType"
+            }
+          },
+          {
+            "name": "reduceOnly",
+            "type": "bool"
+          },
+          {
+            "name": "expiryTimestamp",
+            "docs": [
+              "Timestamp of when the order expires, 0 for never"
+            ],
+            "type": "u64"
+          }
+        ]
+      }
+    },
     {
       "name": "TokenPosition",
       "type": {
@@ -15066,12 +17838,109 @@ export const IDL: MangoV4 = {
             }
           },
           {
-            "name": "clientId",
+            "name": "clientId",
+            "type": "u64"
+          },
+          {
+            "name": "id",
+            "type": "u128"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                64
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "PerpTriggerOrder",
+      "docs": [
+        "A conditional perp order, usable for stop-loss and take-profit.",
+        "",
+        "Once the oracle price crosses trigger_price, anyone may execute it: it is then sent",
+        "to the orderbook as an immediate-or-cancel order and the executor receives the incentive."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "id",
+            "docs": [
+              "Id unique for the account, zero marks an unused slot"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "market",
+            "type": "u16"
+          },
+          {
+            "name": "side",
+            "type": "u8"
+          },
+          {
+            "name": "triggerCondition",
+            "type": "u8"
+          },
+          {
+            "name": "reduceOnly",
+            "type": "u8"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                3
+              ]
+            }
+          },
+          {
+            "name": "triggerPrice",
+            "docs": [
+              "Oracle price threshold, in native quote per native base"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "priceLots",
+            "docs": [
+              "Limit price of the order that gets placed on execution, in quote lots per base lots"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "maxBaseLots",
+            "type": "i64"
+          },
+          {
+            "name": "maxQuoteLots",
+            "type": "i64"
+          },
+          {
+            "name": "clientOrderId",
+            "type": "u64"
+          },
+          {
+            "name": "expiryTimestamp",
+            "docs": [
+              "Timestamp after which the trigger order can't be executed anymore, 0 for never"
+            ],
             "type": "u64"
           },
           {
-            "name": "id",
-            "type": "u128"
+            "name": "incentive",
+            "docs": [
+              "Native settle token amount that the account pays to the executor"
+            ],
+            "type": "u64"
           },
           {
             "name": "reserved",
@@ -15085,6 +17954,20 @@ export const IDL: MangoV4 = {
         ]
       }
     },
+    {
+      "name": "PerpTriggerCondition",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "OraclePriceAbove"
+          },
+          {
+            "name": "OraclePriceBelow"
+          }
+        ]
+      }
+    },
     {
       "name": "MangoAccountFixed",
       "type": {
@@ -15164,12 +18047,16 @@ export const IDL: MangoV4 = {
             "name": "buybackFeesExpiryTimestamp",
             "type": "u64"
           },
+          {
+            "name": "perpTriggerOrderIdCounter",
+            "type": "u64"
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                208
+                200
               ]
             }
           }
@@ -15891,6 +18778,85 @@ export const IDL: MangoV4 = {
         ]
       }
     },
+    {
+      "name": "PerpCancelOrders",
+      "docs": [
+        "Which of the account's orders on the market to cancel before placing new ones"
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "None"
+          },
+          {
+            "name": "All",
+            "fields": [
+              {
+                "name": "side_option",
+                "type": {
+                  "option": {
+                    "defined": "Side"
+                  }
+                }
+              }
+            ]
+          },
+          {
+            "name": "ByOrderIds",
+            "fields": [
+              {
+                "name": "order_ids",
+                "type": {
+                  "vec": "u128"
+                }
+              }
+            ]
+          },
+          {
+            "name": "ByClientOrderIds",
+            "fields": [
+              {
+                "name": "client_order_ids",
+                "type": {
+                  "vec": "u64"
+                }
+              }
+            ]
+          }
+        ]
+      }
+    },
+    {
+      "name": "PerpOrderPrice",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Fixed",
+            "fields": [
+              {
+                "name": "price_lots",
+                "type": "i64"
+              }
+            ]
+          },
+          {
+            "name": "OraclePegged",
+            "fields": [
+              {
+                "name": "price_offset_lots",
+                "type": "i64"
+              },
+              {
+                "name": "peg_limit",
+                "type": "i64"
+              }
+            ]
+          }
+        ]
+      }
+    },
     {
       "name": "Serum3SelfTradeBehavior",
       "docs": [
@@ -16000,6 +18966,9 @@ export const IDL: MangoV4 = {
           },
           {
             "name": "TokenWithdraw"
+          },
+          {
+            "name": "TokenTransfer"
           }
         ]
       }
@@ -16179,6 +19148,39 @@ export const IDL: MangoV4 = {
           },
           {
             "name": "GroupWithdrawInsuranceFund"
+          },
+          {
+            "name": "InsuranceFundCreate"
+          },
+          {
+            "name": "InsuranceFundWithdraw"
+          },
+          {
+            "name": "InsuranceFundClose"
+          },
+          {
+            "name": "PerpPlaceTriggerOrder"
+          },
+          {
+            "name": "PerpCancelTriggerOrder"
+          },
+          {
+            "name": "PerpExecuteTriggerOrder"
+          },
+          {
+            "name": "TokenTransfer"
+          },
+          {
+            "name": "PerpCancelReplaceOrders"
+          },
+          {
+            "name": "PerpCloseExpiredPosition"
+          },
+          {
+            "name": "PerpPlaceOrderV2"
+          },
+          {
+            "name": "PerpPlaceOrderPeggedV2"
           }
         ]
       }
@@ -16293,6 +19295,9 @@ export const IDL: MangoV4 = {
           },
           {
             "name": "PostOnlySlide"
+          },
+          {
+            "name": "FillOrKill"
           }
         ]
       }
@@ -16368,6 +19373,15 @@ export const IDL: MangoV4 = {
               }
             ]
           },
+          {
+            "name": "FillOrKill",
+            "fields": [
+              {
+                "name": "price_lots",
+                "type": "i64"
+              }
+            ]
+          },
           {
             "name": "Fixed",
             "fields": [
@@ -16439,6 +19453,28 @@ export const IDL: MangoV4 = {
           }
         ]
       }
+    },
+    {
+      "name": "PerpFundingModel",
+      "docs": [
+        "How the funding rate of a perp market is computed.",
+        "",
+        "In all cases the resulting rate is clamped to min_funding and max_funding."
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "ImpactPrice"
+          },
+          {
+            "name": "PremiumTwap"
+          },
+          {
+            "name": "StablePrice"
+          }
+        ]
+      }
     }
   ],
   "events": [
@@ -16661,6 +19697,46 @@ export const IDL: MangoV4 = {
         }
       ]
     },
+    {
+      "name": "TokenTransferLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "sourceAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "destinationAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "signer",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i128",
+          "index": false
+        }
+      ]
+    },
     {
       "name": "FillLog",
       "fields": [
@@ -16896,6 +19972,41 @@ export const IDL: MangoV4 = {
         }
       ]
     },
+    {
+      "name": "PerpFundingPremiumIntervalLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "startTs",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "endTs",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "premiumSum",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "premiumWeightTs",
+          "type": "u64",
+          "index": false
+        }
+      ]
+    },
     {
       "name": "UpdateIndexLog",
       "fields": [
@@ -17693,6 +20804,46 @@ export const IDL: MangoV4 = {
         }
       ]
     },
+    {
+      "name": "PerpExecuteTriggerOrderLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "perpMarketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "triggerOrderId",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "executor",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "incentive",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "oraclePrice",
+          "type": "i128",
+          "index": false
+        }
+      ]
+    },
     {
       "name": "PerpForceClosePositionLog",
       "fields": [
@@ -18024,6 +21175,41 @@ export const IDL: MangoV4 = {
       "code": 6046,
       "name": "TokenInForceClose",
       "msg": "token is in force close"
+    },
+    {
+      "code": 6047,
+      "name": "InvalidHealthAccountCount",
+      "msg": "incorrect number of health accounts"
+    },
+    {
+      "code": 6048,
+      "name": "PerpTriggerOrderNotFound",
+      "msg": "perp trigger order not found"
+    },
+    {
+      "code": 6049,
+      "name": "PerpTriggerOrderNotTriggered",
+      "msg": "perp trigger order condition is not met"
+    },
+    {
+      "code": 6050,
+      "name": "WouldSelfTrade",
+      "msg": "the order would self trade"
+    },
+    {
+      "code": 6051,
+      "name": "FillOrKillNotFilled",
+      "msg": "the fill-or-kill order could not be filled completely"
+    },
+    {
+      "code": 6052,
+      "name": "PerpMarketExpired",
+      "msg": "the perp market has expired"
+    },
+    {
+      "code": 6053,
+      "name": "PerpMarketNotExpired",
+      "msg": "the perp market has not expired yet"
     }
   ]
 };